@@ -0,0 +1,221 @@
+use pretty_yaml::config::GlobPattern;
+use std::{fmt::Write, path::PathBuf};
+
+/// Short flag, long flag, value name and description of flags,
+/// which are also used for generating help message and shell completions.
+pub const FLAGS: &[(Option<char>, &str, Option<&str>, &str)] = &[
+    (
+        Some('c'),
+        "check",
+        None,
+        "Check if files are formatted without changing them",
+    ),
+    (
+        None,
+        "diff",
+        None,
+        "Print differences of files that aren't formatted, implies `--check`",
+    ),
+    (Some('w'), "write", None, "Format files in place"),
+    (
+        Some('l'),
+        "list-different",
+        None,
+        "Only print paths of files that will be changed",
+    ),
+    (
+        Some('g'),
+        "github-annotations",
+        None,
+        "Report problems as GitHub Actions workflow commands",
+    ),
+    (
+        None,
+        "config",
+        Some("path"),
+        "Use the given config file instead of discovering one",
+    ),
+    (
+        None,
+        "include",
+        Some("glob"),
+        "Only format files matching the glob when walking directories",
+    ),
+    (
+        None,
+        "exclude",
+        Some("glob"),
+        "Skip files and directories matching the glob",
+    ),
+    (
+        None,
+        "stdin-filepath",
+        Some("path"),
+        "Path of the input from stdin for resolving config",
+    ),
+    (
+        Some('j'),
+        "jobs",
+        Some("n"),
+        "Number of files to format in parallel",
+    ),
+    (
+        Some('d'),
+        "dprint",
+        None,
+        "Also print dprint configuration snippet when running `init`",
+    ),
+    (Some('h'), "help", None, "Print help"),
+];
+
+/// Subcommands and their descriptions.
+pub const SUBCOMMANDS: &[(&str, &str)] = &[
+    ("completions", "Generate shell completions"),
+    ("init", "Create starter config file"),
+];
+
+pub enum Mode {
+    Format,
+    Help,
+    Init,
+    Completions(String),
+}
+
+pub struct Args {
+    pub mode: Mode,
+    pub check: bool,
+    pub diff: bool,
+    pub write: bool,
+    pub list_different: bool,
+    pub github_annotations: bool,
+    pub dprint: bool,
+    pub config: Option<PathBuf>,
+    pub includes: Vec<GlobPattern>,
+    pub excludes: Vec<GlobPattern>,
+    pub stdin_filepath: Option<PathBuf>,
+    pub jobs: Option<usize>,
+    pub paths: Vec<String>,
+}
+
+impl Args {
+    /// Parse arguments without the program name.
+    /// Values of flags can be given as `--flag value` or `--flag=value`.
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args {
+            mode: Mode::Format,
+            check: false,
+            diff: false,
+            write: false,
+            list_different: false,
+            github_annotations: false,
+            dprint: false,
+            config: None,
+            includes: vec![],
+            excludes: vec![],
+            stdin_filepath: None,
+            jobs: None,
+            paths: vec![],
+        };
+        let mut args = args.peekable();
+        if args.next_if(|arg| arg == "completions").is_some() {
+            parsed.mode = Mode::Completions(args.next().unwrap_or_default());
+            return Ok(parsed);
+        }
+        if args.next_if(|arg| arg == "init").is_some() {
+            parsed.mode = Mode::Init;
+        }
+
+        let mut only_paths = false;
+        while let Some(arg) = args.next() {
+            if only_paths || arg == "-" || !arg.starts_with('-') {
+                parsed.paths.push(arg);
+                continue;
+            }
+            if arg == "--" {
+                only_paths = true;
+                continue;
+            }
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (&*arg, None),
+            };
+            let Some((_, long, value_name, _)) = FLAGS.iter().find(|(short, long, ..)| {
+                name.strip_prefix("--") == Some(long)
+                    || short.is_some_and(|short| name == format!("-{short}"))
+            }) else {
+                return Err(format!("unknown flag `{name}`"));
+            };
+            if value_name.is_none() {
+                if inline_value.is_some() {
+                    return Err(format!("flag `--{long}` doesn't take a value"));
+                }
+                match *long {
+                    "check" => parsed.check = true,
+                    "diff" => {
+                        parsed.diff = true;
+                        parsed.check = true;
+                    }
+                    "write" => parsed.write = true,
+                    "list-different" => parsed.list_different = true,
+                    "github-annotations" => parsed.github_annotations = true,
+                    "dprint" => parsed.dprint = true,
+                    _ => parsed.mode = Mode::Help,
+                }
+                continue;
+            }
+            let Some(value) = inline_value.or_else(|| args.next()) else {
+                return Err(format!("flag `--{long}` requires a value"));
+            };
+            match *long {
+                "config" => parsed.config = Some(PathBuf::from(value)),
+                "include" => parsed.includes.push(GlobPattern::new(value)),
+                "exclude" => parsed.excludes.push(GlobPattern::new(value)),
+                "stdin-filepath" => parsed.stdin_filepath = Some(PathBuf::from(value)),
+                _ => match value.parse() {
+                    Ok(jobs) if jobs > 0 => parsed.jobs = Some(jobs),
+                    _ => return Err(format!("invalid number of jobs `{value}`")),
+                },
+            }
+        }
+
+        if parsed.check && parsed.write {
+            return Err("`--check` and `--write` can't be used together".into());
+        }
+        Ok(parsed)
+    }
+}
+
+pub fn help(bin_name: &str) -> String {
+    let mut help = String::new();
+    let _ = writeln!(
+        help,
+        "Usage: {bin_name} [OPTIONS] [PATHS]...\n       {bin_name} <COMMAND>\n"
+    );
+    let _ = writeln!(
+        help,
+        "Format YAML files. Directories are walked recursively for `.yaml` and `.yml` files,\n\
+        and `-` reads from stdin. Formatted output is printed unless `--write` is given.\n"
+    );
+    let _ = writeln!(help, "Commands:");
+    SUBCOMMANDS.iter().for_each(|(name, description)| {
+        let _ = writeln!(help, "  {name:<28}{description}");
+    });
+    let _ = writeln!(help, "\nOptions:");
+    FLAGS
+        .iter()
+        .for_each(|(short, long, value_name, description)| {
+            let flag = match (short, value_name) {
+                (Some(short), Some(value_name)) => format!("-{short}, --{long} <{value_name}>"),
+                (Some(short), None) => format!("-{short}, --{long}"),
+                (None, Some(value_name)) => format!("    --{long} <{value_name}>"),
+                (None, None) => format!("    --{long}"),
+            };
+            let _ = writeln!(help, "  {flag:<28}{description}");
+        });
+    let _ = writeln!(
+        help,
+        "\nExit status is 0 if all files are formatted, 1 if some files aren't formatted\n\
+        with `--check`, `--list-different` or `--github-annotations`, and 2 on errors."
+    );
+    help
+}
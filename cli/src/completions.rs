@@ -0,0 +1,94 @@
+use crate::args::{FLAGS, SUBCOMMANDS};
+use std::fmt::Write;
+
+pub fn generate(shell: &str, bin_name: &str) -> Option<String> {
+    let mut script = String::new();
+    match shell {
+        "bash" => {
+            let words = flag_words()
+                .chain(SUBCOMMANDS.iter().map(|(name, _)| name.to_string()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(script, "complete -o default -W \"{words}\" {bin_name}");
+        }
+        "zsh" => {
+            let _ = writeln!(script, "#compdef {bin_name}");
+            let _ = write!(script, "_arguments");
+            FLAGS
+                .iter()
+                .for_each(|(short, long, value_name, description)| {
+                    let value = match value_name {
+                        Some("path") => ":path:_files".to_string(),
+                        Some(value_name) => format!(":{value_name}:"),
+                        None => String::new(),
+                    };
+                    let _ = match short {
+                        Some(short) => write!(
+                            script,
+                            " \\\n  '(-{short} --{long})'{{-{short},--{long}}}'[{description}]{value}'"
+                        ),
+                        // repeatable flags with values
+                        None => write!(script, " \\\n  '*--{long}[{description}]{value}'"),
+                    };
+                });
+            let _ = writeln!(script, " \\\n  '*:file:_files'");
+        }
+        "fish" => {
+            FLAGS
+                .iter()
+                .for_each(|(short, long, value_name, description)| {
+                    let short = short
+                        .map(|short| format!(" -s {short}"))
+                        .unwrap_or_default();
+                    let value = match value_name {
+                        Some("path") => " -r -F",
+                        Some(_) => " -r",
+                        None => "",
+                    };
+                    let _ = writeln!(
+                        script,
+                        "complete -c {bin_name}{short} -l {long}{value} -d '{description}'"
+                    );
+                });
+            SUBCOMMANDS.iter().for_each(|(name, description)| {
+                let _ = writeln!(
+                    script,
+                    "complete -c {bin_name} -n __fish_use_subcommand -a {name} -d '{description}'"
+                );
+            });
+        }
+        "powershell" => {
+            let words = flag_words()
+                .chain(SUBCOMMANDS.iter().map(|(name, _)| name.to_string()))
+                .map(|word| format!("'{word}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(
+                script,
+                "Register-ArgumentCompleter -Native -CommandName {bin_name} -ScriptBlock {{"
+            );
+            let _ = writeln!(script, "    param($wordToComplete)");
+            let _ = writeln!(
+                script,
+                "    @({words}) | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{"
+            );
+            let _ = writeln!(
+                script,
+                "        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterName', $_)"
+            );
+            let _ = writeln!(script, "    }}");
+            let _ = writeln!(script, "}}");
+        }
+        _ => return None,
+    }
+    Some(script)
+}
+
+fn flag_words() -> impl Iterator<Item = String> {
+    FLAGS.iter().flat_map(|(short, long, ..)| {
+        short
+            .map(|short| format!("-{short}"))
+            .into_iter()
+            .chain([format!("--{long}")])
+    })
+}
@@ -0,0 +1,75 @@
+use pretty_yaml::config::GlobPattern;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Patterns of files to be formatted when walking directories if no `--include` is given.
+const DEFAULT_INCLUDES: &[&str] = &["*.yaml", "*.yml"];
+
+/// Directories that are never walked into.
+const IGNORED_DIRS: &[&str] = &[".git", ".hg", ".svn", "node_modules", "target"];
+
+/// Collect files to be formatted from the given paths.
+///
+/// Directories are walked recursively in sorted order.
+/// Files given explicitly are always included unless they match excludes.
+pub fn collect_files(
+    paths: &[PathBuf],
+    includes: &[GlobPattern],
+    excludes: &[GlobPattern],
+) -> io::Result<Vec<PathBuf>> {
+    let default_includes;
+    let includes = if includes.is_empty() {
+        default_includes = DEFAULT_INCLUDES
+            .iter()
+            .map(|pattern| GlobPattern::new(*pattern))
+            .collect::<Vec<_>>();
+        &default_includes
+    } else {
+        includes
+    };
+    let mut files = vec![];
+    for path in paths {
+        if is_excluded(path, excludes) {
+            continue;
+        }
+        if fs::metadata(path)?.is_dir() {
+            walk(path, includes, excludes, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn walk(
+    dir: &Path,
+    includes: &[GlobPattern],
+    excludes: &[GlobPattern],
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if is_excluded(&path, excludes) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !IGNORED_DIRS.iter().any(|name| entry.file_name() == *name) {
+                walk(&path, includes, excludes, files)?;
+            }
+        } else if file_type.is_file() && includes.iter().any(|pattern| pattern.matches(&path)) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_excluded(path: &Path, excludes: &[GlobPattern]) -> bool {
+    // strip `./` so patterns like `vendor/**` can match from the start
+    let path = path.strip_prefix(".").unwrap_or(path);
+    excludes.iter().any(|pattern| pattern.matches(path))
+}
@@ -0,0 +1,83 @@
+use crate::CONFIG_FILE_NAMES;
+use pretty_yaml::config::FormatOptions;
+use std::{error::Error, fmt::Write, fs, path::Path, process::ExitCode};
+
+/// Write config file with default options and their descriptions.
+pub fn init(dprint: bool) -> Result<ExitCode, Box<dyn Error>> {
+    let config_path = Path::new(CONFIG_FILE_NAMES[0]);
+    if config_path.exists() {
+        eprintln!("`{}` already exists", config_path.display());
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let schema: serde_json::Value =
+        serde_json::from_str(include_str!("../../dprint_plugin/deployment/schema.json"))?;
+    let properties = &schema["properties"];
+    let defaults = toml::Table::try_from(FormatOptions::default())?;
+
+    let mut config = String::new();
+    let (tables, values): (Vec<_>, Vec<_>) =
+        defaults.iter().partition(|(_, value)| value.is_table());
+    values.iter().for_each(|(key, value)| {
+        write_option(&mut config, properties, &camel_case(key), key, value);
+    });
+    tables.iter().for_each(|(key, value)| {
+        let _ = writeln!(config, "[{key}]");
+        value
+            .as_table()
+            .into_iter()
+            .flatten()
+            .for_each(|(sub_key, value)| {
+                let name = format!("{}.{}", camel_case(key), camel_case(sub_key));
+                // the parent option in dprint config controls the first field
+                let name = if properties.get(&name).is_some() {
+                    name
+                } else {
+                    camel_case(key)
+                };
+                write_option(&mut config, properties, &name, sub_key, value);
+            });
+    });
+    fs::write(config_path, config.trim_end().to_string() + "\n")?;
+
+    if dprint {
+        let snippet = properties
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, property)| Some((name.clone(), property.get("default")?.clone())))
+            .collect::<serde_json::Map<_, _>>();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({ "yaml": snippet }))?
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write_option(
+    config: &mut String,
+    properties: &serde_json::Value,
+    name: &str,
+    key: &str,
+    value: &toml::Value,
+) {
+    if let Some(description) = properties[name]["description"].as_str() {
+        let _ = writeln!(config, "# {description}");
+    }
+    let _ = writeln!(config, "{key} = {value}\n");
+}
+
+pub fn camel_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .enumerate()
+        .map(|(i, word)| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) if i > 0 => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                _ => word.to_string(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,349 @@
+use crate::{
+    args::{Args, Mode},
+    files::collect_files,
+};
+use pretty_yaml::{
+    config::FormatOptions,
+    diff::{TextDiff, DEFAULT_CONTEXT},
+    editorconfig::EditorConfig,
+    format_text_with_warnings, FormatWarning,
+};
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+use yaml_parser::SyntaxError;
+
+mod args;
+mod completions;
+mod files;
+mod init;
+
+/// Names of config files that are discovered from current directory and its ancestors.
+const CONFIG_FILE_NAMES: &[&str] = &["pretty_yaml.toml", ".pretty_yaml.toml"];
+
+/// Exit code when some files aren't formatted in check mode.
+const EXIT_UNFORMATTED: u8 = 1;
+/// Exit code when some files can't be read, parsed or written.
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    let mut args = env::args();
+    let bin_name = args
+        .next()
+        .as_deref()
+        .and_then(|arg| Path::new(arg).file_stem())
+        .and_then(|name| name.to_str())
+        .unwrap_or("pretty-yaml")
+        .to_string();
+    let args = match Args::parse(args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}\nRun `{bin_name} --help` for usage.");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    match run(args, &bin_name) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn run(args: Args, bin_name: &str) -> Result<ExitCode, Box<dyn Error>> {
+    match args.mode {
+        Mode::Help => {
+            print!("{}", args::help(bin_name));
+            return Ok(ExitCode::SUCCESS);
+        }
+        Mode::Completions(shell) => {
+            return if let Some(script) = completions::generate(&shell, bin_name) {
+                print!("{script}");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                eprintln!("unsupported shell `{shell}`, expected bash, zsh, fish or powershell");
+                Ok(ExitCode::from(EXIT_ERROR))
+            };
+        }
+        Mode::Init => return init::init(args.dprint),
+        Mode::Format => {}
+    }
+
+    let config_file = match &args.config {
+        Some(path) => Some(fs::read_to_string(path)?),
+        None => discover_config()?,
+    };
+    let options: FormatOptions = match &config_file {
+        Some(s) => toml::from_str(s)?,
+        None => FormatOptions::default(),
+    };
+    if let Err(errors) = options.validate() {
+        errors.iter().for_each(|error| eprintln!("{error}"));
+        return Ok(ExitCode::from(EXIT_ERROR));
+    }
+    // options in config file take precedence over `.editorconfig`
+    let explicit_layout = match &config_file {
+        Some(s) => toml::from_str::<toml::Table>(s)?
+            .keys()
+            .map(|key| init::camel_case(key))
+            .collect(),
+        None => vec![],
+    };
+    let formatter = Formatter {
+        options,
+        explicit_layout,
+    };
+
+    if args.paths.iter().any(|path| path == "-") {
+        if args.paths.len() > 1 {
+            eprintln!("`-` for reading from stdin can't be used with other paths");
+            return Ok(ExitCode::from(EXIT_ERROR));
+        }
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let path = args
+            .stdin_filepath
+            .as_deref()
+            .unwrap_or(Path::new("stdin.yaml"));
+        let result = formatter.format(path, input);
+        return Ok(report(&args, vec![result]));
+    }
+
+    let paths = if args.paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        args.paths.iter().map(PathBuf::from).collect()
+    };
+    let files = collect_files(&paths, &args.includes, &args.excludes)?;
+    let results = process_parallel(&files, args.jobs, |path| {
+        let result = match fs::read_to_string(path) {
+            Ok(input) => formatter.format(path, input),
+            Err(error) => FileResult {
+                path: path.to_path_buf(),
+                input: String::new(),
+                output: Err(FileError::Io(error)),
+            },
+        };
+        if args.write {
+            if let Ok((output, _)) = &result.output {
+                if *output != result.input {
+                    if let Err(error) = fs::write(path, output) {
+                        return FileResult {
+                            output: Err(FileError::Io(error)),
+                            ..result
+                        };
+                    }
+                }
+            }
+        }
+        result
+    });
+    Ok(report(&args, results))
+}
+
+/// Find config file in current directory or its ancestors.
+fn discover_config() -> io::Result<Option<String>> {
+    let current_dir = env::current_dir()?;
+    for dir in current_dir.ancestors() {
+        for name in CONFIG_FILE_NAMES {
+            match fs::read_to_string(dir.join(name)) {
+                Ok(s) => return Ok(Some(s)),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+    Ok(None)
+}
+
+struct Formatter {
+    options: FormatOptions,
+    explicit_layout: Vec<String>,
+}
+
+impl Formatter {
+    fn format(&self, path: &Path, input: String) -> FileResult {
+        let mut options = self.options.for_path(path);
+        let explicit_layout = self
+            .explicit_layout
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        match EditorConfig::for_file(path) {
+            Ok(editorconfig) => editorconfig.apply(&mut options.to_mut().layout, &explicit_layout),
+            Err(error) => {
+                return FileResult {
+                    path: path.to_path_buf(),
+                    input,
+                    output: Err(FileError::Io(error)),
+                }
+            }
+        }
+        let output = format_text_with_warnings(&input, &options).map_err(FileError::Syntax);
+        FileResult {
+            path: path.to_path_buf(),
+            input,
+            output,
+        }
+    }
+}
+
+struct FileResult {
+    path: PathBuf,
+    input: String,
+    output: Result<(String, Vec<FormatWarning>), FileError>,
+}
+
+enum FileError {
+    Io(io::Error),
+    Syntax(SyntaxError),
+}
+
+/// Run the task for each file with multiple threads, and keep results in order of files.
+fn process_parallel<F>(files: &[PathBuf], jobs: Option<usize>, task: F) -> Vec<FileResult>
+where
+    F: Fn(&Path) -> FileResult + Sync,
+{
+    let jobs = jobs
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, files.len().max(1));
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(index) else {
+                            break;
+                        };
+                        results.push((index, task(path)));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Print results according to mode, and decide exit code.
+fn report(args: &Args, results: Vec<FileResult>) -> ExitCode {
+    let mut has_error = false;
+    let mut has_different = false;
+    for FileResult {
+        path,
+        input,
+        output,
+    } in results
+    {
+        let file_path = path.to_string_lossy();
+        let (output, warnings) = match output {
+            Ok(output) => output,
+            Err(FileError::Syntax(error)) if args.github_annotations => {
+                annotate("error", &file_path, &input, error.offset(), error.message());
+                has_error = true;
+                continue;
+            }
+            Err(FileError::Syntax(error)) => {
+                eprintln!("{file_path}: {error}");
+                has_error = true;
+                continue;
+            }
+            Err(FileError::Io(error)) => {
+                eprintln!("{file_path}: {error}");
+                has_error = true;
+                continue;
+            }
+        };
+        let is_different = output != input;
+        if args.github_annotations {
+            warnings.iter().for_each(|warning| {
+                annotate(
+                    "warning",
+                    &file_path,
+                    &input,
+                    warning.range().start,
+                    warning.message(),
+                );
+            });
+            if is_different {
+                let offset = input
+                    .split_inclusive('\n')
+                    .zip(output.split_inclusive('\n'))
+                    .take_while(|(input, output)| input == output)
+                    .map(|(line, _)| line.len())
+                    .sum();
+                annotate(
+                    "error",
+                    &file_path,
+                    &input,
+                    offset,
+                    "File is not formatted.",
+                );
+                has_different = true;
+            }
+        } else if args.list_different {
+            // only print paths of files that will be changed, like Prettier
+            if is_different {
+                println!("{file_path}");
+                has_different = true;
+            }
+        } else if args.check {
+            if is_different {
+                if args.diff {
+                    let diff = TextDiff::new(&input, &output, DEFAULT_CONTEXT);
+                    print!("--- {file_path}\n+++ {file_path}\n{diff}");
+                } else {
+                    eprintln!("{file_path}: not formatted");
+                }
+                has_different = true;
+            }
+        } else if !args.write || args.paths.iter().any(|path| path == "-") {
+            print!("{output}");
+        }
+    }
+
+    if has_error {
+        ExitCode::from(EXIT_ERROR)
+    } else if has_different {
+        ExitCode::from(EXIT_UNFORMATTED)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Print workflow command of GitHub Actions, which will be shown as annotation.
+fn annotate(level: &str, file_path: &str, input: &str, offset: usize, message: &str) {
+    let before = &input[..offset.min(input.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+    let file = escape_annotation(file_path)
+        .replace(':', "%3A")
+        .replace(',', "%2C");
+    println!(
+        "::{level} file={file},line={line},col={col}::{}",
+        escape_annotation(message)
+    );
+}
+
+fn escape_annotation(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
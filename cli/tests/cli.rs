@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+const UNFORMATTED: &str = "a:   1\nb:\n    - c\n";
+const FORMATTED: &str = "a: 1\nb:\n  - c\n";
+
+/// Create a directory with an empty config file,
+/// so config files outside won't be discovered.
+fn setup(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pretty_yaml_cli_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested/vendor")).unwrap();
+    fs::write(dir.join("pretty_yaml.toml"), "").unwrap();
+    fs::write(dir.join("a.yaml"), UNFORMATTED).unwrap();
+    fs::write(dir.join("b.yml"), FORMATTED).unwrap();
+    fs::write(dir.join("nested/c.yaml"), UNFORMATTED).unwrap();
+    fs::write(dir.join("nested/vendor/d.yaml"), UNFORMATTED).unwrap();
+    fs::write(dir.join("nested/e.txt"), UNFORMATTED).unwrap();
+    dir
+}
+
+fn run(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pretty-yaml"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn check() {
+    let dir = setup("check");
+    let output = run(&dir, &["--check", "--jobs", "2"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines = stderr.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "./a.yaml: not formatted",
+            "./nested/c.yaml: not formatted",
+            "./nested/vendor/d.yaml: not formatted",
+        ]
+    );
+
+    let output = run(&dir, &["--check", "b.yml"]);
+    assert_eq!(output.status.code(), Some(0));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn write() {
+    let dir = setup("write");
+    let output = run(&dir, &["--write", "--exclude=vendor", "."]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+    assert_eq!(fs::read_to_string(dir.join("a.yaml")).unwrap(), FORMATTED);
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/c.yaml")).unwrap(),
+        FORMATTED
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/vendor/d.yaml")).unwrap(),
+        UNFORMATTED
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/e.txt")).unwrap(),
+        UNFORMATTED
+    );
+
+    let output = run(&dir, &["--check", "--exclude", "vendor"]);
+    assert_eq!(output.status.code(), Some(0));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn include() {
+    let dir = setup("include");
+    let output = run(&dir, &["-l", "--include", "*.txt", "nested"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "nested/e.txt\n");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stdin() {
+    let dir = setup("stdin");
+    fs::write(dir.join("pretty_yaml.toml"), "indentWidth = 4\n").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pretty-yaml"))
+        .arg("-")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(UNFORMATTED.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "a: 1\nb:\n    - c\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn config() {
+    let dir = setup("config");
+    fs::write(dir.join("custom.toml"), "indentWidth = 4\n").unwrap();
+    let output = run(&dir, &["--config", "custom.toml", "b.yml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "a: 1\nb:\n    - c\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid_config() {
+    let dir = setup("invalid_config");
+    fs::write(dir.join("custom.toml"), "printWidth = 0\n").unwrap();
+    let output = run(&dir, &["--config", "custom.toml", "b.yml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "config `printWidth` must be at least 1, but got 0\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn syntax_error() {
+    let dir = setup("syntax_error");
+    fs::write(dir.join("a.yaml"), "a: [").unwrap();
+    let output = run(&dir, &["--check", "a.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid_args() {
+    let dir = setup("invalid_args");
+    assert_eq!(run(&dir, &["--unknown"]).status.code(), Some(2));
+    assert_eq!(run(&dir, &["--check", "--write"]).status.code(), Some(2));
+    assert_eq!(run(&dir, &["--jobs", "0"]).status.code(), Some(2));
+    assert_eq!(run(&dir, &["--help"]).status.code(), Some(0));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn diff() {
+    let dir = setup("diff");
+    let output = run(&dir, &["--diff", "a.yaml", "b.yml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "--- a.yaml\n+++ a.yaml\n@@ -1,3 +1,3 @@\n-a:   1\n+a: 1\n b:\n-    - c\n+  - c\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
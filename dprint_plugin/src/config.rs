@@ -1,26 +1,78 @@
 use dprint_core::configuration::{
-    get_nullable_value, get_unknown_property_diagnostics, get_value, ConfigKeyMap,
+    get_nullable_value, get_unknown_property_diagnostics, get_value, ConfigKeyMap, ConfigKeyValue,
     ConfigurationDiagnostic, GlobalConfiguration, NewLineKind, ResolveConfigurationResult,
 };
 use pretty_yaml::config::*;
+use serde::Serialize;
+
+/// Resolved configuration of the plugin.
+#[derive(Clone, Debug, Serialize)]
+pub struct Configuration {
+    #[serde(flatten)]
+    pub options: FormatOptions,
+    /// Names of layout options that are specified in plugin or global configuration,
+    /// which take precedence over `.editorconfig`.
+    #[cfg(feature = "editorconfig")]
+    #[serde(skip)]
+    pub explicit_layout: Vec<&'static str>,
+}
 
 pub(crate) fn resolve_config(
     mut config: ConfigKeyMap,
     global_config: &GlobalConfiguration,
-) -> ResolveConfigurationResult<FormatOptions> {
+) -> ResolveConfigurationResult<Configuration> {
     let mut diagnostics = Vec::new();
+    // options of preset are used as default values, so they can be overridden
+    let base =
+        match get_nullable_value::<String>(&mut config, "preset", &mut diagnostics).as_deref() {
+            None => FormatOptions::default(),
+            Some("kubernetes") => FormatOptions::preset(Preset::Kubernetes),
+            Some("githubActions") => FormatOptions::preset(Preset::GithubActions),
+            Some("dockerCompose") => FormatOptions::preset(Preset::DockerCompose),
+            Some("prettier") => FormatOptions::preset(Preset::Prettier),
+            Some("ruamel") => FormatOptions::preset(Preset::Ruamel),
+            Some("yamlfmt") => FormatOptions::preset(Preset::Yamlfmt),
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "preset".into(),
+                    message: "invalid value for config `preset`".into(),
+                });
+                FormatOptions::default()
+            }
+        };
+    #[cfg(feature = "editorconfig")]
+    let explicit_layout = [
+        ("printWidth", global_config.line_width.is_some()),
+        ("indentWidth", global_config.indent_width.is_some()),
+        ("lineBreak", global_config.new_line_kind.is_some()),
+        ("indentKind", global_config.use_tabs.is_some()),
+        ("finalNewline", false),
+        ("tabWidth", false),
+    ]
+    .into_iter()
+    .filter(|(name, global)| *global || config.contains_key(*name))
+    .map(|(name, _)| name)
+    .collect();
+    let language = resolve_language_options(&mut config, &base.language, &mut diagnostics);
+    // overrides inherit options from the main config instead of defaults
+    let overrides = resolve_overrides(&mut config, &language, &mut diagnostics);
     let pretty_yaml_config = FormatOptions {
         layout: LayoutOptions {
             print_width: get_value(
                 &mut config,
                 "printWidth",
-                global_config.line_width.unwrap_or(80),
+                global_config
+                    .line_width
+                    .unwrap_or(base.layout.print_width as u32),
                 &mut diagnostics,
             ) as usize,
             indent_width: get_value(
                 &mut config,
                 "indentWidth",
-                global_config.indent_width.unwrap_or(2),
+                global_config
+                    .indent_width
+                    .map(u32::from)
+                    .unwrap_or(base.layout.indent_width as u32),
                 &mut diagnostics,
             ) as usize,
             line_break: match &*get_value(
@@ -29,6 +81,7 @@ pub(crate) fn resolve_config(
                 match global_config.new_line_kind {
                     Some(NewLineKind::LineFeed) => "lf",
                     Some(NewLineKind::CarriageReturnLineFeed) => "crlf",
+                    Some(NewLineKind::Auto) => "auto",
                     _ => "lf",
                 }
                 .to_string(),
@@ -36,6 +89,7 @@ pub(crate) fn resolve_config(
             ) {
                 "lf" => LineBreak::Lf,
                 "crlf" => LineBreak::Crlf,
+                "auto" => LineBreak::Auto,
                 _ => {
                     diagnostics.push(ConfigurationDiagnostic {
                         property_name: "lineBreak".into(),
@@ -44,83 +98,653 @@ pub(crate) fn resolve_config(
                     LineBreak::Lf
                 }
             },
-        },
-        language: LanguageOptions {
-            quotes: match &*get_value(
+            indent_kind: match &*get_value(
                 &mut config,
-                "quotes",
-                "preferDouble".to_string(),
-                &mut diagnostics,
-            ) {
-                "preferDouble" => Quotes::PreferDouble,
-                "preferSingle" => Quotes::PreferSingle,
-                "forceDouble" => Quotes::ForceDouble,
-                "forceSingle" => Quotes::ForceSingle,
-                _ => {
-                    diagnostics.push(ConfigurationDiagnostic {
-                        property_name: "quotes".into(),
-                        message: "invalid value for config `quotes`".into(),
-                    });
-                    Default::default()
+                "indentKind",
+                if global_config.use_tabs.unwrap_or_default() {
+                    "tab"
+                } else {
+                    "space"
                 }
-            },
-            trailing_comma: get_value(&mut config, "trailingComma", true, &mut diagnostics),
-            format_comments: get_value(&mut config, "formatComments", false, &mut diagnostics),
-            indent_block_sequence_in_map: get_value(
-                &mut config,
-                "indentBlockSequenceInMap",
-                true,
-                &mut diagnostics,
-            ),
-            brace_spacing: get_value(&mut config, "braceSpacing", true, &mut diagnostics),
-            bracket_spacing: get_value(&mut config, "bracketSpacing", false, &mut diagnostics),
-            dash_spacing: match &*get_value(
-                &mut config,
-                "dashSpacing",
-                "oneSpace".to_string(),
+                .to_string(),
                 &mut diagnostics,
             ) {
-                "oneSpace" => DashSpacing::OneSpace,
-                "indent" => DashSpacing::Indent,
+                "space" => IndentKind::Space,
+                "tab" => IndentKind::Tab,
                 _ => {
                     diagnostics.push(ConfigurationDiagnostic {
-                        property_name: "dashSpacing".into(),
-                        message: "invalid value for config `dashSpacing`".into(),
+                        property_name: "indentKind".into(),
+                        message: "invalid value for config `indentKind`".into(),
                     });
-                    Default::default()
+                    IndentKind::Space
                 }
             },
-            prefer_single_line: get_value(&mut config, "preferSingleLine", false, &mut diagnostics),
-            flow_sequence_prefer_single_line: get_nullable_value(
+            final_newline: get_value(
                 &mut config,
-                "flowSequence.preferSingleLine",
+                "finalNewline",
+                base.layout.final_newline,
                 &mut diagnostics,
             ),
-            flow_map_prefer_single_line: get_nullable_value(
+            keep_bom: get_value(
                 &mut config,
-                "flowMap.preferSingleLine",
-                &mut diagnostics,
-            ),
-            trim_trailing_whitespaces: get_value(
-                &mut config,
-                "trimTrailingWhitespaces",
-                true,
-                &mut diagnostics,
-            ),
-            trim_trailing_zero: get_value(&mut config, "trimTrailingZero", false, &mut diagnostics),
-            ignore_comment_directive: get_value(
-                &mut config,
-                "ignoreCommentDirective",
-                "pretty-yaml-ignore".into(),
+                "keepBom",
+                base.layout.keep_bom,
                 &mut diagnostics,
             ),
+            tab_width: get_nullable_value::<u32>(&mut config, "tabWidth", &mut diagnostics)
+                .map(|value| value as usize)
+                .or(base.layout.tab_width),
         },
+        language,
+        overrides,
     };
 
     diagnostics.extend(get_unknown_property_diagnostics(config));
+    if let Err(errors) = pretty_yaml_config.validate() {
+        diagnostics.extend(errors.iter().map(|error| ConfigurationDiagnostic {
+            property_name: error.option(),
+            message: error.to_string(),
+        }));
+    }
 
     ResolveConfigurationResult {
-        config: pretty_yaml_config,
+        config: Configuration {
+            options: pretty_yaml_config,
+            #[cfg(feature = "editorconfig")]
+            explicit_layout,
+        },
         diagnostics,
     }
 }
+
+fn resolve_overrides(
+    config: &mut ConfigKeyMap,
+    base: &LanguageOptions,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Vec<(GlobPattern, LanguageOptions)> {
+    let items = match config.shift_remove("overrides") {
+        None => return vec![],
+        Some(ConfigKeyValue::Array(items)) => items,
+        Some(_) => {
+            diagnostics.push(ConfigurationDiagnostic {
+                property_name: "overrides".into(),
+                message: "config `overrides` must be an array of objects".into(),
+            });
+            return vec![];
+        }
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let property_name = format!("overrides[{index}]");
+            let ConfigKeyValue::Object(mut item) = item else {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name,
+                    message: "override must be an object".into(),
+                });
+                return None;
+            };
+            let files = match item.shift_remove("files") {
+                Some(ConfigKeyValue::String(files)) => Some(files),
+                _ => {
+                    diagnostics.push(ConfigurationDiagnostic {
+                        property_name: format!("{property_name}.files"),
+                        message: "override must have a glob pattern string in `files`".into(),
+                    });
+                    None
+                }
+            };
+            let mut item_diagnostics = Vec::new();
+            let language = resolve_language_options(&mut item, base, &mut item_diagnostics);
+            item_diagnostics.extend(get_unknown_property_diagnostics(item));
+            diagnostics.extend(item_diagnostics.into_iter().map(|diagnostic| {
+                ConfigurationDiagnostic {
+                    property_name: format!("{property_name}.{}", diagnostic.property_name),
+                    message: diagnostic.message,
+                }
+            }));
+            files.map(|files| (GlobPattern::new(files), language))
+        })
+        .collect()
+}
+
+fn resolve_language_options(
+    config: &mut ConfigKeyMap,
+    base: &LanguageOptions,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> LanguageOptions {
+    LanguageOptions {
+        quotes: match get_nullable_value::<String>(config, "quotes", diagnostics).as_deref() {
+            None => base.quotes.clone(),
+            Some("preferDouble") => Quotes::PreferDouble,
+            Some("preferSingle") => Quotes::PreferSingle,
+            Some("forceDouble") => Quotes::ForceDouble,
+            Some("forceSingle") => Quotes::ForceSingle,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "quotes".into(),
+                    message: "invalid value for config `quotes`".into(),
+                });
+                Default::default()
+            }
+        },
+        trailing_comma: get_value(config, "trailingComma", base.trailing_comma, diagnostics),
+        format_comments: FormatComments {
+            space_after_hash: get_value(
+                config,
+                "formatComments",
+                base.format_comments.space_after_hash,
+                diagnostics,
+            ),
+            spaces: get_value(
+                config,
+                "formatComments.spaces",
+                base.format_comments.spaces as u32,
+                diagnostics,
+            ) as usize,
+            banner: get_value(
+                config,
+                "formatComments.banner",
+                base.format_comments.banner,
+                diagnostics,
+            ),
+            capitalize: match get_nullable_value::<String>(
+                config,
+                "formatComments.capitalize",
+                diagnostics,
+            )
+            .as_deref()
+            {
+                None => base.format_comments.capitalize.clone(),
+                Some("never") => CapitalizeComments::Never,
+                Some("ownLine") => CapitalizeComments::OwnLine,
+                Some("always") => CapitalizeComments::Always,
+                _ => {
+                    diagnostics.push(ConfigurationDiagnostic {
+                        property_name: "formatComments.capitalize".into(),
+                        message: "invalid value for config `formatComments.capitalize`".into(),
+                    });
+                    Default::default()
+                }
+            },
+        },
+        indent_block_sequence_in_map: get_value(
+            config,
+            "indentBlockSequenceInMap",
+            base.indent_block_sequence_in_map,
+            diagnostics,
+        ),
+        brace_spacing: get_value(config, "braceSpacing", base.brace_spacing, diagnostics),
+        bracket_spacing: get_value(config, "bracketSpacing", base.bracket_spacing, diagnostics),
+        single_entry_brace_spacing: get_nullable_value(
+            config,
+            "singleEntryBraceSpacing",
+            diagnostics,
+        )
+        .or(base.single_entry_brace_spacing),
+        single_entry_bracket_spacing: get_nullable_value(
+            config,
+            "singleEntryBracketSpacing",
+            diagnostics,
+        )
+        .or(base.single_entry_bracket_spacing),
+        dash_spacing: match get_nullable_value::<String>(config, "dashSpacing", diagnostics)
+            .as_deref()
+        {
+            None => base.dash_spacing.clone(),
+            Some("oneSpace") => DashSpacing::OneSpace,
+            Some("indent") => DashSpacing::Indent,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "dashSpacing".into(),
+                    message: "invalid value for config `dashSpacing`".into(),
+                });
+                Default::default()
+            }
+        },
+        prefer_single_line: get_value(
+            config,
+            "preferSingleLine",
+            base.prefer_single_line,
+            diagnostics,
+        ),
+        flow_sequence_prefer_single_line: get_nullable_value(
+            config,
+            "flowSequence.preferSingleLine",
+            diagnostics,
+        )
+        .or(base.flow_sequence_prefer_single_line),
+        flow_map_prefer_single_line: get_nullable_value(
+            config,
+            "flowMap.preferSingleLine",
+            diagnostics,
+        )
+        .or(base.flow_map_prefer_single_line),
+        trim_trailing_whitespaces: get_value(
+            config,
+            "trimTrailingWhitespaces",
+            base.trim_trailing_whitespaces,
+            diagnostics,
+        ),
+        trim_trailing_zero: get_value(
+            config,
+            "trimTrailingZero",
+            base.trim_trailing_zero,
+            diagnostics,
+        ),
+        ignore_comment_directive: get_value(
+            config,
+            "ignoreCommentDirective",
+            base.ignore_comment_directive.clone(),
+            diagnostics,
+        ),
+        dedupe_keys: match get_nullable_value::<String>(config, "dedupeKeys", diagnostics)
+            .as_deref()
+        {
+            None => base.dedupe_keys.clone(),
+            Some("keepFirst") => Some(DedupeKeys::KeepFirst),
+            Some("keepLast") => Some(DedupeKeys::KeepLast),
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "dedupeKeys".into(),
+                    message: "invalid value for config `dedupeKeys`".into(),
+                });
+                None
+            }
+        },
+        rename_anchors: get_value(config, "renameAnchors", base.rename_anchors, diagnostics),
+        inline_aliases: get_value(config, "inlineAliases", base.inline_aliases, diagnostics),
+        extract_anchors: get_nullable_value::<u32>(config, "extractAnchors", diagnostics)
+            .map(|threshold| threshold as usize)
+            .or(base.extract_anchors),
+        sort_documents: get_value(
+            config,
+            "sortDocuments",
+            base.sort_documents.join(","),
+            diagnostics,
+        )
+        .split(',')
+        .map(str::trim)
+        .filter(|key_path| !key_path.is_empty())
+        .map(String::from)
+        .collect(),
+        directives: match get_nullable_value::<String>(config, "directives", diagnostics).as_deref()
+        {
+            None => base.directives.clone(),
+            Some("preserve") => Directives::Preserve,
+            Some("explicit") => Directives::Explicit,
+            Some("stripRedundant") => Directives::StripRedundant,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "directives".into(),
+                    message: "invalid value for config `directives`".into(),
+                });
+                Default::default()
+            }
+        },
+        key_ordering: match get_nullable_value::<String>(config, "keyOrdering", diagnostics)
+            .as_deref()
+        {
+            None => base.key_ordering.clone(),
+            Some("ignore") => KeyOrdering::Ignore,
+            Some("asc") => KeyOrdering::Asc,
+            Some("desc") => KeyOrdering::Desc,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "keyOrdering".into(),
+                    message: "invalid value for config `keyOrdering`".into(),
+                });
+                Default::default()
+            }
+        },
+        normalize_block_scalar_indent: get_value(
+            config,
+            "normalizeBlockScalarIndent",
+            base.normalize_block_scalar_indent,
+            diagnostics,
+        ),
+        empty_documents: match get_nullable_value::<String>(config, "emptyDocuments", diagnostics)
+            .as_deref()
+        {
+            None => base.empty_documents.clone(),
+            Some("preserve") => EmptyDocuments::Preserve,
+            Some("bare") => EmptyDocuments::Bare,
+            Some("null") => EmptyDocuments::Null,
+            Some("drop") => EmptyDocuments::Drop,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "emptyDocuments".into(),
+                    message: "invalid value for config `emptyDocuments`".into(),
+                });
+                Default::default()
+            }
+        },
+        explicit_key_threshold: get_nullable_value::<u32>(
+            config,
+            "explicitKeyThreshold",
+            diagnostics,
+        )
+        .map(|threshold| threshold as usize)
+        .or(base.explicit_key_threshold),
+        indent_width_flow: get_nullable_value::<u32>(config, "indentWidthFlow", diagnostics)
+            .map(|width| width as usize)
+            .or(base.indent_width_flow),
+        block_scalar_style: match get_nullable_value::<String>(
+            config,
+            "blockScalarStyle",
+            diagnostics,
+        )
+        .as_deref()
+        {
+            None => base.block_scalar_style.clone(),
+            Some("keep") => BlockScalarStyle::Keep,
+            Some("literal") => BlockScalarStyle::Literal,
+            Some("folded") => BlockScalarStyle::Folded,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "blockScalarStyle".into(),
+                    message: "invalid value for config `blockScalarStyle`".into(),
+                });
+                Default::default()
+            }
+        },
+        reflow_folded_scalars: get_value(
+            config,
+            "reflowFoldedScalars",
+            base.reflow_folded_scalars,
+            diagnostics,
+        ),
+        prefer_flow_for_short_sequences: get_value(
+            config,
+            "preferFlowForShortSequences",
+            base.prefer_flow_for_short_sequences,
+            diagnostics,
+        ),
+        wrap_plain_scalars: get_value(
+            config,
+            "wrapPlainScalars",
+            base.wrap_plain_scalars,
+            diagnostics,
+        ),
+        scalar_quoting: match get_nullable_value::<String>(config, "scalarQuoting", diagnostics)
+            .as_deref()
+        {
+            None => base.scalar_quoting.clone(),
+            Some("keep") => ScalarQuoting::Keep,
+            Some("quoteAll") => ScalarQuoting::QuoteAll,
+            Some("unquoteWhenSafe") => ScalarQuoting::UnquoteWhenSafe,
+            Some("quoteAmbiguous") => ScalarQuoting::QuoteAmbiguous,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "scalarQuoting".into(),
+                    message: "invalid value for config `scalarQuoting`".into(),
+                });
+                Default::default()
+            }
+        },
+        comment_indent: match get_nullable_value::<String>(config, "commentIndent", diagnostics)
+            .as_deref()
+        {
+            None => base.comment_indent.clone(),
+            Some("followPrev") => CommentIndent::FollowPrev,
+            Some("followNext") => CommentIndent::FollowNext,
+            Some("dedent") => CommentIndent::Dedent,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "commentIndent".into(),
+                    message: "invalid value for config `commentIndent`".into(),
+                });
+                Default::default()
+            }
+        },
+        align_values: match get_nullable_value::<String>(config, "alignValues", diagnostics)
+            .as_deref()
+        {
+            None => base.align_values.clone(),
+            Some("none") => AlignValues::None,
+            Some("colon") => AlignValues::Colon,
+            Some("value") => AlignValues::Value,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "alignValues".into(),
+                    message: "invalid value for config `alignValues`".into(),
+                });
+                Default::default()
+            }
+        },
+        max_blank_lines: get_value(
+            config,
+            "maxBlankLines",
+            base.max_blank_lines as u32,
+            diagnostics,
+        ) as usize,
+        blank_lines_between_top_level_entries: get_value(
+            config,
+            "blankLinesBetweenTopLevelEntries",
+            base.blank_lines_between_top_level_entries as u32,
+            diagnostics,
+        ) as usize,
+        normalize_null: match get_nullable_value::<String>(config, "normalizeNull", diagnostics)
+            .as_deref()
+        {
+            None => base.normalize_null.clone(),
+            Some("keep") => NormalizeNull::Keep,
+            Some("tilde") => NormalizeNull::Tilde,
+            Some("null") => NormalizeNull::Null,
+            Some("empty") => NormalizeNull::Empty,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "normalizeNull".into(),
+                    message: "invalid value for config `normalizeNull`".into(),
+                });
+                Default::default()
+            }
+        },
+        normalize_boolean_casing: match get_nullable_value::<String>(
+            config,
+            "normalizeBooleanCasing",
+            diagnostics,
+        )
+        .as_deref()
+        {
+            None => base.normalize_boolean_casing.clone(),
+            Some("keep") => NormalizeBooleanCasing::Keep,
+            Some("lowercase") => NormalizeBooleanCasing::Lowercase,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "normalizeBooleanCasing".into(),
+                    message: "invalid value for config `normalizeBooleanCasing`".into(),
+                });
+                Default::default()
+            }
+        },
+        remove_plus_sign: get_value(config, "removePlusSign", base.remove_plus_sign, diagnostics),
+        exponent_case: match get_nullable_value::<String>(config, "exponentCase", diagnostics)
+            .as_deref()
+        {
+            None => base.exponent_case.clone(),
+            Some("keep") => ExponentCase::Keep,
+            Some("lowercase") => ExponentCase::Lowercase,
+            Some("uppercase") => ExponentCase::Uppercase,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "exponentCase".into(),
+                    message: "invalid value for config `exponentCase`".into(),
+                });
+                Default::default()
+            }
+        },
+        pad_leading_zero: get_value(config, "padLeadingZero", base.pad_leading_zero, diagnostics),
+        remove_digit_separators: get_value(
+            config,
+            "removeDigitSeparators",
+            base.remove_digit_separators,
+            diagnostics,
+        ),
+        break_long_values: get_value(
+            config,
+            "breakLongValues",
+            base.break_long_values,
+            diagnostics,
+        ),
+        dedupe_tag_directives: get_value(
+            config,
+            "dedupeTagDirectives",
+            base.dedupe_tag_directives,
+            diagnostics,
+        ),
+        insert_directives_end: get_value(
+            config,
+            "insertDirectivesEnd",
+            base.insert_directives_end,
+            diagnostics,
+        ),
+        explicit_document_markers: match get_nullable_value::<String>(
+            config,
+            "explicitDocumentMarkers",
+            diagnostics,
+        )
+        .as_deref()
+        {
+            None => base.explicit_document_markers.clone(),
+            Some("keep") => ExplicitDocumentMarkers::Keep,
+            Some("always") => ExplicitDocumentMarkers::Always,
+            Some("stripWhenSingle") => ExplicitDocumentMarkers::StripWhenSingle,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "explicitDocumentMarkers".into(),
+                    message: "invalid value for config `explicitDocumentMarkers`".into(),
+                });
+                Default::default()
+            }
+        },
+        template_syntax: match get_nullable_value::<String>(config, "templateSyntax", diagnostics)
+            .as_deref()
+        {
+            None => base.template_syntax.clone(),
+            Some("none") => TemplateSyntax::None,
+            Some("go") => TemplateSyntax::Go,
+            Some("jinja2") => TemplateSyntax::Jinja2,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "templateSyntax".into(),
+                    message: "invalid value for config `templateSyntax`".into(),
+                });
+                Default::default()
+            }
+        },
+        align_trailing_comments: get_value(
+            config,
+            "alignTrailingComments",
+            base.align_trailing_comments,
+            diagnostics,
+        ),
+        comment_space: get_value(
+            config,
+            "commentSpace",
+            base.comment_space as u32,
+            diagnostics,
+        ) as usize,
+        wrap_comments: get_value(config, "wrapComments", base.wrap_comments, diagnostics),
+        anchor_name_case: match get_nullable_value::<String>(config, "anchorNameCase", diagnostics)
+            .as_deref()
+        {
+            None => base.anchor_name_case.clone(),
+            Some("keep") => AnchorNameCase::Keep,
+            Some("camel") => AnchorNameCase::Camel,
+            Some("kebab") => AnchorNameCase::Kebab,
+            Some("snake") => AnchorNameCase::Snake,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "anchorNameCase".into(),
+                    message: "invalid value for config `anchorNameCase`".into(),
+                });
+                Default::default()
+            }
+        },
+        flatten_merge_keys: get_value(
+            config,
+            "flattenMergeKeys",
+            base.flatten_merge_keys,
+            diagnostics,
+        ),
+        sequence_indent_width: get_nullable_value::<u32>(
+            config,
+            "sequenceIndentWidth",
+            diagnostics,
+        )
+        .map(|width| width as usize)
+        .or(base.sequence_indent_width),
+        flow_collection_layout: match get_nullable_value::<String>(
+            config,
+            "flowCollectionLayout",
+            diagnostics,
+        )
+        .as_deref()
+        {
+            None => base.flow_collection_layout.clone(),
+            Some("auto") => FlowCollectionLayout::Auto,
+            Some("preserve") => FlowCollectionLayout::Preserve,
+            Some("singleLine") => FlowCollectionLayout::SingleLine,
+            Some("multiLine") => FlowCollectionLayout::MultiLine,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "flowCollectionLayout".into(),
+                    message: "invalid value for config `flowCollectionLayout`".into(),
+                });
+                Default::default()
+            }
+        },
+        empty_value_style: match get_nullable_value::<String>(
+            config,
+            "emptyValueStyle",
+            diagnostics,
+        )
+        .as_deref()
+        {
+            None => base.empty_value_style.clone(),
+            Some("keep") => EmptyValueStyle::Keep,
+            Some("null") => EmptyValueStyle::Null,
+            Some("tilde") => EmptyValueStyle::Tilde,
+            Some("empty") => EmptyValueStyle::Empty,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "emptyValueStyle".into(),
+                    message: "invalid value for config `emptyValueStyle`".into(),
+                });
+                Default::default()
+            }
+        },
+        yaml_version: match get_nullable_value::<String>(config, "yamlVersion", diagnostics)
+            .as_deref()
+        {
+            None => base.yaml_version.clone(),
+            Some("auto") => YamlVersion::Auto,
+            Some("1.1") => YamlVersion::V1_1,
+            Some("1.2") => YamlVersion::V1_2,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "yamlVersion".into(),
+                    message: "invalid value for config `yamlVersion`".into(),
+                });
+                Default::default()
+            }
+        },
+        explicit_keys: match get_nullable_value::<String>(config, "explicitKeys", diagnostics)
+            .as_deref()
+        {
+            None => base.explicit_keys.clone(),
+            Some("keep") => ExplicitKeys::Keep,
+            Some("never") => ExplicitKeys::Never,
+            Some("always-for-complex") => ExplicitKeys::AlwaysForComplex,
+            _ => {
+                diagnostics.push(ConfigurationDiagnostic {
+                    property_name: "explicitKeys".into(),
+                    message: "invalid value for config `explicitKeys`".into(),
+                });
+                Default::default()
+            }
+        },
+    }
+}
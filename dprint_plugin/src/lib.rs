@@ -1,4 +1,4 @@
-use crate::config::resolve_config;
+use crate::config::{resolve_config, Configuration};
 use anyhow::Result;
 #[cfg(target_arch = "wasm32")]
 use dprint_core::generate_plugin_code;
@@ -6,17 +6,31 @@ use dprint_core::{
     configuration::{ConfigKeyMap, GlobalConfiguration, ResolveConfigurationResult},
     plugins::{FileMatchingInfo, PluginInfo, SyncPluginHandler, SyncPluginInfo},
 };
-use pretty_yaml::{config::FormatOptions, format_text};
+use pretty_yaml::format_text;
+#[cfg(feature = "editorconfig")]
+use pretty_yaml::{config::FormatOptions, editorconfig::EditorConfig};
+#[cfg(feature = "editorconfig")]
+use std::borrow::Cow;
 use std::path::Path;
 
 mod config;
+mod schema;
 
-#[cfg(target_arch = "wasm32")]
-type Configuration = FormatOptions;
+pub use crate::schema::json_schema;
+
+/// Files that are written in YAML but don't have `.yaml` or `.yml` extension.
+const FILE_NAMES: &[&str] = &[
+    ".clang-format",
+    ".clang-tidy",
+    ".clangd",
+    ".condarc",
+    ".yamllint",
+    ".yamlfmt",
+];
 
 pub struct PrettyYamlPluginHandler;
 
-impl SyncPluginHandler<FormatOptions> for PrettyYamlPluginHandler {
+impl SyncPluginHandler<Configuration> for PrettyYamlPluginHandler {
     fn plugin_info(&mut self) -> SyncPluginInfo {
         let version = env!("CARGO_PKG_VERSION").to_string();
         SyncPluginInfo {
@@ -35,7 +49,7 @@ impl SyncPluginHandler<FormatOptions> for PrettyYamlPluginHandler {
             },
             file_matching: FileMatchingInfo {
                 file_extensions: ["yaml", "yml"].into_iter().map(String::from).collect(),
-                file_names: vec![],
+                file_names: FILE_NAMES.iter().map(|name| name.to_string()).collect(),
             },
         }
     }
@@ -48,18 +62,21 @@ impl SyncPluginHandler<FormatOptions> for PrettyYamlPluginHandler {
         &mut self,
         config: ConfigKeyMap,
         global_config: &GlobalConfiguration,
-    ) -> ResolveConfigurationResult<FormatOptions> {
+    ) -> ResolveConfigurationResult<Configuration> {
         resolve_config(config, global_config)
     }
 
     fn format(
         &mut self,
-        _: &Path,
+        file_path: &Path,
         file_text: Vec<u8>,
-        config: &FormatOptions,
+        config: &Configuration,
         _: impl FnMut(&Path, Vec<u8>, &ConfigKeyMap) -> Result<Option<Vec<u8>>>,
     ) -> Result<Option<Vec<u8>>> {
-        let format_result = format_text(std::str::from_utf8(&file_text)?, config);
+        let options = config.options.for_path(file_path);
+        #[cfg(feature = "editorconfig")]
+        let options = apply_editorconfig(options, file_path, &config.explicit_layout);
+        let format_result = format_text(std::str::from_utf8(&file_text)?, &options);
         match format_result {
             Ok(code) => Ok(Some(code.into_bytes())),
             Err(err) => Err(err.into()),
@@ -67,5 +84,18 @@ impl SyncPluginHandler<FormatOptions> for PrettyYamlPluginHandler {
     }
 }
 
+#[cfg(feature = "editorconfig")]
+fn apply_editorconfig<'a>(
+    mut options: Cow<'a, FormatOptions>,
+    file_path: &Path,
+    explicit_layout: &[&str],
+) -> Cow<'a, FormatOptions> {
+    // files may be unreadable in sandboxed environment, so `.editorconfig` is optional
+    if let Ok(editorconfig) = EditorConfig::for_file(file_path) {
+        editorconfig.apply(&mut options.to_mut().layout, explicit_layout);
+    }
+    options
+}
+
 #[cfg(target_arch = "wasm32")]
 generate_plugin_code!(PrettyYamlPluginHandler, PrettyYamlPluginHandler);
@@ -0,0 +1,910 @@
+//! JSON schema of plugin configuration.
+//!
+//! It's generated from option types and their default values,
+//! and it's published as `schema.json` along with the plugin.
+
+use pretty_yaml::config::FormatOptions;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// Generate JSON schema of plugin configuration in pretty-printed JSON.
+///
+/// Default values are taken from [`FormatOptions::default`],
+/// so they're always in sync with the formatter.
+pub fn json_schema() -> String {
+    let defaults =
+        serde_json::to_value(FormatOptions::default()).expect("failed to serialize options");
+    let schema = JsonSchema {
+        schema: "http://json-schema.org/draft-07/schema#",
+        id: format!(
+            "https://plugins.dprint.dev/g-plane/pretty_yaml/v{}/schema.json",
+            env!("CARGO_PKG_VERSION")
+        ),
+        title: "Config",
+        description: "Configuration for dprint-plugin-yaml.",
+        ty: "object",
+        definitions: Properties(vec![(
+            "preferSingleLine",
+            PropertySchema {
+                description: Some(PREFER_SINGLE_LINE),
+                ty: Some("boolean".into()),
+                default: Some(defaults["prefer_single_line"].clone()),
+                ..Default::default()
+            },
+        )]),
+        properties: Properties(
+            PROPERTIES
+                .iter()
+                .map(|property| (property.name, property.schema(&defaults)))
+                .collect(),
+        ),
+    };
+    serde_json::to_string_pretty(&schema).expect("failed to serialize schema")
+}
+
+const PREFER_SINGLE_LINE: &str = "Control whether items should be placed on single line as possible, even they're originally on multiple lines.";
+
+struct Property {
+    /// Name of option in plugin configuration.
+    name: &'static str,
+    description: &'static str,
+    kind: Kind,
+}
+
+enum Kind {
+    Boolean,
+    Integer {
+        minimum: u32,
+    },
+    NullableInteger {
+        minimum: u32,
+    },
+    NullableBoolean,
+    String,
+    Enum(&'static [&'static str]),
+    /// Possible values with their descriptions.
+    OneOf(&'static [(&'static str, &'static str)]),
+    /// Possible values with their descriptions, and description of `null`.
+    NullableOneOf(&'static [(&'static str, &'static str)], &'static str),
+    /// Reference to the shared definition of `preferSingleLine`.
+    PreferSingleLine,
+    Overrides,
+}
+
+impl Property {
+    fn schema(&self, defaults: &Value) -> PropertySchema {
+        if let Kind::PreferSingleLine = self.kind {
+            return PropertySchema {
+                reference: Some("#/definitions/preferSingleLine"),
+                ..Default::default()
+            };
+        }
+        let mut schema = PropertySchema {
+            description: Some(self.description),
+            default: self.default_value(defaults),
+            ..Default::default()
+        };
+        match self.kind {
+            Kind::Boolean => schema.ty = Some("boolean".into()),
+            Kind::Integer { minimum } => {
+                schema.ty = Some("integer".into());
+                schema.minimum = Some(minimum);
+            }
+            Kind::NullableInteger { minimum } => {
+                schema.ty = Some(Value::from(["integer", "null"].as_slice()));
+                schema.minimum = Some(minimum);
+            }
+            Kind::NullableBoolean => {
+                schema.ty = Some(Value::from(["boolean", "null"].as_slice()));
+            }
+            Kind::String => schema.ty = Some("string".into()),
+            Kind::Enum(values) => {
+                schema.ty = Some("string".into());
+                schema.values = Some(values);
+            }
+            Kind::OneOf(variants) => {
+                schema.ty = Some("string".into());
+                schema.one_of = Some(
+                    variants
+                        .iter()
+                        .map(|(value, description)| Variant {
+                            value: Some(value),
+                            description,
+                        })
+                        .collect(),
+                );
+            }
+            Kind::NullableOneOf(variants, null_description) => {
+                schema.ty = Some(Value::from(["string", "null"].as_slice()));
+                schema.one_of = Some(
+                    variants
+                        .iter()
+                        .map(|(value, description)| Variant {
+                            value: Some(value),
+                            description,
+                        })
+                        .chain([Variant {
+                            value: None,
+                            description: null_description,
+                        }])
+                        .collect(),
+                );
+            }
+            Kind::PreferSingleLine => {}
+            Kind::Overrides => {
+                schema.ty = Some("array".into());
+                schema.items = Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "files": {
+                            "description": "Glob pattern of file paths, such as `.github/workflows/**`.",
+                            "type": "string",
+                        },
+                    },
+                    "required": ["files"],
+                }));
+            }
+        }
+        schema
+    }
+
+    /// Look up default value from serialized default options,
+    /// and convert it to the form in plugin configuration.
+    fn default_value(&self, defaults: &Value) -> Option<Value> {
+        let value = match (self.name, &self.kind) {
+            (_, Kind::PreferSingleLine) => return None,
+            ("preset", _) => return Some(Value::Null),
+            (_, Kind::Overrides) => return Some(Value::Array(vec![])),
+            // the parent option controls the first field
+            ("formatComments", _) => &defaults["format_comments"]["space_after_hash"],
+            (name, _) => match name.split_once('.') {
+                Some(("formatComments", field)) => &defaults["format_comments"][snake_case(field)],
+                _ => defaults
+                    .get(snake_case(&name.replace('.', "_")))
+                    .unwrap_or_else(|| panic!("missing default value of `{name}`")),
+            },
+        };
+        match (value, &self.kind) {
+            (Value::Array(items), Kind::String) => Some(Value::String(
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )),
+            (Value::String(value), Kind::OneOf(..) | Kind::NullableOneOf(..)) => {
+                Some(Value::String(camel_case(value)))
+            }
+            (value, _) => Some(value.clone()),
+        }
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    name.chars().fold(String::new(), |mut snake, c| {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+        snake
+    })
+}
+
+/// Convert serialized value of enum in kebab case to camel case.
+fn camel_case(kebab: &str) -> String {
+    kebab
+        .split('-')
+        .enumerate()
+        .map(|(i, word)| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) if i > 0 => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                _ => word.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct JsonSchema {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    #[serde(rename = "$id")]
+    id: String,
+    title: &'static str,
+    description: &'static str,
+    #[serde(rename = "type")]
+    ty: &'static str,
+    definitions: Properties,
+    properties: Properties,
+}
+
+/// Properties that are serialized as JSON object in order.
+struct Properties(Vec<(&'static str, PropertySchema)>);
+
+impl Serialize for Properties {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, schema)| (name, schema)))
+    }
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PropertySchema {
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    reference: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'static str>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    ty: Option<Value>,
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    values: Option<&'static [&'static str]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    one_of: Option<Vec<Variant>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minimum: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    items: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct Variant {
+    #[serde(rename = "const")]
+    value: Option<&'static str>,
+    description: &'static str,
+}
+
+/// Properties are listed by hand for their descriptions,
+/// and test in `tests/schema.rs` checks that no option or variant is missing.
+const PROPERTIES: &[Property] = &[
+    Property {
+        name: "printWidth",
+        description: "The line width limitation that Pretty YAML should *(but not must)* avoid exceeding. Pretty YAML will try its best to keep line width less than this value, but it may exceed for some cases, for example, a very very long single word.",
+        kind: Kind::Integer { minimum: 1 },
+    },
+    Property {
+        name: "indentWidth",
+        description: "Size of indentation.",
+        kind: Kind::Integer { minimum: 1 },
+    },
+    Property {
+        name: "lineBreak",
+        description: "Specify whether use `\\n` (LF) or `\\r\\n` (CRLF) for line break, or detect it from input with `auto`.",
+        kind: Kind::Enum(&["lf", "crlf", "auto"]),
+    },
+    Property {
+        name: "indentKind",
+        description: "Indent with spaces or tabs.",
+        kind: Kind::OneOf(&[
+            (
+                "space",
+                "Indent with spaces.",
+            ),
+            (
+                "tab",
+                "Indent with tabs, while spaces are still used for alignment and content of block scalars. Note that tabs in indentation aren't allowed by YAML spec.",
+            ),
+        ]),
+    },
+    Property {
+        name: "finalNewline",
+        description: "Whether to end output with a line break. It's always kept when output ends with a block scalar.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "keepBom",
+        description: "Whether to keep byte order mark at the beginning of input in output.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "tabWidth",
+        description: "Width of tab characters for measuring columns, such as aligning trailing comments. If it's `null`, it's the same as `indentWidth`.",
+        kind: Kind::NullableInteger { minimum: 1 },
+    },
+    Property {
+        name: "quotes",
+        description: "Control the quotes.",
+        kind: Kind::OneOf(&[
+            (
+                "preferDouble",
+                "Use double quotes as possible. However if there're quotes or escaped characters in strings, quotes will be kept as-is.",
+            ),
+            (
+                "preferSingle",
+                "Use single quotes as possible. However if there're quotes or `\\` characters in strings, quotes will be kept as-is.",
+            ),
+            (
+                "forceDouble",
+                "Use double quotes as possible. However if there're escaped characters in strings, quotes will be kept as-is.",
+            ),
+            (
+                "forceSingle",
+                "Use single quotes as possible. However if there're `\\` char or `\"` char in strings, quotes will be kept as-is.",
+            ),
+        ]),
+    },
+    Property {
+        name: "trailingComma",
+        description: "Control whether trailing comma should be inserted or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "formatComments",
+        description: "Control whether whitespace should be inserted at the beginning of comments or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "formatComments.spaces",
+        description: "Number of spaces to be inserted after `#` of comments.",
+        kind: Kind::Integer { minimum: 1 },
+    },
+    Property {
+        name: "formatComments.banner",
+        description: "Control whether banner comments, such as `####`, `#----` or box-drawing characters, should be formatted or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "formatComments.capitalize",
+        description: "Control whether the first letter of comments should be capitalized or not.",
+        kind: Kind::OneOf(&[
+            (
+                "never",
+                "Keep the case of comments as-is.",
+            ),
+            (
+                "ownLine",
+                "Capitalize comments that are on their own lines.",
+            ),
+            (
+                "always",
+                "Capitalize all comments.",
+            ),
+        ]),
+    },
+    Property {
+        name: "indentBlockSequenceInMap",
+        description: "Control whether block sequence should be indented or not in a block map.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "braceSpacing",
+        description: "Control whether whitespace should be inserted between braces or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "bracketSpacing",
+        description: "Control whether whitespace should be inserted between brackets or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "singleEntryBraceSpacing",
+        description: "Control whether whitespace should be inserted between braces of flow maps that have only one entry. If it's `null`, `braceSpacing` will be used.",
+        kind: Kind::NullableBoolean,
+    },
+    Property {
+        name: "singleEntryBracketSpacing",
+        description: "Control whether whitespace should be inserted between brackets of flow sequences that have only one entry. If it's `null`, `bracketSpacing` will be used.",
+        kind: Kind::NullableBoolean,
+    },
+    Property {
+        name: "dashSpacing",
+        description: "Control the whitespace behavior of block compact map in block sequence value. This option is only effective when `indentWidth` is greater than 2.",
+        kind: Kind::OneOf(&[
+            (
+                "oneSpace",
+                "Insert only one space after `-`.",
+            ),
+            (
+                "indent",
+                "Insert spaces to align indentation, respecting `indentWidth` option.",
+            ),
+        ]),
+    },
+    Property {
+        name: "preferSingleLine",
+        description: PREFER_SINGLE_LINE,
+        kind: Kind::PreferSingleLine,
+    },
+    Property {
+        name: "flowSequence.preferSingleLine",
+        description: PREFER_SINGLE_LINE,
+        kind: Kind::PreferSingleLine,
+    },
+    Property {
+        name: "flowMap.preferSingleLine",
+        description: PREFER_SINGLE_LINE,
+        kind: Kind::PreferSingleLine,
+    },
+    Property {
+        name: "trimTrailingWhitespaces",
+        description: "Control whether trailing whitespaces should be trimmed or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "trimTrailingZero",
+        description: "Control whether trailing zeros should be removed or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "ignoreCommentDirective",
+        description: "Text directive for ignoring formatting specific content.",
+        kind: Kind::String,
+    },
+    Property {
+        name: "dedupeKeys",
+        description: "Remove entries with duplicate keys in maps. This is disabled by default.",
+        kind: Kind::NullableOneOf(
+            &[
+                (
+                    "keepFirst",
+                    "Keep the first entry and remove the later entries with the same key.",
+                ),
+                (
+                    "keepLast",
+                    "Keep the last entry and remove the earlier entries with the same key.",
+                ),
+            ],
+            "Don't remove duplicate keys.",
+        ),
+    },
+    Property {
+        name: "renameAnchors",
+        description: "Control whether anchors should be renamed to `a1`, `a2` and so on in definition order.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "inlineAliases",
+        description: "Control whether aliases should be replaced with copies of their anchored nodes.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "extractAnchors",
+        description: "Minimum number of scalars in identical subtrees to be extracted as anchor and aliases. This is disabled by default.",
+        kind: Kind::NullableInteger { minimum: 1 },
+    },
+    Property {
+        name: "sortDocuments",
+        description: "Comma-separated key paths for sorting documents in a stream, such as `kind,metadata.name`. Documents won't be sorted if it's empty.",
+        kind: Kind::String,
+    },
+    Property {
+        name: "directives",
+        description: "Control how to handle `%YAML` and `%TAG` directives.",
+        kind: Kind::OneOf(&[
+            (
+                "preserve",
+                "Keep directives as-is.",
+            ),
+            (
+                "explicit",
+                "Add `%YAML 1.2` to documents that don't specify YAML version.",
+            ),
+            (
+                "stripRedundant",
+                "Remove directives that are the same as default behavior.",
+            ),
+        ]),
+    },
+    Property {
+        name: "keyOrdering",
+        description: "Control whether and how entries of maps should be sorted by their keys. Entries separated by blank lines are sorted separately.",
+        kind: Kind::OneOf(&[
+            (
+                "ignore",
+                "Keep entries in their original order.",
+            ),
+            (
+                "asc",
+                "Sort entries by keys in ascending order.",
+            ),
+            (
+                "desc",
+                "Sort entries by keys in descending order.",
+            ),
+        ]),
+    },
+    Property {
+        name: "normalizeBlockScalarIndent",
+        description: "Control whether content of block scalars should be indented by exactly one level from the key or `-`.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "emptyDocuments",
+        description: "Control how to print empty documents in a stream.",
+        kind: Kind::OneOf(&[
+            (
+                "preserve",
+                "Keep empty documents as-is.",
+            ),
+            (
+                "bare",
+                "Print empty documents as bare `---`.",
+            ),
+            (
+                "null",
+                "Print empty documents as `--- null`.",
+            ),
+            (
+                "drop",
+                "Remove empty documents from the stream.",
+            ),
+        ]),
+    },
+    Property {
+        name: "explicitKeyThreshold",
+        description: "Keys in block maps that are longer than this number of characters or are flow collections will use explicit key style (`? key`). Explicit keys will be collapsed to implicit keys when possible if this is `null`.",
+        kind: Kind::NullableInteger { minimum: 0 },
+    },
+    Property {
+        name: "indentWidthFlow",
+        description: "Indent width of entries when flow collections are broken into multiple lines. `indentWidth` will be used if this is `null`.",
+        kind: Kind::NullableInteger { minimum: 1 },
+    },
+    Property {
+        name: "blockScalarStyle",
+        description: "Convert block scalars to the specified style when the value won't be changed.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep block scalars as-is.",
+            ),
+            (
+                "literal",
+                "Convert folded block scalars (`>`) to literal block scalars (`|`).",
+            ),
+            (
+                "folded",
+                "Convert literal block scalars (`|`) to folded block scalars (`>`).",
+            ),
+        ]),
+    },
+    Property {
+        name: "reflowFoldedScalars",
+        description: "Control whether text of folded block scalars should be rewrapped at print width or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "preferFlowForShortSequences",
+        description: "Control whether block sequences of scalars should be converted to flow sequences when they fit in print width or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "wrapPlainScalars",
+        description: "Control whether long plain scalars should be wrapped at print width or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "scalarQuoting",
+        description: "Add quotes to plain scalars or remove quotes from quoted scalars when the value won't be changed.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep scalars as-is.",
+            ),
+            (
+                "quoteAll",
+                "Quote all plain scalars that are strings, except keys.",
+            ),
+            (
+                "unquoteWhenSafe",
+                "Remove quotes if the scalar can be written as plain scalar with the same value.",
+            ),
+            (
+                "quoteAmbiguous",
+                "Quote plain scalars that are strings in YAML 1.2 but not in YAML 1.1, such as `no`, `on` or `1_000`.",
+            ),
+        ]),
+    },
+    Property {
+        name: "commentIndent",
+        description: "Control indentation of own-line comments between entries of different nesting levels.",
+        kind: Kind::OneOf(&[
+            (
+                "followPrev",
+                "Comments after the last entry of nested collection keep their original nesting.",
+            ),
+            (
+                "followNext",
+                "Comments are indented as the entry after them.",
+            ),
+            (
+                "dedent",
+                "Comments at the beginning of line are kept at the beginning of line.",
+            ),
+        ]),
+    },
+    Property {
+        name: "alignValues",
+        description: "Control whether values of consecutive entries in block maps are aligned into columns.",
+        kind: Kind::OneOf(&[
+            (
+                "none",
+                "Don't align values.",
+            ),
+            (
+                "colon",
+                "Pad keys before colons, so colons and values are aligned.",
+            ),
+            (
+                "value",
+                "Pad after colons, so values are aligned.",
+            ),
+        ]),
+    },
+    Property {
+        name: "maxBlankLines",
+        description: "Maximum number of consecutive blank lines to keep.",
+        kind: Kind::Integer { minimum: 0 },
+    },
+    Property {
+        name: "blankLinesBetweenTopLevelEntries",
+        description: "Minimum number of blank lines between top-level entries of block maps or block sequences.",
+        kind: Kind::Integer { minimum: 0 },
+    },
+    Property {
+        name: "normalizeNull",
+        description: "Spelling of null values.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep null values as-is.",
+            ),
+            (
+                "tilde",
+                "Print null values as `~`.",
+            ),
+            (
+                "null",
+                "Print null values as `null`.",
+            ),
+            (
+                "empty",
+                "Remove null values from block map entries and block sequence entries.",
+            ),
+        ]),
+    },
+    Property {
+        name: "normalizeBooleanCasing",
+        description: "Casing of boolean values.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep boolean values as-is.",
+            ),
+            (
+                "lowercase",
+                "Print boolean values in lowercase.",
+            ),
+        ]),
+    },
+    Property {
+        name: "removePlusSign",
+        description: "Control whether leading plus signs of numbers should be removed or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "exponentCase",
+        description: "Casing of exponent mark in floats.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep exponent mark as-is.",
+            ),
+            (
+                "lowercase",
+                "Print exponent mark as `e`.",
+            ),
+            (
+                "uppercase",
+                "Print exponent mark as `E`.",
+            ),
+        ]),
+    },
+    Property {
+        name: "padLeadingZero",
+        description: "Control whether zero should be inserted before decimal point of floats like `.5` or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "removeDigitSeparators",
+        description: "Control whether underscores in decimal numbers should be removed or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "breakLongValues",
+        description: "Control whether values of block map entries that exceed print width should be moved to the next line or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "dedupeTagDirectives",
+        description: "Control whether repeated `%TAG` directives in the same document should be removed or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "insertDirectivesEnd",
+        description: "Control whether missing `---` after directives should be inserted when input can't be parsed without it.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "explicitDocumentMarkers",
+        description: "Control whether document markers `---` and `...` are printed.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep document markers as-is.",
+            ),
+            (
+                "always",
+                "Add `---` to documents that don't have it.",
+            ),
+            (
+                "stripWhenSingle",
+                "Remove `---` and `...` when there's only one document in the stream.",
+            ),
+        ]),
+    },
+    Property {
+        name: "templateSyntax",
+        description: "Syntax of template expressions embedded in YAML, which are kept as-is.",
+        kind: Kind::OneOf(&[
+            (
+                "none",
+                "No template syntax.",
+            ),
+            (
+                "go",
+                "Go templates used by Helm, which use `{{ ... }}`.",
+            ),
+            (
+                "jinja2",
+                "Jinja2 templates used by Ansible and Salt, which use `{{ ... }}`, `{% ... %}` and `{# ... #}`.",
+            ),
+        ]),
+    },
+    Property {
+        name: "alignTrailingComments",
+        description: "Control whether trailing comments of consecutive entries should be aligned to the same column or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "commentSpace",
+        description: "Minimum number of spaces after `#` of comments.",
+        kind: Kind::Integer { minimum: 0 },
+    },
+    Property {
+        name: "wrapComments",
+        description: "Control whether long comments should be wrapped at print width or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "anchorNameCase",
+        description: "Case of anchor names, which is also applied to aliases that refer to them.",
+        kind: Kind::OneOf(&[
+            (
+                "keep",
+                "Keep anchor names as-is.",
+            ),
+            (
+                "camel",
+                "Rename anchors like `fooBar`.",
+            ),
+            (
+                "kebab",
+                "Rename anchors like `foo-bar`.",
+            ),
+            (
+                "snake",
+                "Rename anchors like `foo_bar`.",
+            ),
+        ]),
+    },
+    Property {
+        name: "flattenMergeKeys",
+        description: "Control whether merge keys like `<<: *base` should be replaced with the merged entries or not.",
+        kind: Kind::Boolean,
+    },
+    Property {
+        name: "sequenceIndentWidth",
+        description: "Indent width of block sequences in block maps. It depends on `indentBlockSequenceInMap` if this is `null`.",
+        kind: Kind::NullableInteger { minimum: 0 },
+    },
+    Property {
+        name: "flowCollectionLayout",
+        description: "Control whether flow collections are placed on single line or multiple lines.",
+        kind: Kind::OneOf(&[
+            (
+                "auto",
+                "Keep collections multi-line if there's line break after the opening bracket, otherwise place them on single line if they fit. `preferSingleLine` options only take effect in this layout.",
+            ),
+            (
+                "preserve",
+                "Keep collections multi-line if there's any line break between entries, otherwise place them on single line if they fit.",
+            ),
+            (
+                "singleLine",
+                "Place collections on single line if they fit.",
+            ),
+            (
+                "multiLine",
+                "Always place entries of non-empty collections on separate lines.",
+            ),
+        ]),
+    },
+    Property {
+        name: "emptyValueStyle",
+        description: "Control how empty values and null values in block map entries are spelled. It takes precedence over `normalizeNull` for them.",
+        kind: Kind::OneOf(&[
+            ("keep", "Follow the `normalizeNull` option."),
+            ("null", "Print values as `null`, like `key: null`."),
+            ("tilde", "Print values as `~`, like `key: ~`."),
+            ("empty", "Remove values, like `key:`."),
+        ]),
+    },
+    Property {
+        name: "yamlVersion",
+        description: "YAML version that decides how plain scalars are resolved, which affects options that depend on types of scalars.",
+        kind: Kind::OneOf(&[
+            (
+                "auto",
+                "Use the version in `%YAML` directive of each document, or YAML 1.2 if there's no directive.",
+            ),
+            (
+                "1.1",
+                "Treat all documents as YAML 1.1, where `yes`, `off` and `0755` aren't strings.",
+            ),
+            ("1.2", "Treat all documents as YAML 1.2."),
+        ]),
+    },
+    Property {
+        name: "explicitKeys",
+        description: "Control whether the explicit key indicator `?` is printed.",
+        kind: Kind::OneOf(&[
+            ("keep", "Keep `?` of existing explicit keys in block maps."),
+            ("never", "Remove `?` when it's safe to do so."),
+            (
+                "always-for-complex",
+                "Add `?` for keys of block map entries that are collections or multi-line scalars.",
+            ),
+        ]),
+    },
+    Property {
+        name: "preset",
+        description: "Built-in preset whose options are used as default values of other options.",
+        kind: Kind::NullableOneOf(
+            &[
+                (
+                    "kubernetes",
+                    "Kubernetes manifests, in the style of `kubectl` output.",
+                ),
+                (
+                    "githubActions",
+                    "GitHub Actions workflows.",
+                ),
+                (
+                    "dockerCompose",
+                    "Docker Compose files.",
+                ),
+                (
+                    "prettier",
+                    "Emulate output of Prettier.",
+                ),
+                (
+                    "ruamel",
+                    "Emulate output of round-trip dumper of ruamel.yaml with default settings.",
+                ),
+                (
+                    "yamlfmt",
+                    "Emulate output of yamlfmt with default settings.",
+                ),
+            ],
+            "Don't use any presets.",
+        ),
+    },
+    Property {
+        name: "overrides",
+        description: "Language options for files whose paths match glob patterns. Options that aren't specified are inherited from the main config.",
+        kind: Kind::Overrides,
+    },
+];
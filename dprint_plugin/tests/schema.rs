@@ -0,0 +1,257 @@
+use dprint_core::{
+    configuration::{ConfigKeyMap, ConfigKeyValue, GlobalConfiguration},
+    plugins::SyncPluginHandler,
+};
+use dprint_plugin_yaml::{json_schema, PrettyYamlPluginHandler};
+use pretty_yaml::config::*;
+use serde_json::Value;
+use std::{collections::HashMap, env, fs, path::Path};
+
+/// Serialize all variants of enum, and it fails to compile if any variant is missing.
+macro_rules! variants {
+    ($ty:ident: $($variant:ident),+) => {{
+        let variants = [$($ty::$variant),+];
+        variants.iter().for_each(|variant| match variant {
+            $($ty::$variant)|+ => {}
+        });
+        variants
+            .iter()
+            .map(|variant| serde_json::to_value(variant).unwrap())
+            .collect::<Vec<_>>()
+    }};
+}
+
+fn schema() -> Value {
+    serde_json::from_str(&json_schema()).unwrap()
+}
+
+/// Resolve plugin config from JSON object, and assert there're no diagnostics.
+fn resolve(config: Value) -> FormatOptions {
+    let config = config
+        .as_object()
+        .unwrap()
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::Bool(value) => ConfigKeyValue::Bool(*value),
+                Value::Number(value) => ConfigKeyValue::Number(value.as_i64().unwrap() as i32),
+                Value::String(value) => ConfigKeyValue::String(value.clone()),
+                _ => ConfigKeyValue::Null,
+            };
+            (name.clone(), value)
+        })
+        .collect::<ConfigKeyMap>();
+    let result = PrettyYamlPluginHandler.resolve_config(config, &GlobalConfiguration::default());
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+    result.config.options
+}
+
+/// Serialize options as flat JSON object,
+/// where names of fields in nested options are prefixed with the name of their parent.
+fn flatten(options: &FormatOptions) -> serde_json::Map<String, Value> {
+    let mut flat = serde_json::Map::new();
+    for (name, value) in serde_json::to_value(options).unwrap().as_object().unwrap() {
+        if let Value::Object(fields) = value {
+            fields.iter().for_each(|(field, value)| {
+                flat.insert(format!("{name}.{field}"), value.clone());
+            });
+        } else {
+            flat.insert(name.clone(), value.clone());
+        }
+    }
+    flat
+}
+
+/// Convert name of property in schema to the name of option in flattened options.
+fn option_name(property: &str) -> String {
+    if property == "formatComments" {
+        return "format_comments.space_after_hash".into();
+    }
+    property.chars().fold(String::new(), |mut snake, c| {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+        snake
+    })
+}
+
+/// Values of property in schema, excluding `null`.
+fn values(property: &Value) -> impl Iterator<Item = &Value> {
+    property["oneOf"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|variant| &variant["const"])
+        .chain(property["enum"].as_array().into_iter().flatten())
+        .filter(|value| !value.is_null())
+}
+
+#[test]
+fn up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("deployment/schema.json");
+    let schema = json_schema() + "\n";
+    if env::var_os("UPDATE_SCHEMA").is_some() {
+        fs::write(path, schema).unwrap();
+    } else {
+        assert!(
+            fs::read_to_string(path).unwrap() == schema,
+            "schema is outdated, run tests with `UPDATE_SCHEMA=1` to update it"
+        );
+    }
+}
+
+#[test]
+fn defaults() {
+    let schema = schema();
+    let config = schema["properties"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .filter_map(|(name, property)| Some((name.clone(), property.get("default")?.clone())))
+        .filter(|(_, value)| !value.is_null() && !value.is_array())
+        .collect::<serde_json::Map<_, _>>();
+    assert_eq!(
+        serde_json::to_value(resolve(Value::Object(config))).unwrap(),
+        serde_json::to_value(FormatOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn variants() {
+    let schema = schema();
+    schema["properties"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| *name != "preset")
+        .for_each(|(name, property)| {
+            values(property).for_each(|value| {
+                resolve(serde_json::json!({ name: value }));
+            });
+        });
+}
+
+#[test]
+fn complete() {
+    let schema = schema();
+    let properties = schema["properties"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .map(|(name, property)| (option_name(name), (name, property)))
+        .collect::<HashMap<_, _>>();
+    flatten(&FormatOptions::default())
+        .keys()
+        .for_each(|option| {
+            assert!(
+                properties.contains_key(option),
+                "option `{option}` is missing in schema"
+            );
+        });
+
+    let enums = HashMap::from([
+        ("line_break", variants!(LineBreak: Lf, Crlf, Auto)),
+        ("indent_kind", variants!(IndentKind: Space, Tab)),
+        (
+            "quotes",
+            variants!(Quotes: PreferDouble, PreferSingle, ForceDouble, ForceSingle),
+        ),
+        (
+            "format_comments.capitalize",
+            variants!(CapitalizeComments: Never, OwnLine, Always),
+        ),
+        ("dash_spacing", variants!(DashSpacing: OneSpace, Indent)),
+        ("dedupe_keys", variants!(DedupeKeys: KeepFirst, KeepLast)),
+        (
+            "directives",
+            variants!(Directives: Preserve, Explicit, StripRedundant),
+        ),
+        ("key_ordering", variants!(KeyOrdering: Ignore, Asc, Desc)),
+        (
+            "empty_documents",
+            variants!(EmptyDocuments: Preserve, Bare, Null, Drop),
+        ),
+        (
+            "block_scalar_style",
+            variants!(BlockScalarStyle: Keep, Literal, Folded),
+        ),
+        (
+            "scalar_quoting",
+            variants!(ScalarQuoting: Keep, QuoteAll, UnquoteWhenSafe, QuoteAmbiguous),
+        ),
+        (
+            "comment_indent",
+            variants!(CommentIndent: FollowPrev, FollowNext, Dedent),
+        ),
+        ("align_values", variants!(AlignValues: None, Colon, Value)),
+        (
+            "normalize_null",
+            variants!(NormalizeNull: Keep, Tilde, Null, Empty),
+        ),
+        (
+            "normalize_boolean_casing",
+            variants!(NormalizeBooleanCasing: Keep, Lowercase),
+        ),
+        (
+            "exponent_case",
+            variants!(ExponentCase: Keep, Lowercase, Uppercase),
+        ),
+        (
+            "explicit_document_markers",
+            variants!(ExplicitDocumentMarkers: Keep, Always, StripWhenSingle),
+        ),
+        (
+            "template_syntax",
+            variants!(TemplateSyntax: None, Go, Jinja2),
+        ),
+        (
+            "anchor_name_case",
+            variants!(AnchorNameCase: Keep, Camel, Kebab, Snake),
+        ),
+        (
+            "flow_collection_layout",
+            variants!(FlowCollectionLayout: Auto, Preserve, SingleLine, MultiLine),
+        ),
+        (
+            "empty_value_style",
+            variants!(EmptyValueStyle: Keep, Null, Tilde, Empty),
+        ),
+        ("yaml_version", variants!(YamlVersion: Auto, V1_1, V1_2)),
+        (
+            "explicit_keys",
+            variants!(ExplicitKeys: Keep, Never, AlwaysForComplex),
+        ),
+    ]);
+    properties
+        .iter()
+        .filter(|(_, (name, property))| *name != "preset" && values(property).next().is_some())
+        .for_each(|(option, (name, property))| {
+            let variants = enums
+                .get(option.as_str())
+                .unwrap_or_else(|| panic!("variants of `{name}` aren't listed"));
+            let resolved = values(property)
+                .map(|value| flatten(&resolve(serde_json::json!({ *name: value })))[option].clone())
+                .collect::<Vec<_>>();
+            variants.iter().for_each(|variant| {
+                assert!(
+                    resolved.contains(variant),
+                    "variant {variant} of `{name}` is missing in schema"
+                );
+            });
+        });
+
+    let presets = values(properties["preset"].1)
+        .map(|value| {
+            serde_json::to_value(serde_json::from_value::<Preset>(value.clone()).unwrap()).unwrap()
+        })
+        .collect::<Vec<_>>();
+    variants!(Preset: Kubernetes, GithubActions, DockerCompose, Prettier, Ruamel, Yamlfmt)
+        .iter()
+        .for_each(|variant| {
+            assert!(
+                presets.contains(variant),
+                "preset {variant} is missing in schema"
+            );
+        });
+}
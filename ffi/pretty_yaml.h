@@ -0,0 +1,36 @@
+#ifndef PRETTY_YAML_H
+#define PRETTY_YAML_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define PRETTY_YAML_OK 0
+#define PRETTY_YAML_INVALID_ARGUMENT 1
+#define PRETTY_YAML_INVALID_CONFIG 2
+#define PRETTY_YAML_SYNTAX_ERROR 3
+#define PRETTY_YAML_INTERNAL_ERROR 4
+
+/*
+ * Format the given source input.
+ *
+ * `config_json` is a JSON object of options, or NULL for default options.
+ * On success, formatted output is written to `out` and must be freed by `pretty_yaml_free`.
+ * Otherwise `out` is set to NULL and error message can be retrieved by `pretty_yaml_last_error`.
+ */
+int pretty_yaml_format(const char *input, const char *config_json, char **out);
+
+/*
+ * Get message of the last error on current thread, or NULL if the last call succeeded.
+ * The string is owned by the library and valid until next call on the same thread.
+ */
+const char *pretty_yaml_last_error(void);
+
+/* Free string returned by `pretty_yaml_format`. */
+void pretty_yaml_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,147 @@
+//! C bindings of Pretty YAML, for editors and tools written in other languages
+//! to embed the formatter, such as Neovim via LuaJIT FFI or JetBrains IDEs via JNI.
+//!
+//! Declarations for C are in `pretty_yaml.h`.
+
+use pretty_yaml::{config::FormatOptions, format_text};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic, ptr,
+};
+
+/// Formatting succeeded.
+pub const PRETTY_YAML_OK: i32 = 0;
+/// Pointer arguments are null or strings aren't valid UTF-8.
+pub const PRETTY_YAML_INVALID_ARGUMENT: i32 = 1;
+/// Config can't be deserialized or contains invalid values.
+pub const PRETTY_YAML_INVALID_CONFIG: i32 = 2;
+/// Input can't be parsed.
+pub const PRETTY_YAML_SYNTAX_ERROR: i32 = 3;
+/// Formatter panicked unexpectedly.
+pub const PRETTY_YAML_INTERNAL_ERROR: i32 = 4;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Format the given source input.
+///
+/// `config_json` is a JSON object in the same shape as config file of command line,
+/// and default options will be used if it's null.
+///
+/// It returns `PRETTY_YAML_OK` and writes formatted output to `out` on success,
+/// which must be freed by [`pretty_yaml_free`].
+/// Otherwise it returns error code, and `out` is set to null.
+/// Error message can be retrieved by [`pretty_yaml_last_error`].
+///
+/// # Safety
+///
+/// `input` and `config_json` must be null or valid nul-terminated strings,
+/// and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pretty_yaml_format(
+    input: *const c_char,
+    config_json: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    if out.is_null() {
+        set_last_error("output pointer is null");
+        return PRETTY_YAML_INVALID_ARGUMENT;
+    }
+    *out = ptr::null_mut();
+    let Some(input) = to_str(input, "input") else {
+        return PRETTY_YAML_INVALID_ARGUMENT;
+    };
+    let options = if config_json.is_null() {
+        FormatOptions::default()
+    } else {
+        let Some(config_json) = to_str(config_json, "config") else {
+            return PRETTY_YAML_INVALID_ARGUMENT;
+        };
+        match serde_json::from_str::<FormatOptions>(config_json) {
+            Ok(options) => options,
+            Err(error) => {
+                set_last_error(&error.to_string());
+                return PRETTY_YAML_INVALID_CONFIG;
+            }
+        }
+    };
+    if let Err(errors) = options.validate() {
+        let message = errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        set_last_error(&message);
+        return PRETTY_YAML_INVALID_CONFIG;
+    }
+
+    match panic::catch_unwind(|| format_text(input, &options)) {
+        Ok(Ok(output)) => match CString::new(output) {
+            Ok(output) => {
+                *out = output.into_raw();
+                clear_last_error();
+                PRETTY_YAML_OK
+            }
+            Err(_) => {
+                set_last_error("output contains nul character");
+                PRETTY_YAML_INTERNAL_ERROR
+            }
+        },
+        Ok(Err(error)) => {
+            set_last_error(&error.to_string());
+            PRETTY_YAML_SYNTAX_ERROR
+        }
+        Err(_) => {
+            set_last_error("formatter panicked");
+            PRETTY_YAML_INTERNAL_ERROR
+        }
+    }
+}
+
+/// Get message of the last error on current thread, or null if the last call succeeded.
+///
+/// The returned string is owned by the library,
+/// and it's valid until next call of [`pretty_yaml_format`] on the same thread.
+#[no_mangle]
+pub extern "C" fn pretty_yaml_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |error| error.as_ptr()))
+}
+
+/// Free string returned by [`pretty_yaml_format`].
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by [`pretty_yaml_format`] that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn pretty_yaml_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Convert C string to `&str`, and record error if it's null or invalid UTF-8.
+unsafe fn to_str<'a>(s: *const c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        set_last_error(&format!("{name} is null"));
+        return None;
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => {
+            set_last_error(&format!("{name} isn't valid UTF-8"));
+            None
+        }
+    }
+}
+
+fn set_last_error(message: &str) {
+    // messages come from Rust strings which may contain nul characters in theory
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.set(Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.set(None);
+}
@@ -0,0 +1,82 @@
+use pretty_yaml_ffi::*;
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+};
+
+/// Call formatter through C ABI, and return formatted output or error message.
+fn format(input: &str, config: Option<&str>) -> Result<String, (i32, String)> {
+    let input = CString::new(input).unwrap();
+    let config = config.map(|config| CString::new(config).unwrap());
+    let mut out = ptr::null_mut();
+    unsafe {
+        let code = pretty_yaml_format(
+            input.as_ptr(),
+            config
+                .as_ref()
+                .map_or(ptr::null(), |config| config.as_ptr()),
+            &mut out,
+        );
+        if code == PRETTY_YAML_OK {
+            assert!(pretty_yaml_last_error().is_null());
+            let output = CStr::from_ptr(out).to_str().unwrap().to_owned();
+            pretty_yaml_free(out);
+            Ok(output)
+        } else {
+            assert!(out.is_null());
+            let message = CStr::from_ptr(pretty_yaml_last_error())
+                .to_str()
+                .unwrap()
+                .to_owned();
+            Err((code, message))
+        }
+    }
+}
+
+#[test]
+fn format_with_config() {
+    assert_eq!(format("a:   [b]\n", None), Ok("a: [b]\n".into()));
+    assert_eq!(
+        format("a:\n- b\n", Some(r#"{"indentWidth": 4}"#)),
+        Ok("a:\n    - b\n".into())
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        format("a: 1\n", Some(r#"{"printWidth": 0}"#)),
+        Err((
+            PRETTY_YAML_INVALID_CONFIG,
+            "config `printWidth` must be at least 1, but got 0".into()
+        ))
+    );
+    assert!(matches!(
+        format("a: 1\n", Some("{")),
+        Err((PRETTY_YAML_INVALID_CONFIG, _))
+    ));
+    assert!(matches!(
+        format("a: [", None),
+        Err((PRETTY_YAML_SYNTAX_ERROR, _))
+    ));
+    // error is cleared after successful call
+    assert!(format("a: 1\n", None).is_ok());
+}
+
+#[test]
+fn null_arguments() {
+    unsafe {
+        let mut out = ptr::null_mut();
+        assert_eq!(
+            pretty_yaml_format(ptr::null(), ptr::null(), &mut out),
+            PRETTY_YAML_INVALID_ARGUMENT
+        );
+        assert!(out.is_null());
+        let input = CString::new("a: 1\n").unwrap();
+        assert_eq!(
+            pretty_yaml_format(input.as_ptr(), ptr::null(), ptr::null_mut()),
+            PRETTY_YAML_INVALID_ARGUMENT
+        );
+        pretty_yaml_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,61 @@
+//! Re-indentation of block scalar content.
+//!
+//! Only spaces can be used as indentation in YAML,
+//! so tabs after indentation are part of content and they're always kept,
+//! even if they look like indentation in editors.
+
+/// Split content of block scalar into lines with indentation removed.
+///
+/// Indentation is detected from the first line that isn't whitespace-only,
+/// and it's the number of spaces at the beginning of that line.
+/// Whitespace-only lines become empty lines.
+/// Returns `None` if there's no content.
+pub(crate) fn dedent_lines(text: &str, trim_trailing_whitespaces: bool) -> Option<Vec<String>> {
+    let indent = detect_indent(text)?;
+    Some(
+        text.lines()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    String::new()
+                } else {
+                    let line = strip_indent(line, indent);
+                    if trim_trailing_whitespaces {
+                        line.trim_end().to_owned()
+                    } else {
+                        line.to_owned()
+                    }
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Detect indentation of block scalar content.
+///
+/// The first line of text is the rest of header line,
+/// so it's measured from the beginning of text if there's no line break before content.
+fn detect_indent(text: &str) -> Option<usize> {
+    text.lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+}
+
+/// Remove at most `indent` spaces at the beginning of line.
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    &line[spaces.min(indent)..]
+}
+
+/// Check if there're whitespace-only lines that are longer than indentation,
+/// whose extra whitespaces are content and will be lost if re-indented.
+pub(crate) fn has_whitespace_only_content(text: &str) -> bool {
+    let mut lines = text.lines().skip(1);
+    let Some(indent) = lines
+        .clone()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+    else {
+        return false;
+    };
+    lines.any(|line| line.trim().is_empty() && line.len() > indent)
+}
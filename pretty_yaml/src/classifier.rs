@@ -0,0 +1,108 @@
+use crate::{config::ScalarQuoting, transform::entry_key_text};
+use yaml_parser::{SyntaxKind, SyntaxNode};
+
+/// Classifier that decides how scalars are formatted by their key paths,
+/// so schema-specific policies can be applied without changing the printer.
+///
+/// It's called for every scalar that isn't a key, including scalars in flow collections.
+/// Closures like `Fn(&[PathSegment]) -> ScalarStyle` implement this trait.
+///
+/// ```
+/// use pretty_yaml::{
+///     config::{FormatOptions, ScalarQuoting},
+///     format_text_with, PathSegment, ScalarStyle,
+/// };
+///
+/// let classifier = |path: &[PathSegment]| match path {
+///     [.., PathSegment::Key(key), PathSegment::Key(_)] if key == "env" => ScalarStyle {
+///         quoting: Some(ScalarQuoting::QuoteAll),
+///         ..Default::default()
+///     },
+///     _ => ScalarStyle::default(),
+/// };
+/// let input = "env:\n  CI: true\n  NAME: app\nname: app\n";
+/// let output = format_text_with(input, &FormatOptions::default(), &classifier).unwrap();
+/// assert_eq!(output, "env:\n  CI: true\n  NAME: \"app\"\nname: app\n");
+/// ```
+pub trait ScalarClassifier {
+    /// Decide how the scalar at the given key path should be formatted.
+    ///
+    /// Path starts from the root of document,
+    /// and it's empty if the scalar itself is the document.
+    fn classify(&self, path: &[PathSegment]) -> ScalarStyle;
+}
+
+impl<F> ScalarClassifier for F
+where
+    F: Fn(&[PathSegment]) -> ScalarStyle,
+{
+    fn classify(&self, path: &[PathSegment]) -> ScalarStyle {
+        self(path)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Segment of key path to scalar.
+pub enum PathSegment {
+    /// Key of map entry.
+    /// Quotes and escapes are resolved, and complex keys are represented by their source text.
+    Key(String),
+    /// Index of sequence entry.
+    Index(usize),
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+/// Style of specific scalar decided by [`ScalarClassifier`].
+pub struct ScalarStyle {
+    /// Use this instead of `scalarQuoting` option for the scalar.
+    /// `None` means using the option.
+    pub quoting: Option<ScalarQuoting>,
+    /// Keep line breaks of the scalar as-is,
+    /// so it won't be wrapped by `wrapPlainScalars` or reflowed by `reflowFoldedScalars`.
+    pub keep_lines: bool,
+}
+
+/// Get key path from the root of document to the given node.
+/// Returns `None` if the node is inside a key.
+pub(crate) fn key_path(node: &SyntaxNode) -> Option<Vec<PathSegment>> {
+    let mut path = vec![];
+    for ancestor in node.ancestors() {
+        match ancestor.kind() {
+            SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY => return None,
+            SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE => {
+                let entry = ancestor.parent()?;
+                let key = entry_key_text(&entry).unwrap_or_else(|| {
+                    entry
+                        .children()
+                        .find(|child| {
+                            matches!(
+                                child.kind(),
+                                SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+                            )
+                        })
+                        .map(|key| {
+                            key.text()
+                                .to_string()
+                                .trim_start_matches('?')
+                                .trim()
+                                .to_owned()
+                        })
+                        .unwrap_or_default()
+                });
+                path.push(PathSegment::Key(key));
+            }
+            SyntaxKind::BLOCK_SEQ_ENTRY | SyntaxKind::FLOW_SEQ_ENTRY => {
+                let index = ancestor
+                    .siblings(rowan::Direction::Prev)
+                    .filter(|sibling| sibling.kind() == ancestor.kind())
+                    .count()
+                    - 1;
+                path.push(PathSegment::Index(index));
+            }
+            SyntaxKind::DOCUMENT => break,
+            _ => {}
+        }
+    }
+    path.reverse();
+    Some(path)
+}
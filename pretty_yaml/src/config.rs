@@ -5,8 +5,13 @@
 
 #[cfg(feature = "config_serde")]
 use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, error::Error, fmt, path::Path};
+use yaml_parser::{
+    ast::{AstNode, Document},
+    schema, SyntaxElement, SyntaxNode,
+};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(default))]
 /// The whole configuration of Pretty YAML.
@@ -15,9 +20,324 @@ pub struct FormatOptions {
     pub layout: LayoutOptions,
     #[cfg_attr(feature = "config_serde", serde(flatten))]
     pub language: LanguageOptions,
+    /// Language options for files whose paths match the glob patterns.
+    /// If there're multiple matched patterns, the last one will be used.
+    ///
+    /// These are complete options instead of partial ones,
+    /// so unchanged options should be copied from [`FormatOptions::language`].
+    /// Use [`FormatOptions::for_path`] to get options for specific file.
+    #[cfg_attr(feature = "config_serde", serde(skip))]
+    pub overrides: Vec<(GlobPattern, LanguageOptions)>,
 }
 
-#[derive(Clone, Debug)]
+impl FormatOptions {
+    /// Create options from built-in preset.
+    /// Options that aren't covered by the preset will be default values.
+    pub fn preset(preset: Preset) -> Self {
+        let mut options = FormatOptions::default();
+        let language = &mut options.language;
+        match preset {
+            Preset::Kubernetes => {
+                language.trailing_comma = false;
+                language.indent_block_sequence_in_map = false;
+                language.empty_documents = EmptyDocuments::Drop;
+            }
+            Preset::GithubActions => {
+                language.quotes = Quotes::PreferSingle;
+                language.trailing_comma = false;
+            }
+            Preset::DockerCompose => {
+                language.trailing_comma = false;
+                language.directives = Directives::StripRedundant;
+            }
+            Preset::Prettier => {
+                language.trailing_comma = false;
+            }
+            Preset::Ruamel => {
+                language.trailing_comma = false;
+                language.indent_block_sequence_in_map = false;
+                language.brace_spacing = false;
+                language.wrap_plain_scalars = true;
+            }
+            Preset::Yamlfmt => {
+                language.trailing_comma = false;
+                language.brace_spacing = false;
+                language.prefer_single_line = true;
+            }
+        }
+        options
+    }
+
+    /// Get options for the given file path by applying overrides.
+    ///
+    /// ```
+    /// use pretty_yaml::config::{FormatOptions, GlobPattern};
+    /// use std::path::Path;
+    ///
+    /// let mut options = FormatOptions::default();
+    /// let mut language = options.language.clone();
+    /// language.indent_block_sequence_in_map = false;
+    /// options
+    ///     .overrides
+    ///     .push((GlobPattern::new(".github/workflows/*.yml"), language));
+    ///
+    /// let path = Path::new("/repo/.github/workflows/ci.yml");
+    /// assert!(!options.for_path(path).language.indent_block_sequence_in_map);
+    /// let path = Path::new("/repo/ci.yml");
+    /// assert!(options.for_path(path).language.indent_block_sequence_in_map);
+    /// ```
+    pub fn for_path(&self, path: &Path) -> Cow<'_, FormatOptions> {
+        match self
+            .overrides
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(path))
+        {
+            Some((_, language)) => Cow::Owned(FormatOptions {
+                layout: self.layout.clone(),
+                language: language.clone(),
+                overrides: vec![],
+            }),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Check if values of options are in valid ranges,
+    /// including language options in overrides.
+    ///
+    /// Options deserialized from configuration files aren't validated automatically,
+    /// so this should be called before formatting.
+    ///
+    /// ```
+    /// use pretty_yaml::config::{ConfigError, FormatOptions};
+    ///
+    /// let mut options = FormatOptions::default();
+    /// assert!(options.validate().is_ok());
+    /// options.layout.print_width = 0;
+    /// assert_eq!(
+    ///     options.validate(),
+    ///     Err(vec![ConfigError::TooSmall {
+    ///         option: "printWidth",
+    ///         value: 0,
+    ///         min: 1,
+    ///     }]),
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = vec![];
+        check_min(&mut errors, "printWidth", self.layout.print_width, 1);
+        check_min(&mut errors, "indentWidth", self.layout.indent_width, 1);
+        if let Some(width) = self.layout.tab_width {
+            check_min(&mut errors, "tabWidth", width, 1);
+        }
+        validate_language(&self.language, &mut errors);
+        self.overrides
+            .iter()
+            .enumerate()
+            .for_each(|(index, (_, language))| {
+                let mut override_errors = vec![];
+                validate_language(language, &mut override_errors);
+                errors.extend(
+                    override_errors
+                        .into_iter()
+                        .map(|error| ConfigError::Override {
+                            index,
+                            error: Box::new(error),
+                        }),
+                );
+            });
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn validate_language(language: &LanguageOptions, errors: &mut Vec<ConfigError>) {
+    if let Some(width) = language.indent_width_flow {
+        check_min(errors, "indentWidthFlow", width, 1);
+    }
+    if let Some(scalars) = language.extract_anchors {
+        check_min(errors, "extractAnchors", scalars, 1);
+    }
+    if language.ignore_comment_directive.trim().is_empty() {
+        errors.push(ConfigError::Empty {
+            option: "ignoreCommentDirective",
+        });
+    }
+    if language
+        .sort_documents
+        .iter()
+        .any(|key| key.trim().is_empty())
+    {
+        errors.push(ConfigError::Empty {
+            option: "sortDocuments",
+        });
+    }
+}
+
+fn check_min(errors: &mut Vec<ConfigError>, option: &'static str, value: usize, min: usize) {
+    if value < min {
+        errors.push(ConfigError::TooSmall { option, value, min });
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Invalid value of option reported by [`FormatOptions::validate`].
+///
+/// Names of options are in camel case as in configuration files.
+pub enum ConfigError {
+    /// Numeric option is less than its minimum value.
+    TooSmall {
+        option: &'static str,
+        value: usize,
+        min: usize,
+    },
+    /// String option is empty or contains only whitespaces.
+    Empty { option: &'static str },
+    /// Language options of override at the given index are invalid.
+    Override {
+        index: usize,
+        error: Box<ConfigError>,
+    },
+}
+
+impl ConfigError {
+    /// Get path of the invalid option, such as `overrides[0].indentWidthFlow`.
+    pub fn option(&self) -> String {
+        match self {
+            ConfigError::TooSmall { option, .. } | ConfigError::Empty { option } => {
+                (*option).to_owned()
+            }
+            ConfigError::Override { index, error } => {
+                format!("overrides[{index}].{}", error.option())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::TooSmall { value, min, .. } => write!(
+                f,
+                "config `{}` must be at least {min}, but got {value}",
+                self.option()
+            ),
+            ConfigError::Empty { .. } => write!(f, "config `{}` can't be empty", self.option()),
+            ConfigError::Override { index, error } => {
+                write!(f, "{error} (in `overrides[{index}]`)")
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Glob pattern for matching file paths.
+///
+/// `*` matches any characters except `/`, `?` matches single character except `/`,
+/// and `**` matches any number of directories.
+/// Patterns are matched against trailing parts of paths,
+/// so `.github/workflows/*.yml` matches `/repo/.github/workflows/ci.yml`.
+/// Backslashes in paths are treated as `/`.
+pub struct GlobPattern(String);
+
+impl GlobPattern {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self(pattern.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if the given path matches this pattern.
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>();
+        let pattern = self
+            .0
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>();
+        (0..=path.len()).any(|start| match_parts(&pattern, &path[start..]))
+    }
+}
+
+/// Match path parts with pattern parts, where `**` matches any number of parts.
+fn match_parts(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_parts(rest, &path[skip..])),
+        Some((part, rest)) => path
+            .split_first()
+            .is_some_and(|(name, path)| match_wildcard(part, name) && match_parts(rest, path)),
+    }
+}
+
+/// Match single path part with `*` and `?` wildcards.
+fn match_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // position of last `*` in pattern and the position in name it matches up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Built-in presets for specific kinds of YAML files or compatibility with other formatters.
+pub enum Preset {
+    /// Kubernetes manifests, in the style of `kubectl` output.
+    Kubernetes,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "githubActions"))]
+    /// GitHub Actions workflows.
+    GithubActions,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "dockerCompose"))]
+    /// Docker Compose files.
+    DockerCompose,
+
+    /// Emulate output of Prettier.
+    Prettier,
+
+    /// Emulate output of round-trip dumper of ruamel.yaml with default settings.
+    Ruamel,
+
+    /// Emulate output of yamlfmt with default settings.
+    Yamlfmt,
+}
+
+#[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(default))]
 /// Configuration related to layout, such as indentation or print width.
@@ -33,6 +353,31 @@ pub struct LayoutOptions {
         serde(alias = "lineBreak", alias = "linebreak")
     )]
     pub line_break: LineBreak,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "indentKind"))]
+    pub indent_kind: IndentKind,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "finalNewline"))]
+    /// Whether to end output with a line break.
+    /// It's always kept when output ends with a block scalar, since removing it changes the value.
+    pub final_newline: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "keepBom"))]
+    /// Whether to keep byte order mark at the beginning of input in output.
+    /// Byte order mark is removed if this is `false`.
+    pub keep_bom: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "tabWidth"))]
+    /// Width of tab characters for measuring columns, such as aligning trailing comments.
+    /// If this is `None`, it's the same as `indent_width`.
+    pub tab_width: Option<usize>,
+}
+
+impl LayoutOptions {
+    /// Resolve width of tab characters, falling back to `indent_width`.
+    pub(crate) fn tab_width(&self) -> usize {
+        self.tab_width.unwrap_or(self.indent_width).max(1)
+    }
 }
 
 impl Default for LayoutOptions {
@@ -41,29 +386,79 @@ impl Default for LayoutOptions {
             print_width: 80,
             indent_width: 2,
             line_break: LineBreak::Lf,
+            indent_kind: IndentKind::Space,
+            final_newline: true,
+            keep_bom: false,
+            tab_width: None,
         }
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
 pub enum LineBreak {
     #[default]
     Lf,
     Crlf,
+    /// Use the line break that appears most in the input.
+    /// LF is used when there're no line breaks or LF and CRLF appear equally.
+    Auto,
+}
+
+impl LineBreak {
+    /// Resolve line break for printing, detecting it from the given tree if it's `Auto`.
+    pub(crate) fn resolve(&self, node: &SyntaxNode) -> tiny_pretty::LineBreak {
+        match self {
+            LineBreak::Lf => tiny_pretty::LineBreak::Lf,
+            LineBreak::Crlf => tiny_pretty::LineBreak::Crlf,
+            LineBreak::Auto => {
+                let (lf, crlf) = node
+                    .descendants_with_tokens()
+                    .filter_map(SyntaxElement::into_token)
+                    .fold((0, 0), |(lf, crlf), token| {
+                        let text = token.text();
+                        let token_crlf = text.matches("\r\n").count();
+                        (
+                            lf + text.matches('\n').count() - token_crlf,
+                            crlf + token_crlf,
+                        )
+                    });
+                if crlf > lf {
+                    tiny_pretty::LineBreak::Crlf
+                } else {
+                    tiny_pretty::LineBreak::Lf
+                }
+            }
+        }
+    }
 }
 
 impl From<LineBreak> for tiny_pretty::LineBreak {
+    /// `Auto` is converted to LF since there's no input to detect from.
     fn from(value: LineBreak) -> Self {
         match value {
-            LineBreak::Lf => tiny_pretty::LineBreak::Lf,
+            LineBreak::Lf | LineBreak::Auto => tiny_pretty::LineBreak::Lf,
             LineBreak::Crlf => tiny_pretty::LineBreak::Crlf,
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+pub enum IndentKind {
+    #[default]
+    Space,
+    /// Indent with tabs, while spaces are still used for aligning with content after `-`, `?` or `:`,
+    /// and for content of block scalars and multi-line scalars.
+    ///
+    /// Tabs in indentation aren't allowed by YAML spec,
+    /// so output may not be accepted by other YAML parsers.
+    Tab,
+}
+
+#[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(default))]
 /// Configuration related to syntax.
@@ -73,8 +468,14 @@ pub struct LanguageOptions {
     #[cfg_attr(feature = "config_serde", serde(alias = "trailingComma"))]
     pub trailing_comma: bool,
 
-    #[cfg_attr(feature = "config_serde", serde(alias = "formatComments"))]
-    pub format_comments: bool,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            alias = "formatComments",
+            deserialize_with = "deserialize_format_comments"
+        )
+    )]
+    pub format_comments: FormatComments,
 
     #[cfg_attr(feature = "config_serde", serde(alias = "indentBlockSequenceInMap"))]
     pub indent_block_sequence_in_map: bool,
@@ -85,6 +486,12 @@ pub struct LanguageOptions {
     #[cfg_attr(feature = "config_serde", serde(alias = "bracketSpacing"))]
     pub bracket_spacing: bool,
 
+    #[cfg_attr(feature = "config_serde", serde(alias = "singleEntryBraceSpacing"))]
+    pub single_entry_brace_spacing: Option<bool>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "singleEntryBracketSpacing"))]
+    pub single_entry_bracket_spacing: Option<bool>,
+
     #[cfg_attr(feature = "config_serde", serde(alias = "dashSpacing"))]
     pub dash_spacing: DashSpacing,
 
@@ -115,6 +522,133 @@ pub struct LanguageOptions {
 
     #[cfg_attr(feature = "config_serde", serde(alias = "ignoreCommentDirective"))]
     pub ignore_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "dedupeKeys"))]
+    pub dedupe_keys: Option<DedupeKeys>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "renameAnchors"))]
+    pub rename_anchors: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "inlineAliases"))]
+    pub inline_aliases: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "extractAnchors"))]
+    pub extract_anchors: Option<usize>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "sortDocuments"))]
+    pub sort_documents: Vec<String>,
+
+    pub directives: Directives,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "keyOrdering"))]
+    pub key_ordering: KeyOrdering,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "normalizeBlockScalarIndent"))]
+    pub normalize_block_scalar_indent: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "emptyDocuments"))]
+    pub empty_documents: EmptyDocuments,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "explicitKeyThreshold"))]
+    pub explicit_key_threshold: Option<usize>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "indentWidthFlow"))]
+    pub indent_width_flow: Option<usize>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "blockScalarStyle"))]
+    pub block_scalar_style: BlockScalarStyle,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "reflowFoldedScalars"))]
+    pub reflow_folded_scalars: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "preferFlowForShortSequences"))]
+    pub prefer_flow_for_short_sequences: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "wrapPlainScalars"))]
+    pub wrap_plain_scalars: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "scalarQuoting"))]
+    pub scalar_quoting: ScalarQuoting,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "commentIndent"))]
+    pub comment_indent: CommentIndent,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "alignValues"))]
+    pub align_values: AlignValues,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "maxBlankLines"))]
+    pub max_blank_lines: usize,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "blankLinesBetweenTopLevelEntries")
+    )]
+    pub blank_lines_between_top_level_entries: usize,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "normalizeNull"))]
+    pub normalize_null: NormalizeNull,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "normalizeBooleanCasing"))]
+    pub normalize_boolean_casing: NormalizeBooleanCasing,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "removePlusSign"))]
+    pub remove_plus_sign: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "exponentCase"))]
+    pub exponent_case: ExponentCase,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "padLeadingZero"))]
+    pub pad_leading_zero: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "removeDigitSeparators"))]
+    pub remove_digit_separators: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "breakLongValues"))]
+    pub break_long_values: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "dedupeTagDirectives"))]
+    pub dedupe_tag_directives: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "insertDirectivesEnd"))]
+    pub insert_directives_end: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "explicitDocumentMarkers"))]
+    pub explicit_document_markers: ExplicitDocumentMarkers,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "templateSyntax"))]
+    pub template_syntax: TemplateSyntax,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "alignTrailingComments"))]
+    pub align_trailing_comments: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "commentSpace"))]
+    pub comment_space: usize,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "wrapComments"))]
+    pub wrap_comments: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "anchorNameCase"))]
+    pub anchor_name_case: AnchorNameCase,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "flattenMergeKeys"))]
+    pub flatten_merge_keys: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "sequenceIndentWidth"))]
+    /// Indent width of block sequences in block maps.
+    /// If this is `None`, it depends on `indent_block_sequence_in_map`.
+    pub sequence_indent_width: Option<usize>,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "flowCollectionLayout"))]
+    pub flow_collection_layout: FlowCollectionLayout,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "emptyValueStyle"))]
+    pub empty_value_style: EmptyValueStyle,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "yamlVersion"))]
+    pub yaml_version: YamlVersion,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "explicitKeys"))]
+    pub explicit_keys: ExplicitKeys,
 }
 
 impl Default for LanguageOptions {
@@ -122,10 +656,12 @@ impl Default for LanguageOptions {
         LanguageOptions {
             quotes: Quotes::default(),
             trailing_comma: true,
-            format_comments: false,
+            format_comments: FormatComments::default(),
             indent_block_sequence_in_map: true,
             brace_spacing: true,
             bracket_spacing: false,
+            single_entry_brace_spacing: None,
+            single_entry_bracket_spacing: None,
             dash_spacing: DashSpacing::default(),
             prefer_single_line: false,
             flow_sequence_prefer_single_line: None,
@@ -133,11 +669,132 @@ impl Default for LanguageOptions {
             trim_trailing_whitespaces: true,
             trim_trailing_zero: false,
             ignore_comment_directive: "pretty-yaml-ignore".into(),
+            dedupe_keys: None,
+            rename_anchors: false,
+            inline_aliases: false,
+            extract_anchors: None,
+            sort_documents: vec![],
+            directives: Directives::default(),
+            key_ordering: KeyOrdering::default(),
+            normalize_block_scalar_indent: false,
+            empty_documents: EmptyDocuments::default(),
+            explicit_key_threshold: None,
+            indent_width_flow: None,
+            block_scalar_style: BlockScalarStyle::default(),
+            reflow_folded_scalars: false,
+            prefer_flow_for_short_sequences: false,
+            wrap_plain_scalars: false,
+            scalar_quoting: ScalarQuoting::default(),
+            comment_indent: CommentIndent::default(),
+            align_values: AlignValues::default(),
+            max_blank_lines: 1,
+            blank_lines_between_top_level_entries: 0,
+            normalize_null: NormalizeNull::default(),
+            normalize_boolean_casing: NormalizeBooleanCasing::default(),
+            remove_plus_sign: false,
+            exponent_case: ExponentCase::default(),
+            pad_leading_zero: false,
+            remove_digit_separators: false,
+            break_long_values: false,
+            dedupe_tag_directives: false,
+            insert_directives_end: false,
+            explicit_document_markers: ExplicitDocumentMarkers::default(),
+            template_syntax: TemplateSyntax::default(),
+            align_trailing_comments: false,
+            comment_space: 0,
+            wrap_comments: false,
+            anchor_name_case: AnchorNameCase::default(),
+            flatten_merge_keys: false,
+            sequence_indent_width: None,
+            flow_collection_layout: FlowCollectionLayout::default(),
+            empty_value_style: EmptyValueStyle::default(),
+            yaml_version: YamlVersion::default(),
+            explicit_keys: ExplicitKeys::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(default))]
+/// Configuration about formatting comments.
+///
+/// When deserializing, boolean value is also accepted
+/// which only controls the `space_after_hash` option.
+pub struct FormatComments {
+    #[cfg_attr(feature = "config_serde", serde(alias = "spaceAfterHash"))]
+    /// Insert spaces after `#` if there's no whitespace.
+    pub space_after_hash: bool,
+
+    /// Number of spaces after `#` when `space_after_hash` is enabled.
+    /// Existing whitespaces after `#` are replaced with this number of spaces.
+    pub spaces: usize,
+
+    /// Whether banner comments should be formatted or not.
+    ///
+    /// Banner comments are consecutive own-line comments that contain decorative lines,
+    /// like `####`, `#----`, box-drawing characters or table rows.
+    pub banner: bool,
+
+    /// Whether the first letter of comments should be capitalized or not.
+    pub capitalize: CapitalizeComments,
+}
+
+impl Default for FormatComments {
+    fn default() -> Self {
+        Self {
+            space_after_hash: false,
+            spaces: 1,
+            banner: false,
+            capitalize: CapitalizeComments::default(),
         }
     }
 }
 
-#[derive(Clone, Debug, Default)]
+impl From<bool> for FormatComments {
+    fn from(value: bool) -> Self {
+        Self {
+            space_after_hash: value,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "config_serde")]
+fn deserialize_format_comments<'de, D>(deserializer: D) -> Result<FormatComments, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Bool(bool),
+        Options(FormatComments),
+    }
+
+    Value::deserialize(deserializer).map(|value| match value {
+        Value::Bool(value) => value.into(),
+        Value::Options(options) => options,
+    })
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+pub enum CapitalizeComments {
+    #[default]
+    /// Keep the case of comments as-is.
+    Never,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ownLine"))]
+    /// Capitalize comments that are on their own lines, and keep trailing comments as-is.
+    OwnLine,
+
+    /// Capitalize all comments.
+    Always,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
 pub enum Quotes {
@@ -157,7 +814,7 @@ pub enum Quotes {
     ForceSingle,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Hash)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
 pub enum DashSpacing {
@@ -166,3 +823,374 @@ pub enum DashSpacing {
     OneSpace,
     Indent,
 }
+
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+pub enum DedupeKeys {
+    #[cfg_attr(feature = "config_serde", serde(alias = "keepFirst"))]
+    /// Keep the first entry and remove the later entries with the same key.
+    KeepFirst,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "keepLast"))]
+    /// Keep the last entry and remove the earlier entries with the same key.
+    KeepLast,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Empty documents are documents that only have document start marker (`---`)
+/// or explicit `null` value, without any comments or directives.
+pub enum EmptyDocuments {
+    #[default]
+    /// Keep empty documents as-is.
+    Preserve,
+
+    /// Print empty documents as bare `---`.
+    Bare,
+
+    /// Print empty documents as `--- null`.
+    Null,
+
+    /// Remove empty documents from the stream.
+    Drop,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Order of entries in maps by their keys.
+pub enum KeyOrdering {
+    #[default]
+    /// Keep entries in their original order.
+    Ignore,
+
+    /// Sort entries by keys in ascending order.
+    Asc,
+
+    /// Sort entries by keys in descending order.
+    Desc,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+pub enum Directives {
+    #[default]
+    /// Keep directives as-is.
+    Preserve,
+
+    /// Add `%YAML 1.2` to documents that don't specify YAML version.
+    Explicit,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "stripRedundant"))]
+    /// Remove directives that are the same as default behavior,
+    /// such as `%YAML 1.2` or `%TAG !! tag:yaml.org,2002:`.
+    StripRedundant,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Style of block scalars.
+///
+/// Block scalars will be converted only when the value won't be changed,
+/// so block scalars with indentation indicator, more-indented lines
+/// or trailing whitespaces (when they aren't trimmed) are kept as-is.
+/// Chomping indicator is preserved, since trailing line breaks are chomped
+/// in the same way for both styles.
+pub enum BlockScalarStyle {
+    #[default]
+    /// Keep block scalars as-is.
+    Keep,
+
+    /// Convert folded block scalars (`>`) to literal block scalars (`|`).
+    Literal,
+
+    /// Convert literal block scalars (`|`) to folded block scalars (`>`).
+    Folded,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Whether to add quotes to plain scalars or remove quotes from quoted scalars.
+///
+/// Scalars will be converted only when the value won't be changed,
+/// so plain scalars like `null`, `true` or `1` are never quoted,
+/// and scalars with tags or multiple lines are kept as-is.
+pub enum ScalarQuoting {
+    #[default]
+    /// Keep scalars as-is.
+    Keep,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "quoteAll"))]
+    /// Quote all plain scalars that are strings, except keys.
+    QuoteAll,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "unquoteWhenSafe"))]
+    /// Remove quotes if the scalar can be written as plain scalar with the same value.
+    UnquoteWhenSafe,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "quoteAmbiguous"))]
+    /// Quote plain scalars that are strings in YAML 1.2 but not in YAML 1.1,
+    /// such as `no`, `on` or `1_000`.
+    QuoteAmbiguous,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Control indentation of own-line comments between entries of different nesting levels.
+pub enum CommentIndent {
+    #[default]
+    #[cfg_attr(feature = "config_serde", serde(alias = "followPrev"))]
+    /// Comments after the last entry of nested collection keep their original nesting,
+    /// so they're indented as the deepest previous collection
+    /// whose indentation isn't greater than the comment.
+    FollowPrev,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "followNext"))]
+    /// Comments are indented as the entry after them,
+    /// so comments after the last entry of nested collection are moved out of it.
+    FollowNext,
+
+    /// Comments at the beginning of line are kept at the beginning of line,
+    /// which is common for commented-out code.
+    /// Other comments are indented as `FollowPrev`.
+    Dedent,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Control whether values of consecutive entries in block maps are aligned into columns.
+pub enum AlignValues {
+    #[default]
+    /// Don't align values.
+    None,
+
+    /// Pad keys before colons, so colons and values are aligned.
+    Colon,
+
+    /// Pad after colons, so values are aligned.
+    Value,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Spelling of null values.
+///
+/// Only plain scalars that resolve to null are changed, so quoted `"null"` is kept as-is.
+/// Empty values are only recognized in block maps and block sequences.
+pub enum NormalizeNull {
+    #[default]
+    /// Keep null values as-is.
+    Keep,
+
+    /// Print null values as `~`.
+    Tilde,
+
+    /// Print null values as `null`.
+    Null,
+
+    /// Remove null values from block map entries and block sequence entries, like `key:`.
+    /// Null values in other places, such as in flow collections, are kept as-is.
+    Empty,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Casing of boolean values.
+pub enum NormalizeBooleanCasing {
+    #[default]
+    /// Keep boolean values as-is.
+    Keep,
+
+    /// Print boolean values in lowercase, like `true` and `false`.
+    Lowercase,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Casing of exponent mark in floats, like `1e3` and `1E3`.
+pub enum ExponentCase {
+    #[default]
+    /// Keep exponent mark as-is.
+    Keep,
+
+    /// Print exponent mark as `e`.
+    Lowercase,
+
+    /// Print exponent mark as `E`.
+    Uppercase,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Control whether document markers `---` and `...` are printed.
+pub enum ExplicitDocumentMarkers {
+    #[default]
+    /// Keep document markers as-is.
+    Keep,
+
+    /// Add `---` to documents that don't have it.
+    Always,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "stripWhenSingle"))]
+    /// Remove `---` and `...` when there's only one document in the stream.
+    /// Markers are kept if the document has directives or it's empty.
+    StripWhenSingle,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Syntax of template expressions embedded in YAML.
+///
+/// Template expressions are treated as opaque part of plain scalars,
+/// and scalars that contain them are never rewrapped, quoted or unquoted.
+pub enum TemplateSyntax {
+    #[default]
+    /// No template syntax.
+    None,
+
+    /// Go templates used by Helm, which use `{{ ... }}`.
+    Go,
+
+    /// Jinja2 templates used by Ansible and Salt, which use `{{ ... }}`, `{% ... %}` and `{# ... #}`.
+    Jinja2,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Case of anchor names, which is also applied to aliases that refer to them.
+pub enum AnchorNameCase {
+    #[default]
+    /// Keep anchor names as-is.
+    Keep,
+
+    /// Rename anchors like `fooBar`.
+    Camel,
+
+    /// Rename anchors like `foo-bar`.
+    Kebab,
+
+    /// Rename anchors like `foo_bar`.
+    Snake,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Control whether flow collections are placed on single line or multiple lines.
+pub enum FlowCollectionLayout {
+    #[default]
+    /// Keep collections multi-line if there's line break after the opening bracket,
+    /// otherwise place them on single line if they fit.
+    /// `prefer_single_line` options only take effect in this layout.
+    Auto,
+
+    /// Keep collections multi-line if there's any line break between entries,
+    /// otherwise place them on single line if they fit.
+    Preserve,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "singleLine"))]
+    /// Place collections on single line if they fit.
+    SingleLine,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "multiLine"))]
+    /// Always place entries of non-empty collections on separate lines.
+    MultiLine,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Spelling of empty values and null values in block map entries,
+/// which takes precedence over [`NormalizeNull`] for them.
+///
+/// Only plain scalars that resolve to null are changed, so quoted `"null"` is kept as-is.
+pub enum EmptyValueStyle {
+    #[default]
+    /// Follow [`NormalizeNull`].
+    Keep,
+
+    /// Print values as `null`, like `key: null`.
+    Null,
+
+    /// Print values as `~`, like `key: ~`.
+    Tilde,
+
+    /// Remove values, like `key:`.
+    Empty,
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+/// YAML version that decides how plain scalars are resolved,
+/// which affects options that depend on types of scalars, such as `normalizeNull` and `scalarQuoting`.
+pub enum YamlVersion {
+    #[default]
+    #[cfg_attr(feature = "config_serde", serde(rename = "auto"))]
+    /// Use the version in `%YAML` directive of each document, or YAML 1.2 if there's no directive.
+    Auto,
+
+    #[cfg_attr(feature = "config_serde", serde(rename = "1.1"))]
+    /// Treat all documents as YAML 1.1, where `yes`, `off` and `0755` aren't strings.
+    V1_1,
+
+    #[cfg_attr(feature = "config_serde", serde(rename = "1.2"))]
+    /// Treat all documents as YAML 1.2.
+    V1_2,
+}
+
+impl YamlVersion {
+    /// Resolve YAML version for the given node,
+    /// detecting it from the document that contains the node if it's `Auto`.
+    pub(crate) fn resolve(&self, node: &SyntaxNode) -> schema::YamlVersion {
+        match self {
+            YamlVersion::Auto => node
+                .ancestors()
+                .find_map(Document::cast)
+                .map(|document| schema::document_version(&document))
+                .unwrap_or_default(),
+            YamlVersion::V1_1 => schema::YamlVersion::V1_1,
+            YamlVersion::V1_2 => schema::YamlVersion::V1_2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Control whether the explicit key indicator `?` is printed.
+pub enum ExplicitKeys {
+    /// Keep `?` of existing explicit keys in block maps.
+    Keep,
+
+    #[default]
+    /// Remove `?` when it's safe to do so.
+    Never,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "alwaysForComplex"))]
+    /// Add `?` for keys of block map entries that are collections or multi-line scalars,
+    /// and remove `?` for other keys when it's safe to do so.
+    AlwaysForComplex,
+}
+
+impl From<&TemplateSyntax> for yaml_parser::TemplateSyntax {
+    fn from(value: &TemplateSyntax) -> Self {
+        match value {
+            TemplateSyntax::None => yaml_parser::TemplateSyntax::None,
+            TemplateSyntax::Go => yaml_parser::TemplateSyntax::Go,
+            TemplateSyntax::Jinja2 => yaml_parser::TemplateSyntax::Jinja2,
+        }
+    }
+}
@@ -0,0 +1,599 @@
+//! Converting YAML syntax tree to other data formats.
+//!
+//! ```
+//! use pretty_yaml::convert::to_json;
+//! use yaml_parser::ast::{AstNode, Root};
+//!
+//! let root = Root::cast(yaml_parser::parse("base: &base {port: 80}\nweb: *base\n").unwrap()).unwrap();
+//! assert_eq!(
+//!     to_json(&root).unwrap(),
+//!     "{\n  \"base\": {\n    \"port\": 80\n  },\n  \"web\": {\n    \"port\": 80\n  }\n}\n",
+//! );
+//! ```
+
+use crate::{
+    config::FormatOptions, print_tree, transform::is_non_string_plain, value::build_string,
+};
+use std::{error::Error, fmt, mem, ops::Range};
+use yaml_parser::{
+    ast::{AstNode, Block, BlockMap, BlockSeq, Document, Flow, FlowMap, FlowSeq, Properties, Root},
+    resolve::AnchorTable,
+    SyntaxError, SyntaxFactory, SyntaxKind, SyntaxNode,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Error that prevents YAML from being converted, with byte range of the node in source input.
+pub enum ConvertError {
+    /// Alias refers to an undefined anchor.
+    UndefinedAlias(Range<usize>),
+    /// Alias refers to a node that contains the alias itself.
+    RecursiveAlias(Range<usize>),
+    /// Key of map is a collection, which can't be converted to string.
+    ComplexKey(Range<usize>),
+    /// Tag isn't one of the standard tags like `!!str` or `!!int`.
+    UnsupportedTag(Range<usize>),
+    /// Number like `.inf` or `.nan` that can't be represented in the target format.
+    NonFiniteNumber(Range<usize>),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConvertError::UndefinedAlias(range) => {
+                write!(f, "alias at {range:?} refers to undefined anchor")
+            }
+            ConvertError::RecursiveAlias(range) => {
+                write!(f, "alias at {range:?} refers to node that contains itself")
+            }
+            ConvertError::ComplexKey(range) => {
+                write!(f, "key at {range:?} isn't a scalar")
+            }
+            ConvertError::UnsupportedTag(range) => write!(f, "tag at {range:?} isn't supported"),
+            ConvertError::NonFiniteNumber(range) => {
+                write!(f, "number at {range:?} isn't finite")
+            }
+        }
+    }
+}
+
+impl Error for ConvertError {}
+
+/// Convert YAML documents to JSON.
+///
+/// Aliases are replaced with their anchored nodes, and anchors are dropped.
+/// Scalars are resolved by YAML 1.2 core schema, and keys are always converted to strings.
+/// Standard tags like `!!str` are respected, while other tags are rejected.
+///
+/// Each document is printed as a JSON value with 2 spaces indentation, followed by a line break,
+/// so multiple documents are separated by line breaks.
+pub fn to_json(root: &Root) -> Result<String, ConvertError> {
+    let mut converter = JsonConverter {
+        anchors: AnchorTable::new(root),
+        output: String::new(),
+        indent: 0,
+        expanding: vec![],
+    };
+    root.documents()
+        .try_for_each(|document| converter.write_document(&document))?;
+    Ok(converter.output)
+}
+
+/// Convert JSON to YAML in block style, formatted with the given options.
+///
+/// Since JSON is a subset of YAML 1.2, input is parsed by YAML parser,
+/// so order of keys is preserved and YAML syntax like multiple documents is accepted.
+/// Objects and arrays are converted to block maps and block sequences,
+/// and strings are unquoted when they can be plain scalars without changing their types.
+/// Comments are dropped, and nodes that don't come from JSON, such as aliases or tagged nodes,
+/// are kept as-is.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, convert::from_json};
+///
+/// let input = r#"{"name": "app", "ports": [80, 443], "env": {"DEBUG": "true"}}"#;
+/// assert_eq!(
+///     from_json(input, &FormatOptions::default()).unwrap(),
+///     "name: app\nports:\n  - 80\n  - 443\nenv:\n  DEBUG: \"true\"\n",
+/// );
+/// ```
+pub fn from_json(input: &str, options: &FormatOptions) -> Result<String, SyntaxError> {
+    let root = Root::cast(yaml_parser::parse(input)?).expect("expected root node");
+    let documents = root.documents().filter_map(|document| {
+        let content = if let Some(flow) = document.flow() {
+            flow_to_block(&flow)
+        } else {
+            document.block()?.syntax().clone()
+        };
+        Some(SyntaxFactory::document(content))
+    });
+    Ok(print_tree(&SyntaxFactory::root(documents), options))
+}
+
+/// Convert flow collections to block collections recursively,
+/// and requote strings.
+fn flow_to_block(flow: &Flow) -> SyntaxNode {
+    if flow.properties().is_some() {
+        return flow.syntax().clone();
+    }
+    if let Some(flow_map) = flow.flow_map() {
+        let entries = flow_map
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| {
+                convert_pair(
+                    entry.key().and_then(|key| key.flow()),
+                    entry.value().and_then(|value| value.flow()),
+                )
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            SyntaxFactory::flow_map([]).syntax().clone()
+        } else {
+            SyntaxFactory::block_map(entries).syntax().clone()
+        }
+    } else if let Some(flow_seq) = flow.flow_seq() {
+        let items = flow_seq
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| {
+                if let Some(flow) = entry.flow() {
+                    flow_to_block(&flow)
+                } else if let Some(pair) = entry.flow_pair() {
+                    // single pair like `[a: b]` is a map
+                    SyntaxFactory::block_map([convert_pair(
+                        pair.key().and_then(|key| key.flow()),
+                        pair.value().and_then(|value| value.flow()),
+                    )])
+                    .syntax()
+                    .clone()
+                } else {
+                    null()
+                }
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            SyntaxFactory::flow_seq([]).syntax().clone()
+        } else {
+            SyntaxFactory::block_seq(items).syntax().clone()
+        }
+    } else {
+        requote(flow).syntax().clone()
+    }
+}
+
+fn convert_pair(key: Option<Flow>, value: Option<Flow>) -> (Flow, SyntaxNode) {
+    (
+        key.map_or_else(|| SyntaxFactory::plain_scalar("null"), |key| requote(&key)),
+        value.map_or_else(null, |value| flow_to_block(&value)),
+    )
+}
+
+fn null() -> SyntaxNode {
+    SyntaxFactory::plain_scalar("null").syntax().clone()
+}
+
+/// Unquote quoted scalar if it's safe, and keep other nodes as-is.
+fn requote(flow: &Flow) -> Flow {
+    if flow.properties().is_none()
+        && (flow.double_qouted_scalar().is_some() || flow.single_quoted_scalar().is_some())
+    {
+        if let Some(text) = flow.scalar_value() {
+            return build_string(&text);
+        }
+    }
+    flow.clone()
+}
+
+struct JsonConverter {
+    anchors: AnchorTable,
+    output: String,
+    indent: usize,
+    /// Anchored nodes that are being expanded by aliases, for detecting recursive aliases.
+    expanding: Vec<SyntaxNode>,
+}
+
+impl JsonConverter {
+    fn write_document(&mut self, document: &Document) -> Result<(), ConvertError> {
+        if let Some(block) = document.block() {
+            self.write_block(&block)?;
+        } else if let Some(flow) = document.flow() {
+            self.write_flow(&flow)?;
+        } else {
+            self.output.push_str("null");
+        }
+        self.output.push('\n');
+        Ok(())
+    }
+
+    fn write_node(&mut self, node: Option<SyntaxNode>) -> Result<(), ConvertError> {
+        match node {
+            Some(node) => {
+                if let Some(block) = Block::cast(node.clone()) {
+                    self.write_block(&block)
+                } else if let Some(flow) = Flow::cast(node) {
+                    self.write_flow(&flow)
+                } else {
+                    self.output.push_str("null");
+                    Ok(())
+                }
+            }
+            None => {
+                self.output.push_str("null");
+                Ok(())
+            }
+        }
+    }
+
+    fn write_block(&mut self, block: &Block) -> Result<(), ConvertError> {
+        let tag = core_tag(block.properties())?;
+        if let Some(block_map) = block.block_map() {
+            self.write_block_map(&block_map)
+        } else if let Some(block_seq) = block.block_seq() {
+            self.write_block_seq(&block_seq)
+        } else if let Some(block_scalar) = block.block_scalar() {
+            let value = block_scalar.value().unwrap_or_default();
+            match tag.as_deref() {
+                Some("int" | "float" | "bool" | "null") => {
+                    self.write_plain(value.trim(), block_scalar.syntax())
+                }
+                _ => {
+                    write_json_string(&value, &mut self.output);
+                    Ok(())
+                }
+            }
+        } else {
+            self.write_empty(tag.as_deref());
+            Ok(())
+        }
+    }
+
+    fn write_flow(&mut self, flow: &Flow) -> Result<(), ConvertError> {
+        let tag = core_tag(flow.properties())?;
+        if let Some(alias) = flow.alias() {
+            let range = node_range(alias.syntax());
+            let target = self
+                .anchors
+                .resolve_alias(&alias)
+                .ok_or(ConvertError::UndefinedAlias(range.clone()))?;
+            if self.expanding.contains(&target)
+                || alias
+                    .syntax()
+                    .ancestors()
+                    .any(|ancestor| ancestor == target)
+            {
+                return Err(ConvertError::RecursiveAlias(range));
+            }
+            self.expanding.push(target.clone());
+            let result = self.write_node(Some(target));
+            self.expanding.pop();
+            result
+        } else if let Some(flow_map) = flow.flow_map() {
+            self.write_flow_map(&flow_map)
+        } else if let Some(flow_seq) = flow.flow_seq() {
+            self.write_flow_seq(&flow_seq)
+        } else if let Some(text) = flow.scalar_value() {
+            match tag.as_deref() {
+                Some("str") => {
+                    write_json_string(&text, &mut self.output);
+                    Ok(())
+                }
+                Some("int" | "float" | "bool" | "null") => {
+                    self.write_plain(text.trim(), flow.syntax())
+                }
+                // quoted scalars and scalars with non-specific tag `!` are always strings
+                _ if flow.plain_scalar().is_none() || has_non_specific_tag(flow.properties()) => {
+                    write_json_string(&text, &mut self.output);
+                    Ok(())
+                }
+                _ => self.write_plain(&text, flow.syntax()),
+            }
+        } else {
+            self.write_empty(tag.as_deref());
+            Ok(())
+        }
+    }
+
+    /// Write node that only has properties or nothing.
+    fn write_empty(&mut self, tag: Option<&str>) {
+        self.output.push_str(match tag {
+            Some("str") => "\"\"",
+            Some("map") => "{}",
+            Some("seq") => "[]",
+            _ => "null",
+        });
+    }
+
+    fn write_plain(&mut self, text: &str, node: &SyntaxNode) -> Result<(), ConvertError> {
+        if !is_non_string_plain(text) {
+            write_json_string(text, &mut self.output);
+            return Ok(());
+        }
+        match canonical_plain(text) {
+            Some(value) => {
+                self.output.push_str(&value);
+                Ok(())
+            }
+            None => Err(ConvertError::NonFiniteNumber(node_range(node))),
+        }
+    }
+
+    fn write_block_map(&mut self, block_map: &BlockMap) -> Result<(), ConvertError> {
+        let entries = block_map.entries().map(|entry| {
+            let key = entry
+                .key()
+                .and_then(|key| key.block().map(|block| block.syntax().clone()))
+                .or_else(|| {
+                    entry
+                        .key()
+                        .and_then(|key| key.flow().map(|flow| flow.syntax().clone()))
+                });
+            let value = entry.value().and_then(|value| {
+                value
+                    .block()
+                    .map(|block| block.syntax().clone())
+                    .or_else(|| value.flow().map(|flow| flow.syntax().clone()))
+            });
+            (key, value)
+        });
+        self.write_object(entries.collect())
+    }
+
+    fn write_flow_map(&mut self, flow_map: &FlowMap) -> Result<(), ConvertError> {
+        let entries = flow_map
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| {
+                (
+                    entry
+                        .key()
+                        .and_then(|key| key.flow())
+                        .map(|flow| flow.syntax().clone()),
+                    entry
+                        .value()
+                        .and_then(|value| value.flow())
+                        .map(|flow| flow.syntax().clone()),
+                )
+            });
+        self.write_object(entries.collect())
+    }
+
+    fn write_block_seq(&mut self, block_seq: &BlockSeq) -> Result<(), ConvertError> {
+        let items = block_seq.entries().map(|entry| {
+            entry
+                .block()
+                .map(|block| block.syntax().clone())
+                .or_else(|| entry.flow().map(|flow| flow.syntax().clone()))
+        });
+        self.write_array(items.collect())
+    }
+
+    fn write_flow_seq(&mut self, flow_seq: &FlowSeq) -> Result<(), ConvertError> {
+        let items = flow_seq
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| {
+                // single pair like `[a: b]` is a map
+                entry
+                    .flow_pair()
+                    .map(|pair| pair.syntax().clone())
+                    .or_else(|| entry.flow().map(|flow| flow.syntax().clone()))
+            });
+        self.write_array(items.collect())
+    }
+
+    fn write_object(
+        &mut self,
+        entries: Vec<(Option<SyntaxNode>, Option<SyntaxNode>)>,
+    ) -> Result<(), ConvertError> {
+        if entries.is_empty() {
+            self.output.push_str("{}");
+            return Ok(());
+        }
+        self.output.push('{');
+        self.indent += 1;
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.write_line_break();
+            let key = self.key_text(key)?;
+            write_json_string(&key, &mut self.output);
+            self.output.push_str(": ");
+            self.write_node(value)?;
+        }
+        self.indent -= 1;
+        self.write_line_break();
+        self.output.push('}');
+        Ok(())
+    }
+
+    fn write_array(&mut self, items: Vec<Option<SyntaxNode>>) -> Result<(), ConvertError> {
+        if items.is_empty() {
+            self.output.push_str("[]");
+            return Ok(());
+        }
+        self.output.push('[');
+        self.indent += 1;
+        for (i, item) in items.into_iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.write_line_break();
+            match item {
+                Some(pair) if pair.kind() == SyntaxKind::FLOW_PAIR => {
+                    let key = pair
+                        .children()
+                        .find(|child| child.kind() == SyntaxKind::FLOW_MAP_KEY)
+                        .and_then(|key| key.first_child());
+                    let value = pair
+                        .children()
+                        .find(|child| child.kind() == SyntaxKind::FLOW_MAP_VALUE)
+                        .and_then(|value| value.first_child());
+                    self.write_object(vec![(key, value)])?;
+                }
+                item => self.write_node(item)?,
+            }
+        }
+        self.indent -= 1;
+        self.write_line_break();
+        self.output.push(']');
+        Ok(())
+    }
+
+    /// Convert key to string by converting it to JSON first,
+    /// so non-string scalars like `1` or `null` become their canonical text.
+    fn key_text(&mut self, key: Option<SyntaxNode>) -> Result<String, ConvertError> {
+        let Some(key) = key else {
+            return Ok("null".into());
+        };
+        let parent_output = mem::take(&mut self.output);
+        let parent_indent = mem::replace(&mut self.indent, 0);
+        let result = self.write_node(Some(key.clone()));
+        let output = mem::replace(&mut self.output, parent_output);
+        self.indent = parent_indent;
+        result?;
+        if output.starts_with(['{', '[']) {
+            Err(ConvertError::ComplexKey(node_range(&key)))
+        } else if output.starts_with('"') {
+            // the key has been escaped, so unescape it to avoid escaping twice
+            Ok(unescape_json_string(&output))
+        } else {
+            Ok(output)
+        }
+    }
+
+    fn write_line_break(&mut self) {
+        self.output.push('\n');
+        self.output.extend(std::iter::repeat_n("  ", self.indent));
+    }
+}
+
+/// Resolve plain scalar that isn't a string by YAML 1.2 core schema to its canonical JSON text,
+/// so `~` becomes `null`, `0x1F` becomes `31` and `1.50` becomes `1.5`.
+/// Returns `None` for numbers that can't be represented in JSON, like `.inf`.
+pub(crate) fn canonical_plain(text: &str) -> Option<String> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => Some("null".into()),
+        "true" | "True" | "TRUE" => Some("true".into()),
+        "false" | "False" | "FALSE" => Some("false".into()),
+        _ => {
+            if let Some(hex) = text.strip_prefix("0x") {
+                u128::from_str_radix(hex, 16).ok().map(|n| n.to_string())
+            } else if let Some(oct) = text.strip_prefix("0o") {
+                u128::from_str_radix(oct, 8).ok().map(|n| n.to_string())
+            } else if text
+                .trim_start_matches(['+', '-'])
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+            {
+                let (sign, digits) = match text.strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", text.trim_start_matches('+')),
+                };
+                let digits = digits.trim_start_matches('0');
+                Some(if digits.is_empty() {
+                    "0".to_owned()
+                } else {
+                    format!("{sign}{digits}")
+                })
+            } else {
+                text.parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .map(|number| format!("{number:?}"))
+            }
+        }
+    }
+}
+
+/// Get name of core schema tag like `str` from properties.
+/// Returns `None` if there's no tag or the tag is non-specific `!`.
+fn core_tag(properties: Option<Properties>) -> Result<Option<String>, ConvertError> {
+    let Some(tag) = properties.and_then(|properties| properties.tag_property()) else {
+        return Ok(None);
+    };
+    if tag.non_specific_tag().is_some() {
+        return Ok(None);
+    }
+    let text = tag.syntax().to_string();
+    let name = text
+        .strip_prefix("!!")
+        .or_else(|| {
+            text.strip_prefix("!<tag:yaml.org,2002:")
+                .and_then(|name| name.strip_suffix('>'))
+        })
+        .filter(|name| {
+            matches!(
+                *name,
+                "str" | "int" | "float" | "bool" | "null" | "map" | "seq"
+            )
+        });
+    match name {
+        Some(name) => Ok(Some(name.to_owned())),
+        None => Err(ConvertError::UnsupportedTag(node_range(tag.syntax()))),
+    }
+}
+
+fn has_non_specific_tag(properties: Option<Properties>) -> bool {
+    properties
+        .and_then(|properties| properties.tag_property())
+        .is_some_and(|tag| tag.non_specific_tag().is_some())
+}
+
+fn node_range(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    range.start().into()..range.end().into()
+}
+
+fn write_json_string(text: &str, output: &mut String) {
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            c if c.is_control() && (c as u32) < 0x20 => {
+                output.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Reverse [`write_json_string`].
+fn unescape_json_string(text: &str) -> String {
+    let text = &text[1..text.len() - 1];
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('b') => unescaped.push('\u{8}'),
+            Some('f') => unescaped.push('\u{c}'),
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    unescaped.push(c);
+                }
+            }
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,260 @@
+//! Line-based diff between source input and formatted output,
+//! which is useful for showing what would be changed in check mode of CI.
+
+use std::fmt;
+
+/// Default number of unchanged lines around changes.
+pub const DEFAULT_CONTEXT: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Differences between two texts, grouped into hunks with context lines.
+///
+/// It's displayed as hunks of unified diff format without file headers,
+/// so callers can print their own `---` and `+++` lines.
+///
+/// ```
+/// use pretty_yaml::diff::TextDiff;
+///
+/// let diff = TextDiff::new("a:   1\nb: 2\n", "a: 1\nb: 2\n", 3);
+/// assert_eq!(diff.to_string(), "@@ -1,2 +1,2 @@\n-a:   1\n+a: 1\n b: 2\n");
+/// ```
+pub struct TextDiff {
+    hunks: Vec<Hunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Consecutive changed lines and unchanged lines around them.
+pub struct Hunk {
+    /// 1-based line number where this hunk starts in old text.
+    pub old_start: usize,
+    pub old_len: usize,
+    /// 1-based line number where this hunk starts in new text.
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Single line in hunk, including its line break if any.
+pub enum DiffLine {
+    Context(String),
+    Delete(String),
+    Insert(String),
+}
+
+impl TextDiff {
+    /// Compute line-based diff between two texts,
+    /// with `context` unchanged lines around changes.
+    pub fn new(old: &str, new: &str, context: usize) -> Self {
+        let old = old.split_inclusive('\n').collect::<Vec<_>>();
+        let new = new.split_inclusive('\n').collect::<Vec<_>>();
+        let ops = diff_lines(&old, &new);
+
+        let mut hunks = vec![];
+        let mut index = 0;
+        while let Some(first_change) = ops[index..]
+            .iter()
+            .position(|op| !matches!(op, Op::Equal(..)))
+            .map(|position| index + position)
+        {
+            let start = first_change.saturating_sub(context).max(index);
+            // extend hunk until there're enough unchanged lines after last change
+            let mut end = first_change;
+            let mut equal_run = 0;
+            for (i, op) in ops.iter().enumerate().skip(first_change) {
+                if let Op::Equal(..) = op {
+                    equal_run += 1;
+                    if equal_run > context * 2 {
+                        break;
+                    }
+                } else {
+                    equal_run = 0;
+                    end = i;
+                }
+            }
+            let end = (end + 1 + context).min(ops.len());
+
+            let (old_start, new_start) = match ops[start] {
+                Op::Equal(old, new) | Op::Delete(old, new) | Op::Insert(old, new) => (old, new),
+            };
+            let lines = ops[start..end]
+                .iter()
+                .map(|op| match *op {
+                    Op::Equal(i, _) => DiffLine::Context(old[i].to_owned()),
+                    Op::Delete(i, _) => DiffLine::Delete(old[i].to_owned()),
+                    Op::Insert(_, j) => DiffLine::Insert(new[j].to_owned()),
+                })
+                .collect::<Vec<_>>();
+            let old_len = lines
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Insert(..)))
+                .count();
+            let new_len = lines
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Delete(..)))
+                .count();
+            hunks.push(Hunk {
+                // empty range starts at the line before it, like GNU diff
+                old_start: if old_len == 0 {
+                    old_start
+                } else {
+                    old_start + 1
+                },
+                old_len,
+                new_start: if new_len == 0 {
+                    new_start
+                } else {
+                    new_start + 1
+                },
+                new_len,
+                lines,
+            });
+            index = end;
+        }
+        TextDiff { hunks }
+    }
+
+    pub fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    /// Check if there're no differences.
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+impl fmt::Display for TextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.hunks.iter().try_for_each(|hunk| write!(f, "{hunk}"))
+    }
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "@@ -{} +{} @@",
+            HunkRange(self.old_start, self.old_len),
+            HunkRange(self.new_start, self.new_len)
+        )?;
+        self.lines.iter().try_for_each(|line| {
+            let (sign, text) = match line {
+                DiffLine::Context(text) => (' ', text),
+                DiffLine::Delete(text) => ('-', text),
+                DiffLine::Insert(text) => ('+', text),
+            };
+            match text.strip_suffix('\n') {
+                Some(text) => writeln!(f, "{sign}{}", text.strip_suffix('\r').unwrap_or(text)),
+                None => writeln!(f, "{sign}{text}\n\\ No newline at end of file"),
+            }
+        })
+    }
+}
+
+struct HunkRange(usize, usize);
+
+impl fmt::Display for HunkRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.1 == 1 {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "{},{}", self.0, self.1)
+        }
+    }
+}
+
+/// Edit operation with 0-based line indexes of old text and new text
+/// where the operation happens.
+#[derive(Clone, Copy)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize, usize),
+    Insert(usize, usize),
+}
+
+/// Compute shortest edit script with Myers' algorithm.
+/// Common prefix and suffix are skipped first,
+/// since formatting usually changes a few lines only.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut ops = (0..prefix).map(|i| Op::Equal(i, i)).collect::<Vec<_>>();
+    ops.extend(myers(old_mid, new_mid).into_iter().map(|op| match op {
+        Op::Equal(i, j) => Op::Equal(i + prefix, j + prefix),
+        Op::Delete(i, j) => Op::Delete(i + prefix, j + prefix),
+        Op::Insert(i, j) => Op::Insert(i + prefix, j + prefix),
+    }));
+    let (old_suffix, new_suffix) = (old.len() - suffix, new.len() - suffix);
+    ops.extend((0..suffix).map(|i| Op::Equal(old_suffix + i, new_suffix + i)));
+    ops
+}
+
+fn myers(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    let mut trace = vec![];
+    'search: for d in 0..=max as isize {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // backtrack from the end to build edit script
+    let mut ops = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k =
+            if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) {
+                k + 1
+            } else {
+                k - 1
+            };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(Op::Insert(x as usize, prev_y as usize));
+            } else {
+                ops.push(Op::Delete(prev_x as usize, y as usize));
+            }
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    ops.reverse();
+    ops
+}
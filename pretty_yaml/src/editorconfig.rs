@@ -0,0 +1,310 @@
+//! Reading layout options from `.editorconfig` files, available with the `editorconfig` feature.
+//!
+//! Supported properties are `indent_style`, `indent_size`, `tab_width` (also as fallback of `indent_size`),
+//! `end_of_line`, `max_line_length` and `insert_final_newline`.
+//!
+//! ```
+//! use pretty_yaml::{config::LayoutOptions, editorconfig::EditorConfig};
+//! use std::path::Path;
+//!
+//! let config = EditorConfig::parse(
+//!     "root = true\n[*]\nindent_size = 4\n[*.{yml,yaml}]\nmax_line_length = 100\n",
+//!     Path::new("ci/build.yml"),
+//! );
+//! let mut layout = LayoutOptions::default();
+//! // options which are explicitly configured won't be overridden
+//! config.apply(&mut layout, &["printWidth"]);
+//! assert_eq!(layout.indent_width, 4);
+//! assert_eq!(layout.print_width, 80);
+//! ```
+
+use crate::config::{IndentKind, LayoutOptions, LineBreak};
+use std::{fs, io, path::Path};
+
+/// Layout options read from `.editorconfig` files.
+/// Each field is `None` if it isn't specified.
+#[derive(Clone, Debug, Default)]
+pub struct EditorConfig {
+    pub indent_width: Option<usize>,
+    pub indent_kind: Option<IndentKind>,
+    pub line_break: Option<LineBreak>,
+    pub print_width: Option<usize>,
+    pub final_newline: Option<bool>,
+    pub tab_width: Option<usize>,
+    /// Whether the `.editorconfig` file has `root = true`,
+    /// so files in parent directories are ignored.
+    pub root: bool,
+}
+
+impl EditorConfig {
+    /// Read `.editorconfig` files that apply to the given file,
+    /// from its directory up to the directory that has root `.editorconfig` file.
+    ///
+    /// Properties in nearer files take precedence.
+    pub fn for_file(path: &Path) -> io::Result<Self> {
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(path)
+        };
+        let mut configs = vec![];
+        for dir in path.ancestors().skip(1) {
+            let content = match fs::read_to_string(dir.join(".editorconfig")) {
+                Ok(content) => content,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error),
+            };
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let config = EditorConfig::parse(&content, relative);
+            let is_root = config.root;
+            configs.push(config);
+            if is_root {
+                break;
+            }
+        }
+        Ok(configs
+            .into_iter()
+            .rev()
+            .fold(EditorConfig::default(), |base, config| base.merge(config)))
+    }
+
+    /// Parse content of `.editorconfig` file,
+    /// and collect properties of sections that match the given path,
+    /// which is relative to the directory of `.editorconfig` file.
+    pub fn parse(content: &str, path: &Path) -> Self {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let mut config = EditorConfig::default();
+        let mut tab_width = None;
+        // `None` means preamble before any sections
+        let mut matched = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            if let Some(section) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                matched = Some(section_matches(section, &path));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_ascii_lowercase();
+            match matched {
+                None if key == "root" => config.root = value == "true",
+                Some(true) => {
+                    let unset = value == "unset";
+                    match &*key {
+                        "indent_style" => {
+                            config.indent_kind = match &*value {
+                                "space" => Some(IndentKind::Space),
+                                "tab" => Some(IndentKind::Tab),
+                                _ => None,
+                            }
+                        }
+                        // `tab` means using `tab_width`, so leave it to fallback
+                        "indent_size" => config.indent_width = value.parse().ok(),
+                        "tab_width" => tab_width = value.parse().ok(),
+                        "end_of_line" => {
+                            config.line_break = match &*value {
+                                "lf" => Some(LineBreak::Lf),
+                                "crlf" => Some(LineBreak::Crlf),
+                                _ => None,
+                            }
+                        }
+                        "max_line_length" => config.print_width = value.parse().ok(),
+                        "insert_final_newline" if !unset => {
+                            config.final_newline = Some(value == "true")
+                        }
+                        "insert_final_newline" => config.final_newline = None,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        config.tab_width = tab_width.filter(|width| *width > 0);
+        config.indent_width = config.indent_width.or(tab_width).filter(|width| *width > 0);
+        config
+    }
+
+    /// Override layout options with properties in this config,
+    /// except for options whose names are in `explicit`.
+    ///
+    /// Names are the same as keys of configuration, like `indentWidth` or `printWidth`.
+    pub fn apply(&self, layout: &mut LayoutOptions, explicit: &[&str]) {
+        let is_explicit = |name: &str| explicit.contains(&name);
+        if let Some(indent_width) = self.indent_width.filter(|_| !is_explicit("indentWidth")) {
+            layout.indent_width = indent_width;
+        }
+        if let Some(indent_kind) = self
+            .indent_kind
+            .clone()
+            .filter(|_| !is_explicit("indentKind"))
+        {
+            layout.indent_kind = indent_kind;
+        }
+        if let Some(line_break) = self
+            .line_break
+            .clone()
+            .filter(|_| !is_explicit("lineBreak"))
+        {
+            layout.line_break = line_break;
+        }
+        if let Some(print_width) = self.print_width.filter(|_| !is_explicit("printWidth")) {
+            layout.print_width = print_width;
+        }
+        if let Some(final_newline) = self.final_newline.filter(|_| !is_explicit("finalNewline")) {
+            layout.final_newline = final_newline;
+        }
+        if let Some(tab_width) = self.tab_width.filter(|_| !is_explicit("tabWidth")) {
+            layout.tab_width = Some(tab_width);
+        }
+    }
+
+    /// Merge with another config which takes precedence.
+    fn merge(self, other: EditorConfig) -> EditorConfig {
+        EditorConfig {
+            indent_width: other.indent_width.or(self.indent_width),
+            indent_kind: other.indent_kind.or(self.indent_kind),
+            line_break: other.line_break.or(self.line_break),
+            print_width: other.print_width.or(self.print_width),
+            final_newline: other.final_newline.or(self.final_newline),
+            tab_width: other.tab_width.or(self.tab_width),
+            root: self.root || other.root,
+        }
+    }
+}
+
+/// Check if section name matches the path.
+/// Section names without `/` match file names in any directories.
+fn section_matches(section: &str, path: &str) -> bool {
+    let pattern = if let Some(pattern) = section.strip_prefix('/') {
+        pattern.to_owned()
+    } else if section.contains('/') {
+        section.to_owned()
+    } else {
+        format!("**/{section}")
+    };
+    expand_braces(&pattern)
+        .iter()
+        .any(|pattern| glob_match(pattern.as_bytes(), path.as_bytes()))
+}
+
+/// Expand `{a,b}` and `{1..3}` into multiple patterns.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(start) = pattern.find('{') else {
+        return vec![pattern.to_owned()];
+    };
+    let mut depth = 0;
+    let Some(end) = pattern[start..]
+        .char_indices()
+        .find(|(_, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })
+        .map(|(index, _)| start + index)
+    else {
+        return vec![pattern.to_owned()];
+    };
+    let (prefix, inner, suffix) = (
+        &pattern[..start],
+        &pattern[start + 1..end],
+        &pattern[end + 1..],
+    );
+
+    let alternatives = if let Some((from, to)) = inner
+        .split_once("..")
+        .and_then(|(from, to)| Some((from.parse::<i64>().ok()?, to.parse::<i64>().ok()?)))
+    {
+        (from.min(to)..=from.max(to))
+            .take(1024)
+            .map(|n| n.to_string())
+            .collect()
+    } else if inner.contains(',') {
+        let mut alternatives = vec![];
+        let mut depth = 0;
+        let mut last = 0;
+        for (index, c) in inner.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    alternatives.push(inner[last..index].to_owned());
+                    last = index + 1;
+                }
+                _ => {}
+            }
+        }
+        alternatives.push(inner[last..].to_owned());
+        alternatives
+    } else {
+        // single item in braces isn't expanded
+        vec![format!("{{{inner}}}")]
+    };
+
+    alternatives
+        .iter()
+        .flat_map(|alternative| expand_braces(&format!("{prefix}{alternative}{suffix}")))
+        .collect()
+}
+
+/// Match glob pattern with `*`, `**`, `?` and `[...]`.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            // `**/` also matches no directories
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == b'/')
+                    .any(|(index, _)| glob_match(rest, &text[index + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|index| glob_match(rest, &text[index..])),
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|index| *index == 0 || text[index - 1] != b'/')
+            .any(|index| glob_match(rest, &text[index..])),
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(c) if *c != b'/') && glob_match(rest, &text[1..])
+        }
+        [b'[', rest @ ..] => {
+            let Some(end) = rest.iter().skip(1).position(|c| *c == b']').map(|i| i + 1) else {
+                return text.first() == Some(&b'[') && glob_match(rest, &text[1..]);
+            };
+            let (negated, class) = match &rest[..end] {
+                [b'!', class @ ..] => (true, class),
+                class => (false, class),
+            };
+            let Some(c) = text.first().copied() else {
+                return false;
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == b'-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            matched != negated && c != b'/' && glob_match(&rest[end + 1..], &text[1..])
+        }
+        [b'\\', c, rest @ ..] | [c, rest @ ..] => {
+            text.first() == Some(c) && glob_match(rest, &text[1..])
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Detect character encoding of YAML stream, and convert between bytes and text.
+//!
+//! YAML streams can be encoded in UTF-8, UTF-16 or UTF-32.
+//! Encoding is detected by byte order mark or by the pattern of null bytes in the first character,
+//! as described in [YAML spec](https://yaml.org/spec/1.2.2/#52-character-encodings).
+//!
+//! ```
+//! use pretty_yaml::encoding::{decode, encode, Encoding};
+//!
+//! let bytes = b"\xff\xfea\x00:\x00 \x001\x00";
+//! let (text, encoding) = decode(bytes).unwrap();
+//! assert_eq!(encoding, Encoding::Utf16Le);
+//! assert_eq!(text, "\u{feff}a: 1");
+//! assert_eq!(encode(&text, encoding), bytes);
+//! ```
+
+use std::{error::Error, fmt};
+use yaml_parser::SyntaxError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Character encoding of YAML stream.
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    /// Detect encoding of the given bytes.
+    /// UTF-8 is assumed if it can't be detected.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0, 0, 0xfe, 0xff, ..] | [0, 0, 0, _, ..] => Encoding::Utf32Be,
+            [0xff, 0xfe, 0, 0, ..] | [_, 0, 0, 0, ..] => Encoding::Utf32Le,
+            [0xfe, 0xff, ..] | [0, _, ..] => Encoding::Utf16Be,
+            [0xff, 0xfe, ..] | [_, 0, ..] => Encoding::Utf16Le,
+            _ => Encoding::Utf8,
+        }
+    }
+
+    /// Number of bytes of each code unit.
+    fn unit_len(&self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf32Le | Encoding::Utf32Be => 4,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Encoding::Utf8 => write!(f, "UTF-8"),
+            Encoding::Utf16Le => write!(f, "UTF-16LE"),
+            Encoding::Utf16Be => write!(f, "UTF-16BE"),
+            Encoding::Utf32Le => write!(f, "UTF-32LE"),
+            Encoding::Utf32Be => write!(f, "UTF-32BE"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Error of bytes that aren't valid in detected encoding.
+pub struct DecodeError {
+    pub encoding: Encoding,
+    /// Byte offset of the first invalid code unit.
+    pub offset: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} sequence at byte offset {}",
+            self.encoding, self.offset
+        )
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Decode the given bytes with detected encoding.
+///
+/// Byte order mark is kept as `U+FEFF` at the beginning of text,
+/// so it can be preserved by the `keepBom` option.
+pub fn decode(bytes: &[u8]) -> Result<(String, Encoding), DecodeError> {
+    let encoding = Encoding::detect(bytes);
+    let unit_len = encoding.unit_len();
+    let error = |offset| DecodeError { encoding, offset };
+    let text = match encoding {
+        Encoding::Utf8 => String::from_utf8(bytes.to_vec())
+            .map_err(|err| error(err.utf8_error().valid_up_to()))?,
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let units = bytes.chunks_exact(unit_len).map(|chunk| {
+                let chunk = [chunk[0], chunk[1]];
+                if encoding == Encoding::Utf16Le {
+                    u16::from_le_bytes(chunk)
+                } else {
+                    u16::from_be_bytes(chunk)
+                }
+            });
+            let mut text = String::with_capacity(bytes.len() / unit_len);
+            let mut offset = 0;
+            for c in char::decode_utf16(units) {
+                let c = c.map_err(|_| error(offset))?;
+                text.push(c);
+                offset += c.len_utf16() * unit_len;
+            }
+            text
+        }
+        Encoding::Utf32Le | Encoding::Utf32Be => bytes
+            .chunks_exact(unit_len)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let chunk = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                let code = if encoding == Encoding::Utf32Le {
+                    u32::from_le_bytes(chunk)
+                } else {
+                    u32::from_be_bytes(chunk)
+                };
+                char::from_u32(code).ok_or_else(|| error(i * unit_len))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    if bytes.len().is_multiple_of(unit_len) {
+        Ok((text, encoding))
+    } else {
+        Err(error(bytes.len() - bytes.len() % unit_len))
+    }
+}
+
+/// Encode the given text in the given encoding.
+///
+/// Byte order mark is written only if text starts with `U+FEFF`.
+pub fn encode(text: &str, encoding: Encoding) -> Vec<u8> {
+    match encoding {
+        Encoding::Utf8 => text.as_bytes().to_vec(),
+        Encoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        Encoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        Encoding::Utf32Le => text
+            .chars()
+            .flat_map(|c| (c as u32).to_le_bytes())
+            .collect(),
+        Encoding::Utf32Be => text
+            .chars()
+            .flat_map(|c| (c as u32).to_be_bytes())
+            .collect(),
+    }
+}
+
+#[derive(Debug)]
+/// Error that prevents bytes from being formatted by [`format_bytes`](crate::format_bytes).
+pub enum FormatBytesError {
+    /// Source input can't be decoded.
+    Decode(DecodeError),
+    /// Source input can't be parsed.
+    Syntax(SyntaxError),
+}
+
+impl fmt::Display for FormatBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatBytesError::Decode(err) => write!(f, "{err}"),
+            FormatBytesError::Syntax(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for FormatBytesError {}
+
+impl From<DecodeError> for FormatBytesError {
+    fn from(err: DecodeError) -> Self {
+        FormatBytesError::Decode(err)
+    }
+}
+
+impl From<SyntaxError> for FormatBytesError {
+    fn from(err: SyntaxError) -> Self {
+        FormatBytesError::Syntax(err)
+    }
+}
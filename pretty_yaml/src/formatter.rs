@@ -0,0 +1,164 @@
+use crate::{config::FormatOptions, print_tree_with_warnings, restore_bom, FormatWarning};
+use rowan::GreenNode;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+use yaml_parser::{
+    ast::{AstNode, Root},
+    SyntaxError, SyntaxNode,
+};
+
+const DEFAULT_CAPACITY: usize = 64;
+
+/// Formatter that caches syntax trees and formatted output,
+/// which is useful for language servers or file watchers
+/// that format the same documents repeatedly.
+///
+/// Syntax trees are keyed by hash of content and hash of template syntax which affects parsing,
+/// and formatted output is keyed by hash of content and hash of options.
+/// When number of cached documents exceeds capacity, the whole cache will be cleared.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, Formatter};
+///
+/// let mut formatter = Formatter::new(FormatOptions::default());
+/// assert_eq!(formatter.format("a:   1").unwrap(), "a: 1\n");
+/// // this won't parse and print again
+/// assert_eq!(formatter.format("a:   1").unwrap(), "a: 1\n");
+/// ```
+pub struct Formatter {
+    options: FormatOptions,
+    options_hash: u64,
+    syntax_hash: u64,
+    capacity: usize,
+    trees: HashMap<(u64, u64), GreenNode>,
+    outputs: HashMap<(u64, u64), (String, Vec<FormatWarning>)>,
+}
+
+impl Formatter {
+    /// Create formatter with the given options.
+    pub fn new(options: FormatOptions) -> Self {
+        Self::with_capacity(options, DEFAULT_CAPACITY)
+    }
+
+    /// Create formatter with the given options and maximum number of cached documents.
+    pub fn with_capacity(options: FormatOptions, capacity: usize) -> Self {
+        Self {
+            options_hash: hash(&options),
+            syntax_hash: hash(&options.language.template_syntax),
+            options,
+            capacity,
+            trees: HashMap::new(),
+            outputs: HashMap::new(),
+        }
+    }
+
+    /// Current format options.
+    pub fn options(&self) -> &FormatOptions {
+        &self.options
+    }
+
+    /// Change format options.
+    ///
+    /// Cached syntax trees are still valid unless template syntax is changed,
+    /// and cached output for previous options will be reused if those options are set back.
+    pub fn set_options(&mut self, options: FormatOptions) {
+        self.options_hash = hash(&options);
+        self.syntax_hash = hash(&options.language.template_syntax);
+        self.options = options;
+    }
+
+    /// Parse the given source input, or get the cached syntax tree.
+    pub fn parse(&mut self, input: &str) -> Result<Root, SyntaxError> {
+        let content_hash = hash(input);
+        let tree = match self.cached_tree(content_hash, input) {
+            Some(tree) => tree,
+            None => {
+                let tree = yaml_parser::parse_with_template_syntax(
+                    input,
+                    (&self.options.language.template_syntax).into(),
+                )?;
+                self.insert_green(content_hash, tree.green().into_owned());
+                tree
+            }
+        };
+        Ok(Root::cast(tree).expect("expected root node"))
+    }
+
+    /// Format the given source input, or get the cached output.
+    pub fn format(&mut self, input: &str) -> Result<String, SyntaxError> {
+        self.format_with_warnings(input).map(|(output, _)| output)
+    }
+
+    /// Format the given source input and report warnings, or get the cached result.
+    ///
+    /// See [`format_text_with_warnings`](crate::format_text_with_warnings) for details.
+    pub fn format_with_warnings(
+        &mut self,
+        input: &str,
+    ) -> Result<(String, Vec<FormatWarning>), SyntaxError> {
+        let content_hash = hash(input);
+        let key = (content_hash, self.options_hash);
+        if self.outputs.contains_key(&key) && self.cached_tree(content_hash, input).is_some() {
+            return Ok(self.outputs[&key].clone());
+        }
+        let root = self.parse(input)?;
+        let (output, warnings) = print_tree_with_warnings(&root, &self.options);
+        let result = (restore_bom(input, output, &self.options), warnings);
+        self.outputs.insert(key, result.clone());
+        Ok(result)
+    }
+
+    /// Put syntax tree which is produced somewhere else into cache,
+    /// so the next formatting of the same content doesn't need to parse again.
+    /// The tree is expected to be parsed with template syntax of current options.
+    ///
+    /// This is useful when syntax tree is updated by
+    /// [`Transaction`](yaml_parser::Transaction) instead of parsing from scratch.
+    pub fn insert_tree(&mut self, root: &Root) {
+        let content_hash = hash(&*root.syntax().to_string());
+        self.insert_green(content_hash, root.syntax().green().into_owned());
+    }
+
+    /// Remove cached syntax tree and output of the given source input.
+    pub fn invalidate(&mut self, input: &str) {
+        let content_hash = hash(input);
+        self.trees.retain(|(hash, _), _| *hash != content_hash);
+        self.outputs.retain(|(hash, _), _| *hash != content_hash);
+    }
+
+    /// Remove all cached syntax trees and output.
+    pub fn clear(&mut self) {
+        self.trees.clear();
+        self.outputs.clear();
+    }
+
+    /// Get cached syntax tree only if its content is same as input,
+    /// in case of hash collision.
+    fn cached_tree(&self, content_hash: u64, input: &str) -> Option<SyntaxNode> {
+        // byte order mark is skipped by parser
+        let input = input.trim_start_matches('\u{feff}');
+        self.trees
+            .get(&(content_hash, self.syntax_hash))
+            .map(|green| SyntaxNode::new_root(green.clone()))
+            .filter(|tree| tree.text() == input)
+    }
+
+    fn insert_green(&mut self, content_hash: u64, green: GreenNode) {
+        let key = (content_hash, self.syntax_hash);
+        if self.trees.len() >= self.capacity && !self.trees.contains_key(&key) {
+            self.clear();
+        }
+        // output of replaced tree may be produced from different content
+        if self.trees.insert(key, green).is_some() {
+            self.outputs.retain(|(hash, _), _| *hash != content_hash);
+        }
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
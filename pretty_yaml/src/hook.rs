@@ -0,0 +1,39 @@
+use tiny_pretty::Doc;
+use yaml_parser::{SyntaxKind, SyntaxNode};
+
+/// Hook that wraps or replaces generated [`Doc`] of syntax nodes,
+/// so formatting can be tweaked without adding options.
+///
+/// It's called for every node after its doc is generated, from inner nodes to outer nodes,
+/// so doc passed to a hook for a node already contains changes made for its descendants.
+/// Nodes that are kept as-is, such as nodes ignored by comment directive, won't be passed to hooks.
+/// Closures like `Fn(SyntaxKind, &SyntaxNode, Doc<'static>) -> Doc<'static>` implement this trait.
+/// Since docs passed to hooks own their texts instead of borrowing source input,
+/// formatting with a hook allocates more than formatting without it.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_text_with_hook, Doc};
+/// use yaml_parser::{SyntaxKind, SyntaxNode};
+///
+/// let hook = |kind: SyntaxKind, _: &SyntaxNode, doc: Doc<'static>| match kind {
+///     SyntaxKind::FLOW_SEQ => Doc::text("/* seq */ ").append(doc),
+///     _ => doc,
+/// };
+/// let output = format_text_with_hook("a:   [1,  2]\n", &FormatOptions::default(), &hook).unwrap();
+/// assert_eq!(output, "a: /* seq */ [1, 2]\n");
+/// ```
+pub trait FormatHook {
+    /// Return doc for the given node.
+    ///
+    /// Returning `doc` directly means keeping the default formatting.
+    fn on_node(&self, kind: SyntaxKind, node: &SyntaxNode, doc: Doc<'static>) -> Doc<'static>;
+}
+
+impl<F> FormatHook for F
+where
+    F: Fn(SyntaxKind, &SyntaxNode, Doc<'static>) -> Doc<'static>,
+{
+    fn on_node(&self, kind: SyntaxKind, node: &SyntaxNode, doc: Doc<'static>) -> Doc<'static> {
+        self(kind, node, doc)
+    }
+}
@@ -1,39 +1,454 @@
 #![doc = include_str!("../README.md")]
 
+pub use crate::{
+    classifier::{PathSegment, ScalarClassifier, ScalarStyle},
+    formatter::Formatter,
+    hook::FormatHook,
+    range::{format_range, RangeFormatResult},
+    warning::{FormatWarning, FormatWarningKind},
+};
 use crate::{
-    config::FormatOptions,
-    printer::{Ctx, DocGen},
+    config::{AnchorNameCase, EmptyDocuments, FormatOptions, KeyOrdering},
+    printer::{align_comments, align_entries, has_ignore_file_directive, Ctx, DocGen, Source},
 };
+use std::iter;
+/// Re-exported for building docs in [`FormatHook`].
+pub use tiny_pretty::Doc;
 use tiny_pretty::{print, IndentKind, PrintOptions};
 use yaml_parser::{
     ast::{AstNode, Root},
-    SyntaxError,
+    SyntaxError, SyntaxKind, SyntaxNode, TemplateSyntax,
 };
 
+mod block_scalar;
+mod classifier;
 pub mod config;
+pub mod convert;
+pub mod diff;
+#[cfg(feature = "editorconfig")]
+pub mod editorconfig;
+pub mod encoding;
+mod formatter;
+mod hook;
+pub mod mapping;
+#[cfg(feature = "patch")]
+pub mod patch;
 mod printer;
+mod range;
+mod tab;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transform;
+mod value;
+pub mod verify;
+mod warning;
 
 /// Format the given source input.
+///
+/// Options should be valid as checked by [`FormatOptions::validate`],
+/// which is asserted in debug builds.
 pub fn format_text(input: &str, options: &FormatOptions) -> Result<String, SyntaxError> {
-    let syntax = yaml_parser::parse(input)?;
+    debug_assert!(
+        options.validate().is_ok(),
+        "invalid options: {:?}",
+        options.validate()
+    );
+    format_text_with_warnings(input, options).map(|(output, _)| output)
+}
+
+/// Format the given bytes of source input, which can be encoded in UTF-8, UTF-16 or UTF-32.
+///
+/// Encoding is detected as [`encoding`] module describes,
+/// and output is encoded in the same encoding.
+/// Byte order mark of UTF-16 and UTF-32 input is always kept,
+/// since some readers rely on it to detect encoding,
+/// while byte order mark of UTF-8 input is kept only if the `keepBom` option is enabled.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_bytes};
+///
+/// let input = b"\xff\xfea\x00:\x00 \x00 \x001\x00";
+/// let output = format_bytes(input, &FormatOptions::default()).unwrap();
+/// assert_eq!(output, b"\xff\xfea\x00:\x00 \x001\x00\n\x00");
+/// ```
+pub fn format_bytes(
+    input: &[u8],
+    options: &FormatOptions,
+) -> Result<Vec<u8>, encoding::FormatBytesError> {
+    let (input, encoding) = encoding::decode(input)?;
+    let output = format_text(&input, options)?;
+    let output = if encoding != encoding::Encoding::Utf8
+        && input.starts_with('\u{feff}')
+        && !output.starts_with('\u{feff}')
+    {
+        format!("\u{feff}{output}")
+    } else {
+        output
+    };
+    Ok(encoding::encode(&output, encoding))
+}
+
+/// Format the given source input, and report warnings about changes
+/// that may be unexpected, such as removed duplicate keys.
+pub fn format_text_with_warnings(
+    input: &str,
+    options: &FormatOptions,
+) -> Result<(String, Vec<FormatWarning>), SyntaxError> {
+    let syntax = parse(input, options)?;
+    let root = Root::cast(syntax).expect("expected root node");
+    let (output, warnings) = print_tree_with_warnings(&root, options);
+    Ok((restore_bom(input, output, options), warnings))
+}
+
+/// Format the given source input, and compute differences between input and formatted output.
+///
+/// It returns `None` if the input is already formatted.
+/// The diff has [`DEFAULT_CONTEXT`](diff::DEFAULT_CONTEXT) lines of context,
+/// and it can be displayed as hunks of unified diff format.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_diff};
+///
+/// let options = FormatOptions::default();
+/// let diff = format_diff("a:   1\nb: 2\n", &options).unwrap().unwrap();
+/// assert_eq!(diff.to_string(), "@@ -1,2 +1,2 @@\n-a:   1\n+a: 1\n b: 2\n");
+/// assert!(format_diff("a: 1\n", &options).unwrap().is_none());
+/// ```
+pub fn format_diff(
+    input: &str,
+    options: &FormatOptions,
+) -> Result<Option<diff::TextDiff>, SyntaxError> {
+    let output = format_text(input, options)?;
+    if output == input {
+        Ok(None)
+    } else {
+        Ok(Some(diff::TextDiff::new(
+            input,
+            &output,
+            diff::DEFAULT_CONTEXT,
+        )))
+    }
+}
+
+/// Format the given source input, and let the classifier decide
+/// how scalars are formatted by their key paths.
+///
+/// See [`ScalarClassifier`] for details.
+pub fn format_text_with(
+    input: &str,
+    options: &FormatOptions,
+    classifier: &impl ScalarClassifier,
+) -> Result<String, SyntaxError> {
+    let syntax = parse(input, options)?;
     let root = Root::cast(syntax).expect("expected root node");
-    Ok(print_tree(&root, options))
+    let output = print_root(&root, options, Some(classifier), None).0;
+    Ok(restore_bom(input, output, options))
+}
+
+/// Format the given source input, and let the hook wrap or replace
+/// generated docs of syntax nodes.
+///
+/// See [`FormatHook`] for details.
+pub fn format_text_with_hook(
+    input: &str,
+    options: &FormatOptions,
+    hook: &impl FormatHook,
+) -> Result<String, SyntaxError> {
+    let syntax = parse(input, options)?;
+    let root = Root::cast(syntax).expect("expected root node");
+    let output = print_root(&root, options, None, Some(hook)).0;
+    Ok(restore_bom(input, output, options))
+}
+
+/// Parse the given source input, and retry with missing directives end markers inserted
+/// if the `insertDirectivesEnd` option is enabled.
+fn parse(input: &str, options: &FormatOptions) -> Result<yaml_parser::SyntaxNode, SyntaxError> {
+    let template_syntax = (&options.language.template_syntax).into();
+    yaml_parser::parse_with_template_syntax(input, template_syntax).or_else(|err| {
+        options
+            .language
+            .insert_directives_end
+            .then(|| transform::insert_directives_end(input))
+            .flatten()
+            .and_then(|input| yaml_parser::parse_with_template_syntax(&input, template_syntax).ok())
+            .ok_or(err)
+    })
+}
+
+/// Format the given source input, and build a [`SourceMap`](mapping::SourceMap)
+/// for mapping offsets of source input to offsets of formatted output.
+pub fn format_text_with_source_map(
+    input: &str,
+    options: &FormatOptions,
+) -> Result<(String, mapping::SourceMap), SyntaxError> {
+    let syntax =
+        yaml_parser::parse_with_template_syntax(input, (&options.language.template_syntax).into())?;
+    let root = Root::cast(syntax.clone()).expect("expected root node");
+    let output = print_tree(&root, options);
+    // byte order mark is skipped by parser
+    let bom = input.len() - input.trim_start_matches('\u{feff}').len();
+    let source_map = mapping::SourceMap::new(&syntax, &output);
+    let output = restore_bom(input, output, options);
+    let kept_bom = if output.starts_with('\u{feff}') {
+        '\u{feff}'.len_utf8()
+    } else {
+        0
+    };
+    Ok((output, source_map.shift(bom, kept_bom)))
+}
+
+/// Format each document in the given YAML stream independently.
+///
+/// Documents are parsed lazily by [`yaml_parser::parse_documents`],
+/// so formatting can be stopped at the first document with syntax error
+/// without parsing the rest of stream.
+/// Comments after the last document are formatted with the last document,
+/// so concatenating all the formatted documents produces the whole stream.
+///
+/// Options that work across documents such as `sortDocuments` don't take effect.
+/// If the first document has ignore directive for the whole file,
+/// all the documents will be kept as-is.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_documents};
+///
+/// let options = FormatOptions::default();
+/// let mut documents = format_documents("a:   1\n---\nb:   2\n# end\n", &options);
+/// assert_eq!(documents.next().unwrap().unwrap(), "a: 1\n");
+/// assert_eq!(documents.next().unwrap().unwrap(), "---\nb: 2\n# end\n");
+/// assert!(documents.next().is_none());
+/// ```
+pub fn format_documents<'a>(
+    input: &'a str,
+    options: &'a FormatOptions,
+) -> impl Iterator<Item = Result<String, SyntaxError>> + 'a {
+    let mut ignored = None;
+    let mut is_first = true;
+    yaml_parser::parse_documents(input).map(move |document| {
+        let root = document?
+            .syntax()
+            .parent()
+            .and_then(Root::cast)
+            .expect("expected root node");
+        // ignore directive for the whole file can only be found in the first document
+        let output = if *ignored.get_or_insert_with(|| {
+            has_ignore_file_directive(root.syntax(), &options.language.ignore_comment_directive)
+        }) {
+            root.syntax().to_string()
+        } else {
+            print_tree(&root, options)
+        };
+        // byte order mark only belongs to the first document
+        if is_first {
+            is_first = false;
+            Ok(restore_bom(input, output, options))
+        } else {
+            Ok(output)
+        }
+    })
+}
+
+#[cfg(feature = "serde_json")]
+/// Format the given JSON value as YAML.
+///
+/// Syntax tree is built from the value directly, so there's no intermediate YAML text.
+pub fn format_value(value: &serde_json::Value, options: &FormatOptions) -> String {
+    print_tree(&value::build_root(value), options)
 }
 
 /// Print the given concrete syntax tree.
 /// You may use this when you already have the parsed CST.
 pub fn print_tree(root: &Root, options: &FormatOptions) -> String {
-    let ctx = Ctx {
-        indent_width: options.layout.indent_width,
-        options: &options.language,
-    };
-    print(
-        &root.doc(&ctx),
-        &PrintOptions {
-            indent_kind: IndentKind::Space,
-            line_break: options.layout.line_break.clone().into(),
-            width: options.layout.print_width,
-            tab_size: options.layout.indent_width,
-        },
-    )
+    print_tree_with_warnings(root, options).0
+}
+
+/// Print the given concrete syntax tree, and report warnings like [`format_text_with_warnings`].
+pub fn print_tree_with_warnings(
+    root: &Root,
+    options: &FormatOptions,
+) -> (String, Vec<FormatWarning>) {
+    print_root(root, options, None, None)
+}
+
+fn print_root(
+    root: &Root,
+    options: &FormatOptions,
+    classifier: Option<&dyn ScalarClassifier>,
+    hook: Option<&dyn FormatHook>,
+) -> (String, Vec<FormatWarning>) {
+    if has_ignore_file_directive(root.syntax(), &options.language.ignore_comment_directive) {
+        return (root.syntax().to_string(), vec![]);
+    }
+    let mut warnings = vec![];
+    warning::check(root.syntax(), options, &mut warnings);
+    if options.language.dedupe_keys.is_none() {
+        transform::report_duplicate_keys(root, &mut warnings);
+    }
+    let original = root;
+    let template_syntax = (&options.language.template_syntax).into();
+    let flattened = if options.language.flatten_merge_keys {
+        transform::flatten_merge_keys(
+            root,
+            &options.language.ignore_comment_directive,
+            template_syntax,
+        )
+    } else {
+        None
+    };
+    let root = flattened.as_ref().unwrap_or(root);
+    let inlined = if options.language.inline_aliases {
+        transform::inline_aliases(
+            root,
+            &options.language.ignore_comment_directive,
+            template_syntax,
+            &mut warnings,
+        )
+    } else {
+        None
+    };
+    let root = inlined.as_ref().unwrap_or(root);
+    let normalized = transform::normalize_scalars(root, &options.language);
+    let root = normalized.as_ref().unwrap_or(root);
+    let extracted = options.language.extract_anchors.and_then(|threshold| {
+        transform::extract_anchors(
+            root,
+            threshold,
+            &options.language.ignore_comment_directive,
+            template_syntax,
+        )
+    });
+    let root = extracted.as_ref().unwrap_or(root);
+    let sorted = if options.language.sort_documents.is_empty() {
+        None
+    } else {
+        transform::sort_documents(root, &options.language.sort_documents, template_syntax)
+    };
+    let root = sorted.as_ref().unwrap_or(root);
+    let sorted_keys = match options.language.key_ordering {
+        KeyOrdering::Ignore => None,
+        KeyOrdering::Asc | KeyOrdering::Desc => transform::sort_keys(
+            root,
+            &options.language.key_ordering,
+            &options.language.ignore_comment_directive,
+            template_syntax,
+        ),
+    };
+    let root = sorted_keys.as_ref().unwrap_or(root);
+    let mut removed_entries = options
+        .language
+        .dedupe_keys
+        .as_ref()
+        .map(|mode| {
+            if root.syntax() == original.syntax() {
+                transform::dedupe_keys(root, mode, &mut warnings)
+            } else {
+                // tree has been reparsed by previous transformations,
+                // so ranges of warnings are computed against the original tree
+                transform::dedupe_keys(original, mode, &mut warnings);
+                transform::dedupe_keys(root, mode, &mut vec![])
+            }
+        })
+        .unwrap_or_default();
+    if let EmptyDocuments::Drop = options.language.empty_documents {
+        removed_entries.extend(transform::empty_documents(
+            root,
+            &options.language.ignore_comment_directive,
+        ));
+    }
+    let renamed_anchors = if options.language.rename_anchors {
+        transform::rename_anchors(root, &options.language.ignore_comment_directive)
+    } else if !matches!(options.language.anchor_name_case, AnchorNameCase::Keep) {
+        transform::change_anchor_name_case(
+            root,
+            &options.language.anchor_name_case,
+            &options.language.ignore_comment_directive,
+        )
+    } else {
+        Default::default()
+    };
+    let print_options = PrintOptions {
+        indent_kind: IndentKind::Space,
+        line_break: options.layout.line_break.resolve(original.syntax()),
+        width: options.layout.print_width,
+        tab_size: options.layout.tab_width(),
+    };
+    let output = match hook {
+        Some(hook) => {
+            // docs passed to hook must own their texts, so they can't borrow source text
+            let hook = |kind, node: &SyntaxNode, doc| hook.on_node(kind, node, doc);
+            let ctx = Ctx {
+                removed_entries,
+                renamed_anchors,
+                classifier,
+                hook: Some(&hook),
+                ..Ctx::new(options)
+            };
+            print_doc(root, ctx, &print_options)
+        }
+        None => {
+            let source = root.syntax().green().to_string();
+            let ctx = Ctx {
+                removed_entries,
+                renamed_anchors,
+                classifier,
+                source: Some(Source {
+                    text: &source,
+                    offset: root.syntax().text_range().start(),
+                }),
+                ..Ctx::new(options)
+            };
+            print_doc(root, ctx, &print_options)
+        }
+    };
+    let output = match options.layout.indent_kind {
+        config::IndentKind::Space => output,
+        config::IndentKind::Tab => {
+            tab::indent_with_tabs(&output, options.layout.indent_width, template_syntax)
+        }
+    };
+    let output = if options.layout.final_newline {
+        output
+    } else {
+        remove_final_newline(output, template_syntax)
+    };
+    warnings.sort_by_key(|warning| warning.range().start);
+    (output, warnings)
+}
+
+/// Generate doc of the whole tree and print it.
+fn print_doc(root: &Root, mut ctx: Ctx, print_options: &PrintOptions) -> String {
+    ctx.aligned_entries = align_entries(root.syntax(), &ctx);
+    ctx.aligned_comments = align_comments(root.syntax(), print_options.width, &ctx);
+    print(&root.doc(&ctx), print_options)
+}
+
+/// Put byte order mark of source input back to output if the `keepBom` option is enabled,
+/// since it's skipped by parser.
+fn restore_bom(input: &str, output: String, options: &FormatOptions) -> String {
+    if options.layout.keep_bom && input.starts_with('\u{feff}') {
+        let mut with_bom = String::with_capacity(output.len() + '\u{feff}'.len_utf8());
+        with_bom.push('\u{feff}');
+        with_bom.push_str(&output);
+        with_bom
+    } else {
+        output
+    }
+}
+
+/// Remove the last line break of output unless it ends with a block scalar,
+/// whose value contains that line break.
+fn remove_final_newline(mut output: String, template_syntax: TemplateSyntax) -> String {
+    let ends_with_block_scalar = yaml_parser::parse_with_template_syntax(&output, template_syntax)
+        .is_ok_and(|tree| {
+            tree.last_token()
+                .into_iter()
+                .flat_map(|token| iter::successors(Some(token), |token| token.prev_token()))
+                .find(|token| token.kind() != SyntaxKind::WHITESPACE)
+                .is_some_and(|token| token.kind() == SyntaxKind::BLOCK_SCALAR_TEXT)
+        });
+    if !ends_with_block_scalar {
+        let len = output.trim_end_matches(['\n', '\r']).len();
+        output.truncate(len);
+    }
+    output
 }
@@ -0,0 +1,213 @@
+//! Utilities for mapping positions between source input and formatted output.
+//!
+//! These are useful for editor integrations that need to restore cursor
+//! or re-anchor diagnostics after formatting.
+
+use std::ops::Range;
+use yaml_parser::{SyntaxKind, SyntaxNode};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Mapping from byte offsets of source input to byte offsets of formatted output.
+///
+/// It's built by matching tokens of source input with formatted output in order,
+/// where whitespaces are ignored.
+/// Tokens that are changed by formatter (for example, quotes are converted)
+/// or moved by tree transformations (for example, keys are sorted) can't be matched,
+/// so offsets inside them are mapped to the end of previous matched token.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_text_with_source_map};
+///
+/// let input = "a:   [ b,c ]\n";
+/// let (output, source_map) =
+///     format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+/// assert_eq!(output, "a: [b, c]\n");
+/// assert_eq!(source_map.map_old_to_new(9), 7);
+/// ```
+pub struct SourceMap {
+    /// Pairs of old range and new range of matched non-whitespace text, sorted by old range.
+    segments: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl SourceMap {
+    /// Build source map from syntax tree of source input and formatted output.
+    ///
+    /// The tree should be the original tree parsed from source input,
+    /// not the one transformed by formatter.
+    pub fn new(tree: &SyntaxNode, output: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut cursor = 0;
+        tree.descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| token.kind() != SyntaxKind::WHITESPACE)
+            .for_each(|token| {
+                let text = token.text();
+                let start = usize::from(token.text_range().start());
+                if let Some(runs) = candidates(output, cursor)
+                    .find_map(|position| match_runs(text, output, position))
+                {
+                    cursor = runs.last().map_or(cursor, |(_, new)| new.end);
+                    segments.extend(
+                        runs.into_iter()
+                            .map(|(old, new)| (old.start + start..old.end + start, new)),
+                    );
+                }
+            });
+        Self { segments }
+    }
+
+    /// Shift offsets when the tree doesn't start at the beginning of source input,
+    /// or formatted output has something before the printed tree, such as byte order mark.
+    pub(crate) fn shift(mut self, old_offset: usize, new_offset: usize) -> Self {
+        if old_offset > 0 || new_offset > 0 {
+            self.segments.iter_mut().for_each(|(old, new)| {
+                *old = old.start + old_offset..old.end + old_offset;
+                *new = new.start + new_offset..new.end + new_offset;
+            });
+        }
+        self
+    }
+
+    /// Map byte offset of source input to byte offset of formatted output.
+    ///
+    /// Offsets between tokens are mapped to the end of previous token.
+    pub fn map_old_to_new(&self, offset: usize) -> usize {
+        let index = self
+            .segments
+            .partition_point(|(old, _)| old.start <= offset);
+        match index
+            .checked_sub(1)
+            .and_then(|index| self.segments.get(index))
+        {
+            Some((old, new)) if offset < old.end => new.start + (offset - old.start),
+            Some((_, new)) => new.end,
+            None => 0,
+        }
+    }
+}
+
+/// Positions in output where the next token may start:
+/// the first non-whitespace character after cursor, or other ones in the same line or next line,
+/// so tokens that are changed by formatter can be skipped.
+fn candidates(output: &str, cursor: usize) -> impl Iterator<Item = usize> + '_ {
+    let rest = &output[cursor..];
+    let window_end = rest
+        .match_indices('\n')
+        .nth(1)
+        .map_or(rest.len(), |(index, _)| index);
+    rest[..window_end]
+        .char_indices()
+        .filter(|(index, c)| {
+            // don't start matching in the middle of a word
+            !c.is_whitespace()
+                && rest[..*index]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|prev| !prev.is_alphanumeric() || !c.is_alphanumeric())
+        })
+        .map(move |(index, _)| cursor + index)
+}
+
+/// Match non-whitespace runs of token text with output at the given position,
+/// where whitespaces between runs can be any whitespaces in output.
+/// Returns ranges of runs in token and ranges in output.
+#[allow(clippy::type_complexity)]
+fn match_runs(
+    text: &str,
+    output: &str,
+    position: usize,
+) -> Option<Vec<(Range<usize>, Range<usize>)>> {
+    let mut runs = Vec::new();
+    let mut new_offset = position;
+    for (old_start, run) in split_runs(text) {
+        if !runs.is_empty() {
+            let rest = &output[new_offset..];
+            let trimmed = rest.trim_start();
+            if trimmed.len() == rest.len() {
+                return None;
+            }
+            new_offset += rest.len() - trimmed.len();
+        }
+        if !output[new_offset..].starts_with(run) {
+            return None;
+        }
+        runs.push((
+            old_start..old_start + run.len(),
+            new_offset..new_offset + run.len(),
+        ));
+        new_offset += run.len();
+    }
+    Some(runs)
+}
+
+fn split_runs(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |run| (run.as_ptr() as usize - text.as_ptr() as usize, run))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// Zero-based line and column, where column is counted in UTF-16 code units
+/// as Language Server Protocol does.
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+#[derive(Clone, Debug)]
+/// Index of line starts in text, for converting between byte offsets and line/column.
+///
+/// ```
+/// use pretty_yaml::mapping::{LineCol, LineIndex};
+///
+/// let index = LineIndex::new("a: 1\nb: 😀c\n");
+/// assert_eq!(index.line_col(5), LineCol { line: 1, col: 0 });
+/// assert_eq!(index.line_col(12), LineCol { line: 1, col: 5 });
+/// assert_eq!(index.offset(LineCol { line: 1, col: 5 }), 12);
+/// ```
+pub struct LineIndex<'s> {
+    text: &'s str,
+    line_starts: Vec<usize>,
+}
+
+impl<'s> LineIndex<'s> {
+    pub fn new(text: &'s str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    /// Convert byte offset to line and column.
+    /// Offset that exceeds the text will be clamped to the end of text.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let col = self.text[line_start..]
+            .char_indices()
+            .take_while(|(index, _)| line_start + index < offset)
+            .map(|(_, c)| c.len_utf16())
+            .sum();
+        LineCol { line, col }
+    }
+
+    /// Convert line and column to byte offset.
+    /// Line or column that exceeds the text will be clamped to the end of that line or text.
+    pub fn offset(&self, line_col: LineCol) -> usize {
+        let Some(line_start) = self.line_starts.get(line_col.line).copied() else {
+            return self.text.len();
+        };
+        let line_end = self
+            .line_starts
+            .get(line_col.line + 1)
+            .map_or(self.text.len(), |start| start - 1);
+        let mut col = 0;
+        self.text[line_start..line_end]
+            .char_indices()
+            .find(|(_, c)| {
+                col += c.len_utf16();
+                col > line_col.col
+            })
+            .map_or(line_end, |(index, _)| line_start + index)
+    }
+}
@@ -0,0 +1,559 @@
+//! Updating YAML documents with serde values while preserving untouched regions.
+//!
+//! Document is deserialized into any type that implements [`Deserialize`],
+//! then the modified value is compared with the original one,
+//! and only the changed nodes are rewritten in source text.
+//! Comments, anchors, blank lines and styles of other nodes are kept as-is.
+//!
+//! ```
+//! use pretty_yaml::{config::FormatOptions, patch};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Config {
+//!     name: String,
+//!     replicas: u32,
+//! }
+//!
+//! let input = "# app config\nname: app # display name\n\nreplicas: 1\n";
+//! let mut config: Config = patch::parse(input).unwrap();
+//! config.replicas = 3;
+//! assert_eq!(
+//!     patch::update(input, &config, &FormatOptions::default()).unwrap(),
+//!     "# app config\nname: app # display name\n\nreplicas: 3\n",
+//! );
+//! ```
+
+use crate::{
+    config::FormatOptions,
+    convert::{canonical_plain, to_json, ConvertError},
+    format_value,
+    transform::is_non_string_plain,
+};
+use rowan::{TextRange, TextSize};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{error::Error, fmt};
+use yaml_parser::{
+    ast::{AstNode, Block, BlockMap, BlockSeq, Flow, FlowMap, Root},
+    SyntaxError, SyntaxKind, SyntaxNode,
+};
+
+#[derive(Debug)]
+/// Error that prevents YAML from being deserialized or updated.
+pub enum PatchError {
+    /// Source input can't be parsed.
+    Syntax(SyntaxError),
+    /// Source input can't be converted to serde value, such as containing undefined aliases.
+    Convert(ConvertError),
+    /// Value can't be deserialized from or serialized to serde value.
+    Serde(serde_json::Error),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::Syntax(err) => write!(f, "{err}"),
+            PatchError::Convert(err) => write!(f, "{err}"),
+            PatchError::Serde(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+impl From<SyntaxError> for PatchError {
+    fn from(err: SyntaxError) -> Self {
+        PatchError::Syntax(err)
+    }
+}
+
+impl From<ConvertError> for PatchError {
+    fn from(err: ConvertError) -> Self {
+        PatchError::Convert(err)
+    }
+}
+
+impl From<serde_json::Error> for PatchError {
+    fn from(err: serde_json::Error) -> Self {
+        PatchError::Serde(err)
+    }
+}
+
+/// Deserialize the first document of source input.
+///
+/// Aliases are replaced with their anchored nodes,
+/// and scalars are resolved like [`to_json`](crate::convert::to_json).
+pub fn parse<T: DeserializeOwned>(input: &str) -> Result<T, PatchError> {
+    let (_, value) = parse_first_document(input)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Update the first document of source input to represent the given value.
+///
+/// Only nodes whose values are changed are rewritten:
+///
+/// - Changed scalars are replaced.
+/// - Removed entries of block maps and block sequences are deleted with their lines.
+/// - New entries are appended after the last entry of block maps and block sequences.
+/// - Flow collections are replaced as a whole in JSON syntax if their keys or lengths are changed.
+///
+/// New nodes are generated like [`format_value`](crate::format_value) with the given options,
+/// and they're in JSON syntax inside flow collections.
+/// Aliases whose values are changed are replaced with the new values.
+pub fn update<T: Serialize>(
+    input: &str,
+    value: &T,
+    options: &FormatOptions,
+) -> Result<String, PatchError> {
+    let new = serde_json::to_value(value)?;
+    let (root, old) = parse_first_document(input)?;
+    let Some(node) = root.documents().next().and_then(|document| {
+        document
+            .block()
+            .map(|block| block.syntax().clone())
+            .or_else(|| document.flow().map(|flow| flow.syntax().clone()))
+    }) else {
+        return Ok(format_value(&new, options));
+    };
+    let mut patcher = Patcher {
+        source: input,
+        options,
+        edits: vec![],
+    };
+    patcher.patch_node(&node, &old, &new);
+
+    let mut output = input.to_owned();
+    patcher.edits.sort_by_key(|(range, _)| range.start());
+    patcher.edits.iter().rev().for_each(|(range, text)| {
+        output.replace_range(usize::from(range.start())..usize::from(range.end()), text);
+    });
+    Ok(output)
+}
+
+fn parse_first_document(input: &str) -> Result<(Root, Value), PatchError> {
+    let root = Root::cast(yaml_parser::parse(input)?).expect("expected root node");
+    let json = to_json(&root)?;
+    let value = serde_json::Deserializer::from_str(&json)
+        .into_iter::<Value>()
+        .next()
+        .transpose()?
+        .unwrap_or_default();
+    Ok((root, value))
+}
+
+struct Patcher<'a> {
+    source: &'a str,
+    options: &'a FormatOptions,
+    edits: Vec<(TextRange, String)>,
+}
+
+impl Patcher<'_> {
+    /// Patch [`Block`] or [`Flow`] node from old value to new value.
+    fn patch_node(&mut self, node: &SyntaxNode, old: &Value, new: &Value) {
+        if old == new {
+            return;
+        }
+        let is_patched = match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                if let Some(block_map) =
+                    Block::cast(node.clone()).and_then(|block| block.block_map())
+                {
+                    self.patch_block_map(&block_map, old, new)
+                } else if let Some(flow_map) =
+                    Flow::cast(node.clone()).and_then(|flow| flow.flow_map())
+                {
+                    self.patch_flow_entries(&flow_map, old, new)
+                } else {
+                    false
+                }
+            }
+            (Value::Array(old), Value::Array(new)) => {
+                if let Some(block_seq) =
+                    Block::cast(node.clone()).and_then(|block| block.block_seq())
+                {
+                    self.patch_block_seq(&block_seq, old, new)
+                } else if let Some(flow_seq) =
+                    Flow::cast(node.clone()).and_then(|flow| flow.flow_seq())
+                {
+                    self.patch_flow_items(flow_seq.syntax(), old, new)
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        };
+        if !is_patched {
+            self.replace(node, new);
+        }
+    }
+
+    fn patch_block_map(
+        &mut self,
+        block_map: &BlockMap,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+    ) -> bool {
+        let Some(entries) = block_map
+            .entries()
+            .map(|entry| Some((key_of(entry.syntax())?, entry)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        if new.is_empty() {
+            return false;
+        }
+        let Some(removals) = entries
+            .iter()
+            .filter(|(key, _)| !new.contains_key(key))
+            .map(|(_, entry)| line_range(self.source, entry.syntax()))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        let inserted = new
+            .iter()
+            .filter(|(key, _)| entries.iter().all(|(existing, _)| existing != *key))
+            .collect::<Vec<_>>();
+        if removals.len() == entries.len() && !inserted.is_empty() {
+            return false;
+        }
+        let mut last_kept = None;
+        for (key, entry) in &entries {
+            if let Some(new_value) = new.get(key) {
+                let old_value = old.get(key).unwrap_or(&Value::Null);
+                match entry.value().and_then(|value| {
+                    value
+                        .block()
+                        .map(|block| block.syntax().clone())
+                        .or_else(|| value.flow().map(|flow| flow.syntax().clone()))
+                }) {
+                    Some(node) => self.patch_node(&node, old_value, new_value),
+                    None if new_value.is_null() => {}
+                    None => {
+                        let text = self.value_after_indicator(entry.syntax(), new_value);
+                        let offset = entry
+                            .colon()
+                            .map_or(entry.syntax().text_range().end(), |colon| {
+                                colon.text_range().end()
+                            });
+                        self.edits.push((TextRange::empty(offset), text));
+                    }
+                }
+                last_kept = Some(entry.syntax().clone());
+            }
+        }
+        if let Some(last_kept) = last_kept.filter(|_| !inserted.is_empty()) {
+            let column = column(self.source, entries[0].1.syntax().text_range().start());
+            let offset = line_end(self.source, last_kept.text_range().end());
+            let text = inserted
+                .into_iter()
+                .map(|(key, value)| {
+                    let mut entry = Map::new();
+                    entry.insert(key.clone(), value.clone());
+                    self.generate_block(&Value::Object(entry), column)
+                })
+                .fold(String::new(), |mut text, entry| {
+                    text.push('\n');
+                    text.push_str(&" ".repeat(column));
+                    text.push_str(&entry);
+                    text
+                });
+            self.edits.push((TextRange::empty(offset), text));
+        }
+        self.edits
+            .extend(removals.into_iter().map(|range| (range, String::new())));
+        true
+    }
+
+    fn patch_block_seq(&mut self, block_seq: &BlockSeq, old: &[Value], new: &[Value]) -> bool {
+        let entries = block_seq.entries().collect::<Vec<_>>();
+        if new.is_empty() || entries.is_empty() {
+            return false;
+        }
+        let Some(removals) = entries
+            .iter()
+            .skip(new.len())
+            .map(|entry| line_range(self.source, entry.syntax()))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        for (i, entry) in entries.iter().enumerate().take(new.len()) {
+            let old_value = old.get(i).unwrap_or(&Value::Null);
+            match entry
+                .block()
+                .map(|block| block.syntax().clone())
+                .or_else(|| entry.flow().map(|flow| flow.syntax().clone()))
+            {
+                Some(node) => self.patch_node(&node, old_value, &new[i]),
+                None if new[i].is_null() => {}
+                None => {
+                    let text = self.value_after_indicator(entry.syntax(), &new[i]);
+                    let offset = entry
+                        .minus()
+                        .map_or(entry.syntax().text_range().end(), |minus| {
+                            minus.text_range().end()
+                        });
+                    self.edits.push((TextRange::empty(offset), text));
+                }
+            }
+        }
+        if new.len() > entries.len() {
+            let column = column(self.source, entries[0].syntax().text_range().start());
+            let offset = line_end(
+                self.source,
+                entries[entries.len() - 1].syntax().text_range().end(),
+            );
+            let text = new[entries.len()..]
+                .iter()
+                .fold(String::new(), |mut text, item| {
+                    text.push('\n');
+                    text.push_str(&" ".repeat(column));
+                    text.push_str(&self.generate_block(&Value::Array(vec![item.clone()]), column));
+                    text
+                });
+            self.edits.push((TextRange::empty(offset), text));
+        }
+        self.edits
+            .extend(removals.into_iter().map(|range| (range, String::new())));
+        true
+    }
+
+    /// Patch entries of flow map in place if keys aren't changed.
+    fn patch_flow_entries(
+        &mut self,
+        flow_map: &FlowMap,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+    ) -> bool {
+        let Some(entries) = flow_map
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| {
+                let value = entry
+                    .value()
+                    .and_then(|value| value.flow())
+                    .map(|flow| flow.syntax().clone());
+                Some((key_of(entry.syntax())?, value))
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        if entries.len() != new.len()
+            || entries.iter().any(|(key, value)| {
+                new.get(key)
+                    .is_none_or(|new| value.is_none() && !new.is_null())
+            })
+        {
+            return false;
+        }
+        entries.iter().for_each(|(key, value)| {
+            if let Some(value) = value {
+                self.patch_node(value, old.get(key).unwrap_or(&Value::Null), &new[key]);
+            }
+        });
+        true
+    }
+
+    /// Patch items of flow sequence in place if length isn't changed.
+    fn patch_flow_items(&mut self, flow_seq: &SyntaxNode, old: &[Value], new: &[Value]) -> bool {
+        let items = flow_seq
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::FLOW_SEQ_ENTRIES)
+            .flat_map(|entries| entries.children())
+            .map(|entry| {
+                entry
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::FLOW)
+            })
+            .collect::<Option<Vec<_>>>();
+        match items {
+            Some(items) if items.len() == old.len() && items.len() == new.len() => {
+                items
+                    .iter()
+                    .zip(old.iter().zip(new))
+                    .for_each(|(item, (old, new))| self.patch_node(item, old, new));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace content of node with the new value, while keeping its properties like anchors.
+    fn replace(&mut self, node: &SyntaxNode, value: &Value) {
+        let properties = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::PROPERTIES);
+        let is_in_flow = node
+            .ancestors()
+            .skip(1)
+            .any(|node| matches!(node.kind(), SyntaxKind::FLOW_MAP | SyntaxKind::FLOW_SEQ));
+        // keep flow style when flow collection is replaced by another collection
+        let is_flow_collection = (value.is_object() || value.is_array())
+            && node
+                .children()
+                .any(|child| matches!(child.kind(), SyntaxKind::FLOW_MAP | SyntaxKind::FLOW_SEQ));
+        if is_in_flow || is_flow_collection {
+            let start = properties.map_or(node.text_range().start(), |properties| {
+                properties.text_range().end()
+            });
+            let text = if start == node.text_range().start() {
+                value.to_string()
+            } else {
+                format!(" {value}")
+            };
+            self.edits
+                .push((TextRange::new(start, node.text_range().end()), text));
+            return;
+        }
+
+        let parent = node.parent();
+        let indicator = parent.as_ref().and_then(|parent| match parent.kind() {
+            SyntaxKind::BLOCK_MAP_VALUE => parent
+                .siblings_with_tokens(rowan::Direction::Prev)
+                .find(|element| element.kind() == SyntaxKind::COLON),
+            SyntaxKind::BLOCK_SEQ_ENTRY => parent
+                .children_with_tokens()
+                .find(|element| element.kind() == SyntaxKind::MINUS),
+            _ => None,
+        });
+        let start = properties
+            .as_ref()
+            .map(|properties| properties.text_range().end())
+            .or_else(|| {
+                indicator
+                    .as_ref()
+                    .map(|indicator| indicator.text_range().end())
+            });
+        match (start, parent) {
+            (Some(start), Some(parent)) => {
+                let owner = if parent.kind() == SyntaxKind::BLOCK_SEQ_ENTRY {
+                    parent
+                } else {
+                    parent.parent().unwrap_or(parent)
+                };
+                let text = if properties.is_some() && indicator.is_none() {
+                    // node that isn't value of map or sequence, such as document root
+                    let column = column(self.source, node.text_range().start());
+                    format!(" {}", self.generate_block(value, column))
+                } else {
+                    self.value_after_indicator(&owner, value)
+                };
+                self.edits
+                    .push((TextRange::new(start, node.text_range().end()), text));
+            }
+            _ => {
+                let column = column(self.source, node.text_range().start());
+                let text = self.generate_block(value, column);
+                self.edits.push((node.text_range(), text));
+            }
+        }
+    }
+
+    /// Generate text of value that comes after `:` of block map entry or `-` of block sequence entry,
+    /// including leading whitespace.
+    fn value_after_indicator(&self, entry: &SyntaxNode, value: &Value) -> String {
+        let column = column(self.source, entry.text_range().start());
+        if entry.kind() == SyntaxKind::BLOCK_SEQ_ENTRY {
+            format!(" {}", self.generate_block(value, column + 2))
+        } else if is_block_collection(value) {
+            let column = column + self.options.layout.indent_width;
+            format!(
+                "\n{}{}",
+                " ".repeat(column),
+                self.generate_block(value, column)
+            )
+        } else {
+            format!(" {}", self.generate_block(value, column))
+        }
+    }
+
+    /// Generate value in block style, whose lines except the first one are indented by `column`.
+    fn generate_block(&self, value: &Value, column: usize) -> String {
+        let text = format_value(value, self.options);
+        text.trim_end()
+            .split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                if i == 0 || line.is_empty() {
+                    line.to_owned()
+                } else {
+                    format!("{}{line}", " ".repeat(column))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Get range of lines of entry for removing it, including its trailing comment.
+/// It returns `None` if the entry doesn't start at the beginning of line.
+fn line_range(source: &str, entry: &SyntaxNode) -> Option<TextRange> {
+    let start = usize::from(entry.text_range().start());
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    if !source[line_start..start].trim().is_empty() {
+        return None;
+    }
+    let end = usize::from(line_end(source, entry.text_range().end()));
+    let end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |index| end + index + 1);
+    Some(TextRange::new(
+        TextSize::from(line_start as u32),
+        TextSize::from(end as u32),
+    ))
+}
+
+/// Get key of map entry as string, in the same way of converting to JSON.
+fn key_of(entry: &SyntaxNode) -> Option<String> {
+    let flow = entry
+        .children()
+        .find(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+            )
+        })?
+        .children()
+        .find_map(Flow::cast)?;
+    if flow.properties().is_some() || flow.alias().is_some() {
+        return None;
+    }
+    let key = flow.scalar_value()?.into_owned();
+    if flow.plain_scalar().is_some() && is_non_string_plain(&key) {
+        canonical_plain(&key)
+    } else {
+        Some(key)
+    }
+}
+
+fn is_block_collection(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn column(source: &str, offset: TextSize) -> usize {
+    let offset = usize::from(offset);
+    offset - source[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// Offset of line break of the line that contains the given offset, or the end of source.
+fn line_end(source: &str, offset: TextSize) -> TextSize {
+    let offset = usize::from(offset);
+    let end = source[offset..]
+        .find('\n')
+        .map_or(source.len(), |index| offset + index);
+    let end = if source[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    };
+    TextSize::from(end as u32)
+}
@@ -1,40 +1,145 @@
-use crate::config::{LanguageOptions, Quotes};
-use rowan::Direction;
-use std::ops::Range;
-use tiny_pretty::Doc;
-use yaml_parser::{ast::*, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
-
-pub(super) struct Ctx<'a> {
+use crate::{
+    block_scalar::{dedent_lines, has_whitespace_only_content},
+    classifier::{key_path, ScalarClassifier, ScalarStyle},
+    config::{
+        AlignValues, BlockScalarStyle, CommentIndent, ExplicitKeys, ExponentCase,
+        FlowCollectionLayout, FormatOptions, LanguageOptions, Quotes, ScalarQuoting,
+        TemplateSyntax,
+    },
+    transform::{is_non_string_plain, is_yaml11_non_string_plain},
+};
+use rowan::{Direction, TextSize};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    iter, mem,
+    ops::Range,
+};
+use tiny_pretty::{print, Doc, IndentKind, PrintOptions};
+use yaml_parser::{
+    ast::*,
+    schema::{resolve_scalar_with_version, ScalarType, YamlVersion},
+    SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken,
+};
+
+pub(super) struct Ctx<'a, 's> {
     pub indent_width: usize,
+    pub tab_width: usize,
     pub options: &'a LanguageOptions,
+    pub removed_entries: HashSet<SyntaxNode>,
+    pub renamed_anchors: HashMap<SyntaxToken, String>,
+    pub classifier: Option<&'a dyn ScalarClassifier>,
+    pub source: Option<Source<'s>>,
+    pub hook: Option<&'a NodeHook<'a, 's>>,
+    pub aligned_entries: HashMap<SyntaxNode, usize>,
+    pub aligned_comments: HashMap<SyntaxToken, usize>,
+}
+
+/// Format hook that accepts docs with the same lifetime as source text.
+pub(super) type NodeHook<'a, 's> = dyn Fn(SyntaxKind, &SyntaxNode, Doc<'s>) -> Doc<'s> + 'a;
+
+/// Text of the syntax tree that will be printed,
+/// so docs can borrow texts of tokens from it instead of allocating strings for each token.
+///
+/// It's absent when printing with format hook, because docs passed to hook must own their texts.
+pub(super) struct Source<'s> {
+    pub text: &'s str,
+    /// Offset of the start of `text` in syntax tree.
+    pub offset: TextSize,
+}
+
+impl<'a, 's> Ctx<'a, 's> {
+    pub fn new(options: &'a FormatOptions) -> Self {
+        Ctx {
+            indent_width: options.layout.indent_width,
+            tab_width: options.layout.tab_width(),
+            options: &options.language,
+            removed_entries: Default::default(),
+            renamed_anchors: Default::default(),
+            classifier: None,
+            source: None,
+            hook: None,
+            aligned_entries: Default::default(),
+            aligned_comments: Default::default(),
+        }
+    }
+
+    /// Get text of the token, which borrows source text if possible.
+    pub fn token_text(&self, token: &SyntaxToken) -> Cow<'s, str> {
+        self.slice_token(token, token.text())
+    }
+
+    /// Get part of token text, which borrows source text if possible.
+    ///
+    /// If `part` isn't a slice of token text, such as text that has been transformed,
+    /// it will be copied.
+    pub fn slice_token(&self, token: &SyntaxToken, part: &str) -> Cow<'s, str> {
+        let text = token.text();
+        let start = (part.as_ptr() as usize).checked_sub(text.as_ptr() as usize);
+        match (&self.source, start) {
+            (Some(source), Some(start)) if start + part.len() <= text.len() => {
+                let start = usize::from(token.text_range().start() - source.offset) + start;
+                Cow::Borrowed(&source.text[start..start + part.len()])
+            }
+            _ => Cow::Owned(part.to_owned()),
+        }
+    }
+
+    /// Get text of the node, which borrows source text if possible.
+    pub fn node_text(&self, node: &SyntaxNode) -> Cow<'s, str> {
+        match &self.source {
+            Some(source) => Cow::Borrowed(&source.text[node.text_range() - source.offset]),
+            None => Cow::Owned(node.to_string()),
+        }
+    }
 }
 
-pub(super) trait DocGen {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static>;
+pub(super) trait DocGen: AstNode {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s>;
+
+    /// Generate doc of the node, then pass it to format hook if there's one.
+    fn doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+        let doc = self.gen_doc(ctx);
+        match ctx.hook {
+            Some(hook) => {
+                let node = self.syntax();
+                hook(node.kind(), node, doc)
+            }
+            None => doc,
+        }
+    }
 }
 
 impl DocGen for Alias {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = vec![Doc::text("*")];
         if let Some(name) = self.anchor_name() {
-            docs.push(Doc::text(name.to_string()));
+            if let Some(renamed) = ctx.renamed_anchors.get(&name) {
+                docs.push(Doc::text(renamed.clone()));
+            } else {
+                docs.push(Doc::text(ctx.token_text(&name)));
+            }
         }
         Doc::list(docs)
     }
 }
 
 impl DocGen for AnchorProperty {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = vec![Doc::text("&")];
         if let Some(name) = self.anchor_name() {
-            docs.push(Doc::text(name.to_string()));
+            if let Some(renamed) = ctx.renamed_anchors.get(&name) {
+                docs.push(Doc::text(renamed.clone()));
+            } else {
+                docs.push(Doc::text(ctx.token_text(&name)));
+            }
         }
         Doc::list(docs)
     }
 }
 
 impl DocGen for Block {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = Vec::with_capacity(1);
         let mut trivia_after_props_docs = vec![];
         let has_properties = if let Some(properties) = self.properties() {
@@ -70,7 +175,16 @@ impl DocGen for Block {
                     docs.push(Doc::hard_line());
                 }
             }
-            docs.push(block_seq.doc(ctx));
+            let doc = block_seq.doc(ctx);
+            // block sequence as map value is handled when formatting key-value pair
+            match short_flow_seq(self, ctx).filter(|_| {
+                self.syntax()
+                    .parent()
+                    .is_some_and(|parent| parent.kind() != SyntaxKind::BLOCK_MAP_VALUE)
+            }) {
+                Some(flow) => docs.push(flow.union(doc)),
+                None => docs.push(doc),
+            }
         } else if let Some(block_scalar) = self.block_scalar() {
             if has_properties {
                 docs.push(Doc::space());
@@ -85,7 +199,7 @@ impl DocGen for Block {
 }
 
 impl DocGen for BlockMap {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         Doc::list(format_line_break_separated_list::<_, BlockMapEntry, false>(
             self, ctx,
         ))
@@ -93,13 +207,13 @@ impl DocGen for BlockMap {
 }
 
 impl DocGen for BlockMapEntry {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_key_value_pair(self.key(), self.colon(), self.value(), ctx)
     }
 }
 
 impl DocGen for BlockMapKey {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let question_mark = self.question_mark();
         if let Some(block) = self.block() {
             format_key(self, question_mark, Some(block), ctx)
@@ -110,7 +224,7 @@ impl DocGen for BlockMapKey {
 }
 
 impl DocGen for BlockMapValue {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if let Some(block) = self.block() {
             block.doc(ctx)
         } else if let Some(flow) = self.flow() {
@@ -122,7 +236,8 @@ impl DocGen for BlockMapValue {
 }
 
 impl DocGen for BlockScalar {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+        let converted = convert_block_scalar_style(self, ctx);
         Doc::list(
             self.syntax()
                 .children_with_tokens()
@@ -130,47 +245,50 @@ impl DocGen for BlockScalar {
                     SyntaxElement::Token(token) => match token.kind() {
                         SyntaxKind::WHITESPACE => Doc::nil(),
                         SyntaxKind::COMMENT => Doc::space().append(format_comment(&token, ctx)),
+                        SyntaxKind::BAR | SyntaxKind::GREATER_THAN => match &converted {
+                            Some((indicator, _)) => Doc::text(*indicator),
+                            None => Doc::text(ctx.token_text(&token)),
+                        },
                         SyntaxKind::BLOCK_SCALAR_TEXT => {
-                            let text = token.text();
+                            let text = converted
+                                .as_ref()
+                                .map_or(token.text(), |(_, text)| text.as_str());
                             if self
                                 .syntax()
                                 .children_with_tokens()
                                 .any(|element| element.kind() == SyntaxKind::INDENT_INDICATOR)
+                                || ctx.options.normalize_block_scalar_indent
+                                    && has_whitespace_only_content(text)
                             {
                                 let mut docs = Vec::with_capacity(2);
-                                reflow(token.text(), &mut docs);
+                                reflow(ctx.slice_token(&token, text), &mut docs);
                                 return Doc::list(docs);
                             }
-                            let space_len = text.find(|c: char| !c.is_ascii_whitespace()).map(
-                                |first_contentful| {
-                                    let first_linebreak = text[..first_contentful].rfind('\n');
-                                    if let Some(first_linbreak) = first_linebreak {
-                                        (first_contentful - first_linbreak).saturating_sub(1)
-                                    } else {
-                                        first_contentful
-                                    }
-                                },
-                            );
-                            if let Some(space_len) = space_len {
-                                let lines = text.lines().map(|s| {
-                                    if s.trim().is_empty() {
-                                        String::new()
-                                    } else if ctx.options.trim_trailing_whitespaces {
-                                        s[space_len..].trim_end().to_owned()
-                                    } else {
-                                        s[space_len..].to_owned()
-                                    }
-                                });
+                            if let Some(lines) =
+                                dedent_lines(text, ctx.options.trim_trailing_whitespaces)
+                            {
+                                let lines = lines.into_iter();
                                 let mut docs = vec![];
-                                intersperse_lines(&mut docs, lines);
-                                Doc::list(docs).nest(ctx.indent_width)
+                                let is_folded = match &converted {
+                                    Some((indicator, _)) => *indicator == ">",
+                                    None => self.greater_than().is_some(),
+                                };
+                                if ctx.options.reflow_folded_scalars
+                                    && is_folded
+                                    && !classify(self.syntax(), ctx).keep_lines
+                                {
+                                    intersperse_folded_lines(&mut docs, lines);
+                                } else {
+                                    intersperse_lines(&mut docs, lines);
+                                }
+                                Doc::list(docs).nest(block_scalar_indent(self, ctx))
                             } else {
                                 Doc::nil()
                             }
                         }
-                        _ => Doc::text(token.to_string()),
+                        _ => Doc::text(ctx.token_text(&token)),
                     },
-                    SyntaxElement::Node(node) => Doc::text(node.to_string()),
+                    SyntaxElement::Node(node) => Doc::text(ctx.node_text(&node)),
                 })
                 .collect(),
         )
@@ -178,7 +296,7 @@ impl DocGen for BlockScalar {
 }
 
 impl DocGen for BlockSeq {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         Doc::list(format_line_break_separated_list::<_, BlockSeqEntry, false>(
             self, ctx,
         ))
@@ -186,7 +304,7 @@ impl DocGen for BlockSeq {
 }
 
 impl DocGen for BlockSeqEntry {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         use crate::config::DashSpacing;
 
         let mut docs = Vec::with_capacity(3);
@@ -226,7 +344,7 @@ impl DocGen for BlockSeqEntry {
 }
 
 impl DocGen for Directive {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = Vec::with_capacity(2);
         docs.push(Doc::text("%"));
         if let Some(tag) = self.tag_directive() {
@@ -241,72 +359,163 @@ impl DocGen for Directive {
 }
 
 impl DocGen for Document {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+        use crate::{
+            config::{Directives, EmptyDocuments, ExplicitDocumentMarkers},
+            transform::is_empty_document,
+        };
+
         let mut docs = Vec::with_capacity(2);
 
-        let mut children = self.syntax().children_with_tokens().peekable();
-        while let Some(element) = children.next() {
-            match element {
-                SyntaxElement::Node(node) => match node.kind() {
-                    SyntaxKind::BLOCK => {
-                        if let Some(block) = Block::cast(node) {
-                            docs.push(block.doc(ctx));
-                        }
-                    }
-                    SyntaxKind::FLOW => {
-                        if let Some(flow) = Flow::cast(node) {
-                            docs.push(flow.doc(ctx));
+        let is_explicit = matches!(ctx.options.directives, Directives::Explicit);
+        if is_explicit
+            && self
+                .directives()
+                .all(|directive| directive.yaml_directive().is_none())
+        {
+            docs.push(Doc::text("%YAML 1.2"));
+            docs.push(Doc::hard_line());
+            if self.directives_end().is_none() {
+                docs.push(Doc::text("---"));
+                docs.push(Doc::hard_line());
+            }
+        }
+
+        if matches!(
+            ctx.options.empty_documents,
+            EmptyDocuments::Bare | EmptyDocuments::Null
+        ) && is_empty_document(self)
+        {
+            docs.push(Doc::text(
+                if let EmptyDocuments::Null = ctx.options.empty_documents {
+                    "--- null"
+                } else {
+                    "---"
+                },
+            ));
+            if self.document_end().is_some() {
+                docs.push(Doc::hard_line());
+                docs.push(Doc::text("..."));
+            }
+        } else {
+            let has_content = self.block().is_some() || self.flow().is_some();
+            let strip_markers = matches!(
+                ctx.options.explicit_document_markers,
+                ExplicitDocumentMarkers::StripWhenSingle
+            ) && !is_explicit
+                && has_content
+                && self.directives().next().is_none()
+                && self.syntax().parent().is_some_and(|root| {
+                    root.children()
+                        .filter(|child| child.kind() == SyntaxKind::DOCUMENT)
+                        .count()
+                        == 1
+                });
+            if matches!(
+                ctx.options.explicit_document_markers,
+                ExplicitDocumentMarkers::Always
+            ) && !is_explicit
+                && has_content
+                && self.directives_end().is_none()
+            {
+                docs.push(Doc::text("---"));
+                docs.push(Doc::hard_line());
+            }
+
+            let mut children = self.syntax().children_with_tokens().peekable();
+            while let Some(element) = children.next() {
+                match element {
+                    SyntaxElement::Node(node) => match node.kind() {
+                        SyntaxKind::BLOCK => {
+                            if let Some(block) = Block::cast(node) {
+                                docs.push(block.doc(ctx));
+                            }
                         }
-                    }
-                    SyntaxKind::DIRECTIVE => {
-                        if let Some(directive) = Directive::cast(node) {
-                            docs.push(directive.doc(ctx));
+                        SyntaxKind::FLOW => {
+                            if let Some(flow) = Flow::cast(node) {
+                                docs.push(flow.doc(ctx));
+                            }
                         }
-                    }
-                    _ => {}
-                },
-                SyntaxElement::Token(token) => match token.kind() {
-                    SyntaxKind::COMMENT => {
-                        docs.push(format_comment(&token, ctx));
-                    }
-                    SyntaxKind::WHITESPACE => {
-                        match token.text().chars().filter(|c| *c == '\n').count() {
-                            0 => {
-                                if children
-                                    .peek()
-                                    .is_some_and(|element| element.kind() == SyntaxKind::COMMENT)
+                        SyntaxKind::DIRECTIVE => {
+                            if let Some(directive) = Directive::cast(node) {
+                                if matches!(ctx.options.directives, Directives::StripRedundant)
+                                    && is_redundant_directive(&directive)
+                                    || ctx.options.dedupe_tag_directives
+                                        && is_duplicate_tag_directive(&directive)
                                 {
-                                    docs.push(Doc::space());
+                                    children.next_if(|element| {
+                                        element.kind() == SyntaxKind::WHITESPACE
+                                    });
                                 } else {
-                                    docs.push(Doc::hard_line());
+                                    docs.push(directive.doc(ctx));
                                 }
                             }
-                            1 => {
-                                docs.push(Doc::hard_line());
-                            }
-                            _ => {
-                                docs.push(Doc::empty_line());
-                                docs.push(Doc::hard_line());
+                        }
+                        _ => {}
+                    },
+                    SyntaxElement::Token(token) => match token.kind() {
+                        SyntaxKind::COMMENT => {
+                            docs.push(format_comment(&token, ctx));
+                        }
+                        SyntaxKind::WHITESPACE
+                            if strip_markers
+                                && children.peek().is_some_and(|element| {
+                                    element.kind() == SyntaxKind::DOCUMENT_END
+                                }) => {}
+                        SyntaxKind::WHITESPACE => {
+                            match token.text().chars().filter(|c| *c == '\n').count() {
+                                0 => {
+                                    if children.peek().is_some_and(|element| {
+                                        element.kind() == SyntaxKind::COMMENT
+                                    }) {
+                                        docs.push(Doc::space());
+                                    } else {
+                                        docs.push(Doc::hard_line());
+                                    }
+                                }
+                                1 => {
+                                    docs.push(Doc::hard_line());
+                                }
+                                line_breaks => {
+                                    docs.extend(blank_lines(&token, line_breaks, 0, ctx));
+                                    docs.push(Doc::hard_line());
+                                }
                             }
                         }
-                    }
-                    SyntaxKind::DIRECTIVES_END => {
-                        docs.push(Doc::text("---"));
-                    }
-                    SyntaxKind::DOCUMENT_END => {
-                        docs.push(Doc::text("..."));
-                    }
-                    _ => {}
-                },
+                        SyntaxKind::DIRECTIVES_END if strip_markers => {
+                            children.next_if(|element| element.kind() == SyntaxKind::WHITESPACE);
+                        }
+                        SyntaxKind::DIRECTIVES_END => {
+                            docs.push(Doc::text("---"));
+                        }
+                        SyntaxKind::DOCUMENT_END if strip_markers => {}
+                        SyntaxKind::DOCUMENT_END => {
+                            docs.push(Doc::text("..."));
+                        }
+                        _ => {}
+                    },
+                }
             }
         }
 
+        // directives of next document must come after document end marker
+        if is_explicit
+            && self.document_end().is_none()
+            && self
+                .syntax()
+                .next_sibling()
+                .is_some_and(|node| node.kind() == SyntaxKind::DOCUMENT)
+        {
+            docs.push(Doc::hard_line());
+            docs.push(Doc::text("..."));
+        }
+
         Doc::list(docs)
     }
 }
 
 impl DocGen for Flow {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = Vec::with_capacity(1);
         if let Some(properties) = self.properties() {
             docs.push(properties.doc(ctx));
@@ -337,74 +546,86 @@ impl DocGen for Flow {
             let text = text
                 .get(1..text.len() - 1)
                 .expect("expected double quoted scalar");
-            let (quotes_option, quote) = if text.contains('\\') {
-                (None, "\"")
+            let style = classify(self.syntax(), ctx);
+            let is_template = has_template(text, ctx);
+            if !is_template && !text.contains('\\') && can_unquote(text, self, &style, ctx) {
+                docs.push(Doc::text(ctx.slice_token(&double_quoted, text)));
             } else {
-                match &ctx.options.quotes {
-                    Quotes::PreferSingle => {
-                        if text.contains(['\'', '"']) {
-                            (None, "\"")
-                        } else {
-                            (Some(&ctx.options.quotes), "'")
-                        }
+                let (conversion, quote) = match &ctx.options.quotes {
+                    _ if is_template => (QuoteConversion::Keep, "\""),
+                    Quotes::PreferSingle
+                        if count_single_quotable_escapes(text)
+                            .is_some_and(|escapes| text.matches('\'').count() <= escapes) =>
+                    {
+                        (QuoteConversion::ToSingle, "'")
                     }
-                    Quotes::PreferDouble | Quotes::ForceDouble => (None, "\""),
-                    Quotes::ForceSingle => (Some(&ctx.options.quotes), "'"),
-                }
-            };
-            docs.push(Doc::text(quote));
-            format_quoted_scalar(text, quotes_option, &mut docs, ctx);
-            docs.push(Doc::text(quote));
+                    Quotes::ForceSingle if count_single_quotable_escapes(text).is_some() => {
+                        (QuoteConversion::ToSingle, "'")
+                    }
+                    _ => (QuoteConversion::Keep, "\""),
+                };
+                docs.push(Doc::text(quote));
+                format_quoted_scalar(&double_quoted, text, true, conversion, &mut docs, ctx);
+                docs.push(Doc::text(quote));
+            }
         } else if let Some(single_quoted) = self.single_quoted_scalar() {
             let text = single_quoted.text();
             let text = text
                 .get(1..text.len() - 1)
                 .expect("expected single quoted scalar");
-            let (quotes_option, quote) = if text.contains(['\\', '"']) {
-                (None, "'")
+            let unescaped = if text.contains("''") {
+                Cow::Owned(text.replace("''", "'"))
             } else {
-                match &ctx.options.quotes {
-                    Quotes::PreferDouble => {
-                        if text.contains(['\'', '"']) {
-                            (None, "'")
-                        } else {
-                            (Some(&ctx.options.quotes), "\"")
-                        }
-                    }
-                    Quotes::PreferSingle | Quotes::ForceSingle => (None, "'"),
-                    Quotes::ForceDouble => (Some(&ctx.options.quotes), "\""),
-                }
+                ctx.slice_token(&single_quoted, text)
             };
-            docs.push(Doc::text(quote));
-            format_quoted_scalar(text, quotes_option, &mut docs, ctx);
-            docs.push(Doc::text(quote));
+            let style = classify(self.syntax(), ctx);
+            let is_template = has_template(text, ctx);
+            if !is_template && can_unquote(&unescaped, self, &style, ctx) {
+                docs.push(Doc::text(unescaped));
+            } else {
+                let (conversion, quote) = match &ctx.options.quotes {
+                    _ if is_template => (QuoteConversion::Keep, "'"),
+                    Quotes::PreferDouble
+                        if text.matches(['"', '\\']).count() <= text.matches("''").count() =>
+                    {
+                        (QuoteConversion::ToDouble, "\"")
+                    }
+                    Quotes::ForceDouble => (QuoteConversion::ToDouble, "\""),
+                    _ => (QuoteConversion::Keep, "'"),
+                };
+                docs.push(Doc::text(quote));
+                format_quoted_scalar(&single_quoted, text, false, conversion, &mut docs, ctx);
+                docs.push(Doc::text(quote));
+            }
         } else if let Some(plain) = self.plain_scalar() {
             let token_text = plain.text();
+            let style = classify(self.syntax(), ctx);
             'a: {
-                if ctx.options.trim_trailing_zero {
-                    let ranges = parse_float(token_text);
-                    if let Some((range_int, range_fraction, fraction)) = ranges.and_then(|ranges| {
+                if has_template(token_text, ctx) {
+                    intersperse_lines(
+                        &mut docs,
                         token_text
-                            .get(ranges.1.clone())
-                            .filter(|fraction| fraction.ends_with('0'))
-                            .map(|fraction| (ranges.0, ranges.1, fraction))
-                    }) {
-                        let mut token_text = token_text.to_owned();
-                        let trimmed_fraction = fraction.trim_end_matches('0');
-                        if trimmed_fraction == "." {
-                            if token_text.get(range_int.clone()).is_some_and(str::is_empty) {
-                                token_text.replace_range(range_int, "0");
-                            }
-                            token_text.replace_range(range_fraction, "");
-                        } else {
-                            token_text.replace_range(range_fraction, trimmed_fraction);
-                        }
-                        docs.push(Doc::text(token_text));
-                        break 'a;
-                    }
+                            .lines()
+                            .map(|s| ctx.slice_token(&plain, s.trim())),
+                    );
+                    break 'a;
+                }
+                if should_quote(token_text, self, &style, ctx) {
+                    docs.push(Doc::text(quote_plain_scalar(token_text, ctx)));
+                    break 'a;
+                }
+                if let Some(number) = format_number(token_text, self, ctx.options) {
+                    docs.push(Doc::text(number));
+                    break 'a;
+                }
+                let lines = token_text
+                    .lines()
+                    .map(|s| ctx.slice_token(&plain, s.trim()));
+                if !style.keep_lines && can_wrap_plain_scalar(self.syntax(), ctx) {
+                    intersperse_folded_lines(&mut docs, lines);
+                } else {
+                    intersperse_lines(&mut docs, lines);
                 }
-                let lines = token_text.lines().map(|s| s.trim().to_owned());
-                intersperse_lines(&mut docs, lines);
             }
         } else if let Some(flow_seq) = self.flow_seq() {
             docs.push(flow_seq.doc(ctx));
@@ -418,7 +639,7 @@ impl DocGen for Flow {
 }
 
 impl DocGen for FlowMap {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if self
             .entries()
             .is_some_and(|entries| entries.syntax().children_with_tokens().count() == 0)
@@ -431,8 +652,13 @@ impl DocGen for FlowMap {
         }
 
         if let Some(entries) = self.entries() {
-            FlowCollectionFormatter::flow_map(self.l_brace(), self.r_brace(), ctx)
-                .format(entries.doc(ctx))
+            FlowCollectionFormatter::flow_map(
+                self.l_brace(),
+                self.r_brace(),
+                entries.entries().count(),
+                ctx,
+            )
+            .format(entries.doc(ctx))
         } else {
             Doc::nil()
         }
@@ -440,25 +666,25 @@ impl DocGen for FlowMap {
 }
 
 impl DocGen for FlowMapEntries {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_flow_collection_entries(self, self.entries(), ctx)
     }
 }
 
 impl DocGen for FlowMapEntry {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_key_value_pair(self.key(), self.colon(), self.value(), ctx)
     }
 }
 
 impl DocGen for FlowMapKey {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_key(self, self.question_mark(), self.flow(), ctx)
     }
 }
 
 impl DocGen for FlowMapValue {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         self.flow()
             .map(|flow| flow.doc(ctx))
             .unwrap_or_else(Doc::nil)
@@ -466,13 +692,13 @@ impl DocGen for FlowMapValue {
 }
 
 impl DocGen for FlowPair {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_key_value_pair(self.key(), self.colon(), self.value(), ctx)
     }
 }
 
 impl DocGen for FlowSeq {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if self
             .entries()
             .is_some_and(|entries| entries.syntax().children_with_tokens().count() == 0)
@@ -485,8 +711,13 @@ impl DocGen for FlowSeq {
         }
 
         if let Some(entries) = self.entries() {
-            FlowCollectionFormatter::flow_seq(self.l_bracket(), self.r_bracket(), ctx)
-                .format(entries.doc(ctx))
+            FlowCollectionFormatter::flow_seq(
+                self.l_bracket(),
+                self.r_bracket(),
+                entries.entries().count(),
+                ctx,
+            )
+            .format(entries.doc(ctx))
         } else {
             Doc::nil()
         }
@@ -494,13 +725,13 @@ impl DocGen for FlowSeq {
 }
 
 impl DocGen for FlowSeqEntries {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         format_flow_collection_entries(self, self.entries(), ctx)
     }
 }
 
 impl DocGen for FlowSeqEntry {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if let Some(flow) = self.flow() {
             flow.doc(ctx)
         } else if let Some(flow_pair) = self.flow_pair() {
@@ -512,13 +743,13 @@ impl DocGen for FlowSeqEntry {
 }
 
 impl DocGen for NonSpecificTag {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, _: &Ctx<'_, 's>) -> Doc<'s> {
         Doc::text("!")
     }
 }
 
 impl DocGen for Properties {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         Doc::list(
             self.syntax()
                 .children_with_tokens()
@@ -526,7 +757,7 @@ impl DocGen for Properties {
                     SyntaxElement::Token(token) => match token.kind() {
                         SyntaxKind::WHITESPACE => Doc::line_or_space(),
                         SyntaxKind::COMMENT => format_comment(&token, ctx),
-                        _ => Doc::text(token.to_string()),
+                        _ => Doc::text(ctx.token_text(&token)),
                     },
                     SyntaxElement::Node(node) => {
                         if let Some(anchor) = AnchorProperty::cast(node.clone()) {
@@ -545,21 +776,32 @@ impl DocGen for Properties {
 }
 
 impl DocGen for ReservedDirective {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = Vec::with_capacity(3);
         if let Some(name) = self.directive_name() {
-            docs.push(Doc::text(name.to_string()));
+            docs.push(Doc::text(ctx.token_text(&name)));
         }
         if let Some(param) = self.directive_param() {
             docs.push(Doc::space());
-            docs.push(Doc::text(param.to_string()));
+            docs.push(Doc::text(ctx.token_text(&param)));
         }
         Doc::list(docs)
     }
 }
 
 impl DocGen for Root {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+        // stream that has neither documents nor comments is printed as empty,
+        // including the case that all documents are removed
+        let removed = collect_removed_elements(self.syntax(), ctx);
+        if self
+            .syntax()
+            .children_with_tokens()
+            .filter(|element| !removed.contains(&element.index()))
+            .all(|element| element.kind() == SyntaxKind::WHITESPACE)
+        {
+            return Doc::nil();
+        }
         let mut docs = format_line_break_separated_list::<_, Document, true>(self, ctx);
         docs.push(Doc::hard_line());
         Doc::list(docs)
@@ -567,20 +809,20 @@ impl DocGen for Root {
 }
 
 impl DocGen for ShorthandTag {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = Vec::with_capacity(2);
         if let Some(tag_handle) = self.tag_handle() {
             docs.push(tag_handle.doc(ctx));
         }
         if let Some(tag_char) = self.tag_char() {
-            docs.push(Doc::text(tag_char.to_string()));
+            docs.push(Doc::text(ctx.token_text(&tag_char)));
         }
         Doc::list(docs)
     }
 }
 
 impl DocGen for TagDirective {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         let mut docs = vec![Doc::text("TAG")];
         if let Some(tag_handle) = self.tag_handle() {
             docs.push(Doc::space());
@@ -588,20 +830,20 @@ impl DocGen for TagDirective {
         }
         if let Some(tag_prefix) = self.tag_prefix() {
             docs.push(Doc::space());
-            docs.push(Doc::text(tag_prefix.to_string()));
+            docs.push(Doc::text(ctx.token_text(&tag_prefix)));
         }
         Doc::list(docs)
     }
 }
 
 impl DocGen for TagHandle {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if let Some(primary) = self.primary() {
-            Doc::text(primary.to_string())
+            Doc::text(ctx.token_text(&primary))
         } else if let Some(secondary) = self.secondary() {
-            Doc::text(secondary.to_string())
+            Doc::text(ctx.token_text(&secondary))
         } else if let Some(named) = self.named() {
-            Doc::text(named.to_string())
+            Doc::text(ctx.token_text(&named))
         } else {
             unreachable!("expected primary, secondary or named in tag handle")
         }
@@ -609,13 +851,13 @@ impl DocGen for TagHandle {
 }
 
 impl DocGen for TagProperty {
-    fn doc(&self, ctx: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, ctx: &Ctx<'_, 's>) -> Doc<'s> {
         if let Some(shorthand) = self.shorthand_tag() {
             shorthand.doc(ctx)
         } else if let Some(non_specific) = self.non_specific_tag() {
             non_specific.doc(ctx)
         } else if let Some(verbatim) = self.verbatim_tag() {
-            Doc::text(verbatim.to_string())
+            Doc::text(ctx.token_text(&verbatim))
         } else {
             unreachable!("expected shorthand tag or non specific tag in tag property")
         }
@@ -623,7 +865,7 @@ impl DocGen for TagProperty {
 }
 
 impl DocGen for YamlDirective {
-    fn doc(&self, _: &Ctx) -> Doc<'static> {
+    fn gen_doc<'s>(&self, _: &Ctx<'_, 's>) -> Doc<'s> {
         if let Some(version) = self.yaml_version() {
             Doc::text(format!("YAML {}", version.text()))
         } else {
@@ -632,12 +874,12 @@ impl DocGen for YamlDirective {
     }
 }
 
-fn format_key<K, C>(
+fn format_key<'s, K, C>(
     key: &K,
     question_mark: Option<SyntaxToken>,
     content: Option<C>,
-    ctx: &Ctx,
-) -> Doc<'static>
+    ctx: &Ctx<'_, 's>,
+) -> Doc<'s>
 where
     K: AstNode,
     C: AstNode + DocGen,
@@ -645,7 +887,13 @@ where
     let mut docs = Vec::with_capacity(1);
 
     let mut has_line_break = false;
-    let is_question_mark_omitted = question_mark.is_none() || can_omit_question_mark(key.syntax());
+    let requires_question_mark = requires_explicit_key(key.syntax(), ctx);
+    let is_question_mark_omitted = !requires_question_mark
+        && (question_mark.is_none() || can_omit_question_mark(key.syntax(), ctx));
+    if question_mark.is_none() && requires_question_mark {
+        docs.push(Doc::text("?"));
+        docs.push(Doc::space());
+    }
     if let Some(question_mark) = question_mark {
         if !is_question_mark_omitted {
             docs.push(Doc::text("?"));
@@ -713,18 +961,25 @@ where
     }
 }
 
-fn format_key_value_pair<K, V>(
+fn format_key_value_pair<'s, K, V>(
     key: Option<K>,
     colon: Option<SyntaxToken>,
     value: Option<V>,
-    ctx: &Ctx,
-) -> Doc<'static>
+    ctx: &Ctx<'_, 's>,
+) -> Doc<'s>
 where
     K: AstNode + DocGen,
     V: AstNode + DocGen,
 {
     let mut docs = Vec::with_capacity(4);
 
+    // spaces for aligning values with other entries in the same block map
+    let padding = key
+        .as_ref()
+        .and_then(|key| key.syntax().parent())
+        .and_then(|entry| ctx.aligned_entries.get(&entry))
+        .copied()
+        .unwrap_or_default();
     let mut trivia_before_colon_docs = vec![];
     let mut has_question_mark = false;
     if let Some(key) = key {
@@ -732,7 +987,8 @@ where
             .syntax()
             .children_with_tokens()
             .any(|node| node.kind() == SyntaxKind::QUESTION_MARK)
-            && !can_omit_question_mark(key.syntax());
+            && !can_omit_question_mark(key.syntax(), ctx)
+            || requires_explicit_key(key.syntax(), ctx);
         docs.push(key.doc(ctx));
         if let Some(token) = key
             .syntax()
@@ -747,6 +1003,7 @@ where
             .syntax()
             .children()
             .find(|node| node.kind() == SyntaxKind::FLOW)
+            .filter(|_| !has_question_mark)
         {
             if flow
                 .children()
@@ -772,6 +1029,9 @@ where
             }
             docs.push(Doc::text(":"));
         } else {
+            if padding > 0 && matches!(ctx.options.align_values, AlignValues::Colon) {
+                docs.push(Doc::text(" ".repeat(padding)));
+            }
             docs.push(Doc::text(":"));
             if !trivia_before_colon_docs.is_empty() {
                 docs.push(Doc::space());
@@ -782,7 +1042,16 @@ where
         let mut has_line_break = false;
 
         if let Some(value) = value {
+            let value_block = value
+                .syntax()
+                .children()
+                .find(|child| child.kind() == SyntaxKind::BLOCK);
+            let value_flow = value
+                .syntax()
+                .children()
+                .find(|child| child.kind() == SyntaxKind::FLOW);
             let mut value_docs = vec![];
+            let mut is_same_line_value = false;
             if let Some(token) = colon
                 .next_token()
                 .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
@@ -812,10 +1081,8 @@ where
                 } else if value.syntax().kind() == SyntaxKind::FLOW_MAP_VALUE {
                     value_docs.push(Doc::space());
                 } else if token.text().contains(['\n', '\r'])
-                    || value
-                        .syntax()
-                        .children()
-                        .find(|child| child.kind() == SyntaxKind::BLOCK)
+                    || value_block
+                        .as_ref()
                         // for the case that there's no properties
                         // so the block seq comes as first child
                         .and_then(|block| block.first_child())
@@ -824,40 +1091,49 @@ where
                 {
                     value_docs.push(Doc::hard_line());
                     has_line_break = true;
+                } else if padding > 0 && matches!(ctx.options.align_values, AlignValues::Value) {
+                    value_docs.push(Doc::text(" ".repeat(padding + 1)));
                 } else {
+                    is_same_line_value = true;
                     value_docs.push(Doc::space());
                 }
             } else if !has_trivias_before_colon {
                 docs.push(Doc::space());
             }
             let doc = Doc::list(value_docs).append(value.doc(ctx));
-            if value
-                .syntax()
-                .children()
-                .find(|child| child.kind() == SyntaxKind::BLOCK)
+            if value_block
                 .iter()
                 .flat_map(|block| block.children())
                 .any(|child| child.kind() == SyntaxKind::BLOCK_SEQ)
             {
-                if ctx.options.indent_block_sequence_in_map {
-                    docs.push(doc.nest(ctx.indent_width));
-                } else {
-                    docs.push(doc);
+                let doc = match ctx.options.sequence_indent_width {
+                    Some(width) => doc.nest(width),
+                    None if ctx.options.indent_block_sequence_in_map => doc.nest(ctx.indent_width),
+                    None => doc,
+                };
+                let has_comment_before_value = colon
+                    .siblings_with_tokens(Direction::Next)
+                    .take_while(|element| element.as_node() != Some(value.syntax()))
+                    .any(|element| element.kind() == SyntaxKind::COMMENT);
+                match value_block
+                    .and_then(Block::cast)
+                    .and_then(|block| short_flow_seq(&block, ctx))
+                    .filter(|_| !has_comment_before_value && !has_question_mark)
+                {
+                    Some(flow) => docs.push(Doc::space().append(flow).union(doc)),
+                    None => docs.push(doc),
                 }
             } else if has_line_break
-                || value
-                    .syntax()
-                    .children()
-                    .find(|child| child.kind() == SyntaxKind::BLOCK)
+                || value_block
                     .iter()
                     .flat_map(|block| block.children())
                     .any(|child| child.kind() == SyntaxKind::BLOCK_MAP)
-                || value
-                    .syntax()
-                    .children()
-                    .find(|child| child.kind() == SyntaxKind::FLOW)
+                || value_flow
+                    .as_ref()
+                    .is_some_and(|flow| can_wrap_plain_scalar(flow, ctx))
+                || value_flow
                     .iter()
-                    .flat_map(|block| block.children_with_tokens())
+                    .flat_map(|flow| flow.children_with_tokens())
                     .any(|element| {
                         if let SyntaxElement::Token(token) = element {
                             token.text().contains(['\n', '\r'])
@@ -867,6 +1143,8 @@ where
                     })
             {
                 docs.push(doc.nest(ctx.indent_width));
+            } else if is_same_line_value {
+                docs.push(break_long_value(value.syntax(), ctx).unwrap_or(doc));
             } else {
                 docs.push(doc);
             }
@@ -876,79 +1154,373 @@ where
     Doc::list(docs).group()
 }
 
-struct FlowCollectionFormatter<'a> {
+/// Move quoted scalar or flow collection in block map value to the next line
+/// if the entry exceeds print width but the value fits on the next line.
+/// Otherwise, value is kept after colon, and flow collection may be broken as usual.
+fn break_long_value<'s>(value: &SyntaxNode, ctx: &Ctx<'_, 's>) -> Option<Doc<'s>> {
+    if !ctx.options.break_long_values || value.kind() != SyntaxKind::BLOCK_MAP_VALUE {
+        return None;
+    }
+    let flow = value.children().find_map(Flow::cast)?;
+    if flow.double_qouted_scalar().is_none()
+        && flow.single_quoted_scalar().is_none()
+        && flow.flow_seq().is_none()
+        && flow.flow_map().is_none()
+    {
+        return None;
+    }
+    let doc = flow.doc(ctx);
+    let single_line = print(
+        &doc,
+        &PrintOptions {
+            width: usize::MAX,
+            ..Default::default()
+        },
+    );
+    if single_line.contains(['\n', '\r']) {
+        // there're comments or line breaks that must be kept
+        return None;
+    }
+    Some(Doc::flat_or_break(
+        Doc::space().append(doc.clone()),
+        Doc::hard_line()
+            .append(Doc::text(single_line))
+            .nest(ctx.indent_width)
+            .union(Doc::space().append(doc)),
+    ))
+}
+
+/// Compute paddings after keys of block map entries for the `alignValues` option.
+///
+/// Consecutive entries in the same block map are aligned as a group,
+/// and groups are separated by blank lines or entries that can't be aligned,
+/// such as entries whose values are nested collections or start at the next line.
+pub(crate) fn align_entries(node: &SyntaxNode, ctx: &Ctx) -> HashMap<SyntaxNode, usize> {
+    let mut paddings = HashMap::new();
+    if matches!(ctx.options.align_values, AlignValues::None) {
+        return paddings;
+    }
+    let directive = &ctx.options.ignore_comment_directive;
+    node.descendants()
+        .filter(|node| node.kind() == SyntaxKind::BLOCK_MAP)
+        .for_each(|map| {
+            let mut group = vec![];
+            let mut is_in_ignored_region = false;
+            for element in map.children_with_tokens() {
+                match element {
+                    SyntaxElement::Node(entry) => {
+                        if ctx.removed_entries.contains(&entry) {
+                            continue;
+                        }
+                        match alignable_key_width(&entry, ctx)
+                            .filter(|_| !is_in_ignored_region && !should_ignore(&entry, ctx))
+                        {
+                            Some(width) => group.push((entry, width)),
+                            None => flush_aligned_group(&mut group, &mut paddings),
+                        }
+                    }
+                    SyntaxElement::Token(token) => {
+                        if token.kind() == SyntaxKind::WHITESPACE
+                            && token.text().chars().filter(|c| *c == '\n').count() > 1
+                        {
+                            flush_aligned_group(&mut group, &mut paddings);
+                        } else if is_suffixed_directive(&token, directive, "start") {
+                            is_in_ignored_region = true;
+                        } else if is_suffixed_directive(&token, directive, "end") {
+                            is_in_ignored_region = false;
+                        }
+                    }
+                }
+            }
+            flush_aligned_group(&mut group, &mut paddings);
+        });
+    paddings
+}
+
+fn flush_aligned_group(
+    group: &mut Vec<(SyntaxNode, usize)>,
+    paddings: &mut HashMap<SyntaxNode, usize>,
+) {
+    let max = group
+        .iter()
+        .map(|(_, width)| *width)
+        .max()
+        .unwrap_or_default();
+    paddings.extend(
+        group
+            .drain(..)
+            .filter(|(_, width)| *width < max)
+            .map(|(entry, width)| (entry, max - width)),
+    );
+}
+
+/// Get width of formatted key if the entry is a simple key with value at the same line.
+fn alignable_key_width(entry: &SyntaxNode, ctx: &Ctx) -> Option<usize> {
+    let entry = BlockMapEntry::cast(entry.clone())?;
+    let key = entry.key()?;
+    let colon = entry.colon()?;
+    let value = entry.value()?;
+    let flow = key.flow()?;
+    if key.question_mark().is_some() && !can_omit_question_mark(key.syntax(), ctx)
+        || requires_explicit_key(key.syntax(), ctx)
+        || flow.syntax().text().contains_char('\n')
+        || entry
+            .syntax()
+            .children_with_tokens()
+            .any(|element| element.kind() == SyntaxKind::COMMENT)
+        || colon.next_token().is_some_and(|token| {
+            token.kind() == SyntaxKind::WHITESPACE && token.text().contains('\n')
+        })
+        || value.block().is_some_and(|block| {
+            block
+                .syntax()
+                .children()
+                .any(|child| matches!(child.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ))
+        })
+    {
+        return None;
+    }
+    let text = print(
+        &key.doc(ctx),
+        &PrintOptions {
+            indent_kind: IndentKind::Space,
+            line_break: Default::default(),
+            width: usize::MAX,
+            tab_size: ctx.tab_width,
+        },
+    );
+    if text.contains('\n') {
+        return None;
+    }
+    // space is added after aliases or properties without content
+    let has_space = flow
+        .syntax()
+        .children()
+        .any(|child| child.kind() == SyntaxKind::ALIAS)
+        || flow
+            .syntax()
+            .last_child_or_token()
+            .is_some_and(|last| last.kind() == SyntaxKind::PROPERTIES);
+    Some(text_width(&text, ctx.tab_width) + usize::from(has_space))
+}
+
+/// Compute spaces before trailing comments for the `alignTrailingComments` option.
+///
+/// Trailing comments of consecutive entries in the same block collection are aligned as a group,
+/// and groups are separated by blank lines, own-line comments or entries without trailing comments.
+/// Entries that span multiple lines aren't aligned,
+/// and a group isn't aligned if its comments would start beyond print width.
+pub(crate) fn align_comments(
+    node: &SyntaxNode,
+    print_width: usize,
+    ctx: &Ctx,
+) -> HashMap<SyntaxToken, usize> {
+    let mut spaces = HashMap::new();
+    if !ctx.options.align_trailing_comments {
+        return spaces;
+    }
+    let directive = &ctx.options.ignore_comment_directive;
+    node.descendants()
+        .filter(|node| matches!(node.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ))
+        .for_each(|collection| {
+            let mut group = vec![];
+            let mut is_in_ignored_region = false;
+            for element in collection.children_with_tokens() {
+                match element {
+                    SyntaxElement::Node(entry) => {
+                        if ctx.removed_entries.contains(&entry) {
+                            continue;
+                        }
+                        match trailing_comment(&entry)
+                            .filter(|_| !is_in_ignored_region && !should_ignore(&entry, ctx))
+                            .zip(single_line_entry_width(&entry, ctx))
+                        {
+                            Some((comment, width)) => {
+                                let column =
+                                    entry.first_token().map_or(0, |token| column_of(&token));
+                                group.push((comment, column + width));
+                            }
+                            None => flush_comment_group(&mut group, print_width, &mut spaces),
+                        }
+                    }
+                    SyntaxElement::Token(token) => {
+                        if token.kind() == SyntaxKind::WHITESPACE
+                            && token.text().chars().filter(|c| *c == '\n').count() > 1
+                            || token.kind() == SyntaxKind::COMMENT && is_own_line(&token)
+                        {
+                            flush_comment_group(&mut group, print_width, &mut spaces);
+                        }
+                        if is_suffixed_directive(&token, directive, "start") {
+                            is_in_ignored_region = true;
+                        } else if is_suffixed_directive(&token, directive, "end") {
+                            is_in_ignored_region = false;
+                        }
+                    }
+                }
+            }
+            flush_comment_group(&mut group, print_width, &mut spaces);
+        });
+    spaces
+}
+
+fn flush_comment_group(
+    group: &mut Vec<(SyntaxToken, usize)>,
+    print_width: usize,
+    spaces: &mut HashMap<SyntaxToken, usize>,
+) {
+    if group.len() < 2 {
+        group.clear();
+        return;
+    }
+    let max = group
+        .iter()
+        .map(|(_, width)| *width)
+        .max()
+        .unwrap_or_default();
+    if max + 1 >= print_width {
+        group.clear();
+        return;
+    }
+    spaces.extend(
+        group
+            .drain(..)
+            .map(|(comment, width)| (comment, max - width + 1)),
+    );
+}
+
+/// Get the comment at the same line after the given entry.
+///
+/// Such comment may be put into outer collection by parser if the entry is the last one.
+fn trailing_comment(entry: &SyntaxNode) -> Option<SyntaxToken> {
+    let whitespace = entry.last_token()?.next_token()?;
+    if whitespace.kind() != SyntaxKind::WHITESPACE || whitespace.text().contains('\n') {
+        return None;
+    }
+    whitespace
+        .next_token()
+        .filter(|token| token.kind() == SyntaxKind::COMMENT)
+}
+
+/// Get width of formatted entry if it can be printed in a single line.
+fn single_line_entry_width(entry: &SyntaxNode, ctx: &Ctx) -> Option<usize> {
+    let doc = if let Some(entry) = BlockMapEntry::cast(entry.clone()) {
+        entry.doc(ctx)
+    } else {
+        BlockSeqEntry::cast(entry.clone())?.doc(ctx)
+    };
+    let text = print(
+        &doc,
+        &PrintOptions {
+            indent_kind: IndentKind::Space,
+            line_break: Default::default(),
+            width: usize::MAX,
+            tab_size: ctx.tab_width,
+        },
+    );
+    if text.contains('\n') {
+        None
+    } else {
+        Some(text_width(&text, ctx.tab_width))
+    }
+}
+
+struct FlowCollectionFormatter<'a, 's> {
     open_text: &'static str,
     close_text: &'static str,
     space: Doc<'static>,
     open_token: Option<SyntaxToken>,
     close_token: Option<SyntaxToken>,
-    prefer_single_line: bool,
-    ctx: &'a Ctx<'a>,
+    layout: FlowCollectionLayout,
+    ctx: &'a Ctx<'a, 's>,
 }
-impl<'a> FlowCollectionFormatter<'a> {
-    fn flow_seq(open: Option<SyntaxToken>, close: Option<SyntaxToken>, ctx: &'a Ctx) -> Self {
+impl<'a, 's> FlowCollectionFormatter<'a, 's> {
+    fn flow_seq(
+        open: Option<SyntaxToken>,
+        close: Option<SyntaxToken>,
+        entries_count: usize,
+        ctx: &'a Ctx<'a, 's>,
+    ) -> Self {
         Self {
             open_text: "[",
             close_text: "]",
-            space: if ctx.options.bracket_spacing {
+            space: if has_flow_spacing(
+                ctx.options.bracket_spacing,
+                ctx.options.single_entry_bracket_spacing,
+                entries_count,
+            ) {
                 Doc::line_or_space()
             } else {
                 Doc::line_or_nil()
             },
             open_token: open,
             close_token: close,
-            prefer_single_line: ctx
-                .options
-                .flow_sequence_prefer_single_line
-                .unwrap_or(ctx.options.prefer_single_line),
+            layout: Self::layout(ctx.options.flow_sequence_prefer_single_line, ctx),
             ctx,
         }
     }
-    fn flow_map(open: Option<SyntaxToken>, close: Option<SyntaxToken>, ctx: &'a Ctx) -> Self {
+    fn flow_map(
+        open: Option<SyntaxToken>,
+        close: Option<SyntaxToken>,
+        entries_count: usize,
+        ctx: &'a Ctx<'a, 's>,
+    ) -> Self {
         Self {
             open_text: "{",
             close_text: "}",
-            space: if ctx.options.brace_spacing {
+            space: if has_flow_spacing(
+                ctx.options.brace_spacing,
+                ctx.options.single_entry_brace_spacing,
+                entries_count,
+            ) {
                 Doc::line_or_space()
             } else {
                 Doc::line_or_nil()
             },
             open_token: open,
             close_token: close,
-            prefer_single_line: ctx
-                .options
-                .flow_map_prefer_single_line
-                .unwrap_or(ctx.options.prefer_single_line),
+            layout: Self::layout(ctx.options.flow_map_prefer_single_line, ctx),
             ctx,
         }
     }
-    fn format(self, body: Doc<'static>) -> Doc<'static> {
+    /// Resolve layout, where `preferSingleLine` options only take effect in `auto` layout.
+    fn layout(prefer_single_line: Option<bool>, ctx: &Ctx) -> FlowCollectionLayout {
+        match &ctx.options.flow_collection_layout {
+            FlowCollectionLayout::Auto
+                if prefer_single_line.unwrap_or(ctx.options.prefer_single_line) =>
+            {
+                FlowCollectionLayout::SingleLine
+            }
+            layout => layout.clone(),
+        }
+    }
+    fn format(self, body: Doc<'s>) -> Doc<'s> {
         let ctx = self.ctx;
         let mut docs = Vec::with_capacity(5);
 
         docs.push(Doc::text(self.open_text));
 
         if let Some(open) = self.open_token {
-            if let Some(token) = open
+            let whitespace = open
                 .next_token()
-                .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
-            {
-                if self.prefer_single_line {
-                    docs.push(self.space.clone());
-                } else {
-                    if token.text().contains(['\n', '\r']) {
-                        docs.push(Doc::hard_line());
-                    } else {
-                        docs.push(self.space.clone());
-                    }
-                }
-                let mut trivia_docs = format_trivias_after_token(&token, ctx);
-                docs.append(&mut trivia_docs);
+                .filter(|token| token.kind() == SyntaxKind::WHITESPACE);
+            let is_multi_line = match self.layout {
+                FlowCollectionLayout::Auto => whitespace
+                    .as_ref()
+                    .is_some_and(|token| token.text().contains(['\n', '\r'])),
+                FlowCollectionLayout::Preserve => open
+                    .parent()
+                    .is_some_and(|collection| is_multi_line_flow_collection(&collection)),
+                FlowCollectionLayout::SingleLine => false,
+                FlowCollectionLayout::MultiLine => true,
+            };
+            docs.push(if is_multi_line {
+                Doc::hard_line()
             } else {
-                docs.push(self.space.clone());
-                let mut trivia_docs = format_trivias_after_token(&open, ctx);
-                docs.append(&mut trivia_docs);
-            }
+                self.space.clone()
+            });
+            let mut trivia_docs =
+                format_trivias_after_token(whitespace.as_ref().unwrap_or(&open), ctx);
+            docs.append(&mut trivia_docs);
         }
 
         docs.push(body);
@@ -991,7 +1563,7 @@ impl<'a> FlowCollectionFormatter<'a> {
         }
 
         Doc::list(docs)
-            .nest(ctx.indent_width)
+            .nest(ctx.options.indent_width_flow.unwrap_or(ctx.indent_width))
             .append(if has_comment {
                 Doc::hard_line()
             } else {
@@ -1001,25 +1573,46 @@ impl<'a> FlowCollectionFormatter<'a> {
             .group()
     }
 }
-fn format_flow_collection_entries<N, Entry>(
+/// Check if flow collection is written on multiple lines,
+/// ignoring line breaks inside nested flow collections.
+fn is_multi_line_flow_collection(collection: &SyntaxNode) -> bool {
+    collection
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| {
+            token.kind() == SyntaxKind::WHITESPACE && token.text().contains(['\n', '\r'])
+        })
+        .any(|token| {
+            token
+                .parent_ancestors()
+                .find(|node| matches!(node.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP))
+                .as_ref()
+                == Some(collection)
+        })
+}
+
+fn format_flow_collection_entries<'s, N, Entry>(
     node: &N,
     entries: AstChildren<Entry>,
-    ctx: &Ctx,
-) -> Doc<'static>
+    ctx: &Ctx<'_, 's>,
+) -> Doc<'s>
 where
     N: AstNode,
     Entry: AstNode + DocGen,
 {
     let mut docs = vec![];
-    let mut entries = entries.peekable();
-    let mut commas = node
+    let commas = node
         .syntax()
         .children_with_tokens()
         .filter_map(|element| match element {
             SyntaxElement::Token(token) if token.kind() == SyntaxKind::COMMA => Some(token),
             _ => None,
         });
-    while let Some(entry) = entries.next() {
+    let mut entries = entries
+        .zip(commas.map(Some).chain(iter::repeat(None)))
+        .filter(|(entry, _)| !ctx.removed_entries.contains(entry.syntax()))
+        .peekable();
+    while let Some((entry, comma)) = entries.next() {
         docs.push(entry.doc(ctx));
         if entries.peek().is_some() {
             docs.push(Doc::text(","));
@@ -1027,7 +1620,6 @@ where
             docs.push(Doc::flat_or_break(Doc::nil(), Doc::text(",")));
         }
 
-        let comma = commas.next();
         let mut has_comment_before_comma = false;
         let last_ws_index = comma
             .as_ref()
@@ -1047,11 +1639,41 @@ where
         }
 
         if let Some(comma) = &comma {
-            let mut trivia_docs = format_trivias(
-                comma.siblings_with_tokens(Direction::Next),
-                &mut has_comment_before_comma,
-                ctx,
-            );
+            let mut trivia_docs = if entries.peek().is_none()
+                && comma.next_sibling_or_token().is_some()
+                && !ctx.removed_entries.is_empty()
+            {
+                // entries after this were removed, so only comments are kept
+                let trivias = comma
+                    .siblings_with_tokens(Direction::Next)
+                    .take_while(|element| {
+                        matches!(
+                            element.kind(),
+                            SyntaxKind::COMMA | SyntaxKind::WHITESPACE | SyntaxKind::COMMENT
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(last_comment) = trivias
+                    .iter()
+                    .rposition(|element| element.kind() == SyntaxKind::COMMENT)
+                {
+                    let mut trivia_docs = format_trivias(
+                        trivias.into_iter().take(last_comment + 1),
+                        &mut has_comment_before_comma,
+                        ctx,
+                    );
+                    trivia_docs.push(Doc::flat_or_break(Doc::hard_line(), Doc::nil()));
+                    trivia_docs
+                } else {
+                    vec![]
+                }
+            } else {
+                format_trivias(
+                    comma.siblings_with_tokens(Direction::Next),
+                    &mut has_comment_before_comma,
+                    ctx,
+                )
+            };
             if !trivia_docs.is_empty() {
                 docs.append(&mut trivia_docs);
             } else if trivia_docs.is_empty() && entries.peek().is_some() {
@@ -1062,48 +1684,140 @@ where
     Doc::list(docs)
 }
 
-fn format_line_break_separated_list<N, Item, const SKIP_SIDE_WS: bool>(
+#[allow(clippy::collapsible_match)]
+fn format_line_break_separated_list<'s, N, Item, const SKIP_SIDE_WS: bool>(
     node: &N,
-    ctx: &Ctx,
-) -> Vec<Doc<'static>>
+    ctx: &Ctx<'_, 's>,
+) -> Vec<Doc<'s>>
 where
     N: AstNode,
     Item: AstNode + DocGen,
 {
     let mut docs = Vec::with_capacity(2);
 
-    let mut children = node.syntax().children_with_tokens().peekable();
+    let skipped = collect_removed_elements(node.syntax(), ctx);
+    let mut children = node
+        .syntax()
+        .children_with_tokens()
+        .filter(|element| !skipped.contains(&element.index()))
+        .peekable();
     let mut prev_kind = SyntaxKind::WHITESPACE;
+    let is_top_level = node
+        .syntax()
+        .parent()
+        .filter(|parent| parent.kind() == SyntaxKind::BLOCK)
+        .and_then(|block| block.parent())
+        .is_some_and(|parent| parent.kind() == SyntaxKind::DOCUMENT);
+    // original text between `ignore-start` and `ignore-end` directives,
+    // with the column where it starts in source
+    let mut ignored: Option<(String, usize)> = None;
     while let Some(element) = children.next() {
         let kind = element.kind();
-        match element {
-            SyntaxElement::Node(node) => {
-                if should_ignore(&node, ctx) {
-                    reflow(&node.to_string(), &mut docs);
-                } else if let Some(item) = Item::cast(node) {
+        if let Some((text, column)) = &mut ignored {
+            if kind == SyntaxKind::WHITESPACE
+                && children.peek().is_none_or(|next| {
+                    next.as_token().is_some_and(|token| {
+                        is_suffixed_directive(token, &ctx.options.ignore_comment_directive, "end")
+                    })
+                })
+            {
+                reflow_ignored_range(mem::take(text), *column, &mut docs);
+                ignored = None;
+            } else if !(text.is_empty() && kind == SyntaxKind::WHITESPACE) {
+                if text.is_empty() {
+                    *column = match &element {
+                        SyntaxElement::Node(node) => node.first_token(),
+                        SyntaxElement::Token(token) => Some(token.clone()),
+                    }
+                    .map_or(0, |token| column_of(&token));
+                }
+                text.push_str(&element.to_string());
+                prev_kind = kind;
+                continue;
+            }
+        }
+        match element {
+            SyntaxElement::Node(node) => {
+                if should_ignore(&node, ctx) {
+                    reflow(ctx.node_text(&node), &mut docs);
+                } else if let Some(item) = Item::cast(node.clone()) {
                     docs.push(item.doc(ctx));
+                    if matches!(ctx.options.comment_indent, CommentIndent::FollowNext) {
+                        let comments = node
+                            .last_token()
+                            .into_iter()
+                            .flat_map(|token| {
+                                iter::successors(Some(token), SyntaxToken::prev_token)
+                            })
+                            .take_while(|token| {
+                                matches!(token.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT)
+                            })
+                            .collect::<Vec<_>>();
+                        format_incoming_comments(
+                            comments.into_iter().rev(),
+                            node.parent(),
+                            &mut docs,
+                            ctx,
+                        );
+                    }
                 }
             }
+            SyntaxElement::Token(token) if comment_owner(&token, ctx).is_some() => {
+                // printed by another collection
+                continue;
+            }
+            SyntaxElement::Token(token)
+                if token.kind() == SyntaxKind::WHITESPACE
+                    && children.peek().is_some_and(|next| {
+                        next.as_token()
+                            .is_some_and(|next| comment_owner(next, ctx).is_some())
+                    }) =>
+            {
+                continue;
+            }
             SyntaxElement::Token(token) => match token.kind() {
                 SyntaxKind::COMMENT => {
                     docs.push(format_comment(&token, ctx));
+                    if is_suffixed_directive(&token, &ctx.options.ignore_comment_directive, "start")
+                    {
+                        ignored = Some((String::new(), 0));
+                    }
                 }
                 SyntaxKind::WHITESPACE => {
                     if !SKIP_SIDE_WS || token.index() > 0 && children.peek().is_some() {
-                        match token.text().chars().filter(|c| *c == '\n').count() {
+                        let mut line_breaks = token.text().chars().filter(|c| *c == '\n').count();
+                        let at_least = if line_breaks > 0
+                            && is_top_level
+                            && children.peek().is_some()
+                            && is_after_entry(&token, ctx)
+                        {
+                            ctx.options.blank_lines_between_top_level_entries
+                        } else {
+                            0
+                        };
+                        if at_least > 0 {
+                            line_breaks = line_breaks.max(at_least + 1);
+                        }
+                        match line_breaks {
                             0 => {
                                 if prev_kind == SyntaxKind::COMMENT {
                                     docs.push(Doc::hard_line());
+                                } else if let Some(spaces) = children
+                                    .peek()
+                                    .and_then(|next| next.as_token())
+                                    .and_then(|next| ctx.aligned_comments.get(next))
+                                {
+                                    docs.push(Doc::text(" ".repeat(*spaces)));
                                 } else {
                                     docs.push(Doc::space());
                                 }
                             }
                             1 => {
-                                docs.push(Doc::hard_line());
+                                docs.push(line_break_before(children.peek(), ctx));
                             }
-                            _ => {
-                                docs.push(Doc::empty_line());
-                                docs.push(Doc::hard_line());
+                            line_breaks => {
+                                docs.extend(blank_lines(&token, line_breaks, at_least, ctx));
+                                docs.push(line_break_before(children.peek(), ctx));
                             }
                         }
                     }
@@ -1113,11 +1827,337 @@ where
         }
         prev_kind = kind;
     }
+    if let Some((text, column)) = ignored {
+        reflow_ignored_range(text, column, &mut docs);
+    }
+    if !matches!(ctx.options.comment_indent, CommentIndent::FollowNext) {
+        let comments = node
+            .syntax()
+            .last_token()
+            .into_iter()
+            .flat_map(|token| iter::successors(token.next_token(), SyntaxToken::next_token))
+            .take_while(|token| {
+                matches!(token.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT)
+            });
+        format_incoming_comments(comments, Some(node.syntax().clone()), &mut docs, ctx);
+    }
 
     docs
 }
 
-fn format_trivias_after_token(token: &SyntaxToken, ctx: &Ctx) -> Vec<Doc<'static>> {
+/// Print comments that belong to other collections in syntax tree
+/// but should be printed by the given collection according to `commentIndent` option.
+fn format_incoming_comments<'s>(
+    trivias: impl Iterator<Item = SyntaxToken>,
+    collection: Option<SyntaxNode>,
+    docs: &mut Vec<Doc<'s>>,
+    ctx: &Ctx<'_, 's>,
+) {
+    let Some(collection) = collection else {
+        return;
+    };
+    trivias
+        .filter(|token| {
+            token.kind() == SyntaxKind::COMMENT
+                && comment_owner(token, ctx).as_ref() == Some(&collection)
+        })
+        .for_each(|comment| {
+            let whitespace = comment
+                .prev_token()
+                .filter(|token| token.kind() == SyntaxKind::WHITESPACE);
+            let line_breaks = whitespace.as_ref().map_or(0, |token| {
+                token.text().chars().filter(|c| *c == '\n').count()
+            });
+            match (line_breaks, whitespace) {
+                (0, _) | (_, None) => docs.push(Doc::space()),
+                (1, _) => docs.push(line_break_before_comment(&comment, ctx)),
+                (line_breaks, Some(whitespace)) => {
+                    docs.extend(blank_lines(&whitespace, line_breaks, 0, ctx));
+                    docs.push(line_break_before_comment(&comment, ctx));
+                }
+            }
+            docs.push(format_comment(&comment, ctx));
+        });
+}
+
+/// Blank lines for whitespace that has the given number of line breaks,
+/// which is limited by `maxBlankLines` option unless at least some blank lines are required.
+/// Blank lines after block scalars with keep chomping indicator are their content,
+/// so they aren't limited.
+fn blank_lines<'s>(
+    whitespace: &SyntaxToken,
+    line_breaks: usize,
+    at_least: usize,
+    ctx: &Ctx<'_, 's>,
+) -> impl Iterator<Item = Doc<'s>> {
+    let max = if is_after_kept_block_scalar(whitespace) {
+        usize::MAX
+    } else {
+        ctx.options.max_blank_lines
+    };
+    iter::repeat_with(Doc::empty_line).take((line_breaks - 1).min(max).max(at_least))
+}
+
+fn is_after_kept_block_scalar(whitespace: &SyntaxToken) -> bool {
+    whitespace
+        .prev_token()
+        .and_then(|token| token.parent_ancestors().find_map(BlockScalar::cast))
+        .and_then(|scalar| scalar.chomping_indicator())
+        .is_some_and(|indicator| indicator.plus().is_some())
+}
+
+/// Check if the whitespace comes right after an entry and its trailing comments,
+/// which are comments at the same line or comments moved into the entry by `commentIndent` option.
+fn is_after_entry(whitespace: &SyntaxToken, ctx: &Ctx) -> bool {
+    match whitespace.prev_sibling_or_token() {
+        Some(SyntaxElement::Node(_)) => true,
+        Some(SyntaxElement::Token(token)) => {
+            token.kind() == SyntaxKind::COMMENT
+                && (!is_own_line(&token) || comment_owner(&token, ctx).is_some())
+        }
+        None => false,
+    }
+}
+
+fn line_break_before<'s>(next: Option<&SyntaxElement>, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+    match next {
+        Some(SyntaxElement::Token(token)) if token.kind() == SyntaxKind::COMMENT => {
+            line_break_before_comment(token, ctx)
+        }
+        _ => Doc::hard_line(),
+    }
+}
+
+/// Comments at the beginning of line aren't indented when `commentIndent` is `dedent`.
+fn line_break_before_comment<'s>(comment: &SyntaxToken, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+    if matches!(ctx.options.comment_indent, CommentIndent::Dedent)
+        && is_own_line(comment)
+        && column_of(comment) == 0
+    {
+        Doc::empty_line()
+    } else {
+        Doc::hard_line()
+    }
+}
+
+/// Find the block collection that should print the given own-line comment
+/// if it isn't the collection that contains the comment in syntax tree.
+fn comment_owner(comment: &SyntaxToken, ctx: &Ctx) -> Option<SyntaxNode> {
+    if comment.kind() != SyntaxKind::COMMENT {
+        return None;
+    }
+    let parent = comment.parent()?;
+    let directive = &ctx.options.ignore_comment_directive;
+    match ctx.options.comment_indent {
+        CommentIndent::FollowPrev | CommentIndent::Dedent => {
+            // Parser puts comments after the last entry of nested collection
+            // into the outer collection when that entry has a trailing comment
+            // or there're no more entries in the document,
+            // so find the collection that they should belong to by indentation.
+            if !matches!(
+                parent.kind(),
+                SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ | SyntaxKind::ROOT
+            ) {
+                return None;
+            }
+            let prev = comment
+                .siblings_with_tokens(Direction::Prev)
+                .find_map(SyntaxElement::into_node)?;
+            adopted_comments(&prev, ctx)
+                .into_iter()
+                .find(|(token, _)| token == comment)
+                .map(|(_, owner)| owner)
+        }
+        CommentIndent::FollowNext => {
+            if !matches!(parent.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ)
+                || !is_own_line(comment)
+                || comment
+                    .siblings_with_tokens(Direction::Next)
+                    .any(|element| element.as_node().is_some())
+                || comment
+                    .siblings_with_tokens(Direction::Prev)
+                    .map_while(SyntaxElement::into_token)
+                    .any(|token| strip_directive(&token, directive).is_some())
+            {
+                return None;
+            }
+            // move out to the closest outer collection that has entries after the comment
+            let mut inner = parent;
+            loop {
+                let outer = inner.ancestors().skip(1).find(|ancestor| {
+                    matches!(
+                        ancestor.kind(),
+                        SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ
+                    )
+                })?;
+                let branch = inner
+                    .ancestors()
+                    .take_while(|ancestor| ancestor != &outer)
+                    .last()?;
+                if inner
+                    .ancestors()
+                    .take_while(|ancestor| ancestor != &outer)
+                    .any(|ancestor| is_ignored(&ancestor, directive))
+                {
+                    return None;
+                }
+                if branch.next_sibling().is_some() {
+                    return Some(outer);
+                }
+                inner = outer;
+            }
+        }
+    }
+}
+
+/// Comments after the given entry that belong to nested collections at the end of that entry,
+/// with the collections that they belong to.
+fn adopted_comments(prev: &SyntaxNode, ctx: &Ctx) -> Vec<(SyntaxToken, SyntaxNode)> {
+    let directive = &ctx.options.ignore_comment_directive;
+    if is_ignored(prev, directive) || ctx.removed_entries.contains(prev) {
+        return vec![];
+    }
+    let end = prev.text_range().end();
+    let chain = iter::successors(Some(prev.clone()), |node| {
+        node.last_child().filter(|child| {
+            child.text_range().end() == end
+                && !is_ignored(child, directive)
+                && !ctx.removed_entries.contains(child)
+        })
+    })
+    .filter(|node| matches!(node.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ))
+    .map(|node| {
+        let column = node.first_token().map_or(0, |token| column_of(&token));
+        (node, column)
+    })
+    .collect::<Vec<_>>();
+    if chain.is_empty() {
+        return vec![];
+    }
+
+    let mut depth = chain.len();
+    let mut trailing = None;
+    let mut adopted = vec![];
+    for token in prev
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .map_while(SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::COMMENT)
+    {
+        if strip_directive(&token, directive).is_some() {
+            break;
+        }
+        if !is_own_line(&token) {
+            trailing = Some(token);
+            continue;
+        }
+        let column = column_of(&token);
+        let Some(index) = chain[..depth]
+            .iter()
+            .rposition(|(_, list_column)| *list_column <= column)
+        else {
+            break;
+        };
+        depth = index + 1;
+        adopted.push((token, chain[index].0.clone()));
+    }
+    if let Some(trailing) = trailing.filter(|_| !adopted.is_empty()) {
+        // trailing comment of the last entry in the deepest collection
+        if let Some((deepest, _)) = chain.last() {
+            adopted.insert(0, (trailing, deepest.clone()));
+        }
+    }
+    adopted
+}
+
+fn is_own_line(token: &SyntaxToken) -> bool {
+    token.prev_token().is_none_or(|token| {
+        token.text().contains('\n')
+            // indentation at the start of input
+            || token.kind() == SyntaxKind::WHITESPACE && token.prev_token().is_none()
+    })
+}
+
+/// Measure width of single-line text, where tabs advance to the next tab stop.
+fn text_width(text: &str, tab_width: usize) -> usize {
+    text.chars().fold(0, |width, c| {
+        if c == '\t' {
+            width + tab_width - width % tab_width
+        } else {
+            width + 1
+        }
+    })
+}
+
+/// Count characters from the beginning of line to the given token.
+fn column_of(token: &SyntaxToken) -> usize {
+    let mut column = 0;
+    let mut prev = token.prev_token();
+    while let Some(token) = prev {
+        let text = token.text();
+        if let Some(index) = text.rfind(['\n', '\r']) {
+            column += text[index + 1..].chars().count();
+            break;
+        }
+        column += text.chars().count();
+        prev = token.prev_token();
+    }
+    column
+}
+
+/// Collect indexes of removed entries and their surrounding trivias,
+/// so line breaks around them won't be doubled.
+fn collect_removed_elements(node: &SyntaxNode, ctx: &Ctx) -> HashSet<usize> {
+    let mut skipped = HashSet::new();
+    if ctx.removed_entries.is_empty() {
+        return skipped;
+    }
+    node.children()
+        .filter(|child| ctx.removed_entries.contains(child))
+        .for_each(|child| {
+            skipped.insert(child.index());
+            // trailing comment at the same line belongs to the removed entry
+            let mut next = child.next_sibling_or_token();
+            while let Some(element) = next
+                .as_ref()
+                .filter(|element| match element {
+                    SyntaxElement::Token(token) => {
+                        token.kind() == SyntaxKind::COMMENT
+                            || token.kind() == SyntaxKind::WHITESPACE
+                                && !token.text().contains(['\n', '\r'])
+                    }
+                    SyntaxElement::Node(_) => false,
+                })
+                .cloned()
+            {
+                skipped.insert(element.index());
+                next = element.next_sibling_or_token();
+            }
+            if let Some(next) = next.filter(|element| element.kind() == SyntaxKind::WHITESPACE) {
+                skipped.insert(next.index());
+            }
+        });
+    if !skipped.is_empty() {
+        // whitespaces at the start or at the end are left after removing entries
+        let kept = node
+            .children_with_tokens()
+            .filter(|element| !skipped.contains(&element.index()))
+            .collect::<Vec<_>>();
+        kept.iter()
+            .take_while(|element| element.kind() == SyntaxKind::WHITESPACE)
+            .chain(
+                kept.iter()
+                    .rev()
+                    .take_while(|element| element.kind() == SyntaxKind::WHITESPACE),
+            )
+            .for_each(|element| {
+                skipped.insert(element.index());
+            });
+    }
+    skipped
+}
+
+fn format_trivias_after_token<'s>(token: &SyntaxToken, ctx: &Ctx<'_, 's>) -> Vec<Doc<'s>> {
     let mut _has_comment = false;
     format_trivias(
         token.siblings_with_tokens(Direction::Next),
@@ -1126,11 +2166,11 @@ fn format_trivias_after_token(token: &SyntaxToken, ctx: &Ctx) -> Vec<Doc<'static
     )
 }
 
-fn format_trivias(
+fn format_trivias<'s>(
     it: impl Iterator<Item = SyntaxElement>,
     has_comment: &mut bool,
-    ctx: &Ctx,
-) -> Vec<Doc<'static>> {
+    ctx: &Ctx<'_, 's>,
+) -> Vec<Doc<'s>> {
     let mut docs = vec![];
     let mut trivias = it
         .skip(1)
@@ -1166,8 +2206,8 @@ fn format_trivias(
                         docs.push(Doc::line_or_space());
                     }
                 }
-                _ => {
-                    docs.push(Doc::empty_line());
+                line_breaks => {
+                    docs.extend(blank_lines(&token, line_breaks, 0, ctx));
                     docs.push(Doc::hard_line());
                 }
             },
@@ -1181,25 +2221,245 @@ fn format_trivias(
     docs
 }
 
-fn format_comment(token: &SyntaxToken, ctx: &Ctx) -> Doc<'static> {
+fn format_comment<'s>(token: &SyntaxToken, ctx: &Ctx<'_, 's>) -> Doc<'s> {
+    use crate::config::CapitalizeComments;
+
     let text = token.text().trim_end();
-    if ctx.options.format_comments {
-        let content = text.strip_prefix('#').expect("comment must start with '#'");
-        if content.is_empty() || content.starts_with([' ', '\t']) {
-            Doc::text(text.to_string())
+    let options = &ctx.options.format_comments;
+    // headings like `## title` are treated as comments with longer marker
+    let content = text.trim_start_matches('#');
+    let marker = &text[..text.len() - content.len()];
+    if content.is_empty()
+        // markers like shebang `#!` and doc comment `#:` are kept as-is
+        || content.starts_with(['!', ':'])
+        || !options.space_after_hash
+            && matches!(options.capitalize, CapitalizeComments::Never)
+            && ctx.options.comment_space == 0
+            && !ctx.options.wrap_comments
+        // banner comments are kept as-is unless it's allowed
+        || !options.banner && is_in_banner_block(token)
+    {
+        return Doc::text(ctx.slice_token(token, text));
+    }
+
+    let rest = content.trim_start_matches([' ', '\t']);
+    let spaces = &content[..content.len() - rest.len()];
+    let should_capitalize = match options.capitalize {
+        CapitalizeComments::Never => false,
+        CapitalizeComments::OwnLine => token
+            .prev_token()
+            .is_none_or(|token| token.text().contains('\n')),
+        CapitalizeComments::Always => true,
+    } && !(ctx.options.wrap_comments && prev_comment_line(token).is_some());
+    let rest = if should_capitalize && is_prose_word(rest) {
+        let mut chars = rest.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        rest.to_string()
+    };
+    let min_spaces = ctx.options.comment_space;
+    let normalize_spaces = |spaces: &str| {
+        if options.space_after_hash {
+            " ".repeat(options.spaces.max(min_spaces))
+        } else if spaces.chars().count() < min_spaces {
+            " ".repeat(min_spaces)
         } else {
-            Doc::text(format!("# {content}"))
+            spaces.to_owned()
+        }
+    };
+    let prefix = match continued_list_item(token).filter(|_| ctx.options.wrap_comments) {
+        // keep continuation lines aligned after the list marker
+        Some((item_spaces, width)) => {
+            format!(
+                "{marker}{}{}",
+                normalize_spaces(&item_spaces),
+                " ".repeat(width)
+            )
         }
+        None => format!("{marker}{}", normalize_spaces(spaces)),
+    };
+    if ctx.options.wrap_comments
+        && is_own_line(token)
+        && !text.contains(ctx.options.ignore_comment_directive.as_str())
+    {
+        wrap_comment(prefix, &rest)
     } else {
-        Doc::text(text.to_string())
+        Doc::text(prefix + &rest)
+    }
+}
+
+/// Get the previous comment if it's non-empty and at the end of the line right before,
+/// so the comment may be a continuation line of wrapped comment.
+fn prev_comment_line(token: &SyntaxToken) -> Option<SyntaxToken> {
+    token
+        .prev_token()
+        .filter(|whitespace| {
+            whitespace.kind() == SyntaxKind::WHITESPACE
+                && whitespace.text().matches('\n').count() == 1
+        })
+        .and_then(|whitespace| whitespace.prev_token())
+        .filter(|prev| {
+            prev.kind() == SyntaxKind::COMMENT
+                && !prev.text().trim_start_matches('#').trim().is_empty()
+        })
+}
+
+/// Find the list item comment like `# - item` that the comment continues,
+/// and return spaces after `#` of that list item with width of its list marker.
+fn continued_list_item(token: &SyntaxToken) -> Option<(String, usize)> {
+    let spaces = split_comment(token.text()).1.len();
+    let mut current = token.clone();
+    while let Some(prev) = prev_comment_line(&current)
+        .filter(|prev| is_own_line(prev) && column_of(prev) == column_of(token))
+    {
+        let (_, prev_spaces, rest) = split_comment(prev.text());
+        if let Some(width) = comment_list_marker_width(rest) {
+            return (prev_spaces.len() + width == spaces).then(|| (prev_spaces.to_owned(), width));
+        }
+        if prev_spaces.len() != spaces {
+            return None;
+        }
+        current = prev;
+    }
+    None
+}
+
+/// Split comment into `#` marker, spaces after marker and the rest content.
+fn split_comment(text: &str) -> (&str, &str, &str) {
+    let content = text.trim_start_matches('#');
+    let rest = content.trim_start_matches([' ', '\t']);
+    (
+        &text[..text.len() - content.len()],
+        &content[..content.len() - rest.len()],
+        rest,
+    )
+}
+
+/// Break comment into multiple lines at print width for the `wrapComments` option.
+///
+/// Only single spaces between words can be broken,
+/// and continuation lines of list items like `# - item` are indented after the list marker.
+fn wrap_comment(prefix: String, rest: &str) -> Doc<'static> {
+    let continuation = format!(
+        "{prefix}{}",
+        " ".repeat(comment_list_marker_width(rest).unwrap_or_default())
+    );
+    let mut docs = vec![Doc::text(prefix)];
+    let mut start = 0;
+    let bytes = rest.as_bytes();
+    for (i, byte) in bytes.iter().enumerate() {
+        if *byte == b' '
+            && i > start
+            && bytes
+                .get(i + 1)
+                .is_some_and(|next| !next.is_ascii_whitespace())
+            && !bytes[i - 1].is_ascii_whitespace()
+        {
+            push_comment_word(&mut docs, &rest[start..i], &continuation);
+            start = i + 1;
+        }
+    }
+    push_comment_word(&mut docs, &rest[start..], &continuation);
+    Doc::list(docs)
+}
+
+/// Get width of list item marker like `- ` or `1. ` at the start of comment content.
+fn comment_list_marker_width(rest: &str) -> Option<usize> {
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let marker_len = if digits > 0 {
+        matches!(rest.as_bytes().get(digits), Some(b'.' | b')')).then_some(digits + 1)?
+    } else {
+        matches!(rest.as_bytes().first(), Some(b'-' | b'*' | b'+')).then_some(1)?
+    };
+    (rest.as_bytes().get(marker_len) == Some(&b' ')).then_some(marker_len + 1)
+}
+
+fn push_comment_word<'s>(docs: &mut Vec<Doc<'s>>, word: &str, continuation: &str) {
+    let word = Doc::text(word.to_owned());
+    if docs.len() == 1 {
+        docs.push(word);
+    } else {
+        docs.push(
+            Doc::flat_or_break(
+                Doc::space(),
+                Doc::hard_line().append(Doc::text(continuation.to_owned())),
+            )
+            .append(word)
+            .group(),
+        );
+    }
+}
+
+/// Check if the comment is a part of banner block,
+/// which is a group of consecutive own-line comments that contains decorative lines
+/// like `####`, `#----`, box-drawing characters or table rows.
+fn is_in_banner_block(token: &SyntaxToken) -> bool {
+    // comments in the same block are separated by exactly one line break
+    let is_single_line_break = |token: &SyntaxToken| {
+        token.kind() == SyntaxKind::WHITESPACE
+            && token.text().chars().filter(|c| *c == '\n').count() == 1
+    };
+    if !is_own_line(token) {
+        return is_decorative_comment(token.text());
+    }
+
+    let mut first = token.clone();
+    while let Some(prev) = first
+        .prev_token()
+        .filter(is_single_line_break)
+        .and_then(|whitespace| whitespace.prev_token())
+        .filter(|token| token.kind() == SyntaxKind::COMMENT && is_own_line(token))
+    {
+        first = prev;
     }
+    iter::successors(Some(first), |token| {
+        token
+            .next_token()
+            .filter(is_single_line_break)
+            .and_then(|whitespace| whitespace.next_token())
+            .filter(|token| token.kind() == SyntaxKind::COMMENT)
+    })
+    .any(|token| is_decorative_comment(token.text()))
+}
+
+/// Check if the comment is a decorative line made of only decoration characters,
+/// a line that contains box-drawing characters, or a table row like `| a | b |`.
+fn is_decorative_comment(text: &str) -> bool {
+    let content = text.strip_prefix('#').unwrap_or(text).trim();
+    content.chars().count() >= 3
+        && content
+            .chars()
+            .all(|c| matches!(c, '-' | '=' | '*' | '~' | '_' | '+' | '/' | '\\' | '#'))
+        || content
+            .chars()
+            .any(|c| matches!(c, '\u{2500}'..='\u{259f}'))
+        || content.len() >= 3 && content.starts_with('|') && content.ends_with('|')
+}
+
+/// Check if the first word looks like a word of natural language
+/// instead of code, key or directive, so it can be capitalized.
+fn is_prose_word(text: &str) -> bool {
+    text.split_whitespace().next().is_some_and(|word| {
+        word.trim_end_matches(['.', ',', ';', '!', '?'])
+            .chars()
+            .all(char::is_alphabetic)
+    })
 }
 
-fn format_quoted_scalar(
+/// Format content of quoted scalar line by line.
+///
+/// Line breaks are kept, and white spaces around them are trimmed since they're folded when decoding,
+/// while escaped white spaces and escaped line breaks in double quoted scalar are kept.
+fn format_quoted_scalar<'s>(
+    token: &SyntaxToken,
     text: &str,
-    quotes_option: Option<&Quotes>,
-    docs: &mut Vec<Doc<'static>>,
-    ctx: &Ctx,
+    is_double_quoted: bool,
+    conversion: QuoteConversion,
+    docs: &mut Vec<Doc<'s>>,
+    ctx: &Ctx<'_, 's>,
 ) {
     if text.is_empty() {
         return;
@@ -1208,30 +2468,146 @@ fn format_quoted_scalar(
     let last_index = lines.len() - 1;
     for (i, mut line) in lines.into_iter().enumerate() {
         if i > 0 {
-            line = line.trim_start();
+            // only spaces and tabs are white spaces in YAML,
+            // other Unicode white spaces like U+00A0 are content
+            line = line.trim_start_matches([' ', '\t']);
         }
-        if i < last_index && ctx.options.trim_trailing_whitespaces {
-            line = line.trim_end();
+        if i < last_index {
+            line = line.strip_suffix('\r').unwrap_or(line);
         }
+        let line = if i < last_index && ctx.options.trim_trailing_whitespaces {
+            trim_quoted_scalar_line_end(line, is_double_quoted)
+        } else {
+            Cow::Borrowed(line)
+        };
+        let line = match format_quoted_scalar_line(&line, conversion) {
+            Cow::Borrowed(line) => ctx.slice_token(token, line),
+            Cow::Owned(line) => Cow::Owned(line),
+        };
         if i == 0 {
-            docs.push(Doc::text(format_quoted_scalar_line(line, quotes_option)));
+            docs.push(Doc::text(line));
         } else if line.is_empty() {
             docs.push(Doc::empty_line());
         } else {
             docs.push(Doc::hard_line());
-            docs.push(Doc::text(format_quoted_scalar_line(line, quotes_option)));
+            docs.push(Doc::text(line));
         }
     }
 }
-fn format_quoted_scalar_line(s: &str, quotes_option: Option<&Quotes>) -> String {
-    match quotes_option {
-        Some(Quotes::ForceDouble) => s.replace("''", "'"),
-        Some(Quotes::ForceSingle) => s.replace('\'', "''"),
-        Some(Quotes::PreferDouble | Quotes::PreferSingle) | None => s.to_owned(),
+
+/// Trim trailing white spaces of line in quoted scalar that is followed by line break.
+///
+/// In double quoted scalar, white space after an odd number of backslashes is escaped,
+/// so it's content and must be kept, otherwise the backslash will escape the line break.
+/// It's converted to `\x20` or `\t` so there won't be trailing white spaces.
+fn trim_quoted_scalar_line_end(line: &str, is_double_quoted: bool) -> Cow<'_, str> {
+    let trimmed = line.trim_end_matches([' ', '\t']);
+    if is_double_quoted
+        && trimmed.len() < line.len()
+        && (trimmed.len() - trimmed.trim_end_matches('\\').len()) % 2 == 1
+    {
+        let escape = if line[trimmed.len()..].starts_with(' ') {
+            "x20"
+        } else {
+            "t"
+        };
+        Cow::Owned(format!("{trimmed}{escape}"))
+    } else {
+        Cow::Borrowed(trimmed)
     }
 }
 
-fn can_omit_question_mark(key: &SyntaxNode) -> bool {
+fn format_quoted_scalar_line(s: &str, conversion: QuoteConversion) -> Cow<'_, str> {
+    match conversion {
+        QuoteConversion::Keep => Cow::Borrowed(s),
+        QuoteConversion::ToDouble => Cow::Owned(
+            s.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace("''", "'"),
+        ),
+        QuoteConversion::ToSingle => {
+            let mut result = String::with_capacity(s.len());
+            let mut chars = s.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    // other escapes are rejected by `count_single_quotable_escapes`
+                    '\\' => result.extend(chars.next()),
+                    '\'' => result.push_str("''"),
+                    c => result.push(c),
+                }
+            }
+            Cow::Owned(result)
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+/// How to convert content of quoted scalar when changing its quotes.
+enum QuoteConversion {
+    Keep,
+    /// Unescape `\"`, `\\` and `\/`, then escape single quotes.
+    ToSingle,
+    /// Escape backslashes and double quotes, then unescape single quotes.
+    ToDouble,
+}
+
+/// Count escape sequences in content of double quoted scalar.
+/// Returns `None` if it can't be represented in single quoted scalar,
+/// which doesn't support escape sequences other than `''`.
+fn count_single_quotable_escapes(text: &str) -> Option<usize> {
+    let mut escapes = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if !matches!(chars.next(), Some('"' | '\\' | '/')) {
+                return None;
+            }
+            escapes += 1;
+        }
+    }
+    Some(escapes)
+}
+
+/// Check if key of block map entry should be explicit,
+/// which is controlled by `explicitKeyThreshold` option and `explicitKeys` option.
+fn requires_explicit_key(key: &SyntaxNode, ctx: &Ctx) -> bool {
+    if key.kind() != SyntaxKind::BLOCK_MAP_KEY {
+        return false;
+    }
+    let Some(flow) = key
+        .children()
+        .find(|child| child.kind() == SyntaxKind::FLOW)
+    else {
+        return false;
+    };
+    // plain key ending with colon can't be converted, otherwise it will be parsed differently
+    if flow
+        .children_with_tokens()
+        .any(|element| element.kind() == SyntaxKind::PLAIN_SCALAR)
+        && flow.text().to_string().trim_end().ends_with(':')
+    {
+        return false;
+    }
+    let is_collection = flow
+        .children()
+        .any(|child| matches!(child.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP));
+    match ctx.options.explicit_key_threshold {
+        Some(threshold) if is_collection || flow.text().to_string().chars().count() > threshold => {
+            true
+        }
+        _ => {
+            matches!(ctx.options.explicit_keys, ExplicitKeys::AlwaysForComplex)
+                && (is_collection || flow.text().contains_char('\n'))
+        }
+    }
+}
+
+fn can_omit_question_mark(key: &SyntaxNode, ctx: &Ctx) -> bool {
+    if matches!(ctx.options.explicit_keys, ExplicitKeys::Keep)
+        && key.kind() == SyntaxKind::BLOCK_MAP_KEY
+    {
+        return false;
+    }
     let parent = key.parent();
     // question mark can be omitted in flow map
     (parent
@@ -1280,6 +2656,94 @@ fn can_omit_question_mark(key: &SyntaxNode) -> bool {
             })
 }
 
+/// Check if scalar contains template expressions of the `templateSyntax` option.
+/// Such scalars are kept as-is, since changing quotes or line breaks may break templates.
+fn has_template(text: &str, ctx: &Ctx) -> bool {
+    match ctx.options.template_syntax {
+        TemplateSyntax::None => false,
+        TemplateSyntax::Go => text.contains("{{"),
+        TemplateSyntax::Jinja2 => text.contains("{{") || text.contains("{%") || text.contains("{#"),
+    }
+}
+
+/// Apply numeric options to plain scalar, and return `None` if nothing changed.
+///
+/// Trimming trailing zeros only looks at the text, while other options are only applied
+/// to scalars that resolve to integers or floats.
+fn format_number(text: &str, flow: &Flow, options: &LanguageOptions) -> Option<String> {
+    let mut text = Cow::Borrowed(text);
+    if (options.remove_plus_sign
+        || options.pad_leading_zero
+        || options.remove_digit_separators
+        || !matches!(options.exponent_case, ExponentCase::Keep))
+        && matches!(
+            resolve_scalar_with_version(flow, options.yaml_version.resolve(flow.syntax())),
+            ScalarType::Int | ScalarType::Float
+        )
+    {
+        let unsigned = text.strip_prefix(['+', '-']).unwrap_or(&text);
+        // binary, octal and hexadecimal numbers and base 60 numbers of YAML 1.1 are kept as-is
+        let is_decimal = !unsigned.contains(':')
+            && (unsigned.len() == 1
+                || !unsigned.starts_with('0')
+                || unsigned[1..].starts_with(['.', 'e', 'E']));
+        if options.remove_digit_separators && is_decimal && text.contains('_') {
+            text = Cow::Owned(text.replace('_', ""));
+        }
+        if options.remove_plus_sign && text.starts_with('+') {
+            text = Cow::Owned(text[1..].to_owned());
+        }
+        let sign_len = text.len() - text.trim_start_matches(['+', '-']).len();
+        if options.pad_leading_zero
+            && text[sign_len..]
+                .strip_prefix('.')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            text.to_mut().insert(sign_len, '0');
+        }
+        let exponent_mark = match options.exponent_case {
+            ExponentCase::Keep => None,
+            ExponentCase::Lowercase => Some(('E', "e")),
+            ExponentCase::Uppercase => Some(('e', "E")),
+        };
+        if let Some((from, to)) =
+            exponent_mark.filter(|(from, _)| is_decimal && text.contains(*from))
+        {
+            text = Cow::Owned(text.replace(from, to));
+        }
+    }
+
+    if options.trim_trailing_zero {
+        let ranges = parse_float(&text);
+        if let Some((range_int, range_fraction, trimmed_fraction)) = ranges.and_then(|ranges| {
+            text.get(ranges.1.clone())
+                .filter(|fraction| fraction.ends_with('0'))
+                .map(|fraction| {
+                    (
+                        ranges.0,
+                        ranges.1,
+                        fraction.trim_end_matches('0').to_owned(),
+                    )
+                })
+        }) {
+            let text = text.to_mut();
+            if trimmed_fraction == "." {
+                if text.get(range_int.clone()).is_some_and(str::is_empty) {
+                    text.replace_range(range_int, "0");
+                }
+                text.replace_range(range_fraction, "");
+            } else {
+                text.replace_range(range_fraction, &trimmed_fraction);
+            }
+        }
+    }
+
+    match text {
+        Cow::Owned(text) => Some(text),
+        Cow::Borrowed(..) => None,
+    }
+}
+
 fn parse_float(literal: &str) -> Option<(Range<usize>, Range<usize>)> {
     let mut s = literal.strip_prefix(['+', '-']).unwrap_or(literal);
     let int_start = literal.len() - s.len();
@@ -1301,11 +2765,15 @@ fn parse_float(literal: &str) -> Option<(Range<usize>, Range<usize>)> {
     Some((int_start..int_end, fraction_start..fraction_end))
 }
 
-fn intersperse_lines(docs: &mut Vec<Doc<'static>>, mut lines: impl Iterator<Item = String>) {
+fn intersperse_lines<'s, S>(docs: &mut Vec<Doc<'s>>, mut lines: impl Iterator<Item = S>)
+where
+    S: Into<Cow<'s, str>>,
+{
     if let Some(line) = lines.next() {
         docs.push(Doc::text(line));
     }
     for line in lines {
+        let line = line.into();
         if line.is_empty() {
             docs.push(Doc::empty_line());
         } else {
@@ -1315,18 +2783,484 @@ fn intersperse_lines(docs: &mut Vec<Doc<'static>>, mut lines: impl Iterator<Item
     }
 }
 
-fn reflow(text: &str, docs: &mut Vec<Doc<'static>>) {
-    let mut lines = text.lines();
+/// Like [`intersperse_lines`], but consecutive lines of folded block scalar
+/// are joined and rewrapped at print width.
+/// Empty lines and more-indented lines are kept as-is,
+/// because line breaks around them aren't folded.
+fn intersperse_folded_lines<'s, S>(docs: &mut Vec<Doc<'s>>, lines: impl Iterator<Item = S>)
+where
+    S: Into<Cow<'s, str>>,
+{
+    let mut paragraphs: Vec<(Cow<'s, str>, bool)> = vec![];
+    for line in lines {
+        let line = line.into();
+        let is_foldable = !line.is_empty() && !line.starts_with([' ', '\t']);
+        match paragraphs.last_mut() {
+            Some((paragraph, true)) if is_foldable => {
+                let paragraph = paragraph.to_mut();
+                paragraph.push(' ');
+                paragraph.push_str(&line);
+            }
+            _ => paragraphs.push((line, is_foldable)),
+        }
+    }
+
+    let mut paragraphs = paragraphs.into_iter();
+    if let Some((paragraph, is_foldable)) = paragraphs.next() {
+        docs.push(fill_words(paragraph, is_foldable));
+    }
+    for (paragraph, is_foldable) in paragraphs {
+        if paragraph.is_empty() {
+            docs.push(Doc::empty_line());
+        } else {
+            docs.push(Doc::hard_line());
+            docs.push(fill_words(paragraph, is_foldable));
+        }
+    }
+}
+
+fn fill_words<'s>(paragraph: Cow<'s, str>, is_foldable: bool) -> Doc<'s> {
+    if !is_foldable {
+        return Doc::text(paragraph);
+    }
+    // only single space between words can be folded,
+    // otherwise it will leave whitespaces at the end or start of line
+    let mut docs = vec![];
+    let mut is_first = true;
+    let mut push_word = |range: Range<usize>| {
+        let word = Doc::text(match &paragraph {
+            Cow::Borrowed(paragraph) => Cow::Borrowed(&paragraph[range]),
+            Cow::Owned(paragraph) => Cow::Owned(paragraph[range].to_owned()),
+        });
+        if is_first {
+            docs.push(word);
+            is_first = false;
+        } else {
+            docs.push(Doc::line_or_space().append(word).group());
+        }
+    };
+    let mut start = 0;
+    let bytes = paragraph.as_bytes();
+    for (i, byte) in bytes.iter().enumerate() {
+        // words that start with indicators may be parsed as other syntax at the start of line
+        if *byte == b' '
+            && i > start
+            && bytes
+                .get(i + 1)
+                .is_some_and(|next| !next.is_ascii_whitespace() && !is_indicator(*next))
+            && !bytes[i - 1].is_ascii_whitespace()
+        {
+            push_word(start..i);
+            start = i + 1;
+        }
+    }
+    push_word(start..bytes.len());
+    Doc::list(docs)
+}
+
+/// Indentation of block scalar content relative to its parent.
+/// When normalization is enabled, content is indented by exactly `indent_width`
+/// from the `-` of block sequence entry, instead of from the entry content.
+fn block_scalar_indent(block_scalar: &BlockScalar, ctx: &Ctx) -> usize {
+    use crate::config::DashSpacing;
+
+    if ctx.options.normalize_block_scalar_indent
+        && block_scalar
+            .syntax()
+            .parent()
+            .and_then(|block| block.parent())
+            .is_some_and(|parent| parent.kind() == SyntaxKind::BLOCK_SEQ_ENTRY)
+    {
+        let entry_indent = match ctx.options.dash_spacing {
+            DashSpacing::OneSpace => 2,
+            DashSpacing::Indent => ctx.indent_width,
+        };
+        ctx.indent_width.saturating_sub(entry_indent)
+    } else {
+        ctx.indent_width
+    }
+}
+
+/// Convert block scalar to the preferred style if its value won't be changed,
+/// returning the new indicator and text.
+fn convert_block_scalar_style(
+    block_scalar: &BlockScalar,
+    ctx: &Ctx,
+) -> Option<(&'static str, String)> {
+    let to_literal = match ctx.options.block_scalar_style {
+        BlockScalarStyle::Keep => return None,
+        BlockScalarStyle::Literal if block_scalar.greater_than().is_some() => true,
+        BlockScalarStyle::Folded if block_scalar.bar().is_some() => false,
+        _ => return None,
+    };
+    if block_scalar.indent_indicator().is_some() {
+        return None;
+    }
+    let token = block_scalar.text()?;
+    let mut lines = token.text().lines();
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        return None;
+    }
+    // trailing whitespaces will be removed anyway if they're trimmed
+    let lines = lines
+        .map(|line| {
+            if ctx.options.trim_trailing_whitespaces {
+                line.trim_end_matches([' ', '\t'])
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>();
+    // leading empty lines are kept as line breaks in both styles
+    let first_line = lines.iter().find(|line| !line.trim().is_empty())?;
+    // indentation can be zero for block scalars at document level
+    let indent = first_line.len() - first_line.trim_start_matches(' ').len();
+    // more-indented lines and trailing whitespaces
+    // have special meanings, so they are kept as-is
+    if lines.iter().any(|line| {
+        !line.trim().is_empty()
+            && (line.len() - line.trim_start_matches(' ').len() != indent
+                || line[indent..].starts_with('\t')
+                || line.ends_with([' ', '\t']))
+    }) {
+        return None;
+    }
+
+    let indentation = &first_line[..indent];
+    let mut text = String::with_capacity(token.text().len());
+    let mut empty_lines = 0;
+    let mut is_first = true;
+    for line in lines {
+        if line.trim().is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        if is_first {
+            text.push_str(&"\n".repeat(empty_lines + 1));
+        } else if to_literal {
+            // in folded style, single line break becomes a space,
+            // and line break followed by empty lines becomes those line breaks
+            if empty_lines == 0 {
+                text.push(' ');
+            } else {
+                text.push_str(&"\n".repeat(empty_lines));
+            }
+        } else {
+            text.push_str(&"\n".repeat(empty_lines + 2));
+        }
+        if is_first || !to_literal || empty_lines > 0 {
+            text.push_str(indentation);
+        }
+        text.push_str(&line[indent..]);
+        empty_lines = 0;
+        is_first = false;
+    }
+    Some((if to_literal { "|" } else { ">" }, text))
+}
+
+/// Print block sequence as flow sequence if it only contains single-line scalars
+/// without comments or properties, which is used when it fits in print width.
+fn short_flow_seq<'s>(block: &Block, ctx: &Ctx<'_, 's>) -> Option<Doc<'s>> {
+    if !ctx.options.prefer_flow_for_short_sequences
+        || block.properties().is_some()
+        || yaml_parser::trailing_comment(block.syntax()).is_some()
+    {
+        return None;
+    }
+    let block_seq = block.block_seq()?;
+    if block_seq
+        .syntax()
+        .descendants_with_tokens()
+        .any(|element| element.kind() == SyntaxKind::COMMENT)
+    {
+        return None;
+    }
+
+    let has_spacing = has_flow_spacing(
+        ctx.options.bracket_spacing,
+        ctx.options.single_entry_bracket_spacing,
+        block_seq.entries().count(),
+    );
+    let mut docs = vec![Doc::text("[")];
+    if has_spacing {
+        docs.push(Doc::space());
+    }
+    for (i, entry) in block_seq.entries().enumerate() {
+        let flow = entry.flow()?;
+        if flow.properties().is_some()
+            || flow.syntax().children_with_tokens().any(|element| {
+                element.as_token().is_some_and(|token| {
+                    token.text().contains(['\n', '\r'])
+                        // flow indicators change meaning of plain scalars in flow collections
+                        || token.kind() == SyntaxKind::PLAIN_SCALAR
+                            && token.text().contains(is_flow_indicator)
+                })
+            })
+            || flow.plain_scalar().is_none()
+                && flow.single_quoted_scalar().is_none()
+                && flow.double_qouted_scalar().is_none()
+        {
+            return None;
+        }
+        if i > 0 {
+            docs.push(Doc::text(", "));
+        }
+        docs.push(flow.doc(ctx));
+    }
+    if has_spacing {
+        docs.push(Doc::space());
+    }
+    docs.push(Doc::text("]"));
+    Some(Doc::list(docs))
+}
+
+/// Check if whitespace should be inserted between brackets or braces of flow collection,
+/// where the option for single entry takes precedence if there's only one entry.
+fn has_flow_spacing(spacing: bool, single_entry: Option<bool>, entries_count: usize) -> bool {
+    match single_entry {
+        Some(single_entry) if entries_count == 1 => single_entry,
+        _ => spacing,
+    }
+}
+
+/// Check if plain scalar is in block context and isn't a key,
+/// so it can be wrapped with `wrapPlainScalars` option.
+fn can_wrap_plain_scalar(flow: &SyntaxNode, ctx: &Ctx) -> bool {
+    ctx.options.wrap_plain_scalars
+        && !has_document_marker_word(flow)
+        && flow
+            .children_with_tokens()
+            .any(|element| element.kind() == SyntaxKind::PLAIN_SCALAR)
+        && flow.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind(),
+                SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::BLOCK_SEQ_ENTRY | SyntaxKind::DOCUMENT
+            )
+        })
+}
+
+/// Check if plain scalar at the root of document has words like `---` or `...`,
+/// which will become document markers if they're wrapped to the beginning of line.
+pub(crate) fn has_document_marker_word(flow: &SyntaxNode) -> bool {
+    flow.parent()
+        .is_some_and(|parent| parent.kind() == SyntaxKind::DOCUMENT)
+        && flow
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::PLAIN_SCALAR)
+            .any(|token| {
+                token
+                    .text()
+                    .split_whitespace()
+                    .skip(1)
+                    .any(|word| word.starts_with("---") || word.starts_with("..."))
+            })
+}
+
+/// Ask scalar classifier how the scalar should be formatted.
+fn classify(node: &SyntaxNode, ctx: &Ctx) -> ScalarStyle {
+    ctx.classifier
+        .and_then(|classifier| key_path(node).map(|path| classifier.classify(&path)))
+        .unwrap_or_default()
+}
+
+/// Check if plain scalar should be quoted, which is controlled by `scalarQuoting` option
+/// or scalar classifier.
+fn should_quote(text: &str, flow: &Flow, style: &ScalarStyle, ctx: &Ctx) -> bool {
+    // scalars like `no` are already non-strings in YAML 1.1, so quoting them changes values
+    let is_yaml11 = matches!(
+        ctx.options.yaml_version.resolve(flow.syntax()),
+        YamlVersion::V1_1
+    );
+    let quote = match style
+        .quoting
+        .as_ref()
+        .unwrap_or(&ctx.options.scalar_quoting)
+    {
+        ScalarQuoting::QuoteAll => !is_key(flow.syntax()),
+        ScalarQuoting::QuoteAmbiguous => !is_yaml11 && is_yaml11_non_string_plain(text),
+        ScalarQuoting::Keep | ScalarQuoting::UnquoteWhenSafe => false,
+    };
+    let is_non_string = is_non_string_plain(text) || is_yaml11 && is_yaml11_non_string_plain(text);
+    quote && !text.contains(['\n', '\r']) && !is_non_string && !has_tag(flow)
+}
+
+/// Check if quoted scalar can be printed as plain scalar without changing its value,
+/// which is controlled by `scalarQuoting` option or scalar classifier.
+/// The given text should be unescaped content of quoted scalar.
+fn can_unquote(text: &str, flow: &Flow, style: &ScalarStyle, ctx: &Ctx) -> bool {
+    let in_flow = flow
+        .syntax()
+        .ancestors()
+        .skip(1)
+        .any(|ancestor| matches!(ancestor.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP));
+    matches!(
+        style
+            .quoting
+            .as_ref()
+            .unwrap_or(&ctx.options.scalar_quoting),
+        ScalarQuoting::UnquoteWhenSafe
+    ) && !text.is_empty()
+        && text.trim() == text
+        && !text.starts_with(|c: char| c.is_ascii() && is_indicator(c as u8))
+        && !text.starts_with("...")
+        && !text.contains(|c: char| c.is_control())
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.ends_with(':')
+        && !(in_flow && text.contains(is_flow_indicator))
+        && !is_non_string_plain(text)
+        && !is_yaml11_non_string_plain(text)
+        && !has_tag(flow)
+}
+
+fn quote_plain_scalar(text: &str, ctx: &Ctx) -> String {
+    let double = match ctx.options.quotes {
+        Quotes::PreferDouble => !text.contains(['"', '\\']) || text.contains('\''),
+        Quotes::PreferSingle => text.contains('\'') && !text.contains(['"', '\\']),
+        Quotes::ForceDouble => true,
+        Quotes::ForceSingle => false,
+    };
+    if double {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        format!("'{}'", text.replace('\'', "''"))
+    }
+}
+
+fn is_key(flow: &SyntaxNode) -> bool {
+    flow.parent().is_some_and(|parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+        )
+    })
+}
+
+fn has_tag(flow: &Flow) -> bool {
+    flow.properties()
+        .is_some_and(|properties| properties.tag_property().is_some())
+}
+
+fn is_indicator(c: u8) -> bool {
+    matches!(
+        c,
+        b'-' | b'?'
+            | b':'
+            | b','
+            | b'['
+            | b']'
+            | b'{'
+            | b'}'
+            | b'#'
+            | b'&'
+            | b'*'
+            | b'!'
+            | b'|'
+            | b'>'
+            | b'\''
+            | b'"'
+            | b'%'
+            | b'@'
+            | b'`'
+    )
+}
+
+fn is_flow_indicator(c: char) -> bool {
+    matches!(c, ',' | '[' | ']' | '{' | '}')
+}
+
+/// Check if block scalar has whitespace-only lines that are longer than its indentation,
+/// whose content will be changed after re-indenting.
+fn reflow<'s>(text: Cow<'s, str>, docs: &mut Vec<Doc<'s>>) {
+    match text {
+        Cow::Borrowed(text) => reflow_lines(text.lines(), docs),
+        Cow::Owned(text) => reflow_lines(text.lines().map(str::to_owned), docs),
+    }
+}
+
+fn reflow_lines<'s, S>(mut lines: impl Iterator<Item = S>, docs: &mut Vec<Doc<'s>>)
+where
+    S: Into<Cow<'s, str>>,
+{
     if let Some(line) = lines.next() {
-        docs.push(Doc::text(line.to_owned()));
+        docs.push(Doc::text(line));
     }
     for line in lines {
         docs.push(Doc::empty_line());
+        docs.push(Doc::text(line));
+    }
+}
+
+/// Print lines of ignored range which starts at the given column in source.
+///
+/// The first line will be printed at current indentation level,
+/// so other lines are re-indented by the same amount to keep their relative indentation.
+fn reflow_ignored_range<'s>(text: String, column: usize, docs: &mut Vec<Doc<'s>>) {
+    let mut lines = text.lines();
+    if let Some(line) = lines.next() {
         docs.push(Doc::text(line.to_owned()));
     }
+    for line in lines {
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        if indent == line.len() {
+            docs.push(Doc::empty_line());
+        } else if indent >= column {
+            docs.push(Doc::hard_line());
+            docs.push(Doc::text(line[column..].to_owned()));
+        } else {
+            docs.push(Doc::empty_line());
+            docs.push(Doc::text(line.to_owned()));
+        }
+    }
+}
+
+/// Check if the directive is the same as default behavior of YAML 1.2.
+fn is_redundant_directive(directive: &Directive) -> bool {
+    if let Some(yaml) = directive.yaml_directive() {
+        yaml.yaml_version()
+            .is_some_and(|version| version.text() == "1.2")
+    } else if let Some(tag) = directive.tag_directive() {
+        tag.tag_handle()
+            .zip(tag.tag_prefix())
+            .is_some_and(|(handle, prefix)| {
+                matches!(
+                    (&*handle.syntax().to_string(), prefix.text()),
+                    ("!", "!") | ("!!", "tag:yaml.org,2002:")
+                )
+            })
+    } else {
+        false
+    }
+}
+
+/// Check if there's a `%TAG` directive with the same handle and prefix before it in the same document.
+/// Directives of other documents aren't checked, since `%TAG` directive only applies to its document.
+fn is_duplicate_tag_directive(directive: &Directive) -> bool {
+    let Some(tag) = directive.tag_directive() else {
+        return false;
+    };
+    let text = tag.syntax().to_string();
+    let key = text.split_ascii_whitespace();
+    directive
+        .syntax()
+        .siblings(Direction::Prev)
+        .skip(1)
+        .filter_map(Directive::cast)
+        .filter_map(|directive| directive.tag_directive())
+        .any(|prev| {
+            prev.syntax()
+                .to_string()
+                .split_ascii_whitespace()
+                .eq(key.clone())
+        })
 }
 
 fn should_ignore(node: &SyntaxNode, ctx: &Ctx) -> bool {
+    has_ignore_directive(node, &ctx.options.ignore_comment_directive)
+}
+
+fn has_ignore_directive(node: &SyntaxNode, directive: &str) -> bool {
     // for the case that comment comes in the middle of a list of nodes
     node.prev_sibling_or_token()
         .and_then(|element| element.prev_sibling_or_token())
@@ -1338,13 +3272,61 @@ fn should_ignore(node: &SyntaxNode, ctx: &Ctx) -> bool {
         })
         .as_ref()
         .and_then(|element| match element {
-            SyntaxElement::Token(token) if token.kind() == SyntaxKind::COMMENT => {
-                token.text().strip_prefix('#').and_then(|s| {
-                    s.trim_start()
-                        .strip_prefix(&ctx.options.ignore_comment_directive)
-                })
-            }
+            SyntaxElement::Token(token) => strip_directive(token, directive),
             _ => None,
         })
-        .is_some_and(|rest| rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_whitespace()))
+        .is_some_and(is_directive_end)
+}
+
+/// Check if the node is ignored by the ignore directive,
+/// or it's between `ignore-start` and `ignore-end` directives.
+pub(crate) fn is_ignored(node: &SyntaxNode, directive: &str) -> bool {
+    has_ignore_directive(node, directive)
+        || node
+            .siblings_with_tokens(Direction::Prev)
+            .skip(1)
+            .filter_map(SyntaxElement::into_token)
+            .find_map(|token| {
+                if is_suffixed_directive(&token, directive, "start") {
+                    Some(true)
+                } else if is_suffixed_directive(&token, directive, "end") {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
+}
+
+/// Check if there's ignore directive suffixed by `-file` before any content of the stream,
+/// so the whole input should be kept as-is.
+pub(crate) fn has_ignore_file_directive(root: &SyntaxNode, directive: &str) -> bool {
+    root.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .take_while(|token| matches!(token.kind(), SyntaxKind::COMMENT | SyntaxKind::WHITESPACE))
+        .any(|token| is_suffixed_directive(&token, directive, "file"))
+}
+
+/// Check if the comment is ignore directive with suffix like `pretty-yaml-ignore-start`.
+fn is_suffixed_directive(token: &SyntaxToken, directive: &str, suffix: &str) -> bool {
+    strip_directive(token, directive)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|rest| rest.strip_prefix(suffix))
+        .is_some_and(is_directive_end)
+}
+
+/// Get the rest text of comment after the directive.
+fn strip_directive<'a>(token: &'a SyntaxToken, directive: &str) -> Option<&'a str> {
+    if token.kind() == SyntaxKind::COMMENT {
+        token
+            .text()
+            .strip_prefix('#')
+            .and_then(|s| s.trim_start().strip_prefix(directive))
+    } else {
+        None
+    }
+}
+
+fn is_directive_end(rest: &str) -> bool {
+    rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_whitespace())
 }
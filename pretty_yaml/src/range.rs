@@ -0,0 +1,171 @@
+use crate::{
+    config::{self, EmptyDocuments, FormatOptions, KeyOrdering, LanguageOptions},
+    format_text,
+    printer::{
+        align_comments, align_entries, has_ignore_file_directive, is_ignored, Ctx, DocGen, Source,
+    },
+};
+use rowan::{TextRange, TextSize};
+use std::ops::Range;
+use tiny_pretty::{print, Doc, IndentKind, PrintOptions};
+use yaml_parser::{
+    ast::{AstNode, Block, Flow},
+    SyntaxElement, SyntaxError, SyntaxKind, SyntaxNode,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Result of [`format_range`].
+pub struct RangeFormatResult {
+    /// Byte range of source input that should be replaced.
+    /// It may be larger than the requested range.
+    pub range: Range<usize>,
+    /// Formatted text for replacing.
+    pub text: String,
+}
+
+/// Format part of the given source input that covers the given byte range.
+///
+/// The smallest block or flow node that covers the range will be formatted,
+/// while text outside that node is kept as-is,
+/// so indentation of the node is the same as its original indentation.
+/// If there isn't such node, the whole input will be formatted.
+/// Nodes ignored by the ignore comment directive are never formatted alone,
+/// so the node that contains them will be formatted instead.
+///
+/// Options that transform the whole tree, such as `keyOrdering`, `dedupeKeys` or
+/// `renameAnchors`, may affect text outside the range,
+/// so the whole input will be formatted when any of them is enabled,
+/// to keep the result same as formatting the whole input.
+/// So does indenting with tabs, since indentation of each line depends on previous lines.
+///
+/// ```
+/// use pretty_yaml::{config::FormatOptions, format_range};
+///
+/// let input = "a:   1\nb:\n  c:   2\n  d: [ 3 ]\n";
+/// let result = format_range(input, 19..20, &FormatOptions::default()).unwrap();
+/// assert_eq!(result.range, 12..29);
+/// assert_eq!(result.text, "c: 2\n  d: [3]");
+/// ```
+pub fn format_range(
+    input: &str,
+    range: Range<usize>,
+    options: &FormatOptions,
+) -> Result<RangeFormatResult, SyntaxError> {
+    let tree =
+        yaml_parser::parse_with_template_syntax(input, (&options.language.template_syntax).into())?;
+    if has_ignore_file_directive(&tree, &options.language.ignore_comment_directive) {
+        return Ok(RangeFormatResult {
+            range: 0..input.len(),
+            text: input.to_owned(),
+        });
+    }
+    if transforms_tree(&options.language)
+        || matches!(options.layout.indent_kind, config::IndentKind::Tab)
+    {
+        return Ok(RangeFormatResult {
+            range: 0..input.len(),
+            text: format_text(input, options)?,
+        });
+    }
+    let end = range.end.min(input.len());
+    let start = range.start.min(end);
+    let range = TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32));
+
+    let node = match tree.covering_element(range) {
+        SyntaxElement::Node(node) => Some(node),
+        SyntaxElement::Token(token) => token.parent(),
+    }
+    .map(|node| {
+        // ignored nodes are kept as-is by their parents,
+        // so search from the parent of the outermost ignored node
+        let directive = &options.language.ignore_comment_directive;
+        match node
+            .ancestors()
+            .filter(|ancestor| is_ignored(ancestor, directive))
+            .last()
+        {
+            Some(ignored) => ignored.parent().unwrap_or(ignored),
+            None => node,
+        }
+    })
+    .into_iter()
+    .flat_map(|node| node.ancestors())
+    .find_map(|node| find_formattable(node, input));
+    let Some((node, column, indent)) = node else {
+        return Ok(RangeFormatResult {
+            range: 0..input.len(),
+            text: format_text(input, options)?,
+        });
+    };
+
+    let mut ctx = Ctx {
+        // byte order mark is skipped by parser
+        source: Some(Source {
+            text: input.trim_start_matches('\u{feff}'),
+            offset: TextSize::default(),
+        }),
+        ..Ctx::new(options)
+    };
+    ctx.aligned_entries = align_entries(&node, &ctx);
+    ctx.aligned_comments = align_comments(&node, options.layout.print_width, &ctx);
+    let doc = match node.kind() {
+        SyntaxKind::BLOCK => Block::cast(node.clone()).map(|block| block.doc(&ctx)),
+        _ => Flow::cast(node.clone()).map(|flow| flow.doc(&ctx)),
+    }
+    .unwrap_or_else(Doc::nil);
+    // print with the original column of first line,
+    // so the width of first line can be measured correctly
+    let output = print(
+        &Doc::text(" ".repeat(column)).append(doc.nest(indent)),
+        &PrintOptions {
+            indent_kind: IndentKind::Space,
+            line_break: options.layout.line_break.resolve(&tree),
+            width: options.layout.print_width,
+            tab_size: options.layout.tab_width(),
+        },
+    );
+    Ok(RangeFormatResult {
+        range: node.text_range().start().into()..node.text_range().end().into(),
+        text: output[column..].to_owned(),
+    })
+}
+
+/// Check if the node can be formatted independently,
+/// and return it with its column and indentation of its line.
+fn find_formattable(node: SyntaxNode, input: &str) -> Option<(SyntaxNode, usize, usize)> {
+    let start = usize::from(node.text_range().start());
+    let line_start = input[..start].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+    let prefix = &input[line_start..start];
+    let column = prefix.chars().count();
+    let indent = prefix.len() - prefix.trim_start_matches(' ').len();
+    match node.kind() {
+        // block collection must start at its own line,
+        // otherwise its indentation is decided by its parent like `-` or `?`
+        SyntaxKind::BLOCK if indent == prefix.len() => Some((node, column, indent)),
+        // indentation of flow nodes in another flow collection is decided by the outer one,
+        // and continuation lines of multi-line scalars, properties and comments
+        // are indented by their parents
+        SyntaxKind::FLOW
+            if !node.ancestors().skip(1).any(|ancestor| {
+                matches!(ancestor.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP)
+            }) && !node.children_with_tokens().any(|element| {
+                !matches!(element.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP)
+                    && element.to_string().contains(['\n', '\r'])
+            }) =>
+        {
+            Some((node, column, indent))
+        }
+        _ => None,
+    }
+}
+
+/// Check if any options that transform the whole tree are enabled.
+fn transforms_tree(options: &LanguageOptions) -> bool {
+    options.rename_anchors
+        || options.inline_aliases
+        || options.extract_anchors.is_some()
+        || !options.sort_documents.is_empty()
+        || !matches!(options.key_ordering, KeyOrdering::Ignore)
+        || options.dedupe_keys.is_some()
+        || matches!(options.empty_documents, EmptyDocuments::Drop)
+}
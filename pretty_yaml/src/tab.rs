@@ -0,0 +1,80 @@
+use yaml_parser::{SyntaxElement, SyntaxKind, TemplateSyntax};
+
+/// Replace indentation of printed output with tabs.
+///
+/// Parser measures indentation by characters, so a tab is one column for parser
+/// while it represents `indent_width` columns in printed output.
+/// To keep structure unchanged, each indentation level is replaced with tabs only
+/// when it's indented from its parent, and spaces are kept when it's aligned with
+/// content after `-`, `?` or `:` in previous line.
+/// Lines in block scalars and multi-line scalars are kept as-is,
+/// since leading tabs there are part of their content.
+pub(crate) fn indent_with_tabs(
+    output: &str,
+    indent_width: usize,
+    template_syntax: TemplateSyntax,
+) -> String {
+    let Ok(tree) = yaml_parser::parse_with_template_syntax(output, template_syntax) else {
+        return output.to_owned();
+    };
+    let verbatim = tree
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| token.kind() != SyntaxKind::WHITESPACE && token.text().contains('\n'))
+        .map(|token| {
+            let range = token.text_range();
+            usize::from(range.start())..usize::from(range.end())
+        })
+        .collect::<Vec<_>>();
+
+    let indent_width = indent_width.max(1);
+    let mut result = String::with_capacity(output.len());
+    // columns of indentation levels in printed output, with their indentation in result
+    let mut levels = vec![(0, String::new())];
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_start_matches(' ');
+        if content.trim().is_empty()
+            || verbatim
+                .iter()
+                .any(|range| range.start < start && start < range.end)
+        {
+            result.push_str(line);
+            continue;
+        }
+
+        let column = line.len() - content.len();
+        while levels.last().is_some_and(|(level, _)| *level > column) {
+            levels.pop();
+        }
+        let indent = match levels.last() {
+            Some((level, indent)) if *level == column => indent.clone(),
+            Some((level, indent)) => {
+                let diff = column - level;
+                let mut indent = indent.clone();
+                indent.push_str(&"\t".repeat(diff / indent_width));
+                indent.push_str(&" ".repeat(diff % indent_width));
+                levels.push((column, indent.clone()));
+                indent
+            }
+            None => String::new(),
+        };
+        result.push_str(&indent);
+        result.push_str(content);
+
+        // content after compact indicators like `- a: 1` begins new levels
+        let mut rest = content;
+        while let Some(after_indicator) = rest.strip_prefix(['-', '?', ':']) {
+            let trimmed = after_indicator.trim_start_matches(' ');
+            if trimmed.len() == after_indicator.len() || trimmed.trim().is_empty() {
+                break;
+            }
+            let aligned = content.len() - trimmed.len();
+            levels.push((column + aligned, format!("{indent}{}", " ".repeat(aligned))));
+            rest = trimmed;
+        }
+    }
+    result
+}
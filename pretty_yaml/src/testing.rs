@@ -0,0 +1,298 @@
+//! Utilities for testing formatter stability, available with the `testing` feature.
+//!
+//! [`arbitrary_yaml`] builds YAML from raw bytes, so it works with any fuzzer or property testing
+//! library that can produce bytes, such as `cargo fuzz`, `arbitrary` or `proptest`:
+//!
+//! ```
+//! use pretty_yaml::{
+//!     config::FormatOptions,
+//!     testing::{arbitrary_yaml, check_stability},
+//! };
+//!
+//! for seed in 0..=255u8 {
+//!     let input = arbitrary_yaml(&[seed, seed.wrapping_mul(31), seed ^ 0x5a, 7, 42]);
+//!     check_stability(&input, &FormatOptions::default()).unwrap();
+//! }
+//! ```
+
+use crate::{config::FormatOptions, format_text};
+use rowan::NodeOrToken;
+use std::{error::Error, fmt, ops::Range};
+use yaml_parser::{SyntaxError, SyntaxNode};
+
+/// Failure found by [`check_stability`].
+#[derive(Clone, Debug)]
+pub enum StabilityError {
+    /// Input can't be parsed, so it isn't a valid test case.
+    InvalidInput(SyntaxError),
+    /// Formatted output can't be parsed.
+    InvalidOutput { output: String, error: SyntaxError },
+    /// Formatting the output again produces different result.
+    Unstable {
+        first: String,
+        second: String,
+        /// Range in the first output where syntax trees of two outputs begin to differ.
+        range: Range<usize>,
+    },
+}
+
+impl fmt::Display for StabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StabilityError::InvalidInput(error) => write!(f, "invalid input: {error}"),
+            StabilityError::InvalidOutput { error, .. } => {
+                write!(f, "formatted output is invalid: {error}")
+            }
+            StabilityError::Unstable { range, .. } => {
+                write!(f, "format is unstable at {range:?}")
+            }
+        }
+    }
+}
+
+impl Error for StabilityError {}
+
+/// Format the input twice and check if the second formatting changes nothing.
+///
+/// Unlike comparing strings, syntax trees of the two outputs are compared
+/// so the error tells where they begin to differ.
+pub fn check_stability(input: &str, options: &FormatOptions) -> Result<(), StabilityError> {
+    let first = format_text(input, options).map_err(StabilityError::InvalidInput)?;
+    let template_syntax = (&options.language.template_syntax).into();
+    let first_tree =
+        yaml_parser::parse_with_template_syntax(&first, template_syntax).map_err(|error| {
+            StabilityError::InvalidOutput {
+                output: first.clone(),
+                error,
+            }
+        })?;
+    let second = format_text(&first, options).map_err(|error| StabilityError::InvalidOutput {
+        output: first.clone(),
+        error,
+    })?;
+    let second_tree =
+        yaml_parser::parse_with_template_syntax(&second, template_syntax).map_err(|error| {
+            StabilityError::InvalidOutput {
+                output: second.clone(),
+                error,
+            }
+        })?;
+    match first_difference(&first_tree, &second_tree) {
+        Some(range) => Err(StabilityError::Unstable {
+            first,
+            second,
+            range,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Find the range of first node or token in `a` whose kind or text is different from `b`.
+fn first_difference(a: &SyntaxNode, b: &SyntaxNode) -> Option<Range<usize>> {
+    let mut a_elements = a.descendants_with_tokens();
+    let mut b_elements = b.descendants_with_tokens();
+    loop {
+        match (a_elements.next(), b_elements.next()) {
+            (Some(a_element), Some(b_element)) => {
+                let same = a_element.kind() == b_element.kind()
+                    && match (&a_element, &b_element) {
+                        (NodeOrToken::Token(a), NodeOrToken::Token(b)) => a.text() == b.text(),
+                        _ => true,
+                    };
+                if !same {
+                    return Some(a_element.text_range().into());
+                }
+            }
+            (Some(a_element), None) => return Some(a_element.text_range().into()),
+            (None, Some(_)) => {
+                let end = a.text_range().end().into();
+                return Some(end..end);
+            }
+            (None, None) => return None,
+        }
+    }
+}
+
+/// Build YAML from raw bytes, which is useful for fuzzing.
+///
+/// The same bytes always produce the same YAML,
+/// and running out of bytes is fine as the rest is filled with zeros.
+/// Generated YAML covers block and flow collections, all scalar styles,
+/// comments, anchors, aliases, tags and multiple documents,
+/// and it is always syntactically valid.
+pub fn arbitrary_yaml(data: &[u8]) -> String {
+    let mut generator = Generator {
+        data,
+        pos: 0,
+        output: String::new(),
+        anchors: 0,
+    };
+    let documents = generator.choose(3) + 1;
+    for i in 0..documents {
+        if i > 0 || generator.choose(4) == 0 {
+            generator.output.push_str("---\n");
+        }
+        generator.block_node(0, 0);
+        generator.output.push('\n');
+    }
+    generator.output
+}
+
+const MAX_DEPTH: usize = 4;
+const WORDS: &[&str] = &[
+    "a", "key", "value", "foo bar", "true", "null", "~", "1", "-2.50", "0x1F", ".inf", "yes",
+];
+
+struct Generator<'a> {
+    data: &'a [u8],
+    pos: usize,
+    output: String,
+    anchors: usize,
+}
+
+impl Generator<'_> {
+    /// Take next byte and map it to `0..n`.
+    fn choose(&mut self, n: usize) -> usize {
+        let byte = self.data.get(self.pos).copied().unwrap_or_default();
+        self.pos += 1;
+        byte as usize % n
+    }
+
+    fn word(&mut self) -> &'static str {
+        WORDS[self.choose(WORDS.len())]
+    }
+
+    fn newline(&mut self, indent: usize) {
+        match self.choose(8) {
+            0 => self.output.push_str("\n\n"),
+            1 => self.output.push_str(" # comment\n"),
+            _ => self.output.push('\n'),
+        }
+        self.output.push_str(&" ".repeat(indent));
+    }
+
+    fn properties(&mut self) {
+        match self.choose(8) {
+            0 => {
+                self.anchors += 1;
+                self.output.push_str(&format!("&a{} ", self.anchors));
+            }
+            1 => self.output.push_str("!!str "),
+            _ => {}
+        }
+    }
+
+    /// Print a node which begins at the current position, with its content indented by `indent`.
+    fn block_node(&mut self, indent: usize, depth: usize) {
+        let kind = if depth >= MAX_DEPTH {
+            2 + self.choose(3)
+        } else {
+            self.choose(6)
+        };
+        match kind {
+            0 => {
+                let entries = self.choose(3) + 1;
+                for i in 0..entries {
+                    if i > 0 {
+                        self.newline(indent);
+                    }
+                    self.output.push_str(&format!("k{i}:"));
+                    self.block_value(indent + 2, depth + 1);
+                }
+            }
+            1 => {
+                let entries = self.choose(3) + 1;
+                for i in 0..entries {
+                    if i > 0 {
+                        self.newline(indent);
+                    }
+                    self.output.push('-');
+                    self.block_value(indent + 2, depth + 1);
+                }
+            }
+            _ => self.flow_node(depth),
+        }
+    }
+
+    /// Print a value after a `:` or `-` indicator.
+    fn block_value(&mut self, indent: usize, depth: usize) {
+        match self.choose(6) {
+            0 if depth < MAX_DEPTH => {
+                self.output.push(' ');
+                self.properties();
+                self.newline(indent);
+                self.block_node(indent, depth);
+            }
+            1 => {
+                let indicator = ["|", ">", "|-", ">+"][self.choose(4)];
+                self.output.push(' ');
+                self.output.push_str(indicator);
+                let lines = self.choose(3) + 1;
+                for i in 0..lines {
+                    self.output.push('\n');
+                    if i > 0 && self.choose(4) == 0 {
+                        self.output.push('\n');
+                    }
+                    self.output.push_str(&" ".repeat(indent));
+                    let word = self.word();
+                    self.output.push_str(word);
+                }
+            }
+            2 if self.anchors > 0 => {
+                let anchor = self.choose(self.anchors) + 1;
+                self.output.push_str(&format!(" *a{anchor}"));
+            }
+            3 => {}
+            _ => {
+                self.output.push(' ');
+                self.properties();
+                self.flow_node(depth);
+            }
+        }
+    }
+
+    fn flow_node(&mut self, depth: usize) {
+        let kind = if depth >= MAX_DEPTH {
+            2 + self.choose(3)
+        } else {
+            self.choose(5)
+        };
+        match kind {
+            0 => {
+                self.output.push('[');
+                let entries = self.choose(4);
+                for i in 0..entries {
+                    if i > 0 {
+                        self.output.push_str(", ");
+                    }
+                    self.flow_node(depth + 1);
+                }
+                self.output.push(']');
+            }
+            1 => {
+                self.output.push('{');
+                let entries = self.choose(4);
+                for i in 0..entries {
+                    if i > 0 {
+                        self.output.push_str(", ");
+                    }
+                    self.output.push_str(&format!("k{i}: "));
+                    self.flow_node(depth + 1);
+                }
+                self.output.push('}');
+            }
+            2 => {
+                let word = self.word();
+                self.output.push_str(&format!("'{word}'"));
+            }
+            3 => {
+                let word = self.word();
+                self.output.push_str(&format!("\"{word}\\t\""));
+            }
+            _ => {
+                let word = self.word();
+                self.output.push_str(word);
+            }
+        }
+    }
+}
@@ -0,0 +1,1650 @@
+use crate::{
+    config::{
+        AnchorNameCase, DedupeKeys, EmptyValueStyle, KeyOrdering, LanguageOptions,
+        NormalizeBooleanCasing, NormalizeNull,
+    },
+    printer::is_ignored,
+    warning::{FormatWarning, FormatWarningKind},
+};
+use rowan::{Direction, TextRange};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+use yaml_parser::{
+    analyze::{duplicate_keys, effective_entries, is_merge_entry},
+    ast::*,
+    resolve::AnchorTable,
+    schema::{resolve_scalar_with_version, ScalarType},
+    SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TemplateSyntax,
+};
+
+/// Collect map entries that should be removed because of duplicated keys.
+pub(crate) fn dedupe_keys(
+    root: &Root,
+    mode: &DedupeKeys,
+    warnings: &mut Vec<FormatWarning>,
+) -> HashSet<SyntaxNode> {
+    let removed = duplicate_keys(root)
+        .into_iter()
+        .map(|duplicate| {
+            let entry = match mode {
+                DedupeKeys::KeepFirst => duplicate.entry,
+                DedupeKeys::KeepLast => duplicate.previous,
+            };
+            let range = entry.text_range();
+            warnings.push(FormatWarning::new(
+                FormatWarningKind::DuplicateKeyRemoved,
+                range.start().into()..range.end().into(),
+                format!("removed entry with duplicate key `{}`", duplicate.key),
+            ));
+            entry
+        })
+        .collect();
+    warnings.sort_by_key(|warning| warning.range().start);
+    removed
+}
+
+/// Report map entries with duplicated keys, which are kept as-is.
+pub(crate) fn report_duplicate_keys(root: &Root, warnings: &mut Vec<FormatWarning>) {
+    warnings.extend(duplicate_keys(root).into_iter().map(|duplicate| {
+        let range = duplicate.range();
+        FormatWarning::new(
+            FormatWarningKind::DuplicateKey,
+            range.start().into()..range.end().into(),
+            format!("found entry with duplicate key `{}`", duplicate.key),
+        )
+    }));
+}
+
+/// Collect empty documents that can be removed from the stream.
+/// Documents whose document end marker is required by next document's directives are kept.
+pub(crate) fn empty_documents(root: &Root, directive: &str) -> HashSet<SyntaxNode> {
+    root.documents()
+        .filter(|document| {
+            is_empty_document(document)
+                && !is_ignored(document.syntax(), directive)
+                && (document.document_end().is_none()
+                    || document
+                        .syntax()
+                        .next_sibling()
+                        .and_then(Document::cast)
+                        .is_none_or(|next| next.directives().next().is_none()))
+        })
+        .map(|document| document.syntax().clone())
+        .collect()
+}
+
+/// Check if the document has nothing but document start marker and optional `null` value.
+/// Comments after the document are considered as part of it.
+pub(crate) fn is_empty_document(document: &Document) -> bool {
+    document.directives_end().is_some()
+        && document.block().is_none()
+        && document
+            .syntax()
+            .siblings_with_tokens(Direction::Next)
+            .skip(1)
+            .find(|element| element.kind() != SyntaxKind::WHITESPACE)
+            .is_none_or(|element| element.kind() != SyntaxKind::COMMENT)
+        && document
+            .syntax()
+            .children_with_tokens()
+            .all(|element| match element {
+                SyntaxElement::Node(node) => Flow::cast(node).is_some_and(|flow| {
+                    flow.properties().is_none()
+                        && flow.plain_scalar().is_some_and(|scalar| {
+                            matches!(scalar.text(), "null" | "Null" | "NULL" | "~")
+                        })
+                }),
+                SyntaxElement::Token(token) => token.kind() != SyntaxKind::COMMENT,
+            })
+}
+
+/// Change spelling of null and boolean values
+/// by `normalizeNull`, `emptyValueStyle` and `normalizeBooleanCasing` options.
+///
+/// Values are resolved with the YAML version of their documents,
+/// and empty values are only added or removed in block map entries and block sequence entries.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn normalize_scalars(root: &Root, options: &LanguageOptions) -> Option<Root> {
+    if matches!(options.normalize_null, NormalizeNull::Keep)
+        && matches!(options.empty_value_style, EmptyValueStyle::Keep)
+        && matches!(
+            options.normalize_boolean_casing,
+            NormalizeBooleanCasing::Keep
+        )
+    {
+        return None;
+    }
+    // `emptyValueStyle` takes precedence over `normalizeNull` for values of block map entries
+    let null_style = |in_block_map: bool| match options.empty_value_style {
+        EmptyValueStyle::Null if in_block_map => &NormalizeNull::Null,
+        EmptyValueStyle::Tilde if in_block_map => &NormalizeNull::Tilde,
+        EmptyValueStyle::Empty if in_block_map => &NormalizeNull::Empty,
+        _ => &options.normalize_null,
+    };
+    let spelling = |style: &NormalizeNull| match style {
+        NormalizeNull::Tilde => Some("~"),
+        NormalizeNull::Null => Some("null"),
+        NormalizeNull::Keep | NormalizeNull::Empty => None,
+    };
+
+    let mut edits = vec![];
+    root.syntax()
+        .descendants()
+        .filter(|node| {
+            !node
+                .ancestors()
+                .any(|node| is_ignored(&node, &options.ignore_comment_directive))
+        })
+        .for_each(|node| {
+            if let Some(flow) = Flow::cast(node.clone()) {
+                let Some(plain) = flow.plain_scalar() else {
+                    return;
+                };
+                match resolve_scalar_with_version(&flow, options.yaml_version.resolve(&node)) {
+                    ScalarType::Null => {
+                        let style =
+                            null_style(node.parent().is_some_and(|parent| {
+                                parent.kind() == SyntaxKind::BLOCK_MAP_VALUE
+                            }));
+                        if let Some(null) = spelling(style).filter(|null| plain.text() != *null) {
+                            edits.push((plain.text_range(), null.to_owned()));
+                        } else if matches!(style, NormalizeNull::Empty)
+                            && flow.properties().is_none()
+                            && node.parent().is_some_and(|parent| match parent.kind() {
+                                SyntaxKind::BLOCK_MAP_VALUE => true,
+                                SyntaxKind::BLOCK_SEQ_ENTRY => can_empty_seq_entry(&parent),
+                                _ => false,
+                            })
+                        {
+                            let start = plain
+                                .prev_token()
+                                .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
+                                .map_or(plain.text_range().start(), |token| {
+                                    token.text_range().start()
+                                });
+                            edits.push((
+                                TextRange::new(start, plain.text_range().end()),
+                                String::new(),
+                            ));
+                        }
+                    }
+                    ScalarType::Bool
+                        if matches!(
+                            options.normalize_boolean_casing,
+                            NormalizeBooleanCasing::Lowercase
+                        ) =>
+                    {
+                        let lowercase = plain.text().to_ascii_lowercase();
+                        if lowercase != plain.text() {
+                            edits.push((plain.text_range(), lowercase));
+                        }
+                    }
+                    _ => {}
+                }
+            } else {
+                // empty values are null, too
+                let indicator = match node.kind() {
+                    SyntaxKind::BLOCK_MAP_ENTRY
+                        if node
+                            .children()
+                            .all(|child| child.kind() != SyntaxKind::BLOCK_MAP_VALUE) =>
+                    {
+                        spelling(null_style(true))
+                            .zip(BlockMapEntry::cast(node).and_then(|entry| entry.colon()))
+                    }
+                    SyntaxKind::BLOCK_SEQ_ENTRY if node.first_child().is_none() => {
+                        spelling(null_style(false))
+                            .zip(BlockSeqEntry::cast(node).and_then(|entry| entry.minus()))
+                    }
+                    _ => None,
+                };
+                if let Some((null, indicator)) = indicator {
+                    edits.push((
+                        TextRange::empty(indicator.text_range().end()),
+                        format!(" {null}"),
+                    ));
+                }
+            }
+        });
+    if edits.is_empty() {
+        None
+    } else {
+        reparse_with_edits(
+            &root.syntax().to_string(),
+            edits,
+            (&options.template_syntax).into(),
+        )
+    }
+}
+
+/// Check if the value of block sequence entry can be removed without changing the structure.
+///
+/// An empty entry followed by other content would take that content as its value when reparsing,
+/// so this is only allowed when the entry is followed by another entry of the same sequence
+/// or by nothing else in the document.
+fn can_empty_seq_entry(entry: &SyntaxNode) -> bool {
+    if entry
+        .next_sibling()
+        .is_some_and(|sibling| sibling.kind() == SyntaxKind::BLOCK_SEQ_ENTRY)
+    {
+        return true;
+    }
+    let mut token = entry.last_token().and_then(|token| token.next_token());
+    while let Some(current) = token {
+        match current.kind() {
+            SyntaxKind::WHITESPACE | SyntaxKind::COMMENT => token = current.next_token(),
+            kind => return matches!(kind, SyntaxKind::DOCUMENT_END | SyntaxKind::DIRECTIVES_END),
+        }
+    }
+    true
+}
+
+/// Compute new names of anchors in definition order, such as `a1`, `a2` and so on.
+/// Returned map contains both anchor name tokens of anchors and aliases.
+///
+/// Each alias refers to the closest anchor with the same name that comes before it,
+/// so redefined anchors get different names.
+/// Documents that have anchors or aliases inside ignored nodes are left untouched.
+pub(crate) fn rename_anchors(
+    root: &Root,
+    ignore_comment_directive: &str,
+) -> HashMap<SyntaxToken, String> {
+    rename_anchors_with(root, ignore_comment_directive, |_, count| {
+        format!("a{count}")
+    })
+}
+
+/// Convert anchor names to the given case for the `anchorNameCase` option,
+/// and update aliases that refer to them.
+///
+/// Documents are left untouched if different anchors would get the same name after conversion,
+/// since aliases may refer to other anchors then.
+pub(crate) fn change_anchor_name_case(
+    root: &Root,
+    case: &AnchorNameCase,
+    ignore_comment_directive: &str,
+) -> HashMap<SyntaxToken, String> {
+    rename_anchors_with(root, ignore_comment_directive, |name, _| {
+        convert_case(name, case)
+    })
+}
+
+fn rename_anchors_with(
+    root: &Root,
+    ignore_comment_directive: &str,
+    rename: impl Fn(&str, usize) -> String,
+) -> HashMap<SyntaxToken, String> {
+    let mut renamed = HashMap::new();
+    root.documents().for_each(|document| {
+        let references = resolve_references(&document);
+        if references.iter().any(|reference| {
+            reference
+                .syntax()
+                .ancestors()
+                .any(|node| is_ignored(&node, ignore_comment_directive))
+        }) {
+            return;
+        }
+
+        let mut count = 0;
+        let mut document_renamed = HashMap::new();
+        // new names of anchors with their original names for detecting conflicts
+        let mut new_names = HashMap::new();
+        for reference in &references {
+            if let Reference::Anchor(anchor) = reference {
+                if let Some(name) = anchor.anchor_name() {
+                    count += 1;
+                    let new_name = rename(name.text(), count);
+                    if new_names
+                        .insert(new_name.clone(), name.text().to_owned())
+                        .is_some_and(|original| original != name.text())
+                    {
+                        return;
+                    }
+                    document_renamed.insert(name, new_name);
+                }
+            }
+        }
+        for reference in &references {
+            match reference {
+                Reference::Alias(alias, Some(anchor)) => {
+                    if let Some((name, new_name)) = alias.anchor_name().zip(
+                        anchor
+                            .anchor_name()
+                            .and_then(|name| document_renamed.get(&name).cloned()),
+                    ) {
+                        document_renamed.insert(name, new_name);
+                    }
+                }
+                Reference::Alias(alias, None) => {
+                    // unresolved alias would refer to renamed anchor accidentally
+                    if alias
+                        .anchor_name()
+                        .is_some_and(|name| new_names.contains_key(name.text()))
+                    {
+                        return;
+                    }
+                }
+                Reference::Anchor(_) => {}
+            }
+        }
+        renamed.extend(
+            document_renamed
+                .into_iter()
+                .filter(|(name, new_name)| name.text() != new_name),
+        );
+    });
+    renamed
+}
+
+/// Convert name to the given case.
+///
+/// Words are split at `-`, `_` and case changes like `fooBar` or `HTTPServer`,
+/// and other characters are kept as parts of words.
+fn convert_case(name: &str, case: &AnchorNameCase) -> String {
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    let chars = name.chars().collect::<Vec<_>>();
+    for (i, c) in chars.iter().enumerate() {
+        if matches!(c, '-' | '_') {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let is_boundary = prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || prev.is_uppercase() && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if is_boundary {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    if words.is_empty() {
+        return name.to_owned();
+    }
+    match case {
+        AnchorNameCase::Keep => name.to_owned(),
+        AnchorNameCase::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i == 0 {
+                    word.clone()
+                } else {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                }
+            })
+            .collect(),
+        AnchorNameCase::Kebab => words.join("-"),
+        AnchorNameCase::Snake => words.join("_"),
+    }
+}
+
+/// Replace aliases with copies of their anchored nodes,
+/// then remove anchors that aren't referred by any aliases.
+///
+/// Aliases that refer to their ancestors, are used by merge keys,
+/// or can't be replaced without changing the meaning will be kept and reported.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn inline_aliases(
+    root: &Root,
+    ignore_comment_directive: &str,
+    template_syntax: TemplateSyntax,
+    warnings: &mut Vec<FormatWarning>,
+) -> Option<Root> {
+    let is_ignored = |node: &SyntaxNode| {
+        node.ancestors()
+            .any(|node| is_ignored(&node, ignore_comment_directive))
+    };
+
+    let report_failure = |warnings: &mut Vec<FormatWarning>| {
+        warnings.push(FormatWarning::new(
+            FormatWarningKind::AliasNotInlined,
+            0..0,
+            "aliases aren't inlined because the result can't be parsed".into(),
+        ));
+        None
+    };
+
+    let mut current = root.clone();
+    let mut is_first_pass = true;
+    loop {
+        let source = current.syntax().to_string();
+        let plans = current
+            .documents()
+            .flat_map(|document| resolve_references(&document))
+            .filter_map(|reference| match reference {
+                Reference::Alias(alias, Some(anchor)) if !is_ignored(alias.syntax()) => {
+                    let target = anchor.syntax().parent()?.parent()?;
+                    let plan = plan_inline(&alias, &target, &source);
+                    Some((alias, target, plan))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if is_first_pass {
+            plans.iter().for_each(|(alias, _, plan)| {
+                if let Err(reason) = plan {
+                    let range = alias.syntax().text_range();
+                    warnings.push(FormatWarning::new(
+                        FormatWarningKind::AliasNotInlined,
+                        range.start().into()..range.end().into(),
+                        format!("alias `{}` isn't inlined because {reason}", alias.syntax()),
+                    ));
+                }
+            });
+            is_first_pass = false;
+        }
+
+        // Only replace aliases whose anchored nodes don't contain aliases to be replaced,
+        // so nested aliases will be replaced first and copied in later passes.
+        let edits = plans
+            .iter()
+            .filter_map(|(alias, target, plan)| {
+                let replacement = plan.as_ref().ok()?;
+                if plans.iter().any(|(other, _, plan)| {
+                    plan.is_ok()
+                        && target
+                            .text_range()
+                            .contains_range(other.syntax().text_range())
+                }) {
+                    None
+                } else {
+                    Some((alias.syntax().text_range(), replacement.clone()))
+                }
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            break;
+        }
+        current = reparse_with_edits(&source, edits, template_syntax)
+            .or_else(|| report_failure(warnings))?;
+    }
+
+    let source = current.syntax().to_string();
+    let references = current
+        .documents()
+        .flat_map(|document| resolve_references(&document))
+        .collect::<Vec<_>>();
+    let used_anchors = references
+        .iter()
+        .filter_map(|reference| match reference {
+            Reference::Alias(_, anchor) => anchor.clone(),
+            Reference::Anchor(..) => None,
+        })
+        .collect::<HashSet<_>>();
+    let edits = references
+        .iter()
+        .filter_map(|reference| match reference {
+            Reference::Anchor(anchor)
+                if !used_anchors.contains(anchor) && !is_ignored(anchor.syntax()) =>
+            {
+                Some((anchor_removal_range(anchor)?, String::new()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !edits.is_empty() {
+        current = reparse_with_edits(&source, edits, template_syntax)
+            .or_else(|| report_failure(warnings))?;
+    }
+
+    if current.syntax().to_string() == root.syntax().to_string() {
+        None
+    } else {
+        Some(current)
+    }
+}
+
+/// Find identical subtrees which contain at least `threshold` scalars,
+/// then add an anchor to the first one and replace the others with aliases.
+///
+/// Subtrees that contain comments, anchors or aliases won't be extracted.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn extract_anchors(
+    root: &Root,
+    threshold: usize,
+    ignore_comment_directive: &str,
+    template_syntax: TemplateSyntax,
+) -> Option<Root> {
+    let source = root.syntax().to_string();
+    let mut edits = vec![];
+    root.documents().for_each(|document| {
+        let mut names = document
+            .syntax()
+            .descendants()
+            .filter_map(AnchorProperty::cast)
+            .filter_map(|anchor| anchor.anchor_name())
+            .map(|name| name.text().to_owned())
+            .collect::<HashSet<_>>();
+
+        let mut groups = HashMap::<String, (usize, Vec<SyntaxNode>)>::new();
+        document
+            .syntax()
+            .descendants()
+            .filter(|node| {
+                matches!(node.kind(), SyntaxKind::BLOCK | SyntaxKind::FLOW)
+                    && node.parent().is_some_and(|parent| {
+                        matches!(
+                            parent.kind(),
+                            SyntaxKind::BLOCK_MAP_VALUE
+                                | SyntaxKind::BLOCK_SEQ_ENTRY
+                                | SyntaxKind::FLOW_MAP_VALUE
+                                | SyntaxKind::FLOW_SEQ_ENTRY
+                        )
+                    })
+                    && !node
+                        .children()
+                        .any(|child| child.kind() == SyntaxKind::PROPERTIES)
+                    && !node
+                        .ancestors()
+                        .any(|node| is_ignored(&node, ignore_comment_directive))
+            })
+            .for_each(|node| {
+                let mut signature = String::new();
+                let mut size = 0;
+                if write_signature(&node, &mut signature, &mut size) && size >= threshold {
+                    groups
+                        .entry(signature)
+                        .or_insert_with(|| (size, vec![]))
+                        .1
+                        .push(node);
+                }
+            });
+
+        // Larger subtrees go first, so smaller subtrees inside replaced subtrees won't be extracted.
+        let mut groups = groups
+            .into_values()
+            .filter(|(_, nodes)| nodes.len() > 1)
+            .collect::<Vec<_>>();
+        groups.sort_by_key(|(size, nodes)| (Reverse(*size), nodes[0].text_range().start()));
+        let mut replaced = Vec::<TextRange>::new();
+        groups.into_iter().for_each(|(_, nodes)| {
+            let nodes = nodes
+                .into_iter()
+                .filter(|node| {
+                    !replaced
+                        .iter()
+                        .any(|range| range.contains_range(node.text_range()))
+                })
+                .collect::<Vec<_>>();
+            let Some((first, rest)) = nodes.split_first().filter(|(_, rest)| !rest.is_empty())
+            else {
+                return;
+            };
+            let name = new_anchor_name(first, &mut names);
+            let Some(edit) = anchor_insertion(first, &name, &source) else {
+                return;
+            };
+            edits.push(edit);
+            edits.extend(rest.iter().map(|node| alias_replacement(node, &name)));
+            replaced.extend(rest.iter().map(|node| node.text_range()));
+        });
+    });
+
+    if edits.is_empty() {
+        None
+    } else {
+        reparse_with_edits(&source, edits, template_syntax)
+    }
+}
+
+/// Write structural representation of node to `signature` and count scalars.
+/// Returns `false` if the node can't be extracted.
+fn write_signature(node: &SyntaxNode, signature: &mut String, size: &mut usize) -> bool {
+    if matches!(node.kind(), SyntaxKind::ANCHOR_PROPERTY | SyntaxKind::ALIAS) {
+        return false;
+    }
+    let _ = write!(signature, "({:?}", node.kind());
+    for element in node.children_with_tokens() {
+        match element {
+            SyntaxElement::Node(node) => {
+                if !write_signature(&node, signature, size) {
+                    return false;
+                }
+            }
+            SyntaxElement::Token(token) => match token.kind() {
+                SyntaxKind::WHITESPACE => {}
+                SyntaxKind::COMMENT | SyntaxKind::INDENT_INDICATOR => return false,
+                SyntaxKind::PLAIN_SCALAR => {
+                    *size += 1;
+                    let text = token
+                        .text()
+                        .lines()
+                        .map(str::trim)
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    let _ = write!(signature, "{:?}{text:?}", token.kind());
+                }
+                kind => {
+                    if matches!(
+                        kind,
+                        SyntaxKind::SINGLE_QUOTED_SCALAR
+                            | SyntaxKind::DOUBLE_QUOTED_SCALAR
+                            | SyntaxKind::BLOCK_SCALAR_TEXT
+                    ) {
+                        *size += 1;
+                    }
+                    let _ = write!(signature, "{kind:?}{:?}", token.text());
+                }
+            },
+        }
+    }
+    signature.push(')');
+    true
+}
+
+/// Generate anchor name from key of map entry if possible,
+/// and make sure it doesn't conflict with existing names.
+fn new_anchor_name(node: &SyntaxNode, names: &mut HashSet<String>) -> String {
+    let base = node
+        .parent()
+        .filter(|parent| {
+            matches!(
+                parent.kind(),
+                SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE
+            )
+        })
+        .and_then(|value| value.parent())
+        .and_then(|entry| entry_key_text(&entry))
+        .filter(|key| {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+        .unwrap_or_else(|| "anchor".into());
+    let name = if names.contains(&base) {
+        (2..)
+            .map(|i| format!("{base}{i}"))
+            .find(|name| !names.contains(name))
+            .unwrap_or_default()
+    } else {
+        base
+    };
+    names.insert(name.clone());
+    name
+}
+
+/// Compute the edit that replaces the given node with alias.
+/// If the node starts on a new line, alias will be moved after the indicator (`:` or `-`).
+fn alias_replacement(node: &SyntaxNode, name: &str) -> (TextRange, String) {
+    let range = node.text_range();
+    if let Some(whitespace) = node
+        .first_token()
+        .and_then(|token| token.prev_token())
+        .filter(|token| {
+            token.kind() == SyntaxKind::WHITESPACE
+                && token.prev_token().is_some_and(|token| {
+                    matches!(token.kind(), SyntaxKind::COLON | SyntaxKind::MINUS)
+                })
+        })
+    {
+        (
+            TextRange::new(whitespace.text_range().start(), range.end()),
+            format!(" *{name}"),
+        )
+    } else {
+        (range, format!("*{name}"))
+    }
+}
+
+/// Compute the edit that adds anchor property to the given node.
+fn anchor_insertion(node: &SyntaxNode, name: &str, source: &str) -> Option<(TextRange, String)> {
+    let start = node.text_range().start();
+    let block = Block::cast(node.clone());
+    if block.is_none() || block.is_some_and(|block| block.block_scalar().is_some()) {
+        return Some((TextRange::empty(start), format!("&{name} ")));
+    }
+
+    // Anchor of block map or block sequence must come after the indicator (`:` or `-`),
+    // and the map or sequence itself must start on a new line.
+    let mut token = node.first_token()?.prev_token()?;
+    let mut has_line_break = false;
+    while matches!(token.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT) {
+        has_line_break |= token.text().contains('\n');
+        token = token.prev_token()?;
+    }
+    let end = token.text_range().end();
+    if has_line_break {
+        Some((TextRange::empty(end), format!(" &{name}")))
+    } else {
+        Some((
+            TextRange::new(end, start),
+            format!(" &{name}\n{}", " ".repeat(column(source, start.into()))),
+        ))
+    }
+}
+
+/// Reorder documents by scalar values at the given key paths.
+/// Documents that don't have the value go last, and the order of equal documents is kept.
+///
+/// Comments before a document are moved together with it,
+/// while comments after the last document are kept at the end of stream with blank lines between them.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn sort_documents(
+    root: &Root,
+    key_paths: &[String],
+    template_syntax: TemplateSyntax,
+) -> Option<Root> {
+    let mut documents = vec![];
+    let mut comments = vec![];
+    root.syntax()
+        .children_with_tokens()
+        .for_each(|element| match element {
+            SyntaxElement::Node(node) => {
+                if let Some(document) = Document::cast(node) {
+                    documents.push((std::mem::take(&mut comments), document));
+                }
+            }
+            SyntaxElement::Token(token) => {
+                if token.kind() == SyntaxKind::COMMENT {
+                    comments.push(token);
+                }
+            }
+        });
+    let trailing_trivia = root
+        .trailing_trivia()
+        .iter()
+        .map(|token| token.text())
+        .collect::<String>();
+
+    let mut sorted = documents.iter().collect::<Vec<_>>();
+    sorted.sort_by_cached_key(|(_, document)| {
+        let content = document
+            .syntax()
+            .children()
+            .find(|child| matches!(child.kind(), SyntaxKind::BLOCK | SyntaxKind::FLOW));
+        key_paths
+            .iter()
+            .map(|key_path| {
+                let value = content
+                    .clone()
+                    .and_then(|content| value_at_key_path(content, key_path));
+                (value.is_none(), value)
+            })
+            .collect::<Vec<_>>()
+    });
+    if sorted.iter().zip(&documents).all(|((_, a), (_, b))| a == b) {
+        return None;
+    }
+
+    let mut source = String::new();
+    let mut prev_has_end = false;
+    sorted
+        .iter()
+        .enumerate()
+        .for_each(|(i, (comments, document))| {
+            let has_token = |kind| {
+                document
+                    .syntax()
+                    .children_with_tokens()
+                    .any(|element| element.kind() == kind)
+            };
+            // directives must come after document end marker if there's previous document
+            if i > 0 && !prev_has_end && has_token(SyntaxKind::DIRECTIVE) {
+                source.push_str("...\n");
+            }
+            if i > 0 && !has_token(SyntaxKind::DIRECTIVES_END) {
+                source.push_str("---\n");
+            }
+            comments.iter().for_each(|comment| {
+                source.push_str(comment.text());
+                source.push('\n');
+            });
+            source.push_str(&document.syntax().to_string());
+            source.push('\n');
+            prev_has_end = has_token(SyntaxKind::DOCUMENT_END);
+        });
+    // the last document may be changed, so trailing comment at the same line is moved to next line
+    let trailing_trivia = trailing_trivia.trim_start_matches([' ', '\t']);
+    source.push_str(
+        trailing_trivia
+            .strip_prefix("\r\n")
+            .or_else(|| trailing_trivia.strip_prefix('\n'))
+            .unwrap_or(trailing_trivia),
+    );
+    yaml_parser::parse_with_template_syntax(&source, template_syntax)
+        .ok()
+        .and_then(Root::cast)
+}
+
+/// Get the scalar value at key path like `metadata.name`.
+fn value_at_key_path(mut node: SyntaxNode, key_path: &str) -> Option<String> {
+    for key in key_path.split('.') {
+        let map = node
+            .children()
+            .find(|child| matches!(child.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::FLOW_MAP))?;
+        let entry = map
+            .children()
+            .find(|child| child.kind() == SyntaxKind::FLOW_MAP_ENTRIES)
+            .unwrap_or(map)
+            .children()
+            .find(|entry| entry_key_text(entry).is_some_and(|text| text == key))?;
+        node = entry
+            .children()
+            .find(|child| {
+                matches!(
+                    child.kind(),
+                    SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE
+                )
+            })?
+            .children()
+            .find(|child| matches!(child.kind(), SyntaxKind::BLOCK | SyntaxKind::FLOW))?;
+    }
+    Flow::cast(node).and_then(|flow| flow.scalar_value().map(Cow::into_owned))
+}
+
+/// Sort entries of block maps and flow maps by their keys.
+///
+/// Entries separated by blank lines are treated as different groups,
+/// and entries won't be moved across groups.
+/// Comments before an entry are moved together with it,
+/// except comments at the beginning of a group which are kept there as group header.
+/// Trailing comment of an entry is moved with it, even if it's the last entry of a map.
+/// Groups are left unsorted if aliases would refer to different anchors after sorting.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn sort_keys(
+    root: &Root,
+    ordering: &KeyOrdering,
+    ignore_comment_directive: &str,
+    template_syntax: TemplateSyntax,
+) -> Option<Root> {
+    let source = root.syntax().to_string();
+    let mut sorted = String::with_capacity(source.len());
+    write_sorted(
+        root.syntax(),
+        &source,
+        ordering,
+        ignore_comment_directive,
+        &mut HashSet::new(),
+        &mut sorted,
+    );
+    if sorted == source {
+        None
+    } else {
+        yaml_parser::parse_with_template_syntax(&sorted, template_syntax)
+            .ok()
+            .and_then(Root::cast)
+    }
+}
+
+struct SortedEntry {
+    key: Option<String>,
+    text: String,
+    anchors: HashSet<String>,
+    aliases: HashSet<String>,
+}
+
+/// Write text of the node with map entries sorted.
+///
+/// Tokens in `moved` have been written with another entry, so they're skipped.
+fn write_sorted(
+    node: &SyntaxNode,
+    source: &str,
+    ordering: &KeyOrdering,
+    ignore_comment_directive: &str,
+    moved: &mut HashSet<SyntaxToken>,
+    output: &mut String,
+) {
+    let is_sortable = match node.kind() {
+        SyntaxKind::BLOCK_MAP => true,
+        SyntaxKind::FLOW_MAP_ENTRIES => !node
+            .children_with_tokens()
+            .any(|element| element.kind() == SyntaxKind::COMMENT),
+        _ => false,
+    } && !node
+        .ancestors()
+        .chain(node.children())
+        .any(|node| is_ignored(&node, ignore_comment_directive));
+    if !is_sortable {
+        node.children_with_tokens()
+            .for_each(|element| match element {
+                SyntaxElement::Node(node) => write_sorted(
+                    &node,
+                    source,
+                    ordering,
+                    ignore_comment_directive,
+                    moved,
+                    output,
+                ),
+                SyntaxElement::Token(token) => {
+                    if !moved.contains(&token) {
+                        output.push_str(token.text());
+                    }
+                }
+            });
+        return;
+    }
+
+    let sort_group = |entries: &mut Vec<SortedEntry>| {
+        if entries.iter().any(|entry| entry.key.is_none()) {
+            return;
+        }
+        let mut order = (0..entries.len()).collect::<Vec<_>>();
+        match ordering {
+            KeyOrdering::Desc => order.sort_by(|a, b| entries[*b].key.cmp(&entries[*a].key)),
+            _ => order.sort_by(|a, b| entries[*a].key.cmp(&entries[*b].key)),
+        }
+        if resolve_entry_aliases(entries, 0..entries.len())
+            == resolve_entry_aliases(entries, order.iter().copied())
+        {
+            let mut taken = entries.drain(..).map(Some).collect::<Vec<_>>();
+            entries.extend(order.into_iter().filter_map(|index| taken[index].take()));
+        }
+    };
+    let write_entry = |entry: &SyntaxNode, moved: &mut HashSet<SyntaxToken>| {
+        let mut text = String::new();
+        write_sorted(
+            entry,
+            source,
+            ordering,
+            ignore_comment_directive,
+            moved,
+            &mut text,
+        );
+        let mut anchors = HashSet::new();
+        let mut aliases = HashSet::new();
+        entry.descendants().for_each(|node| {
+            if let Some(name) =
+                AnchorProperty::cast(node.clone()).and_then(|anchor| anchor.anchor_name())
+            {
+                anchors.insert(name.text().to_owned());
+            } else if let Some(name) = Alias::cast(node).and_then(|alias| alias.anchor_name()) {
+                aliases.insert(name.text().to_owned());
+            }
+        });
+        SortedEntry {
+            key: entry_key_text(entry),
+            text,
+            anchors,
+            aliases,
+        }
+    };
+
+    if node.kind() == SyntaxKind::FLOW_MAP_ENTRIES {
+        // keep commas and whitespace where they are, only swap entries in each group
+        let mut slots = vec![];
+        let mut groups = vec![vec![]];
+        node.children_with_tokens()
+            .for_each(|element| match element {
+                SyntaxElement::Node(entry) => {
+                    slots.push(None);
+                    let entry = write_entry(&entry, moved);
+                    if let Some(group) = groups.last_mut() {
+                        group.push(entry);
+                    }
+                }
+                SyntaxElement::Token(token) => {
+                    if token.kind() == SyntaxKind::WHITESPACE
+                        && token.text().matches('\n').count() > 1
+                    {
+                        groups.push(vec![]);
+                    }
+                    slots.push(Some(token.text().to_owned()));
+                }
+            });
+        let mut entries = groups.into_iter().flat_map(|mut group| {
+            sort_group(&mut group);
+            group
+        });
+        slots.into_iter().for_each(|slot| match slot {
+            Some(text) => output.push_str(&text),
+            None => {
+                if let Some(entry) = entries.next() {
+                    output.push_str(&entry.text);
+                }
+            }
+        });
+        return;
+    }
+
+    let last_entry = node.last_child();
+    let separator = format!(
+        "\n{}",
+        " ".repeat(column(source, node.text_range().start().into()))
+    );
+    let mut children = node.children_with_tokens().peekable();
+    let mut is_group_start = true;
+    let mut header = vec![];
+    let mut comments = vec![];
+    let mut entries = vec![];
+    let flush = |header: &mut Vec<String>,
+                 comments: &mut Vec<String>,
+                 entries: &mut Vec<SortedEntry>,
+                 output: &mut String| {
+        sort_group(entries);
+        let lines = header
+            .drain(..)
+            .chain(entries.drain(..).map(|entry| entry.text))
+            .chain(comments.drain(..))
+            .collect::<Vec<_>>();
+        output.push_str(&lines.join(&separator));
+    };
+    while let Some(element) = children.next() {
+        match element {
+            SyntaxElement::Node(node) => {
+                let mut entry = write_entry(&node, moved);
+                // trailing comment on the same line
+                if let Some(whitespace) = children.next_if(|element| {
+                    element.as_token().is_some_and(|token| {
+                        token.kind() == SyntaxKind::WHITESPACE
+                            && !token.text().contains('\n')
+                            && !moved.contains(token)
+                    })
+                }) {
+                    if let Some(comment) =
+                        children.next_if(|element| element.kind() == SyntaxKind::COMMENT)
+                    {
+                        entry.text.push_str(&whitespace.to_string());
+                        entry.text.push_str(&comment.to_string());
+                    }
+                }
+                if last_entry.as_ref() == Some(&node) {
+                    // trailing comment of the last entry is placed after the whole map by parser,
+                    // and it may have been taken by the last entry of nested map
+                    if let Some((whitespace, comment)) = node
+                        .last_token()
+                        .and_then(|token| token.next_token())
+                        .filter(|token| {
+                            token.kind() == SyntaxKind::WHITESPACE
+                                && !token.text().contains('\n')
+                                && !moved.contains(token)
+                        })
+                        .and_then(|whitespace| {
+                            whitespace
+                                .next_token()
+                                .filter(|token| token.kind() == SyntaxKind::COMMENT)
+                                .map(|comment| (whitespace, comment))
+                        })
+                    {
+                        entry.text.push_str(whitespace.text());
+                        entry.text.push_str(comment.text());
+                        moved.insert(whitespace);
+                        moved.insert(comment);
+                    }
+                }
+                comments.push(std::mem::take(&mut entry.text));
+                entry.text = std::mem::take(&mut comments).join(&separator);
+                entries.push(entry);
+                is_group_start = false;
+            }
+            SyntaxElement::Token(token) if moved.contains(&token) => {}
+            SyntaxElement::Token(token) => match token.kind() {
+                SyntaxKind::COMMENT => {
+                    if is_group_start {
+                        header.push(token.text().to_owned());
+                    } else {
+                        comments.push(token.text().to_owned());
+                    }
+                }
+                SyntaxKind::WHITESPACE if token.text().matches('\n').count() > 1 => {
+                    flush(&mut header, &mut comments, &mut entries, output);
+                    output.push_str(token.text());
+                    is_group_start = true;
+                }
+                _ => {}
+            },
+        }
+    }
+    flush(&mut header, &mut comments, &mut entries, output);
+}
+
+/// Resolve aliases of entries that come in the given order.
+///
+/// Returned map has entry indexes of referred anchors, keyed by entry index of alias and name,
+/// and entry indexes where anchors are defined at last, keyed by `None` and name,
+/// since they're referred by aliases after the map.
+/// Aliases that refer to anchors in the same entry are skipped.
+fn resolve_entry_aliases(
+    entries: &[SortedEntry],
+    order: impl Iterator<Item = usize>,
+) -> HashMap<(Option<usize>, &str), usize> {
+    let mut resolved = HashMap::new();
+    for index in order {
+        let entry = &entries[index];
+        entry
+            .aliases
+            .iter()
+            .filter(|name| !entry.anchors.contains(*name))
+            .for_each(|name| {
+                if let Some(anchor) = resolved.get(&(None, name.as_str())).copied() {
+                    resolved.insert((Some(index), name.as_str()), anchor);
+                }
+            });
+        entry.anchors.iter().for_each(|name| {
+            resolved.insert((None, name.as_str()), index);
+        });
+    }
+    resolved
+}
+
+/// Compute the text that replaces the given alias,
+/// or the reason why it can't be replaced.
+fn plan_inline(alias: &Alias, target: &SyntaxNode, source: &str) -> Result<String, &'static str> {
+    let flow = alias.syntax().parent().ok_or("it's not a node")?;
+    if flow.ancestors().any(|node| &node == target) {
+        return Err("it refers to its ancestor");
+    }
+    if is_merge_value(&flow) {
+        return Err("it's used by merge key");
+    }
+    let in_flow_collection = flow
+        .ancestors()
+        .skip(1)
+        .any(|node| matches!(node.kind(), SyntaxKind::FLOW_SEQ | SyntaxKind::FLOW_MAP));
+    let is_key = flow.parent().is_some_and(|parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+        )
+    });
+    let indent = column(source, flow.text_range().start().into()).max(1);
+    let tag = target
+        .children()
+        .find_map(Properties::cast)
+        .and_then(|properties| properties.tag_property())
+        .map(|tag| tag.syntax().to_string());
+
+    if let Some(block) = Block::cast(target.clone()) {
+        if in_flow_collection || is_key {
+            return Err("block node can't be put there");
+        }
+        if let Some(block_scalar) = block.block_scalar() {
+            if block_scalar.indent_indicator().is_some() {
+                return Err("block scalar has indentation indicator");
+            }
+            let text = block_scalar.syntax().to_string();
+            let old_indent = text
+                .split('\n')
+                .skip(1)
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start_matches(' ').len())
+                .unwrap_or_default();
+            let text = reindent(&text, old_indent, indent);
+            Ok(match tag {
+                Some(tag) => format!("{tag} {text}"),
+                None => text,
+            })
+        } else {
+            let node = block
+                .block_map()
+                .map(|block_map| block_map.syntax().clone())
+                .or_else(|| {
+                    block
+                        .block_seq()
+                        .map(|block_seq| block_seq.syntax().clone())
+                })
+                .ok_or("anchored node is empty")?;
+            let old_indent = column(source, node.text_range().start().into());
+            let text = reindent(&node.to_string(), old_indent, indent);
+            Ok(format!(
+                "{}\n{}{text}",
+                tag.unwrap_or_default(),
+                " ".repeat(indent)
+            ))
+        }
+    } else {
+        let content = target
+            .children_with_tokens()
+            .skip_while(|element| {
+                matches!(
+                    element.kind(),
+                    SyntaxKind::PROPERTIES | SyntaxKind::WHITESPACE
+                )
+            })
+            .map(|element| element.to_string())
+            .collect::<String>();
+        if content.is_empty() {
+            return if in_flow_collection {
+                Err("anchored node is empty")
+            } else {
+                Ok(tag.unwrap_or_default())
+            };
+        }
+        if in_flow_collection
+            && target
+                .children_with_tokens()
+                .any(|element| element.kind() == SyntaxKind::PLAIN_SCALAR)
+            && content.contains([',', '[', ']', '{', '}'])
+        {
+            return Err("plain scalar contains flow indicators");
+        }
+        if is_key && content.contains(['\n', '\r']) {
+            return Err("implicit key can't span multiple lines");
+        }
+        let text = content
+            .split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                if i == 0 || line.trim().is_empty() {
+                    line.to_owned()
+                } else {
+                    format!("{}{}", " ".repeat(indent), line.trim_start())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(match tag {
+            Some(tag) => format!("{tag} {text}"),
+            None => text,
+        })
+    }
+}
+
+/// Check if the given flow node is value of merge key,
+/// or an item of sequence which is value of merge key.
+/// Replace merge keys like `<<: *base` in block maps with the merged entries
+/// for the `flattenMergeKeys` option.
+///
+/// Merged entries that are overridden by entries of the map itself are omitted.
+/// Merge keys are kept if any of their aliases can't be resolved to maps in the same document,
+/// or if they refer to their ancestors.
+/// This returns a new tree parsed from the modified source, or `None` if nothing changed.
+pub(crate) fn flatten_merge_keys(
+    root: &Root,
+    ignore_comment_directive: &str,
+    template_syntax: TemplateSyntax,
+) -> Option<Root> {
+    let source = root.syntax().to_string();
+    let mut edits = vec![];
+    root.documents().for_each(|document| {
+        let table = AnchorTable::for_document(&document);
+        document
+            .syntax()
+            .descendants()
+            .filter(|node| {
+                node.kind() == SyntaxKind::BLOCK_MAP
+                    && !node
+                        .ancestors()
+                        .any(|node| is_ignored(&node, ignore_comment_directive))
+            })
+            .for_each(|map| {
+                let effective = effective_entries(&map, &table);
+                map.children()
+                    .filter(|entry| is_merge_entry(entry) && can_flatten(entry, &table))
+                    .for_each(|merge| {
+                        let merged = effective
+                            .iter()
+                            .filter(|entry| entry.merged_by.as_ref() == Some(&merge))
+                            .map(|entry| &entry.entry)
+                            .collect::<Vec<_>>();
+                        if merged.iter().any(|entry| {
+                            !entry
+                                .children_with_tokens()
+                                .any(|element| element.kind() == SyntaxKind::COLON)
+                        }) {
+                            return;
+                        }
+                        let indent = column(&source, merge.text_range().start().into());
+                        let replacement = merged
+                            .iter()
+                            .map(|entry| {
+                                let old_indent = column(&source, entry.text_range().start().into());
+                                reindent(&entry.to_string(), old_indent, indent)
+                            })
+                            .collect::<Vec<_>>()
+                            .join(&format!("\n{}", " ".repeat(indent)));
+                        let range = if replacement.is_empty() {
+                            // remove the whole line of merge key
+                            merge
+                                .next_sibling_or_token()
+                                .filter(|element| element.kind() == SyntaxKind::WHITESPACE)
+                                .map(|whitespace| {
+                                    TextRange::new(
+                                        merge.text_range().start(),
+                                        whitespace.text_range().end(),
+                                    )
+                                })
+                                .or_else(|| {
+                                    merge
+                                        .prev_sibling_or_token()
+                                        .filter(|element| element.kind() == SyntaxKind::WHITESPACE)
+                                        .map(|whitespace| {
+                                            TextRange::new(
+                                                whitespace.text_range().start(),
+                                                merge.text_range().end(),
+                                            )
+                                        })
+                                })
+                                .unwrap_or(merge.text_range())
+                        } else {
+                            merge.text_range()
+                        };
+                        edits.push((range, replacement));
+                    });
+            });
+    });
+    if edits.is_empty() {
+        None
+    } else {
+        reparse_with_edits(&source, edits, template_syntax)
+    }
+}
+
+/// Check if all maps merged by the merge key entry can be resolved,
+/// and none of them is the ancestor of the entry.
+fn can_flatten(merge: &SyntaxNode, table: &AnchorTable) -> bool {
+    let Some(value) = BlockMapEntry::cast(merge.clone())
+        .and_then(|entry| entry.value())
+        .and_then(|value| value.flow())
+    else {
+        return false;
+    };
+    let flows = match value.flow_seq() {
+        Some(seq) => seq
+            .entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .map(|entry| entry.flow())
+            .collect::<Option<Vec<_>>>(),
+        None => Some(vec![value]),
+    };
+    flows.is_some_and(|flows| {
+        flows.iter().all(|flow| {
+            let target = match flow.alias() {
+                Some(alias) => table.resolve_alias(&alias),
+                None => Some(flow.syntax().clone()),
+            };
+            target.is_some_and(|target| {
+                target.children().any(|child| {
+                    matches!(child.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::FLOW_MAP)
+                }) && !merge.ancestors().any(|node| node == target)
+            })
+        })
+    })
+}
+
+fn is_merge_value(flow: &SyntaxNode) -> bool {
+    let mut node = flow.parent();
+    if let Some(entry) = node.as_ref().filter(|node| {
+        matches!(
+            node.kind(),
+            SyntaxKind::FLOW_SEQ_ENTRY | SyntaxKind::BLOCK_SEQ_ENTRY
+        )
+    }) {
+        node = entry
+            .ancestors()
+            .find(|node| matches!(node.kind(), SyntaxKind::FLOW | SyntaxKind::BLOCK))
+            .and_then(|node| node.parent());
+    }
+    node.filter(|node| {
+        matches!(
+            node.kind(),
+            SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE
+        )
+    })
+    .and_then(|value| value.parent())
+    .and_then(|entry| entry_key_text(&entry))
+    .is_some_and(|key| key == "<<")
+}
+
+/// Range of anchor property to be removed, including whitespace that separates it from others.
+fn anchor_removal_range(anchor: &AnchorProperty) -> Option<TextRange> {
+    let range = anchor.syntax().text_range();
+    let properties = anchor.syntax().parent()?;
+    if let Some(prev) = anchor
+        .syntax()
+        .prev_sibling_or_token()
+        .filter(|element| element.kind() == SyntaxKind::WHITESPACE)
+    {
+        Some(TextRange::new(prev.text_range().start(), range.end()))
+    } else if let Some(next) = anchor
+        .syntax()
+        .next_sibling_or_token()
+        .filter(|element| element.kind() == SyntaxKind::WHITESPACE)
+    {
+        Some(TextRange::new(range.start(), next.text_range().end()))
+    } else if let Some(next) = properties
+        .next_sibling_or_token()
+        .and_then(SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::WHITESPACE && !token.text().contains('\n'))
+    {
+        Some(TextRange::new(range.start(), next.text_range().end()))
+    } else {
+        Some(range)
+    }
+}
+
+/// Insert `---` after directives that aren't followed by directives end marker.
+///
+/// Directives can only appear at the start of stream or after document end marker `...`,
+/// so `%` at the start of line in other places isn't treated as directive.
+/// It returns `None` if nothing is inserted.
+pub(crate) fn insert_directives_end(source: &str) -> Option<String> {
+    fn is_marker(line: &str, marker: &str) -> bool {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\r', '\n']))
+    }
+
+    let source = source.trim_start_matches('\u{feff}');
+    let mut output = String::with_capacity(source.len() + 4);
+    let mut inserted = false;
+    let mut can_have_directives = true;
+    let mut has_directives = false;
+    for line in source.split_inclusive('\n') {
+        if can_have_directives {
+            if line.starts_with('%') {
+                has_directives = true;
+            } else if !line.starts_with('#') && !line.trim().is_empty() {
+                if has_directives && !is_marker(line, "---") {
+                    output.push_str("---\n");
+                    inserted = true;
+                }
+                can_have_directives = false;
+                has_directives = false;
+            }
+        }
+        if is_marker(line, "...") {
+            can_have_directives = true;
+        }
+        output.push_str(line);
+    }
+    if has_directives {
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str("---\n");
+        inserted = true;
+    }
+    inserted.then_some(output)
+}
+
+fn reparse_with_edits(
+    source: &str,
+    mut edits: Vec<(TextRange, String)>,
+    template_syntax: TemplateSyntax,
+) -> Option<Root> {
+    let mut source = source.to_owned();
+    edits.sort_by_key(|(range, _)| range.start());
+    edits.iter().rev().for_each(|(range, replacement)| {
+        source.replace_range(
+            usize::from(range.start())..usize::from(range.end()),
+            replacement,
+        );
+    });
+    yaml_parser::parse_with_template_syntax(&source, template_syntax)
+        .ok()
+        .and_then(Root::cast)
+}
+
+/// Change indentation of lines except the first line from `old` to `new`.
+fn reindent(text: &str, old: usize, new: usize) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let leading = line.len() - line.trim_start_matches(' ').len();
+            if i == 0 {
+                line.to_owned()
+            } else if leading >= old {
+                format!("{}{}", " ".repeat(new), &line[old..])
+            } else if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(new), line.trim_start())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn column(source: &str, offset: usize) -> usize {
+    source[..offset]
+        .rsplit('\n')
+        .next()
+        .map(|line| line.chars().count())
+        .unwrap_or_default()
+}
+
+/// Anchor definition or alias which refers to an anchor.
+enum Reference {
+    Anchor(AnchorProperty),
+    Alias(Alias, Option<AnchorProperty>),
+}
+impl Reference {
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Reference::Anchor(anchor) => anchor.syntax(),
+            Reference::Alias(alias, _) => alias.syntax(),
+        }
+    }
+}
+
+/// Collect anchors and aliases in source order.
+/// Each alias refers to the closest anchor with the same name that comes before it.
+fn resolve_references(document: &Document) -> Vec<Reference> {
+    let table = AnchorTable::for_document(document);
+    document
+        .syntax()
+        .descendants()
+        .filter_map(|node| {
+            if let Some(anchor) = AnchorProperty::cast(node.clone()) {
+                Some(Reference::Anchor(anchor))
+            } else {
+                Alias::cast(node).map(|alias| {
+                    let anchor = table.anchor_of(&alias).cloned();
+                    Reference::Alias(alias, anchor)
+                })
+            }
+        })
+        .collect()
+}
+
+/// Get the text of key of map entry if it's a scalar without properties.
+pub(crate) fn entry_key_text(entry: &SyntaxNode) -> Option<String> {
+    entry_key_flow(entry).and_then(|flow| flow.scalar_value().map(Cow::into_owned))
+}
+
+/// Get the key of map entry if it's a scalar without properties.
+fn entry_key_flow(entry: &SyntaxNode) -> Option<Flow> {
+    let flow = entry
+        .children()
+        .find(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+            )
+        })?
+        .children()
+        .find_map(Flow::cast)?;
+    if flow.properties().is_some() {
+        return None;
+    }
+    Some(flow)
+}
+
+/// Check if the plain scalar is resolved to a non-string value like `null`, `true` or `1`
+/// in YAML core schema, so it's different from the quoted one with the same text.
+pub(crate) fn is_non_string_plain(text: &str) -> bool {
+    matches!(
+        text,
+        "" | "~"
+            | "null"
+            | "Null"
+            | "NULL"
+            | "true"
+            | "True"
+            | "TRUE"
+            | "false"
+            | "False"
+            | "FALSE"
+            | ".inf"
+            | ".Inf"
+            | ".INF"
+            | "+.inf"
+            | "+.Inf"
+            | "+.INF"
+            | "-.inf"
+            | "-.Inf"
+            | "-.INF"
+            | ".nan"
+            | ".NaN"
+            | ".NAN"
+    ) || text
+        .strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        || text
+            .strip_prefix("0o")
+            .is_some_and(|oct| !oct.is_empty() && oct.bytes().all(|b| matches!(b, b'0'..=b'7')))
+        || text
+            .trim_start_matches(['+', '-'])
+            .starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && text.parse::<f64>().is_ok()
+}
+
+/// Check if the plain scalar that is a string in YAML 1.2 is resolved to a non-string value
+/// in YAML 1.1, such as booleans like `no` and `on`,
+/// or numbers like `1_000`, `0b101` and `12:30`.
+pub(crate) fn is_yaml11_non_string_plain(text: &str) -> bool {
+    if matches!(
+        text,
+        "y" | "Y"
+            | "yes"
+            | "Yes"
+            | "YES"
+            | "n"
+            | "N"
+            | "no"
+            | "No"
+            | "NO"
+            | "on"
+            | "On"
+            | "ON"
+            | "off"
+            | "Off"
+            | "OFF"
+    ) {
+        return true;
+    }
+    let number = text.trim_start_matches(['+', '-']);
+    if !number.starts_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+    number
+        .strip_prefix("0b")
+        .is_some_and(|bin| !bin.is_empty() && bin.bytes().all(|b| matches!(b, b'0' | b'1' | b'_')))
+        || number.contains('_') && number.replace('_', "").parse::<f64>().is_ok()
+        // sexagesimal numbers
+        || number.contains(':')
+            && number.split(':').all(|part| {
+                !part.is_empty()
+                    && part
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || matches!(b, b'_' | b'.'))
+            })
+}
@@ -0,0 +1,117 @@
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+use yaml_parser::{ast::Flow, SyntaxFactory};
+#[cfg(feature = "serde_json")]
+use yaml_parser::{
+    ast::{AstNode, Root},
+    SyntaxNode,
+};
+
+#[cfg(feature = "serde_json")]
+pub(crate) fn build_root(value: &Value) -> Root {
+    SyntaxFactory::root([SyntaxFactory::document(build_node(value))])
+}
+
+#[cfg(feature = "serde_json")]
+fn build_node(value: &Value) -> SyntaxNode {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            SyntaxFactory::block_seq(items.iter().map(build_node))
+                .syntax()
+                .clone()
+        }
+        Value::Object(entries) if !entries.is_empty() => SyntaxFactory::block_map(
+            entries
+                .iter()
+                .map(|(key, value)| (build_string(key), build_node(value))),
+        )
+        .syntax()
+        .clone(),
+        Value::Array(..) => SyntaxFactory::flow_seq([]).syntax().clone(),
+        Value::Object(..) => SyntaxFactory::flow_map([]).syntax().clone(),
+        Value::Null => SyntaxFactory::plain_scalar("null").syntax().clone(),
+        Value::Bool(bool) => SyntaxFactory::plain_scalar(if *bool { "true" } else { "false" })
+            .syntax()
+            .clone(),
+        Value::Number(number) => SyntaxFactory::plain_scalar(&number.to_string())
+            .syntax()
+            .clone(),
+        Value::String(string) => build_string(string).syntax().clone(),
+    }
+}
+
+/// Build plain scalar if it's safe, otherwise double quoted scalar.
+pub(crate) fn build_string(string: &str) -> Flow {
+    if can_be_plain(string) {
+        SyntaxFactory::plain_scalar(string)
+    } else {
+        SyntaxFactory::double_quoted_scalar(string)
+    }
+}
+
+/// Check if string can be plain scalar without changing its content or type.
+fn can_be_plain(string: &str) -> bool {
+    let Some(first) = string.chars().next() else {
+        return false;
+    };
+    let second = string.chars().nth(1);
+    string.trim() == string
+        && !string.contains(|c: char| c.is_control())
+        && !string.contains(": ")
+        && !string.contains(" #")
+        && !string.ends_with(':')
+        && !string.starts_with("---")
+        && !string.starts_with("...")
+        && (!matches!(
+            first,
+            '-' | '?'
+                | ':'
+                | ','
+                | '['
+                | ']'
+                | '{'
+                | '}'
+                | '#'
+                | '&'
+                | '*'
+                | '!'
+                | '|'
+                | '>'
+                | '\''
+                | '"'
+                | '%'
+                | '@'
+                | '`'
+        ) || matches!(first, '-' | '?' | ':')
+            && second
+                .is_some_and(|c| !c.is_whitespace() && !matches!(c, ',' | '[' | ']' | '{' | '}')))
+        && !looks_like_other_type(string)
+}
+
+/// Check if plain scalar will be resolved as non-string value,
+/// including values of YAML 1.1 such as `yes` or `off`.
+fn looks_like_other_type(string: &str) -> bool {
+    matches!(
+        &*string.to_ascii_lowercase(),
+        "~" | "null"
+            | "true"
+            | "false"
+            | "yes"
+            | "no"
+            | "on"
+            | "off"
+            | "y"
+            | "n"
+            | ".inf"
+            | "+.inf"
+            | "-.inf"
+            | ".nan"
+    ) || string.parse::<f64>().is_ok()
+        || string
+            .trim_start_matches(['+', '-', '.'])
+            .starts_with(|c: char| c.is_ascii_digit())
+            && string.chars().all(|c| {
+                c.is_ascii_hexdigit()
+                    || matches!(c, 'x' | 'X' | 'o' | 'O' | '_' | '.' | ':' | '+' | '-')
+            })
+}
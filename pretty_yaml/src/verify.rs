@@ -0,0 +1,245 @@
+//! Checking whether two YAML inputs have the same meaning.
+
+use crate::{convert::canonical_plain, transform::is_non_string_plain};
+use yaml_parser::{
+    ast::{AstNode, Block, Flow, Properties, Root},
+    resolve::AnchorTable,
+    SyntaxError, SyntaxKind, SyntaxNode,
+};
+
+/// Check if two YAML inputs have the same meaning,
+/// which is useful for asserting that formatting doesn't change anything except style.
+///
+/// Inputs are compared by their resolved node trees:
+///
+/// - Comments, whitespaces and styles of scalars and collections are ignored.
+/// - Scalars are compared by their values after line folding and escaping,
+///   and plain scalars that aren't strings are compared by their values in YAML 1.2 core schema,
+///   so `1.50` and `1.5` are same while `1` and `"1"` are different.
+///   Scalars with explicit tags of core schema like `!!null` or `!!float` are resolved in the same way,
+///   so `!!null ~` and `null` are same.
+/// - Entries of maps are compared as multisets, so order of keys doesn't matter.
+/// - Aliases are replaced with their anchored nodes, so names of anchors don't matter.
+/// - Tags must be same, while documents are compared in order.
+///
+/// ```
+/// use pretty_yaml::verify::equivalent;
+///
+/// assert!(equivalent("b: 'x'\na: [1.50, ~]\n", "a:\n  - 1.5\n  - null\nb: x\n").unwrap());
+/// assert!(!equivalent("a: 1\n", "a: \"1\"\n").unwrap());
+/// ```
+pub fn equivalent(before: &str, after: &str) -> Result<bool, SyntaxError> {
+    let before = Root::cast(yaml_parser::parse(before)?).expect("expected root node");
+    let after = Root::cast(yaml_parser::parse(after)?).expect("expected root node");
+    Ok(resolve_root(&before) == resolve_root(&after))
+}
+
+/// Resolved node with its tag.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Value {
+    /// Scalar of core schema that isn't a string, with its tag name and canonical value.
+    Core(&'static str, String),
+    Str(Option<String>, String),
+    Seq(Option<String>, Vec<Value>),
+    /// Entries are sorted, so maps are compared as multisets of entries.
+    Map(Option<String>, Vec<(Value, Value)>),
+    /// Alias that refers to the node that contains itself.
+    Recursive,
+}
+
+fn resolve_root(root: &Root) -> Vec<Value> {
+    let mut resolver = Resolver {
+        anchors: AnchorTable::new(root),
+        expanding: vec![],
+    };
+    root.documents()
+        .map(|document| {
+            resolver.resolve(
+                document
+                    .block()
+                    .map(|block| block.syntax().clone())
+                    .or_else(|| document.flow().map(|flow| flow.syntax().clone())),
+            )
+        })
+        .collect()
+}
+
+struct Resolver {
+    anchors: AnchorTable,
+    /// Anchored nodes that are being expanded by aliases.
+    expanding: Vec<SyntaxNode>,
+}
+
+impl Resolver {
+    fn resolve(&mut self, node: Option<SyntaxNode>) -> Value {
+        if let Some(block) = node.clone().and_then(Block::cast) {
+            self.resolve_block(&block)
+        } else if let Some(flow) = node.and_then(Flow::cast) {
+            self.resolve_flow(&flow)
+        } else {
+            scalar(None, "", true)
+        }
+    }
+
+    fn resolve_block(&mut self, block: &Block) -> Value {
+        let tag = tag(block.properties());
+        if let Some(block_map) = block.block_map() {
+            let entries = block_map
+                .entries()
+                .map(|entry| {
+                    let key = self.resolve(entry.key().and_then(|key| child_of(key.syntax())));
+                    let value =
+                        self.resolve(entry.value().and_then(|value| child_of(value.syntax())));
+                    (key, value)
+                })
+                .collect();
+            map(tag, entries)
+        } else if let Some(block_seq) = block.block_seq() {
+            let items = block_seq
+                .entries()
+                .map(|entry| self.resolve(child_of(entry.syntax())))
+                .collect();
+            Value::Seq(tag, items)
+        } else if let Some(block_scalar) = block.block_scalar() {
+            Value::Str(tag, block_scalar.value().unwrap_or_default())
+        } else {
+            scalar(tag, "", true)
+        }
+    }
+
+    fn resolve_flow(&mut self, flow: &Flow) -> Value {
+        let tag = tag(flow.properties());
+        if let Some(alias) = flow.alias() {
+            let Some(target) = self.anchors.resolve_alias(&alias) else {
+                // undefined aliases are compared by their names
+                return Value::Str(Some("*".into()), alias.syntax().to_string());
+            };
+            if self.expanding.contains(&target)
+                || alias
+                    .syntax()
+                    .ancestors()
+                    .any(|ancestor| ancestor == target)
+            {
+                return Value::Recursive;
+            }
+            self.expanding.push(target.clone());
+            let value = self.resolve(Some(target));
+            self.expanding.pop();
+            value
+        } else if let Some(flow_map) = flow.flow_map() {
+            let entries = flow_map
+                .entries()
+                .into_iter()
+                .flat_map(|entries| entries.entries())
+                .map(|entry| self.resolve_pair(entry.syntax()))
+                .collect();
+            map(tag, entries)
+        } else if let Some(flow_seq) = flow.flow_seq() {
+            let items = flow_seq
+                .entries()
+                .into_iter()
+                .flat_map(|entries| entries.entries())
+                .map(|entry| {
+                    if let Some(pair) = entry.flow_pair() {
+                        // single pair like `[a: b]` is a map
+                        let entry = self.resolve_pair(pair.syntax());
+                        map(None, vec![entry])
+                    } else {
+                        self.resolve(entry.flow().map(|flow| flow.syntax().clone()))
+                    }
+                })
+                .collect();
+            Value::Seq(tag, items)
+        } else if let Some(text) = flow.scalar_value() {
+            scalar(tag, &text, flow.plain_scalar().is_some())
+        } else {
+            scalar(tag, "", true)
+        }
+    }
+
+    fn resolve_pair(&mut self, pair: &SyntaxNode) -> (Value, Value) {
+        let key = pair
+            .children()
+            .find(|child| child.kind() == SyntaxKind::FLOW_MAP_KEY)
+            .and_then(|key| child_of(&key));
+        let value = pair
+            .children()
+            .find(|child| child.kind() == SyntaxKind::FLOW_MAP_VALUE)
+            .and_then(|value| child_of(&value));
+        (self.resolve(key), self.resolve(value))
+    }
+}
+
+/// Resolve scalar by its tag, or by its content if it's plain and untagged.
+/// Scalars that don't match their core schema tags are compared as tagged strings.
+fn scalar(tag: Option<String>, text: &str, is_plain: bool) -> Value {
+    let expected = match tag.as_deref().map(core_tag_name) {
+        None if is_plain && is_non_string_plain(text) => None,
+        Some(Some(name)) => Some(name),
+        _ => return Value::Str(tag, text.into()),
+    };
+    match (expected, core_value(text)) {
+        (None, Some((name, value))) => Value::Core(name, value),
+        // numbers that are out of range can't be canonicalized
+        (None, None) => Value::Core("float", text.to_ascii_lowercase()),
+        (Some(expected), Some((name, value))) if expected == name => Value::Core(name, value),
+        // integers are also valid floats
+        (Some("float"), Some(("int", value))) => match value.parse::<f64>() {
+            Ok(number) => Value::Core("float", format!("{number:?}")),
+            Err(_) => Value::Str(tag, text.into()),
+        },
+        _ => Value::Str(tag, text.into()),
+    }
+}
+
+/// Get tag name and canonical value of scalar that isn't a string in core schema.
+fn core_value(text: &str) -> Option<(&'static str, String)> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => Some(("null", "null".into())),
+        "true" | "True" | "TRUE" => Some(("bool", "true".into())),
+        "false" | "False" | "FALSE" => Some(("bool", "false".into())),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => Some(("float", ".inf".into())),
+        "-.inf" | "-.Inf" | "-.INF" => Some(("float", "-.inf".into())),
+        ".nan" | ".NaN" | ".NAN" => Some(("float", ".nan".into())),
+        _ => canonical_plain(text).map(|value| {
+            if value
+                .trim_start_matches('-')
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+            {
+                ("int", value)
+            } else {
+                ("float", value)
+            }
+        }),
+    }
+}
+
+/// Get name of core schema tag that isn't string or collection,
+/// like `null` of `!!null` or `!<tag:yaml.org,2002:null>`.
+fn core_tag_name(tag: &str) -> Option<&'static str> {
+    let name = tag.strip_prefix("!!").or_else(|| {
+        tag.strip_prefix("!<tag:yaml.org,2002:")
+            .and_then(|name| name.strip_suffix('>'))
+    })?;
+    ["null", "bool", "int", "float"]
+        .into_iter()
+        .find(|core| *core == name)
+}
+
+fn map(tag: Option<String>, mut entries: Vec<(Value, Value)>) -> Value {
+    entries.sort();
+    Value::Map(tag, entries)
+}
+
+/// Get the block or flow node in map key, map value or sequence entry.
+fn child_of(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.children()
+        .find(|child| matches!(child.kind(), SyntaxKind::BLOCK | SyntaxKind::FLOW))
+}
+
+fn tag(properties: Option<Properties>) -> Option<String> {
+    properties
+        .and_then(|properties| properties.tag_property())
+        .map(|tag| tag.syntax().to_string())
+}
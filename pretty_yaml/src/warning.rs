@@ -0,0 +1,142 @@
+use crate::{config::FormatOptions, printer::has_document_marker_word};
+use std::{fmt, ops::Range};
+use yaml_parser::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Warning reported when formatter changed or skipped something
+/// that users may want to know about.
+pub struct FormatWarning {
+    kind: FormatWarningKind,
+    range: Range<usize>,
+    message: String,
+}
+
+impl FormatWarning {
+    pub(crate) fn new(kind: FormatWarningKind, range: Range<usize>, message: String) -> Self {
+        Self {
+            kind,
+            range,
+            message,
+        }
+    }
+
+    #[inline]
+    /// Kind of this warning.
+    pub fn kind(&self) -> &FormatWarningKind {
+        &self.kind
+    }
+
+    #[inline]
+    /// Byte range in the source input that this warning refers to.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    #[inline]
+    /// Human readable message of this warning.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for FormatWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatWarningKind {
+    /// A map entry was removed because its key duplicates another entry's key.
+    DuplicateKeyRemoved,
+    /// An alias was kept because it can't be replaced with its anchored node.
+    AliasNotInlined,
+    /// A map entry has the same key as a previous entry in the same map,
+    /// and it's kept because `dedupeKeys` isn't enabled.
+    DuplicateKey,
+    /// Indentation contains tab characters, which isn't allowed by YAML spec.
+    /// They're replaced with spaces in formatted output, unless `indentKind` is `tab`.
+    TabIndentation,
+    /// A long plain scalar was kept as-is with `wrapPlainScalars` enabled,
+    /// because wrapping it would change its meaning.
+    PlainScalarNotWrapped,
+}
+
+/// Check source tree for things that are accepted by parser but may be unexpected,
+/// and whether options can't take effect without changing meaning.
+pub(crate) fn check(root: &SyntaxNode, options: &FormatOptions, warnings: &mut Vec<FormatWarning>) {
+    root.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .for_each(|token| match token.kind() {
+            SyntaxKind::WHITESPACE => check_tab_indentation(&token, warnings),
+            SyntaxKind::PLAIN_SCALAR if options.language.wrap_plain_scalars => {
+                check_unwrappable_plain_scalar(&token, options, warnings)
+            }
+            _ => {}
+        });
+}
+
+fn check_tab_indentation(token: &SyntaxToken, warnings: &mut Vec<FormatWarning>) {
+    let text = token.text();
+    // most whitespaces don't have tabs, so checking it first avoids visiting sibling tokens
+    if !text.contains('\t') {
+        return;
+    }
+    let at_line_start = token
+        .prev_token()
+        .is_none_or(|prev| prev.text().ends_with(['\n', '\r']));
+    // only whitespaces before content are indentation, and tabs before comments are allowed
+    let Some(indent_start) = text
+        .rfind(['\n', '\r'])
+        .map(|index| index + 1)
+        .or(at_line_start.then_some(0))
+    else {
+        return;
+    };
+    if text[indent_start..].contains('\t')
+        && token
+            .next_token()
+            .is_some_and(|next| next.kind() != SyntaxKind::COMMENT)
+    {
+        let start = usize::from(token.text_range().start());
+        warnings.push(FormatWarning::new(
+            FormatWarningKind::TabIndentation,
+            start + indent_start..start + text.len(),
+            "found tab character in indentation".into(),
+        ));
+    }
+}
+
+fn check_unwrappable_plain_scalar(
+    token: &SyntaxToken,
+    options: &FormatOptions,
+    warnings: &mut Vec<FormatWarning>,
+) {
+    let Some(flow) = token.parent() else {
+        return;
+    };
+    let text = token.text();
+    let reason = if has_document_marker_word(&flow) {
+        "it has words that would become document markers"
+    } else if flow.parent().is_some_and(|parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+        ) && parent
+            .children_with_tokens()
+            .all(|element| element.kind() != SyntaxKind::QUESTION_MARK)
+    }) && text.contains(' ')
+        && text.chars().count() > options.layout.print_width
+    {
+        "implicit key can't span multiple lines"
+    } else {
+        return;
+    };
+    let range = token.text_range();
+    warnings.push(FormatWarning::new(
+        FormatWarningKind::PlainScalarNotWrapped,
+        range.start().into()..range.end().into(),
+        format!("plain scalar isn't wrapped because {reason}"),
+    ));
+}
@@ -0,0 +1,81 @@
+use pretty_yaml::{
+    config::{FormatOptions, ScalarQuoting},
+    format_text_with, PathSegment, ScalarStyle,
+};
+use std::cell::RefCell;
+
+fn key(key: &str) -> PathSegment {
+    PathSegment::Key(key.into())
+}
+
+#[test]
+fn key_paths() {
+    let paths = RefCell::new(vec![]);
+    let classifier = |path: &[PathSegment]| {
+        paths.borrow_mut().push(path.to_vec());
+        ScalarStyle::default()
+    };
+    let input = "a: {b: c, d: [e, f: g]}\nh:\n  - i\n  - j\n\"k\": l\n? [m]\n: n\n---\no\n";
+    format_text_with(input, &FormatOptions::default(), &classifier).unwrap();
+    assert_eq!(
+        paths.into_inner(),
+        vec![
+            vec![key("a"), key("b")],
+            vec![key("a"), key("d"), PathSegment::Index(0)],
+            vec![key("a"), key("d"), PathSegment::Index(1), key("f")],
+            vec![key("h"), PathSegment::Index(0)],
+            vec![key("h"), PathSegment::Index(1)],
+            vec![key("k")],
+            vec![key("[m]")],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn override_quoting() {
+    let mut options = FormatOptions::default();
+    options.language.scalar_quoting = ScalarQuoting::UnquoteWhenSafe;
+    let classifier = |path: &[PathSegment]| match path.first() {
+        Some(PathSegment::Key(key)) if key == "env" => ScalarStyle {
+            quoting: Some(ScalarQuoting::QuoteAll),
+            ..Default::default()
+        },
+        Some(PathSegment::Key(key)) if key == "keep" => ScalarStyle {
+            quoting: Some(ScalarQuoting::Keep),
+            ..Default::default()
+        },
+        _ => ScalarStyle::default(),
+    };
+    let input = "env:\n  A: a\n  B: [b, 1]\nkeep: 'c'\nother: 'd'\n";
+    assert_eq!(
+        format_text_with(input, &options, &classifier).unwrap(),
+        "env:\n  A: \"a\"\n  B: [\"b\", 1]\nkeep: \"c\"\nother: d\n"
+    );
+}
+
+#[test]
+fn keep_lines() {
+    let mut options = FormatOptions::default();
+    options.layout.print_width = 20;
+    options.language.wrap_plain_scalars = true;
+    options.language.reflow_folded_scalars = true;
+    let classifier = |path: &[PathSegment]| ScalarStyle {
+        keep_lines: path
+            .first()
+            .is_some_and(|segment| *segment == key("description")),
+        ..Default::default()
+    };
+    let input = "description: aaaa bbbb cccc dddd eeee\n  ffff\nfolded: >\n  aaaa bbbb cccc dddd eeee\n  ffff\nsummary: aaaa bbbb cccc dddd eeee\n";
+    assert_eq!(
+        format_text_with(input, &options, &classifier).unwrap(),
+        "description: aaaa bbbb cccc dddd eeee
+  ffff
+folded: >
+  aaaa bbbb cccc
+  dddd eeee ffff
+summary: aaaa bbbb
+  cccc dddd eeee
+"
+    );
+}
@@ -0,0 +1,168 @@
+use pretty_yaml::{
+    config::FormatOptions,
+    convert::{from_json, to_json, ConvertError},
+};
+use serde_json::{json, Value};
+use yaml_parser::ast::{AstNode, Root};
+
+fn convert(input: &str) -> Result<String, ConvertError> {
+    let root = Root::cast(yaml_parser::parse(input).unwrap()).unwrap();
+    to_json(&root)
+}
+
+fn convert_value(input: &str) -> Value {
+    serde_json::from_str(&convert(input).unwrap()).unwrap()
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(
+        convert_value(
+            "a: ~\nb: True\nc: 0x1F\nd: 0o17\ne: +012\nf: 1.50\ng: 1e3\nh: '1'\ni: \"a\\tb\"\nj: no\nk:\nl: !!str 1\nm: !!int '2'\nn: ! 3\n"
+        ),
+        json!({
+            "a": null,
+            "b": true,
+            "c": 31,
+            "d": 15,
+            "e": 12,
+            "f": 1.5,
+            "g": 1000.0,
+            "h": "1",
+            "i": "a\tb",
+            "j": "no",
+            "k": null,
+            "l": "1",
+            "m": 2,
+            "n": "3",
+        })
+    );
+}
+
+#[test]
+fn block_scalars() {
+    assert_eq!(
+        convert_value(
+            "literal: |\n  a\n    b\n\n  c\nfolded: >\n  a\n  b\n\n  c\n    d\n  e\nstrip: |-\n  a\n\nkeep: |+\n  a\n\nindicator: |2\n    a\n"
+        ),
+        json!({
+            "literal": "a\n  b\n\nc\n",
+            "folded": "a b\nc\n  d\ne\n",
+            "strip": "a",
+            "keep": "a\n\n",
+            "indicator": "  a\n",
+        })
+    );
+}
+
+#[test]
+fn collections_and_keys() {
+    assert_eq!(
+        convert(
+            "- [a, b: c, {d: e}]\n- {}\n- 1: one\n  null: none\n  \"q\\\"\": quoted\n- !!seq\n"
+        )
+        .unwrap(),
+        r#"[
+  [
+    "a",
+    {
+      "b": "c"
+    },
+    {
+      "d": "e"
+    }
+  ],
+  {},
+  {
+    "1": "one",
+    "null": "none",
+    "q\"": "quoted"
+  },
+  []
+]
+"#
+    );
+}
+
+#[test]
+fn aliases() {
+    assert_eq!(
+        convert_value("a: &a\n  b: &b [1]\nc: *a\nd: *b\ne: &k key\n*k : v\n"),
+        json!({"a": {"b": [1]}, "c": {"b": [1]}, "d": [1], "e": "key", "key": "v"}),
+    );
+}
+
+#[test]
+fn multiple_documents() {
+    assert_eq!(
+        convert("a: 1\n---\n- b\n---\n").unwrap(),
+        "{\n  \"a\": 1\n}\n[\n  \"b\"\n]\nnull\n"
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(convert("a: *x\n"), Err(ConvertError::UndefinedAlias(3..5)));
+    assert_eq!(
+        convert("a: &a\n  b: *a\n"),
+        Err(ConvertError::RecursiveAlias(11..13))
+    );
+    assert_eq!(convert("[a]: 1\n"), Err(ConvertError::ComplexKey(0..3)));
+    assert_eq!(
+        convert("a: !custom 1\n"),
+        Err(ConvertError::UnsupportedTag(3..10))
+    );
+    assert_eq!(
+        convert("a: .inf\n"),
+        Err(ConvertError::NonFiniteNumber(3..7))
+    );
+}
+
+#[test]
+fn json_to_yaml() {
+    let input = r#"{
+  "name": "app",
+  "version": "1.0",
+  "enabled": true,
+  "nothing": null,
+  "empty": {"list": [], "map": {}},
+  "quoted": ["yes", "123", "", "a: b", "line\nbreak", "- item"],
+  "nested": [{"key": "value", "list": [1, [2, 3]]}, [{"a": 1.5e3}]]
+}"#;
+    similar_asserts::assert_eq!(
+        from_json(input, &FormatOptions::default()).unwrap(),
+        r#"name: app
+version: "1.0"
+enabled: true
+nothing: null
+empty:
+  list: []
+  map: {}
+quoted:
+  - "yes"
+  - "123"
+  - ""
+  - "a: b"
+  - "line\nbreak"
+  - "- item"
+nested:
+  - key: value
+    list:
+      - 1
+      - - 2
+        - 3
+  - - a: 1.5e3
+"#
+    );
+}
+
+#[test]
+fn json_to_yaml_round_trip() {
+    let input = r#"[{"a": "b\tc", "d": [true, -0.5, "null"]}, "x"]"#;
+    let yaml = from_json(input, &FormatOptions::default()).unwrap();
+    let root = Root::cast(yaml_parser::parse(&yaml).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(&to_json(&root).unwrap()).unwrap(),
+        serde_json::from_str::<Value>(input).unwrap(),
+    );
+}
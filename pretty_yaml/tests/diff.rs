@@ -0,0 +1,79 @@
+use pretty_yaml::{
+    config::FormatOptions,
+    diff::{DiffLine, TextDiff},
+    format_diff,
+};
+
+#[test]
+fn formatted() {
+    assert!(format_diff("a: 1\n", &FormatOptions::default())
+        .unwrap()
+        .is_none());
+    assert!(format_diff("a: [", &FormatOptions::default()).is_err());
+}
+
+#[test]
+fn separated_hunks() {
+    let old = (1..=20).map(|i| format!("k{i}: {i}\n")).collect::<String>();
+    let new = old.replace("k2: 2\n", "k2:   2\n").replace("k18: 18\n", "");
+    let diff = TextDiff::new(&old, &new, 3);
+    assert_eq!(diff.hunks().len(), 2);
+    assert_eq!(
+        diff.to_string(),
+        "@@ -1,5 +1,5 @@
+ k1: 1
+-k2: 2
++k2:   2
+ k3: 3
+ k4: 4
+ k5: 5
+@@ -15,6 +15,5 @@
+ k15: 15
+ k16: 16
+ k17: 17
+-k18: 18
+ k19: 19
+ k20: 20
+"
+    );
+}
+
+#[test]
+fn merged_hunks() {
+    let diff = TextDiff::new("a\nb\nc\nd\ne\n", "a\nB\nc\nd\nE\n", 1);
+    assert_eq!(diff.hunks().len(), 1);
+    assert_eq!(
+        diff.to_string(),
+        "@@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n-e\n+E\n"
+    );
+}
+
+#[test]
+fn insertion_only() {
+    let diff = TextDiff::new("a\n", "a\nb\n", 0);
+    assert_eq!(diff.to_string(), "@@ -1,0 +2 @@\n+b\n");
+    let hunk = &diff.hunks()[0];
+    assert_eq!(hunk.lines, [DiffLine::Insert("b\n".into())]);
+}
+
+#[test]
+fn missing_final_newline() {
+    let diff = TextDiff::new("a:   1", "a: 1\n", 3);
+    assert_eq!(
+        diff.to_string(),
+        "@@ -1 +1 @@\n-a:   1\n\\ No newline at end of file\n+a: 1\n"
+    );
+}
+
+#[test]
+fn crlf() {
+    let diff = TextDiff::new("a:  1\r\n", "a: 1\r\n", 3);
+    assert_eq!(diff.to_string(), "@@ -1 +1 @@\n-a:  1\n+a: 1\n");
+}
+
+#[test]
+fn identical() {
+    let diff = TextDiff::new("a\nb\n", "a\nb\n", 3);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "");
+}
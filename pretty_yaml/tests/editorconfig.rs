@@ -0,0 +1,109 @@
+#![cfg(feature = "editorconfig")]
+
+use pretty_yaml::{
+    config::{IndentKind, LayoutOptions, LineBreak},
+    editorconfig::EditorConfig,
+};
+use std::{fs, path::Path};
+
+fn layout(config: &EditorConfig) -> LayoutOptions {
+    let mut layout = LayoutOptions::default();
+    config.apply(&mut layout, &[]);
+    layout
+}
+
+#[test]
+fn properties() {
+    let config = EditorConfig::parse(
+        "root = true
+[*]
+indent_style = tab
+indent_size = 4
+end_of_line = crlf
+max_line_length = 120
+insert_final_newline = false
+",
+        Path::new("a.yaml"),
+    );
+    assert!(config.root);
+    let layout = layout(&config);
+    assert!(matches!(layout.indent_kind, IndentKind::Tab));
+    assert_eq!(layout.indent_width, 4);
+    assert!(matches!(layout.line_break, LineBreak::Crlf));
+    assert_eq!(layout.print_width, 120);
+    assert!(!layout.final_newline);
+}
+
+#[test]
+fn sections() {
+    let content = "
+[*]
+indent_size = 4
+max_line_length = 100
+
+[*.{yml,yaml}]
+indent_size = 2
+
+[ci/**.yml]
+max_line_length = off
+
+[{Makefile,*.md}]
+indent_size = 8
+
+[file[0-9].yaml]
+indent_size = unset
+tab_width = 3
+";
+    let parse = |path: &str| EditorConfig::parse(content, Path::new(path));
+    assert_eq!(parse("a.yaml").indent_width, Some(2));
+    assert_eq!(parse("a.yaml").print_width, Some(100));
+    assert_eq!(parse("a.json").indent_width, Some(4));
+    assert_eq!(parse("dir/sub/a.yml").indent_width, Some(2));
+    assert_eq!(parse("ci/deep/a.yml").print_width, None);
+    assert_eq!(parse("other/ci/a.yml").print_width, Some(100));
+    assert_eq!(parse("README.md").indent_width, Some(8));
+    assert_eq!(parse("file1.yaml").indent_width, Some(3));
+    assert_eq!(parse("file1.yaml").tab_width, Some(3));
+    assert_eq!(parse("a.yaml").tab_width, None);
+    assert_eq!(parse("fileA.yaml").indent_width, Some(2));
+}
+
+#[test]
+fn explicit_options() {
+    let config = EditorConfig::parse(
+        "[*]\nindent_size = 4\nmax_line_length = 100\n",
+        Path::new("a.yaml"),
+    );
+    let mut layout = LayoutOptions::default();
+    config.apply(&mut layout, &["indentWidth"]);
+    assert_eq!(layout.indent_width, 2);
+    assert_eq!(layout.print_width, 100);
+}
+
+#[test]
+fn nested_files() {
+    let dir = std::env::temp_dir().join(format!("pretty_yaml_editorconfig_{}", std::process::id()));
+    let sub = dir.join("project").join("sub");
+    fs::create_dir_all(&sub).unwrap();
+    fs::write(
+        dir.join(".editorconfig"),
+        "[*]\nindent_size = 8\nmax_line_length = 60\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("project").join(".editorconfig"),
+        "root = true\n[*]\nindent_size = 4\nend_of_line = crlf\n",
+    )
+    .unwrap();
+    fs::write(sub.join(".editorconfig"), "[*.yaml]\nindent_size = 2\n").unwrap();
+
+    let config = EditorConfig::for_file(&sub.join("a.yaml")).unwrap();
+    assert_eq!(config.indent_width, Some(2));
+    assert!(matches!(config.line_break, Some(LineBreak::Crlf)));
+    // files above root `.editorconfig` are ignored
+    assert_eq!(config.print_width, None);
+    let config = EditorConfig::for_file(&sub.join("a.json")).unwrap();
+    assert_eq!(config.indent_width, Some(4));
+
+    fs::remove_dir_all(dir).unwrap();
+}
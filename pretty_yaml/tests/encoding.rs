@@ -0,0 +1,142 @@
+use pretty_yaml::{
+    config::{FormatOptions, LayoutOptions},
+    encoding::{decode, encode, DecodeError, Encoding},
+    format_bytes, format_documents, format_text, format_text_with_source_map, Formatter,
+};
+
+fn keep_bom() -> FormatOptions {
+    FormatOptions {
+        layout: LayoutOptions {
+            keep_bom: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn detect() {
+    assert_eq!(Encoding::detect(b"a: 1"), Encoding::Utf8);
+    assert_eq!(Encoding::detect(b"\xef\xbb\xbfa: 1"), Encoding::Utf8);
+    assert_eq!(Encoding::detect(b""), Encoding::Utf8);
+    assert_eq!(Encoding::detect(b"\xff\xfea\x00"), Encoding::Utf16Le);
+    assert_eq!(Encoding::detect(b"a\x00:\x00"), Encoding::Utf16Le);
+    assert_eq!(Encoding::detect(b"\xfe\xff\x00a"), Encoding::Utf16Be);
+    assert_eq!(Encoding::detect(b"\x00a\x00:"), Encoding::Utf16Be);
+    assert_eq!(Encoding::detect(b"\xff\xfe\x00\x00"), Encoding::Utf32Le);
+    assert_eq!(Encoding::detect(b"a\x00\x00\x00"), Encoding::Utf32Le);
+    assert_eq!(Encoding::detect(b"\x00\x00\xfe\xff"), Encoding::Utf32Be);
+    assert_eq!(Encoding::detect(b"\x00\x00\x00a"), Encoding::Utf32Be);
+}
+
+#[test]
+fn round_trip() {
+    let text = "\u{feff}key: é 😀\n";
+    [
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Utf32Le,
+        Encoding::Utf32Be,
+    ]
+    .into_iter()
+    .for_each(|encoding| {
+        assert_eq!(
+            decode(&encode(text, encoding)).unwrap(),
+            (text.into(), encoding)
+        );
+    });
+}
+
+#[test]
+fn invalid_bytes() {
+    assert_eq!(
+        decode(b"a: \xff").unwrap_err(),
+        DecodeError {
+            encoding: Encoding::Utf8,
+            offset: 3
+        }
+    );
+    assert_eq!(
+        decode(b"a\x00:\x00\x00\xd8").unwrap_err(),
+        DecodeError {
+            encoding: Encoding::Utf16Le,
+            offset: 4
+        }
+    );
+    assert_eq!(
+        decode(b"a\x00:").unwrap_err(),
+        DecodeError {
+            encoding: Encoding::Utf16Le,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        decode(b"a\x00\x00\x00\x00\x00\x11\x00").unwrap_err(),
+        DecodeError {
+            encoding: Encoding::Utf32Le,
+            offset: 4
+        }
+    );
+}
+
+#[test]
+fn format_text_bom() {
+    let input = "\u{feff}a:   1\n";
+    assert_eq!(
+        format_text(input, &FormatOptions::default()).unwrap(),
+        "a: 1\n"
+    );
+    assert_eq!(format_text(input, &keep_bom()).unwrap(), "\u{feff}a: 1\n");
+    assert_eq!(format_text("a:   1\n", &keep_bom()).unwrap(), "a: 1\n");
+
+    let mut formatter = Formatter::new(keep_bom());
+    assert_eq!(formatter.format(input).unwrap(), "\u{feff}a: 1\n");
+
+    let documents = format_documents("\u{feff}a:   1\n---\nb:   2\n", &keep_bom())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(documents, ["\u{feff}a: 1\n", "---\nb: 2\n"]);
+}
+
+#[test]
+fn source_map_bom() {
+    let input = "\u{feff}a:   1\n";
+    let (output, source_map) = format_text_with_source_map(input, &keep_bom()).unwrap();
+    assert_eq!(output, "\u{feff}a: 1\n");
+    assert_eq!(
+        source_map.map_old_to_new(input.find('1').unwrap()),
+        output.find('1').unwrap()
+    );
+}
+
+#[test]
+fn bytes() {
+    let options = FormatOptions::default();
+    assert_eq!(format_bytes(b"a:   1", &options).unwrap(), b"a: 1\n");
+    assert_eq!(
+        format_bytes(b"\xef\xbb\xbfa:   1", &options).unwrap(),
+        b"a: 1\n"
+    );
+    assert_eq!(
+        format_bytes(b"\xef\xbb\xbfa:   1", &keep_bom()).unwrap(),
+        b"\xef\xbb\xbfa: 1\n"
+    );
+
+    let input = encode("\u{feff}a:   [1,2]\n", Encoding::Utf16Be);
+    assert_eq!(
+        format_bytes(&input, &options).unwrap(),
+        encode("\u{feff}a: [1, 2]\n", Encoding::Utf16Be)
+    );
+    let input = encode("a:   é\n", Encoding::Utf32Le);
+    assert_eq!(
+        format_bytes(&input, &options).unwrap(),
+        encode("a: é\n", Encoding::Utf32Le)
+    );
+
+    assert!(format_bytes(b"a: \xff", &options)
+        .unwrap_err()
+        .to_string()
+        .contains("invalid UTF-8 sequence at byte offset 3"));
+    assert!(format_bytes(&encode("a: [", Encoding::Utf16Le), &options).is_err());
+}
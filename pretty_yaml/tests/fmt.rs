@@ -1,5 +1,8 @@
 use insta::{assert_snapshot, glob, Settings};
-use pretty_yaml::{config::FormatOptions, format_text};
+use pretty_yaml::{
+    config::{BlockScalarStyle, FormatOptions},
+    format_text,
+};
 use std::{collections::HashMap, fs, path::Path};
 
 #[test]
@@ -16,6 +19,7 @@ fn fmt_snapshot() {
         if let Some(options) = options {
             options.into_iter().for_each(|(option_name, options)| {
                 let output = run_format_test(path, &input, &options);
+                run_block_scalar_style_test(path, &input, &options);
                 build_settings(path).bind(|| {
                     let name = path.file_stem().unwrap().to_str().unwrap();
                     assert_snapshot!(format!("{name}.{option_name}"), output);
@@ -23,6 +27,7 @@ fn fmt_snapshot() {
             })
         } else {
             let output = run_format_test(path, &input, &Default::default());
+            run_block_scalar_style_test(path, &input, &Default::default());
             build_settings(path).bind(|| {
                 let name = path.file_stem().unwrap().to_str().unwrap();
                 assert_snapshot!(name, output);
@@ -31,6 +36,7 @@ fn fmt_snapshot() {
     });
 }
 
+#[allow(clippy::needless_borrow)]
 fn run_format_test(path: &Path, input: &str, options: &FormatOptions) -> String {
     let output = format_text(&input, &options)
         .map_err(|err| format!("failed to format '{}': {:?}", path.display(), err))
@@ -61,6 +67,18 @@ fn run_format_test(path: &Path, input: &str, options: &FormatOptions) -> String
     output
 }
 
+/// Converting block scalar style must be stable for all fixtures, not only the dedicated ones.
+fn run_block_scalar_style_test(path: &Path, input: &str, options: &FormatOptions) {
+    if !matches!(options.language.block_scalar_style, BlockScalarStyle::Keep) {
+        return;
+    }
+    for style in [BlockScalarStyle::Literal, BlockScalarStyle::Folded] {
+        let mut options = options.clone();
+        options.language.block_scalar_style = style;
+        run_format_test(path, input, &options);
+    }
+}
+
 fn build_settings(path: &Path) -> Settings {
     let mut settings = Settings::clone_current();
     settings.set_snapshot_path(path.parent().unwrap());
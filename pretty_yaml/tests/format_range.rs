@@ -0,0 +1,105 @@
+use pretty_yaml::{
+    config::{DedupeKeys, FormatOptions, KeyOrdering},
+    format_range, format_text,
+};
+
+fn apply(input: &str, range: std::ops::Range<usize>, options: &FormatOptions) -> String {
+    let result = format_range(input, range, options).unwrap();
+    let mut output = input.to_owned();
+    output.replace_range(result.range, &result.text);
+    output
+}
+
+#[test]
+fn nested_block() {
+    let input = "a:   1\nb:\n  c:   2\n  d:\n    - [ 3 ]\n";
+    let options = FormatOptions::default();
+    let result = format_range(input, 31..32, &options).unwrap();
+    assert_eq!(&input[result.range.clone()], "[ 3 ]");
+    assert_eq!(result.text, "[3]");
+
+    assert_eq!(
+        apply(input, 13..14, &options),
+        "a:   1\nb:\n  c: 2\n  d:\n    - [3]\n"
+    );
+}
+
+#[test]
+fn flow_collection() {
+    let input = "key: [ aaaaaaaaaa, bbbbbbbbbb, cccccccccc ]\nother:   1\n";
+    let mut options = FormatOptions::default();
+    options.layout.print_width = 30;
+    assert_eq!(
+        apply(input, 8..9, &options),
+        "key: [\n  aaaaaaaaaa,\n  bbbbbbbbbb,\n  cccccccccc,\n]\nother:   1\n"
+    );
+}
+
+#[test]
+fn byte_order_mark() {
+    let input = "\u{feff}a:\n  b:   [ 'c',  d ]\n";
+    let result = format_range(input, 11..12, &FormatOptions::default()).unwrap();
+    assert_eq!(result.text, "[\"c\", d]");
+}
+
+#[test]
+fn fallback_to_whole_input() {
+    let input = "a:   1\n---\nb:   2\n";
+    let options = FormatOptions::default();
+    let result = format_range(input, 0..input.len(), &options).unwrap();
+    assert_eq!(result.range, 0..input.len());
+    assert_eq!(result.text, format_text(input, &options).unwrap());
+}
+
+#[test]
+fn syntax_error() {
+    assert!(format_range("a: [", 0..1, &FormatOptions::default()).is_err());
+}
+
+#[test]
+fn tree_transforms() {
+    let input = "b:   [ 2 ]\na:   1\nb:   3\n";
+    let mut options = FormatOptions::default();
+    options.language.key_ordering = KeyOrdering::Asc;
+    options.language.dedupe_keys = Some(DedupeKeys::KeepLast);
+    let result = format_range(input, 7..8, &options).unwrap();
+    assert_eq!(result.range, 0..input.len());
+    assert_eq!(result.text, format_text(input, &options).unwrap());
+    assert_eq!(apply(input, 7..8, &options), "a: 1\nb: 3\n");
+}
+
+#[test]
+fn ignore_file() {
+    let input = "# pretty-yaml-ignore-file\na:   [ 1 ]\n";
+    let result = format_range(input, 5..6, &FormatOptions::default()).unwrap();
+    assert_eq!(result.range, 0..input.len());
+    assert_eq!(result.text, input);
+}
+
+#[test]
+fn ignored_node() {
+    let input =
+        "abc:  !!str #   hello\n   123\n# pretty-yaml-ignore\ndef:  !!str #   hello\n   456\n";
+    let options = FormatOptions::default();
+    assert_eq!(
+        apply(input, 65..74, &options),
+        format_text(input, &options).unwrap()
+    );
+
+    let input =
+        "a:\n  b:   1\n  # pretty-yaml-ignore-start\n  c:   [ 2 ]\n  # pretty-yaml-ignore-end\n";
+    assert_eq!(
+        apply(input, 47..48, &options),
+        "a:\n  b: 1\n  # pretty-yaml-ignore-start\n  c:   [ 2 ]\n  # pretty-yaml-ignore-end\n"
+    );
+}
+
+#[test]
+fn multi_line_scalar() {
+    let input = "a:\n  b:   c\n    d\n  e:   [ 1 ]\n";
+    let options = FormatOptions::default();
+    assert_eq!(
+        apply(input, 10..11, &options),
+        "a:\n  b: c\n    d\n  e: [1]\n"
+    );
+}
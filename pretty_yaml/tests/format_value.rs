@@ -0,0 +1,53 @@
+#![cfg(feature = "serde_json")]
+
+use pretty_yaml::{config::FormatOptions, format_value};
+use serde_json::json;
+
+#[test]
+fn format_json_value() {
+    let value = json!({
+        "name": "app",
+        "version": "1.0",
+        "enabled": true,
+        "count": 3,
+        "ratio": 0.5,
+        "nothing": null,
+        "empty_list": [],
+        "empty_map": {},
+        "quoted": ["yes", "123", "", " padded", "a: b", "line\nbreak", "- item", "--flag"],
+        "nested": [{ "key": "value", "list": [1, [2, 3]] }, [{ "a": 1 }]],
+    });
+    let output = format_value(&value, &FormatOptions::default());
+    similar_asserts::assert_eq!(
+        output,
+        r#"count: 3
+empty_list: []
+empty_map: {}
+enabled: true
+name: app
+nested:
+  - key: value
+    list:
+      - 1
+      - - 2
+        - 3
+  - - a: 1
+nothing: null
+quoted:
+  - "yes"
+  - "123"
+  - ""
+  - " padded"
+  - "a: b"
+  - "line\nbreak"
+  - "- item"
+  - --flag
+ratio: 0.5
+version: "1.0"
+"#
+    );
+    assert_eq!(
+        pretty_yaml::format_text(&output, &FormatOptions::default()).unwrap(),
+        output
+    );
+}
@@ -0,0 +1,70 @@
+use pretty_yaml::{
+    config::{FormatOptions, TemplateSyntax},
+    format_text, Formatter,
+};
+use yaml_parser::{ast::AstNode, Transaction};
+
+#[test]
+fn cached_output() {
+    let mut options = FormatOptions::default();
+    let mut formatter = Formatter::new(options.clone());
+    let input = "a:   [1,2]\n";
+    assert_eq!(formatter.format(input).unwrap(), "a: [1, 2]\n");
+    assert_eq!(formatter.format(input).unwrap(), "a: [1, 2]\n");
+
+    options.language.bracket_spacing = true;
+    formatter.set_options(options.clone());
+    assert_eq!(
+        formatter.format(input).unwrap(),
+        format_text(input, &options).unwrap()
+    );
+
+    assert!(formatter.format("a: [").is_err());
+}
+
+#[test]
+fn capacity() {
+    let mut formatter = Formatter::with_capacity(FormatOptions::default(), 1);
+    assert_eq!(formatter.format("a:  1").unwrap(), "a: 1\n");
+    assert_eq!(formatter.format("b:  2").unwrap(), "b: 2\n");
+    assert_eq!(formatter.format("a:  1").unwrap(), "a: 1\n");
+}
+
+#[test]
+fn insert_tree() {
+    let mut formatter = Formatter::new(FormatOptions::default());
+    let root = formatter.parse("a: 1\n").unwrap();
+    let mut transaction = Transaction::new(root.syntax());
+    transaction.insert(5.into(), "b:  2\n");
+    let (tree, _) = transaction.commit().unwrap();
+    let root = yaml_parser::ast::Root::cast(tree).unwrap();
+    formatter.insert_tree(&root);
+    assert_eq!(formatter.format("a: 1\nb:  2\n").unwrap(), "a: 1\nb: 2\n");
+
+    formatter.invalidate("a: 1\nb:  2\n");
+    assert_eq!(formatter.format("a: 1\nb:  2\n").unwrap(), "a: 1\nb: 2\n");
+}
+
+#[test]
+fn template_syntax() {
+    let mut options = FormatOptions::default();
+    let mut formatter = Formatter::new(options.clone());
+    let input = "a: [{{ x }}]\n";
+    assert_eq!(
+        formatter.format(input).unwrap(),
+        format_text(input, &options).unwrap()
+    );
+
+    options.language.template_syntax = TemplateSyntax::Go;
+    formatter.set_options(options.clone());
+    assert_eq!(formatter.format(input).unwrap(), "a: [{{ x }}]\n");
+}
+
+#[test]
+fn byte_order_mark() {
+    let mut formatter = Formatter::new(FormatOptions::default());
+    let input = "\u{feff}a:   1\n";
+    let root = formatter.parse(input).unwrap();
+    assert_eq!(formatter.parse(input).unwrap().syntax(), root.syntax());
+    assert_eq!(formatter.format(input).unwrap(), "a: 1\n");
+}
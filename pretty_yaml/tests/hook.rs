@@ -0,0 +1,97 @@
+use pretty_yaml::{config::FormatOptions, format_text, format_text_with_hook, Doc};
+use std::cell::RefCell;
+use yaml_parser::{SyntaxKind, SyntaxNode};
+
+fn noop(_: SyntaxKind, _: &SyntaxNode, doc: Doc<'static>) -> Doc<'static> {
+    doc
+}
+
+#[test]
+fn visit_order() {
+    let kinds = RefCell::new(vec![]);
+    let hook = |kind: SyntaxKind, node: &SyntaxNode, doc: Doc<'static>| {
+        assert_eq!(node.kind(), kind);
+        kinds.borrow_mut().push(kind);
+        doc
+    };
+    format_text_with_hook("a: [b]\n", &FormatOptions::default(), &hook).unwrap();
+    let kinds = kinds.into_inner();
+    assert_eq!(kinds.last(), Some(&SyntaxKind::ROOT));
+    let seq = kinds
+        .iter()
+        .position(|kind| *kind == SyntaxKind::FLOW_SEQ)
+        .unwrap();
+    let map = kinds
+        .iter()
+        .position(|kind| *kind == SyntaxKind::BLOCK_MAP)
+        .unwrap();
+    assert!(seq < map);
+}
+
+#[test]
+fn unchanged() {
+    let options = FormatOptions::default();
+    [
+        "# comment\na:   [1,  2]\nb:\n  -   c\n---\nd: |\n  e\n",
+        "%YAML 1.2\n%TAG !e! tag:example.com,2000:\n---\n!e!a &x b:   *y\nf: !!str g\nc: !<tag:d> e\n",
+        "\u{feff}a: 'b'\nc: \"d\\\"e\"\nf: 'g''h\n  i'\nj:   k\n  l\n",
+        "a: >\n  b\n  c\n\n  d\n# pretty-yaml-ignore\ne:   [ f ]\n#   g\n",
+    ]
+    .into_iter()
+    .for_each(|input| {
+        assert_eq!(
+            format_text_with_hook(input, &options, &noop).unwrap(),
+            format_text(input, &options).unwrap()
+        );
+    });
+}
+
+#[test]
+fn wrap_docs() {
+    let hook = |kind: SyntaxKind, node: &SyntaxNode, doc: Doc<'static>| match kind {
+        SyntaxKind::BLOCK_MAP_ENTRY if node.text().to_string().starts_with("secret") => {
+            Doc::text("# managed\n").append(doc)
+        }
+        _ => doc,
+    };
+    let output = format_text_with_hook(
+        "name:   app\nsecret:   x\n",
+        &FormatOptions::default(),
+        &hook,
+    )
+    .unwrap();
+    assert_eq!(output, "name: app\n# managed\nsecret: x\n");
+}
+
+#[test]
+fn replace_docs() {
+    let hook = |kind: SyntaxKind, _: &SyntaxNode, doc: Doc<'static>| match kind {
+        SyntaxKind::ALIAS => Doc::text("*REDACTED"),
+        _ => doc,
+    };
+    let output = format_text_with_hook(
+        "a: &x 1\nb:   *x\nc: [*x]\n",
+        &FormatOptions::default(),
+        &hook,
+    )
+    .unwrap();
+    assert_eq!(output, "a: &x 1\nb: *REDACTED\nc: [*REDACTED]\n");
+}
+
+#[test]
+fn ignored_nodes() {
+    let nodes = RefCell::new(vec![]);
+    let hook = |kind: SyntaxKind, node: &SyntaxNode, doc: Doc<'static>| {
+        if kind == SyntaxKind::FLOW_SEQ {
+            nodes.borrow_mut().push(node.to_string());
+        }
+        doc
+    };
+    let input = "a: 0\n# pretty-yaml-ignore\nb: [1,   2]\nc: [3,   4]\n";
+    let output = format_text_with_hook(input, &FormatOptions::default(), &hook).unwrap();
+    assert_eq!(
+        output,
+        "a: 0\n# pretty-yaml-ignore\nb: [1,   2]\nc: [3, 4]\n"
+    );
+    assert_eq!(nodes.into_inner(), ["[3,   4]"]);
+}
@@ -0,0 +1,88 @@
+use pretty_yaml::{
+    config::{FormatOptions, LayoutOptions, LineBreak},
+    format_range, format_text,
+};
+
+fn auto() -> FormatOptions {
+    FormatOptions {
+        layout: LayoutOptions {
+            line_break: LineBreak::Auto,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn detect_crlf() {
+    assert_eq!(
+        format_text("a:   1\r\nb:\r\n  - c\r\n", &auto()).unwrap(),
+        "a: 1\r\nb:\r\n  - c\r\n"
+    );
+}
+
+#[test]
+fn detect_lf() {
+    assert_eq!(
+        format_text("a:   1\nb:\n  - c\n", &auto()).unwrap(),
+        "a: 1\nb:\n  - c\n"
+    );
+}
+
+#[test]
+fn mixed_line_breaks() {
+    assert_eq!(
+        format_text("a: 1\r\nb: 2\r\nc: 3\n", &auto()).unwrap(),
+        "a: 1\r\nb: 2\r\nc: 3\r\n"
+    );
+    assert_eq!(
+        format_text("a: 1\r\nb: 2\nc: 3\n", &auto()).unwrap(),
+        "a: 1\nb: 2\nc: 3\n"
+    );
+    // LF is preferred when they appear equally
+    assert_eq!(
+        format_text("a: 1\r\nb: 2\n", &auto()).unwrap(),
+        "a: 1\nb: 2\n"
+    );
+}
+
+#[test]
+fn range() {
+    let result = format_range("a: 1\r\nb:\r\n  c:   [ 1,\r\n    2 ]\r\n", 14..16, &auto()).unwrap();
+    assert_eq!(result.text, "c: [1, 2]");
+    let result = format_range("a:\r\n  - b:   1\r\n    c: 2\r\n", 9..20, &auto()).unwrap();
+    assert_eq!(result.text, "- b: 1\r\n    c: 2");
+}
+
+#[test]
+fn final_newline() {
+    let options = FormatOptions {
+        layout: LayoutOptions {
+            final_newline: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        format_text("a: 1\n# comment\n\n", &options).unwrap(),
+        "a: 1\n# comment"
+    );
+    assert_eq!(format_text("a: 1\r\n", &options).unwrap(), "a: 1");
+    // removing line break changes value of block scalar
+    assert_eq!(
+        format_text("a: |\n  text\n", &options).unwrap(),
+        "a: |\n  text\n"
+    );
+}
+
+#[test]
+fn empty_stream() {
+    let options = FormatOptions::default();
+    assert_eq!(format_text("", &options).unwrap(), "");
+    assert_eq!(format_text("\n\n  \n", &options).unwrap(), "");
+    assert_eq!(format_text("\n# comment", &options).unwrap(), "# comment\n");
+    assert_eq!(
+        format_text("# comment\r\n\r\n", &auto()).unwrap(),
+        "# comment\r\n"
+    );
+}
@@ -0,0 +1,86 @@
+use pretty_yaml::{
+    config::{FormatOptions, KeyOrdering},
+    format_text_with_source_map,
+    mapping::{LineCol, LineIndex},
+};
+
+#[test]
+fn map_tokens() {
+    let input = "a:   1\nb:\n    - [ c,d ]\n";
+    let (output, source_map) =
+        format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: 1\nb:\n  - [c, d]\n");
+    for (old, expected) in [("1", "1"), ("b", "b"), ("-", "-"), ("d", "d")] {
+        let offset = source_map.map_old_to_new(input.find(old).unwrap());
+        assert_eq!(offset, output.find(expected).unwrap(), "offset of `{old}`");
+    }
+    // start and end of input
+    assert_eq!(source_map.map_old_to_new(0), 0);
+    assert_eq!(source_map.map_old_to_new(input.len()), output.len() - 1);
+}
+
+#[test]
+fn map_whitespaces_to_previous_token() {
+    let input = "a:   1\n";
+    let (_, source_map) = format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+    assert_eq!(source_map.map_old_to_new(3), 2);
+}
+
+#[test]
+fn map_multi_line_scalar() {
+    let input = "a: |\n      b\n      c\n";
+    let (output, source_map) =
+        format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: |\n  b\n  c\n");
+    assert_eq!(
+        source_map.map_old_to_new(input.find('c').unwrap()),
+        output.find('c').unwrap()
+    );
+}
+
+#[test]
+fn skip_changed_tokens() {
+    let input = "a: 'x'\nb: 1\n";
+    let (output, source_map) =
+        format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: \"x\"\nb: 1\n");
+    assert_eq!(source_map.map_old_to_new(4), 2);
+    assert_eq!(
+        source_map.map_old_to_new(input.find('b').unwrap()),
+        output.find('b').unwrap()
+    );
+}
+
+#[test]
+fn moved_tokens() {
+    let mut options = FormatOptions::default();
+    options.language.key_ordering = KeyOrdering::Asc;
+    let input = "b: 1\na: 2\n";
+    let (output, source_map) = format_text_with_source_map(input, &options).unwrap();
+    assert_eq!(output, "a: 2\nb: 1\n");
+    assert!(source_map.map_old_to_new(input.len()) <= output.len());
+}
+
+#[test]
+fn byte_order_mark() {
+    let input = "\u{feff}a:   1\n";
+    let (output, source_map) =
+        format_text_with_source_map(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: 1\n");
+    assert_eq!(source_map.map_old_to_new(input.find('1').unwrap()), 3);
+}
+
+#[test]
+fn line_index() {
+    let text = "a: 1\r\nb: é\n\nc";
+    let index = LineIndex::new(text);
+    assert_eq!(index.line_col(0), LineCol { line: 0, col: 0 });
+    assert_eq!(index.line_col(6), LineCol { line: 1, col: 0 });
+    assert_eq!(index.line_col(11), LineCol { line: 1, col: 4 });
+    assert_eq!(index.line_col(13), LineCol { line: 3, col: 0 });
+    assert_eq!(index.line_col(100), LineCol { line: 3, col: 1 });
+    assert_eq!(index.offset(LineCol { line: 1, col: 3 }), 9);
+    assert_eq!(index.offset(LineCol { line: 1, col: 100 }), 11);
+    assert_eq!(index.offset(LineCol { line: 2, col: 0 }), 12);
+    assert_eq!(index.offset(LineCol { line: 9, col: 0 }), text.len());
+}
@@ -0,0 +1,83 @@
+use pretty_yaml::{
+    config::{FormatOptions, GlobPattern},
+    format_text,
+};
+use std::path::Path;
+
+fn matches(pattern: &str, path: &str) -> bool {
+    GlobPattern::new(pattern).matches(Path::new(path))
+}
+
+#[test]
+fn glob_pattern() {
+    assert!(matches("*.yml", "/repo/a.yml"));
+    assert!(!matches("*.yml", "/repo/a.yaml"));
+    assert!(matches("a?c.yml", "abc.yml"));
+    assert!(!matches("a?c.yml", "ac.yml"));
+    assert!(matches("*a*b*", "xaybz"));
+    assert!(!matches("*a*b*", "xbya"));
+
+    assert!(matches(
+        ".github/workflows/*.yml",
+        "/repo/.github/workflows/ci.yml"
+    ));
+    assert!(!matches(
+        ".github/workflows/*.yml",
+        "/repo/.github/workflows/a/ci.yml"
+    ));
+    assert!(!matches(".github/workflows/*.yml", "/repo/.github/ci.yml"));
+
+    assert!(matches(
+        ".github/workflows/**",
+        "/repo/.github/workflows/ci.yml"
+    ));
+    assert!(matches(
+        ".github/workflows/**",
+        "/repo/.github/workflows/a/b/ci.yml"
+    ));
+    assert!(matches("k8s/**/*.yaml", "k8s/deploy.yaml"));
+    assert!(matches("k8s/**/*.yaml", "k8s/a/b/deploy.yaml"));
+    assert!(!matches("k8s/**/*.yaml", "k8s/a/b/deploy.yml"));
+
+    assert!(matches("workflows/*.yml", r"C:\repo\workflows\ci.yml"));
+}
+
+#[test]
+fn last_matched_override() {
+    let mut options = FormatOptions::default();
+    let mut language = options.language.clone();
+    language.indent_block_sequence_in_map = false;
+    options
+        .overrides
+        .push((GlobPattern::new(".github/**"), language.clone()));
+    language.trailing_comma = false;
+    options
+        .overrides
+        .push((GlobPattern::new(".github/workflows/*.yml"), language));
+
+    let input = "steps:\n  - [a, b, c, dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd]\n";
+    assert_eq!(
+        format_text(input, &options.for_path(Path::new("a.yml"))).unwrap(),
+        "steps:\n  - [\n      a,\n      b,\n      c,\n      dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd,\n    ]\n"
+    );
+    assert_eq!(
+        format_text(input, &options.for_path(Path::new(".github/dependabot.yml"))).unwrap(),
+        "steps:\n- [\n    a,\n    b,\n    c,\n    dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd,\n  ]\n"
+    );
+    assert_eq!(
+        format_text(input, &options.for_path(Path::new(".github/workflows/ci.yml"))).unwrap(),
+        "steps:\n- [\n    a,\n    b,\n    c,\n    dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd\n  ]\n"
+    );
+}
+
+#[test]
+fn keep_layout_options() {
+    let mut options = FormatOptions::default();
+    options.layout.indent_width = 4;
+    options
+        .overrides
+        .push((GlobPattern::new("*.yml"), options.language.clone()));
+    let options = options.for_path(Path::new("a.yml"));
+    assert_eq!(options.layout.indent_width, 4);
+    assert!(options.overrides.is_empty());
+}
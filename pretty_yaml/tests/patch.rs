@@ -0,0 +1,109 @@
+#![cfg(feature = "patch")]
+
+use pretty_yaml::{
+    config::FormatOptions,
+    patch::{self, PatchError},
+};
+use serde_json::{json, Value};
+
+fn update(input: &str, f: impl FnOnce(&mut Value)) -> String {
+    let mut value: Value = patch::parse(input).unwrap();
+    f(&mut value);
+    patch::update(input, &value, &FormatOptions::default()).unwrap()
+}
+
+#[test]
+fn unchanged() {
+    let input = "# comment\na:   1 # one\n\nb: [x,   y]\n";
+    assert_eq!(update(input, |_| {}), input);
+}
+
+#[test]
+fn change_scalars() {
+    let input = "# comment\nname: app # display name\n\nport: 80\nenv:\n  debug: true # flag\n";
+    let output = update(input, |value| {
+        value["port"] = json!(8080);
+        value["env"]["debug"] = json!(false);
+    });
+    assert_eq!(
+        output,
+        "# comment\nname: app # display name\n\nport: 8080\nenv:\n  debug: false # flag\n"
+    );
+}
+
+#[test]
+fn keep_properties() {
+    let input = "base: &base !!str a\nother: *base\n";
+    let output = update(input, |value| value["base"] = json!("b"));
+    assert_eq!(output, "base: &base !!str b\nother: *base\n");
+}
+
+#[test]
+fn remove_entries() {
+    let input = "a: 1\nb: 2 # two\nc:\n  d: 3\ne: 4\n";
+    let output = update(input, |value| {
+        let map = value.as_object_mut().unwrap();
+        map.remove("b");
+        map.remove("c");
+    });
+    assert_eq!(output, "a: 1\ne: 4\n");
+}
+
+#[test]
+fn add_entries() {
+    let input = "a: 1 # one\nb:\n  c: 2\n";
+    let output = update(input, |value| {
+        value["b"]["d"] = json!([1, 2]);
+        value["e"] = json!({ "f": "g" });
+    });
+    assert_eq!(
+        output,
+        "a: 1 # one\nb:\n  c: 2\n  d:\n    - 1\n    - 2\ne:\n  f: g\n"
+    );
+}
+
+#[test]
+fn sequence_items() {
+    let input = "list:\n  - a # first\n  - b\n  - c\n";
+    let output = update(input, |value| {
+        value["list"] = json!(["a", "x"]);
+    });
+    assert_eq!(output, "list:\n  - a # first\n  - x\n");
+
+    let output = update(input, |value| {
+        value["list"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "k": "v" }));
+    });
+    assert_eq!(output, "list:\n  - a # first\n  - b\n  - c\n  - k: v\n");
+}
+
+#[test]
+fn flow_collections() {
+    let input = "a: {x: 1,   y: 2}\nb: [1,  2]\n";
+    let output = update(input, |value| value["a"]["y"] = json!(3));
+    assert_eq!(output, "a: {x: 1,   y: 3}\nb: [1,  2]\n");
+
+    let output = update(input, |value| {
+        value["a"]["z"] = json!(3);
+        value["b"].as_array_mut().unwrap().push(json!(3));
+    });
+    assert_eq!(output, "a: {\"x\":1,\"y\":2,\"z\":3}\nb: [1,2,3]\n");
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        patch::parse::<Value>("a: *b\n"),
+        Err(PatchError::Convert(_))
+    ));
+    assert!(matches!(
+        patch::parse::<Vec<u8>>("a: b\n"),
+        Err(PatchError::Serde(_))
+    ));
+    assert!(matches!(
+        patch::parse::<Value>("a: [b\n"),
+        Err(PatchError::Syntax(_))
+    ));
+}
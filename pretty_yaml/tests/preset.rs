@@ -0,0 +1,61 @@
+use insta::{assert_snapshot, Settings};
+use pretty_yaml::{
+    config::{FormatOptions, Preset},
+    format_text,
+};
+
+const INPUT: &str = "%YAML 1.2
+---
+name: 'app'
+items:
+  - a
+  - b
+flow: [aaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccc, dddddddddddddddddddd]
+map: {a: 1, b: 2}
+description: Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt
+---
+";
+
+fn assert_preset(name: &str, preset: Preset) {
+    let mut settings = Settings::clone_current();
+    settings.set_snapshot_path("preset");
+    settings.set_prepend_module_to_snapshot(false);
+    settings.set_omit_expression(true);
+    settings.remove_info();
+    settings.bind(|| {
+        assert_snapshot!(
+            name,
+            format_text(INPUT, &FormatOptions::preset(preset)).unwrap()
+        );
+    });
+}
+
+#[test]
+fn kubernetes() {
+    assert_preset("kubernetes", Preset::Kubernetes);
+}
+
+#[test]
+fn github_actions() {
+    assert_preset("github-actions", Preset::GithubActions);
+}
+
+#[test]
+fn docker_compose() {
+    assert_preset("docker-compose", Preset::DockerCompose);
+}
+
+#[test]
+fn prettier() {
+    assert_preset("prettier", Preset::Prettier);
+}
+
+#[test]
+fn ruamel() {
+    assert_preset("ruamel", Preset::Ruamel);
+}
+
+#[test]
+fn yamlfmt() {
+    assert_preset("yamlfmt", Preset::Yamlfmt);
+}
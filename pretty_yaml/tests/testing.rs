@@ -0,0 +1,55 @@
+#![cfg(feature = "testing")]
+
+use pretty_yaml::{
+    config::{FormatOptions, IndentKind, LanguageOptions, LayoutOptions, Quotes},
+    testing::{arbitrary_yaml, check_stability, StabilityError},
+};
+
+#[test]
+fn arbitrary_yaml_is_valid() {
+    for seed in 0..2048u32 {
+        let data = seed.to_le_bytes().repeat(16);
+        let input = arbitrary_yaml(&data);
+        assert_eq!(input, arbitrary_yaml(&data));
+        yaml_parser::parse(&input)
+            .unwrap_or_else(|err| panic!("invalid YAML generated:\n{input}\n{err}"));
+    }
+}
+
+#[test]
+fn stability() {
+    let options = [
+        FormatOptions::default(),
+        FormatOptions {
+            language: LanguageOptions {
+                quotes: Quotes::ForceSingle,
+                indent_block_sequence_in_map: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        FormatOptions {
+            layout: LayoutOptions {
+                indent_kind: IndentKind::Tab,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ];
+    for seed in 0..512u32 {
+        let input = arbitrary_yaml(&seed.wrapping_mul(2654435761).to_le_bytes().repeat(8));
+        options.iter().for_each(|options| {
+            if let Err(err) = check_stability(&input, options) {
+                panic!("{err} for input:\n{input}\n{err:?}");
+            }
+        });
+    }
+}
+
+#[test]
+fn invalid_input() {
+    assert!(matches!(
+        check_stability("a: [\n", &FormatOptions::default()),
+        Err(StabilityError::InvalidInput(..))
+    ));
+}
@@ -0,0 +1,62 @@
+use pretty_yaml::config::{ConfigError, FormatOptions, GlobPattern};
+
+#[test]
+fn valid_options() {
+    assert_eq!(FormatOptions::default().validate(), Ok(()));
+}
+
+#[test]
+fn invalid_options() {
+    let mut options = FormatOptions::default();
+    options.layout.indent_width = 0;
+    options.language.indent_width_flow = Some(0);
+    options.language.ignore_comment_directive = " ".into();
+    let mut language = options.language.clone();
+    language.indent_width_flow = Some(2);
+    language.extract_anchors = Some(0);
+    language.ignore_comment_directive = "ignore".into();
+    options
+        .overrides
+        .push((GlobPattern::new("*.yml"), language));
+
+    let errors = options.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ConfigError::TooSmall {
+                option: "indentWidth",
+                value: 0,
+                min: 1,
+            },
+            ConfigError::TooSmall {
+                option: "indentWidthFlow",
+                value: 0,
+                min: 1,
+            },
+            ConfigError::Empty {
+                option: "ignoreCommentDirective",
+            },
+            ConfigError::Override {
+                index: 0,
+                error: Box::new(ConfigError::TooSmall {
+                    option: "extractAnchors",
+                    value: 0,
+                    min: 1,
+                }),
+            },
+        ]
+    );
+    assert_eq!(
+        errors.iter().map(ConfigError::option).collect::<Vec<_>>(),
+        [
+            "indentWidth",
+            "indentWidthFlow",
+            "ignoreCommentDirective",
+            "overrides[0].extractAnchors"
+        ]
+    );
+    assert_eq!(
+        errors[3].to_string(),
+        "config `extractAnchors` must be at least 1, but got 0 (in `overrides[0]`)"
+    );
+}
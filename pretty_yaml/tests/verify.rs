@@ -0,0 +1,77 @@
+use pretty_yaml::{config::FormatOptions, format_text, verify::equivalent};
+
+#[test]
+fn formatted_output() {
+    let input = r#"
+# comment
+b:   &anchor { x: 'single', y: "dou\u0062le" }
+a: [ 0x1F, +012, 1.50, ~, True ]
+c: *anchor
+d: >
+  folded
+  text
+? complex
+: value
+"#;
+    let output = format_text(input, &FormatOptions::default()).unwrap();
+    assert!(equivalent(input, &output).unwrap());
+}
+
+#[test]
+fn styles() {
+    assert!(equivalent("a: [1, 2]\n", "a:\n  - 1\n  - 2\n").unwrap());
+    assert!(equivalent("{a: 1, b: 2}\n", "b: 2\na: 1\n").unwrap());
+    assert!(equivalent("a: 31\n", "a: 0x1F\n").unwrap());
+    assert!(equivalent("a: null\n", "a:\n").unwrap());
+    assert!(equivalent("a: |\n  x\n  y\n", "a: \"x\\ny\\n\"\n").unwrap());
+    assert!(equivalent("a: >-\n  x\n  y\n", "a: x y\n").unwrap());
+    assert!(equivalent("a: &x 1\nb: *x\n", "a: &y 1\nb: 1\n").unwrap());
+    assert!(equivalent("[a: b]\n", "- a: b\n").unwrap());
+}
+
+#[test]
+fn core_schema() {
+    assert!(equivalent("a: +.inf\n", "a: .inf\n").unwrap());
+    assert!(equivalent("a: -.Inf\n", "a: -.inf\n").unwrap());
+    assert!(equivalent("a: .NaN\n", "a: .nan\n").unwrap());
+    assert!(equivalent("a: !!null null\n", "a: !!null ~\n").unwrap());
+    assert!(equivalent("a: !!null\n", "a: null\n").unwrap());
+    assert!(equivalent("a: !!bool True\n", "a: true\n").unwrap());
+    assert!(equivalent("a: !!int 0x1F\n", "a: !<tag:yaml.org,2002:int> 31\n").unwrap());
+    assert!(equivalent("a: !!float 1\n", "a: 1.0\n").unwrap());
+    assert!(equivalent("a: !!float \"+.INF\"\n", "a: .inf\n").unwrap());
+    assert!(!equivalent("a: .inf\n", "a: -.inf\n").unwrap());
+    assert!(!equivalent("a: !!float 1\n", "a: 1\n").unwrap());
+    assert!(!equivalent("a: !!str null\n", "a: null\n").unwrap());
+    assert!(!equivalent("a: !!null x\n", "a: null\n").unwrap());
+}
+
+#[test]
+fn double_quoted_escapes() {
+    let input = "a: \"b\\ \n  c\\\t  \n  d\\\n  \\ e\\\\  \n  f   \"\n";
+    let output = format_text(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: \"b\\x20\n  c\\t\n  d\\\n  \\ e\\\\\n  f   \"\n");
+    assert!(equivalent(input, &output).unwrap());
+    assert!(!equivalent(input, "a: \"b\\\n  c\\\n  d\\\n  \\ e\\\\\n  f   \"\n").unwrap());
+}
+
+#[test]
+fn changed_meaning() {
+    assert!(!equivalent("a: 1\n", "a: \"1\"\n").unwrap());
+    assert!(!equivalent("a: 1\n", "a: 1\na: 1\n").unwrap());
+    assert!(!equivalent("a: 1\n", "a: !!str 1\n").unwrap());
+    assert!(!equivalent("a: [1, 2]\n", "a: [2, 1]\n").unwrap());
+    assert!(!equivalent("a: |\n  x\n", "a: |-\n  x\n").unwrap());
+    assert!(!equivalent("a: 1\n", "a: 1\n---\n").unwrap());
+    assert!(!equivalent("a: \"\"\n", "a:\n").unwrap());
+}
+
+#[test]
+fn recursive_aliases() {
+    assert!(equivalent("a: &a\n  b: *a\n", "a: &x\n  b: *x\n").unwrap());
+}
+
+#[test]
+fn syntax_error() {
+    assert!(equivalent("a: 1\n", "a: [\n").is_err());
+}
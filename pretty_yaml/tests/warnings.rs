@@ -0,0 +1,126 @@
+use pretty_yaml::{
+    config::{DedupeKeys, FormatOptions, KeyOrdering},
+    format_text_with_warnings, FormatWarningKind,
+};
+
+#[test]
+fn duplicate_keys() {
+    let mut options = FormatOptions::default();
+    options.language.dedupe_keys = Some(DedupeKeys::KeepFirst);
+    let input = "a: 1\nb: 2\n\"a\": 3\n";
+    let (output, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(output, "a: 1\nb: 2\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind(), &FormatWarningKind::DuplicateKeyRemoved);
+    assert_eq!(warnings[0].range(), 10..16);
+    assert_eq!(&input[warnings[0].range()], "\"a\": 3");
+    assert_eq!(
+        warnings[0].message(),
+        "removed entry with duplicate key `a`"
+    );
+}
+
+#[test]
+fn duplicate_keys_keep_last() {
+    let mut options = FormatOptions::default();
+    options.language.dedupe_keys = Some(DedupeKeys::KeepLast);
+    let input = "a: 1\nb: 2\na: 3\n";
+    let (output, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(output, "b: 2\na: 3\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(&input[warnings[0].range()], "a: 1");
+}
+
+#[test]
+fn duplicate_keys_after_transformations() {
+    let mut options = FormatOptions::default();
+    options.language.dedupe_keys = Some(DedupeKeys::KeepFirst);
+    options.language.inline_aliases = true;
+    options.language.key_ordering = KeyOrdering::Asc;
+    let input = "x: &v 1\nb: *v\ny: 2\nb: 3\n";
+    let (output, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(output, "b: 1\nx: 1\ny: 2\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind(), &FormatWarningKind::DuplicateKeyRemoved);
+    assert_eq!(input.get(warnings[0].range()), Some("b: 3"));
+    assert_eq!(
+        warnings[0].message(),
+        "removed entry with duplicate key `b`"
+    );
+}
+
+#[test]
+fn alias_not_inlined() {
+    let mut options = FormatOptions::default();
+    options.language.inline_aliases = true;
+    let input = "a: &x\n  b: 1\nc:\n  <<: *x\n";
+    let (output, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(output, input);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind(), &FormatWarningKind::AliasNotInlined);
+    assert_eq!(&input[warnings[0].range()], "*x");
+    assert_eq!(
+        warnings[0].message(),
+        "alias `*x` isn't inlined because it's used by merge key"
+    );
+}
+
+#[test]
+fn duplicate_keys_kept() {
+    let input = "a: 1\nb: {c: 2, c: 3}\na: 4\n";
+    let (output, warnings) = format_text_with_warnings(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a: 1\nb: { c: 2, c: 3 }\na: 4\n");
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].kind(), &FormatWarningKind::DuplicateKey);
+    assert_eq!(&input[warnings[0].range()], "c: 3");
+    assert_eq!(warnings[1].kind(), &FormatWarningKind::DuplicateKey);
+    assert_eq!(&input[warnings[1].range()], "a: 4");
+    assert_eq!(warnings[1].message(), "found entry with duplicate key `a`");
+}
+
+#[test]
+fn tab_indentation() {
+    let input = "a:\n\tb: 1\n\t# comment\nc: [\n  \td,\n]\n";
+    let (output, warnings) = format_text_with_warnings(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a:\n  b: 1\n  # comment\nc: [\n  d,\n]\n");
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].kind(), &FormatWarningKind::TabIndentation);
+    assert_eq!(warnings[0].range(), 3..4);
+    assert_eq!(warnings[1].kind(), &FormatWarningKind::TabIndentation);
+    assert_eq!(&input[warnings[1].range()], "  \t");
+    assert_eq!(warnings[1].message(), "found tab character in indentation");
+}
+
+#[test]
+fn plain_scalar_not_wrapped() {
+    let mut options = FormatOptions::default();
+    options.layout.print_width = 10;
+    options.language.wrap_plain_scalars = true;
+
+    let input = "aaaaaaaa ... b\n";
+    let (output, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(output, input);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].kind(),
+        &FormatWarningKind::PlainScalarNotWrapped
+    );
+    assert_eq!(warnings[0].range(), 0..14);
+    assert_eq!(
+        warnings[0].message(),
+        "plain scalar isn't wrapped because it has words that would become document markers"
+    );
+
+    let input = "aaaa bbbb cccc: 1\n? dddd eeee ffff\n: 2\n";
+    let (_, warnings) = format_text_with_warnings(input, &options).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].kind(),
+        &FormatWarningKind::PlainScalarNotWrapped
+    );
+    assert_eq!(&input[warnings[0].range()], "aaaa bbbb cccc");
+    assert_eq!(
+        warnings[0].message(),
+        "plain scalar isn't wrapped because implicit key can't span multiple lines"
+    );
+}
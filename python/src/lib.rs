@@ -0,0 +1,150 @@
+//! Python bindings of Pretty YAML, built as `pretty_yaml` module by maturin.
+
+use ::pretty_yaml::config::FormatOptions;
+use pyo3::{
+    create_exception,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+};
+use rowan::NodeOrToken;
+use serde_json::Value;
+use yaml_parser::SyntaxElement;
+
+create_exception!(
+    pretty_yaml,
+    ParseError,
+    PyValueError,
+    "Raised when input can't be parsed as YAML."
+);
+
+/// Format the given source input.
+///
+/// Options are given as keyword arguments in snake case,
+/// such as `format_text(text, indent_width=4)`.
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn format_text(text: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<String> {
+    let options = match options {
+        Some(options) => serde_json::from_value::<FormatOptions>(to_json(options.as_any())?)
+            .map_err(|error| PyValueError::new_err(error.to_string()))?,
+        None => FormatOptions::default(),
+    };
+    if let Err(errors) = options.validate() {
+        let message = errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(PyValueError::new_err(message));
+    }
+    ::pretty_yaml::format_text(text, &options)
+        .map_err(|error| ParseError::new_err(error.to_string()))
+}
+
+/// Parse the given source input into concrete syntax tree.
+#[pyfunction]
+fn parse(py: Python<'_>, text: &str) -> PyResult<Node> {
+    let tree = yaml_parser::parse(text).map_err(|error| ParseError::new_err(error.to_string()))?;
+    let mut offset = 0;
+    Node::build(py, NodeOrToken::Node(tree), &mut offset)
+}
+
+/// Node or token of concrete syntax tree.
+///
+/// Offsets are in characters, so they can be used for slicing Python strings.
+#[pyclass(frozen, get_all, module = "pretty_yaml")]
+struct Node {
+    kind: String,
+    start: usize,
+    end: usize,
+    /// Text of token, or `None` for nodes.
+    text: Option<String>,
+    children: Py<PyList>,
+}
+
+#[pymethods]
+impl Node {
+    fn __repr__(&self) -> String {
+        format!("Node({}, {}..{})", self.kind, self.start, self.end)
+    }
+}
+
+impl Node {
+    /// Convert syntax element recursively, while tracking offset in characters.
+    fn build(py: Python<'_>, element: SyntaxElement, offset: &mut usize) -> PyResult<Self> {
+        let kind = format!("{:?}", element.kind());
+        let start = *offset;
+        match element {
+            NodeOrToken::Node(node) => {
+                let children = node
+                    .children_with_tokens()
+                    .map(|child| Node::build(py, child, offset))
+                    .collect::<PyResult<Vec<_>>>()?;
+                Ok(Node {
+                    kind,
+                    start,
+                    end: *offset,
+                    text: None,
+                    children: PyList::new(py, children)?.unbind(),
+                })
+            }
+            NodeOrToken::Token(token) => {
+                *offset += token.text().chars().count();
+                Ok(Node {
+                    kind,
+                    start,
+                    end: *offset,
+                    text: Some(token.text().to_owned()),
+                    children: PyList::empty(py).unbind(),
+                })
+            }
+        }
+    }
+}
+
+/// Convert Python object of options to JSON value, which will be deserialized as options.
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = value.downcast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if let Ok(value) = value.downcast::<PyInt>() {
+        Ok(Value::from(value.extract::<i64>()?))
+    } else if let Ok(value) = value.downcast::<PyFloat>() {
+        Ok(Value::from(value.value()))
+    } else if let Ok(value) = value.downcast::<PyString>() {
+        Ok(Value::String(value.extract()?))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        dict.iter()
+            .map(|(key, value)| Ok((key.extract::<String>()?, to_json(&value)?)))
+            .collect::<PyResult<_>>()
+            .map(Value::Object)
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        list.iter()
+            .map(|item| to_json(&item))
+            .collect::<PyResult<_>>()
+            .map(Value::Array)
+    } else if let Ok(tuple) = value.downcast::<PyTuple>() {
+        tuple
+            .iter()
+            .map(|item| to_json(&item))
+            .collect::<PyResult<_>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "unsupported option value: {}",
+            value.repr()?
+        )))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "pretty_yaml")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(format_text, m)?)?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_class::<Node>()?;
+    m.add("ParseError", m.py().get_type::<ParseError>())?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+import pytest
+
+import pretty_yaml
+
+
+def test_format_text():
+    assert pretty_yaml.format_text("a:   [b]\n") == "a: [b]\n"
+    assert pretty_yaml.format_text("a:\n- b\n", indent_width=4) == "a:\n    - b\n"
+    assert pretty_yaml.format_text("a: 'b'\n", quotes="prefer-single") == "a: 'b'\n"
+    assert (
+        pretty_yaml.format_text("a: #c\n", format_comments={"space_after_hash": True})
+        == "a: # c\n"
+    )
+
+
+def test_errors():
+    with pytest.raises(ValueError, match="printWidth"):
+        pretty_yaml.format_text("a: 1\n", print_width=0)
+    with pytest.raises(ValueError):
+        pretty_yaml.format_text("a: 1\n", quotes="unknown")
+    with pytest.raises(pretty_yaml.ParseError):
+        pretty_yaml.format_text("a: [")
+    with pytest.raises(pretty_yaml.ParseError):
+        pretty_yaml.parse("a: [")
+
+
+def test_parse():
+    text = "ä: 1\n"
+    root = pretty_yaml.parse(text)
+    assert root.kind == "ROOT"
+    assert (root.start, root.end) == (0, len(text))
+    assert root.text is None
+
+    tokens = []
+
+    def collect(node):
+        if node.text is not None:
+            assert not node.children
+            tokens.append(node)
+        for child in node.children:
+            collect(child)
+
+    collect(root)
+    assert "".join(token.text for token in tokens) == text
+    assert all(text[token.start : token.end] == token.text for token in tokens)
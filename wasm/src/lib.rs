@@ -0,0 +1,93 @@
+//! WebAssembly bindings of Pretty YAML for browsers,
+//! such as documentation site and online syntax tree explorer.
+
+use pretty_yaml::{config::FormatOptions, format_text};
+use rowan::NodeOrToken;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use yaml_parser::SyntaxElement;
+
+/// Format the given source input.
+///
+/// Config is an object in the same shape as config file of command line,
+/// with options in snake case or camel case.
+/// Default options will be used if it's `undefined` or `null`.
+#[wasm_bindgen]
+pub fn format(input: &str, config: JsValue) -> Result<String, JsValue> {
+    let options = if config.is_undefined() || config.is_null() {
+        FormatOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value::<FormatOptions>(config)?
+    };
+    if let Err(errors) = options.validate() {
+        let message = errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(JsError::new(&message).into());
+    }
+    format_text(input, &options).map_err(|error| JsError::new(&error.to_string()).into())
+}
+
+/// Parse the given source input into concrete syntax tree.
+///
+/// Each node is an object with `kind`, `start`, `end` and `children`,
+/// and each token is an object with `kind`, `start`, `end` and `text`.
+/// Offsets are in UTF-16 code units, so they can be used with JavaScript strings directly.
+#[wasm_bindgen(js_name = parseCst)]
+pub fn parse_cst(input: &str) -> Result<JsValue, JsValue> {
+    let tree = yaml_parser::parse(input).map_err(|error| JsError::new(&error.to_string()))?;
+    let mut offset = 0;
+    let element = Element::build(NodeOrToken::Node(tree), &mut offset);
+    Ok(element.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Element {
+    Node {
+        kind: String,
+        start: usize,
+        end: usize,
+        children: Vec<Element>,
+    },
+    Token {
+        kind: String,
+        start: usize,
+        end: usize,
+        text: String,
+    },
+}
+
+impl Element {
+    /// Convert syntax element recursively, while tracking offset in UTF-16 code units.
+    fn build(element: SyntaxElement, offset: &mut usize) -> Self {
+        let kind = format!("{:?}", element.kind());
+        let start = *offset;
+        match element {
+            NodeOrToken::Node(node) => {
+                let children = node
+                    .children_with_tokens()
+                    .map(|child| Element::build(child, offset))
+                    .collect();
+                Element::Node {
+                    kind,
+                    start,
+                    end: *offset,
+                    children,
+                }
+            }
+            NodeOrToken::Token(token) => {
+                let text = token.text().to_owned();
+                *offset += text.encode_utf16().count();
+                Element::Token {
+                    kind,
+                    start,
+                    end: *offset,
+                    text,
+                }
+            }
+        }
+    }
+}
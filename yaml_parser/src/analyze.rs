@@ -0,0 +1,256 @@
+//! Static analysis on syntax tree.
+//!
+//! ```
+//! use yaml_parser::{analyze::duplicate_keys, ast::{AstNode, Root}};
+//!
+//! let root = Root::cast(yaml_parser::parse("a: 1\nb: {c: 2, 'c': 3}\na: 4\n").unwrap()).unwrap();
+//! let duplicates = duplicate_keys(&root);
+//! assert_eq!(duplicates.len(), 2);
+//! assert_eq!(duplicates[0].to_string(), "duplicate key `c`");
+//! assert_eq!(duplicates[1].entry.to_string(), "a: 4");
+//! assert_eq!(duplicates[1].previous.to_string(), "a: 1");
+//! ```
+
+use crate::{
+    ast::{AstNode, Flow, Root},
+    resolve::AnchorTable,
+    scalar::is_non_string_plain,
+    SyntaxKind, SyntaxNode,
+};
+use rowan::TextRange;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Map entry whose key is the same as the key of a previous entry in the same map.
+pub struct DuplicateKey {
+    /// Value of the key, with quotes, escapes and line folding resolved.
+    pub key: String,
+    /// The duplicate entry, which is [`BlockMapEntry`](crate::ast::BlockMapEntry)
+    /// or [`FlowMapEntry`](crate::ast::FlowMapEntry).
+    pub entry: SyntaxNode,
+    /// The closest previous entry that has the same key.
+    pub previous: SyntaxNode,
+}
+
+impl DuplicateKey {
+    /// Range of the duplicate entry.
+    pub fn range(&self) -> TextRange {
+        self.entry.text_range()
+    }
+}
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "duplicate key `{}`", self.key)
+    }
+}
+
+/// Find map entries whose keys repeat within the same block map or flow map, in source order.
+///
+/// Keys are compared by their values instead of source text, so `a`, `'a'` and `"a"` are same,
+/// while plain `1` and quoted `"1"` are different because the former isn't a string.
+/// Keys that aren't scalars or have properties like tags are skipped.
+pub fn duplicate_keys(root: &Root) -> Vec<DuplicateKey> {
+    let mut duplicates = vec![];
+    root.syntax()
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::BLOCK_MAP | SyntaxKind::FLOW_MAP_ENTRIES
+            )
+        })
+        .for_each(|map| {
+            let mut seen = HashMap::new();
+            map.children()
+                .filter(|child| {
+                    matches!(
+                        child.kind(),
+                        SyntaxKind::BLOCK_MAP_ENTRY | SyntaxKind::FLOW_MAP_ENTRY
+                    )
+                })
+                .for_each(|entry| {
+                    let Some(flow) = entry_key_flow(&entry) else {
+                        return;
+                    };
+                    let Some(key) = flow.scalar_value().map(Cow::into_owned) else {
+                        return;
+                    };
+                    let is_string = flow.plain_scalar().is_none() || !is_non_string_plain(&key);
+                    if let Some(previous) = seen.insert((key.clone(), is_string), entry.clone()) {
+                        duplicates.push(DuplicateKey {
+                            key,
+                            entry,
+                            previous,
+                        });
+                    }
+                });
+        });
+    duplicates.sort_by_key(|duplicate| duplicate.range().start());
+    duplicates
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Entry of map after merge keys (`<<`) are applied, which is returned by [`effective_entries`].
+pub struct EffectiveEntry {
+    /// Value of the key, or `None` if the key isn't a scalar without properties.
+    pub key: Option<String>,
+    /// The map entry that provides the key and value,
+    /// which may come from another map through merge keys.
+    pub entry: SyntaxNode,
+    /// The merge key entry in the given map that brings this entry,
+    /// or `None` if this entry is defined in the given map directly.
+    pub merged_by: Option<SyntaxNode>,
+}
+
+/// Compute entries of block map or flow map after merge keys like `<<: *base` are applied.
+///
+/// Value of merge key can be an alias, a map or a flow sequence of them.
+/// Keys defined in the map directly override merged keys,
+/// and for sequence of maps, keys from earlier maps override keys from later maps.
+/// Merged maps are resolved recursively,
+/// while aliases that are undefined, cyclic or don't refer to maps are skipped.
+/// Entries are in source order, with merged entries put at the place of their merge keys.
+///
+/// ```
+/// use yaml_parser::{analyze::effective_entries, ast::{AstNode, Root}, resolve::AnchorTable, SyntaxKind};
+///
+/// let root = Root::cast(yaml_parser::parse("base: &b {x: 1, y: 2}\njob:\n  <<: *b\n  y: 3\n").unwrap()).unwrap();
+/// let table = AnchorTable::new(&root);
+/// let job = root.syntax().descendants().filter(|node| node.kind() == SyntaxKind::BLOCK_MAP).nth(1).unwrap();
+/// let entries = effective_entries(&job, &table);
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[0].entry.to_string(), "x: 1");
+/// assert_eq!(entries[1].entry.to_string(), "y: 3");
+/// ```
+pub fn effective_entries(map: &SyntaxNode, table: &AnchorTable) -> Vec<EffectiveEntry> {
+    collect_effective_entries(map, table, &mut vec![map.clone()])
+        .into_iter()
+        .map(|(key, entry, merged_by)| EffectiveEntry {
+            key: key.map(|(key, _)| key),
+            entry,
+            merged_by,
+        })
+        .collect()
+}
+
+type EntryKey = (String, bool);
+
+fn collect_effective_entries(
+    map: &SyntaxNode,
+    table: &AnchorTable,
+    visited: &mut Vec<SyntaxNode>,
+) -> Vec<(Option<EntryKey>, SyntaxNode, Option<SyntaxNode>)> {
+    let entries = map_entries(map).collect::<Vec<_>>();
+    let mut seen = entries
+        .iter()
+        .filter(|entry| !is_merge_entry(entry))
+        .filter_map(entry_key)
+        .collect::<HashSet<_>>();
+    let mut effective = vec![];
+    for entry in entries {
+        if !is_merge_entry(&entry) {
+            effective.push((entry_key(&entry), entry, None));
+            continue;
+        }
+        for source in merge_sources(&entry, table) {
+            if visited.contains(&source) {
+                continue;
+            }
+            visited.push(source.clone());
+            for (key, merged, _) in collect_effective_entries(&source, table, visited) {
+                if key.as_ref().is_none_or(|key| seen.insert(key.clone())) {
+                    effective.push((key, merged, Some(entry.clone())));
+                }
+            }
+            visited.pop();
+        }
+    }
+    effective
+}
+
+/// Check if the entry is a merge key entry like `<<: *base`.
+pub fn is_merge_entry(entry: &SyntaxNode) -> bool {
+    entry_key_flow(entry)
+        .and_then(|flow| flow.plain_scalar())
+        .is_some_and(|scalar| scalar.text() == "<<")
+}
+
+fn map_entries(map: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> {
+    let entries = if map.kind() == SyntaxKind::FLOW_MAP {
+        map.children()
+            .find(|child| child.kind() == SyntaxKind::FLOW_MAP_ENTRIES)
+    } else {
+        Some(map.clone())
+    };
+    entries.into_iter().flat_map(|entries| {
+        entries.children().filter(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP_ENTRY | SyntaxKind::FLOW_MAP_ENTRY
+            )
+        })
+    })
+}
+
+fn entry_key(entry: &SyntaxNode) -> Option<EntryKey> {
+    let flow = entry_key_flow(entry)?;
+    let key = flow.scalar_value()?.into_owned();
+    let is_string = flow.plain_scalar().is_none() || !is_non_string_plain(&key);
+    Some((key, is_string))
+}
+
+/// Find maps that are merged by the merge key entry, in order of precedence.
+fn merge_sources(entry: &SyntaxNode, table: &AnchorTable) -> Vec<SyntaxNode> {
+    let Some(value) = entry
+        .children()
+        .find(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE
+            )
+        })
+        .and_then(|value| value.first_child())
+    else {
+        return vec![];
+    };
+    if let Some(seq) = Flow::cast(value.clone()).and_then(|flow| flow.flow_seq()) {
+        seq.entries()
+            .into_iter()
+            .flat_map(|entries| entries.entries())
+            .filter_map(|entry| entry.flow())
+            .filter_map(|flow| map_of(flow.syntax(), table))
+            .collect()
+    } else {
+        map_of(&value, table).into_iter().collect()
+    }
+}
+
+/// Get the block map or flow map of the given node, following alias if it's an alias.
+fn map_of(node: &SyntaxNode, table: &AnchorTable) -> Option<SyntaxNode> {
+    let node = match Flow::cast(node.clone()).and_then(|flow| flow.alias()) {
+        Some(alias) => table.resolve_alias(&alias)?,
+        None => node.clone(),
+    };
+    node.children()
+        .find(|child| matches!(child.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::FLOW_MAP))
+}
+
+/// Get the key of map entry if it's a scalar without properties.
+pub(crate) fn entry_key_flow(entry: &SyntaxNode) -> Option<Flow> {
+    entry
+        .children()
+        .find(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP_KEY | SyntaxKind::FLOW_MAP_KEY
+            )
+        })?
+        .children()
+        .find_map(Flow::cast)
+        .filter(|flow| flow.properties().is_none())
+}
@@ -1,8 +1,13 @@
 //! Abstract Syntax Tree, layered on top of untyped `SyntaxNode`s.
 
-use super::{SyntaxKind, SyntaxNode, SyntaxToken, YamlLanguage};
-use rowan::SyntaxNodeChildren;
-use std::marker::PhantomData;
+use super::{scalar, SyntaxKind, SyntaxNode, SyntaxToken, YamlLanguage};
+use rowan::{Direction, SyntaxNodeChildren};
+use std::{borrow::Cow, marker::PhantomData};
+
+pub use crate::comment::CommentAttachment;
+
+pub mod edit;
+pub mod visit;
 
 // --------------- Code below are copied from rust-analyzer ----------------
 
@@ -571,6 +576,23 @@ impl Flow {
     pub fn alias(&self) -> Option<Alias> {
         child(&self.syntax)
     }
+    /// Value of plain, single quoted or double quoted scalar,
+    /// with line folding and escapes resolved.
+    ///
+    /// ```
+    /// use yaml_parser::ast::{AstNode, Flow};
+    ///
+    /// let tree = yaml_parser::parse("- 'it''s'\n- \"a\\tb\n\n  c\"\n").unwrap();
+    /// let values = tree
+    ///     .descendants()
+    ///     .filter_map(Flow::cast)
+    ///     .filter_map(|flow| flow.scalar_value().map(|value| value.into_owned()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(values, ["it's", "a\tb\nc"]);
+    /// ```
+    pub fn scalar_value(&self) -> Option<Cow<'_, str>> {
+        scalar::scalar_value(self)
+    }
 }
 impl AstNode for Flow {
     fn can_cast(kind: SyntaxKind) -> bool {
@@ -650,6 +672,18 @@ impl BlockScalar {
     pub fn text(&self) -> Option<SyntaxToken> {
         token(&self.syntax, SyntaxKind::BLOCK_SCALAR_TEXT)
     }
+    /// Value of block scalar, with indentation removed and line folding and chomping applied.
+    ///
+    /// ```
+    /// use yaml_parser::ast::{AstNode, BlockScalar};
+    ///
+    /// let tree = yaml_parser::parse("a: >-\n  b\n  c\n\n  d\n").unwrap();
+    /// let block_scalar = tree.descendants().find_map(BlockScalar::cast).unwrap();
+    /// assert_eq!(block_scalar.value().unwrap(), "b c\nd");
+    /// ```
+    pub fn value(&self) -> Option<String> {
+        scalar::block_scalar_value(self)
+    }
 }
 impl AstNode for BlockScalar {
     fn can_cast(kind: SyntaxKind) -> bool {
@@ -1059,6 +1093,47 @@ impl Root {
     pub fn documents(&self) -> AstChildren<Document> {
         children(&self.syntax)
     }
+    /// Whitespaces and comments before the first document.
+    /// If there're no documents, such as stream that only has comments,
+    /// all the whitespaces and comments of stream are leading trivia.
+    ///
+    /// ```
+    /// use yaml_parser::ast::{AstNode, Root};
+    ///
+    /// let root = Root::cast(yaml_parser::parse("# a\n\n# b\n").unwrap()).unwrap();
+    /// let comments = root
+    ///     .leading_trivia()
+    ///     .into_iter()
+    ///     .filter(|token| token.kind() == yaml_parser::SyntaxKind::COMMENT)
+    ///     .map(|token| token.to_string())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(comments, ["# a", "# b"]);
+    /// assert!(root.trailing_trivia().is_empty());
+    /// ```
+    pub fn leading_trivia(&self) -> Vec<SyntaxToken> {
+        self.syntax
+            .children_with_tokens()
+            .map_while(|element| element.into_token())
+            .filter(is_trivia)
+            .collect()
+    }
+    /// Whitespaces and comments after the last document.
+    /// It's empty if there're no documents.
+    pub fn trailing_trivia(&self) -> Vec<SyntaxToken> {
+        self.syntax
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::DOCUMENT)
+            .last()
+            .into_iter()
+            .flat_map(|document| document.siblings_with_tokens(Direction::Next).skip(1))
+            .filter_map(|element| element.into_token())
+            .filter(is_trivia)
+            .collect()
+    }
+}
+
+fn is_trivia(token: &SyntaxToken) -> bool {
+    matches!(token.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT)
 }
 impl AstNode for Root {
     fn can_cast(kind: SyntaxKind) -> bool {
@@ -0,0 +1,335 @@
+//! Methods for editing syntax trees in place.
+//!
+//! These methods only work on mutable trees, so create one with
+//! [`clone_for_update`](super::AstNode::clone_for_update) before editing:
+//!
+//! ```
+//! use yaml_parser::{ast::{AstNode, BlockMap}, SyntaxFactory};
+//!
+//! let tree = yaml_parser::parse("a: 1\n").unwrap().clone_for_update();
+//! let map = tree.descendants().find_map(BlockMap::cast).unwrap();
+//! map.push_entry(
+//!     SyntaxFactory::plain_scalar("b"),
+//!     SyntaxFactory::plain_scalar("2").syntax().clone(),
+//! );
+//! assert_eq!(tree.to_string(), "a: 1\nb: 2\n");
+//! ```
+//!
+//! Nodes for inserting can be created by [`SyntaxFactory`](crate::SyntaxFactory).
+//! Indentation of inserted nodes is adjusted automatically, and trivias that aren't
+//! touched are preserved, so the tree can be printed directly or passed to formatter.
+
+use super::{AstNode, BlockMap, BlockMapEntry, BlockSeq, BlockSeqEntry, Document, Flow};
+use crate::{
+    comment::trailing_comment, factory::indent, SyntaxElement, SyntaxFactory, SyntaxKind,
+    SyntaxNode, SyntaxToken,
+};
+use rowan::{GreenNode, GreenToken, NodeOrToken};
+
+impl BlockMap {
+    /// Insert a new entry at the given index.
+    /// If index is out of bounds, the entry will be appended.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn insert_entry(&self, index: usize, key: Flow, value: SyntaxNode) {
+        let entry = first_grandchild(SyntaxFactory::block_map([(key, value)]).syntax());
+        insert_entry(&self.syntax, SyntaxKind::BLOCK_MAP_ENTRY, index, entry);
+    }
+
+    /// Append a new entry to the end of this map.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn push_entry(&self, key: Flow, value: SyntaxNode) {
+        self.insert_entry(usize::MAX, key, value);
+    }
+}
+
+impl BlockMapEntry {
+    /// Replace value of this entry, while key and trailing comment are kept.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn replace_value(&self, value: SyntaxNode) {
+        let key = self
+            .key()
+            .and_then(|key| key.flow())
+            .unwrap_or_else(|| SyntaxFactory::plain_scalar(""));
+        let entry = first_grandchild(SyntaxFactory::block_map([(key, value)]).syntax());
+        replace_after(&self.syntax, SyntaxKind::COLON, entry);
+    }
+
+    /// Remove this entry from its map, including its trailing comment.
+    ///
+    /// Removing the only entry will leave an empty map which isn't valid YAML,
+    /// so remove or replace the whole map instead in that case.
+    pub fn remove(&self) {
+        remove_entry(&self.syntax);
+    }
+}
+
+impl BlockSeq {
+    /// Insert a new entry at the given index.
+    /// If index is out of bounds, the entry will be appended.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn insert_entry(&self, index: usize, value: SyntaxNode) {
+        let entry = first_grandchild(SyntaxFactory::block_seq([value]).syntax());
+        insert_entry(&self.syntax, SyntaxKind::BLOCK_SEQ_ENTRY, index, entry);
+    }
+
+    /// Append a new entry to the end of this sequence.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn push_entry(&self, value: SyntaxNode) {
+        self.insert_entry(usize::MAX, value);
+    }
+}
+
+impl BlockSeqEntry {
+    /// Replace value of this entry, while trailing comment is kept.
+    ///
+    /// Value must be either [`Block`](super::Block) or [`Flow`] node.
+    pub fn replace_value(&self, value: SyntaxNode) {
+        let entry = first_grandchild(SyntaxFactory::block_seq([value]).syntax());
+        replace_after(&self.syntax, SyntaxKind::MINUS, entry);
+    }
+
+    /// Remove this entry from its sequence, including its trailing comment.
+    ///
+    /// Removing the only entry will leave an empty sequence which isn't valid YAML,
+    /// so remove or replace the whole sequence instead in that case.
+    pub fn remove(&self) {
+        remove_entry(&self.syntax);
+    }
+}
+
+impl Flow {
+    /// Replace content of this node, such as scalar, alias or flow collection,
+    /// with content of the given node, while properties like anchor and tag are kept.
+    pub fn replace_scalar(&self, scalar: Flow) {
+        let scalar = SyntaxNode::new_root(scalar.syntax().green().into_owned()).clone_for_update();
+        let new_content = scalar
+            .children_with_tokens()
+            .filter(|element| is_flow_content(element.kind()))
+            .collect::<Vec<_>>();
+        let children = self.syntax.children_with_tokens().collect::<Vec<_>>();
+        if let Some(index) = children
+            .iter()
+            .position(|element| is_flow_content(element.kind()))
+        {
+            self.syntax.splice_children(index..index + 1, new_content);
+        } else {
+            // only properties without content, like `&anchor`
+            let mut elements = vec![whitespace(" ")];
+            elements.extend(new_content);
+            self.syntax
+                .splice_children(children.len()..children.len(), elements);
+        }
+    }
+}
+
+impl Document {
+    /// Remove the entry of block or flow collection that contains the given node,
+    /// or the node itself if it's an entry.
+    ///
+    /// Returns `false` if the node isn't in this document or it isn't in any entries.
+    pub fn remove_node(&self, node: &SyntaxNode) -> bool {
+        if !node.ancestors().any(|ancestor| ancestor == self.syntax) {
+            return false;
+        }
+        let Some(entry) = node.ancestors().find(|ancestor| {
+            matches!(
+                ancestor.kind(),
+                SyntaxKind::BLOCK_MAP_ENTRY
+                    | SyntaxKind::BLOCK_SEQ_ENTRY
+                    | SyntaxKind::FLOW_MAP_ENTRY
+                    | SyntaxKind::FLOW_SEQ_ENTRY
+            )
+        }) else {
+            return false;
+        };
+        match entry.kind() {
+            SyntaxKind::FLOW_MAP_ENTRY | SyntaxKind::FLOW_SEQ_ENTRY => remove_flow_entry(&entry),
+            _ => remove_entry(&entry),
+        }
+        true
+    }
+}
+
+fn is_flow_content(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::PLAIN_SCALAR
+            | SyntaxKind::SINGLE_QUOTED_SCALAR
+            | SyntaxKind::DOUBLE_QUOTED_SCALAR
+            | SyntaxKind::ALIAS
+            | SyntaxKind::FLOW_SEQ
+            | SyntaxKind::FLOW_MAP
+    )
+}
+
+/// Get green node of entry from block collection created by factory,
+/// which is `BLOCK > BLOCK_MAP | BLOCK_SEQ > entry`.
+fn first_grandchild(block: &SyntaxNode) -> GreenNode {
+    block
+        .first_child()
+        .and_then(|collection| collection.first_child())
+        .expect("block collection should have entry")
+        .green()
+        .into_owned()
+}
+
+fn insert_entry(collection: &SyntaxNode, kind: SyntaxKind, index: usize, entry: GreenNode) {
+    let column = column(collection);
+    let entry = SyntaxElement::Node(SyntaxNode::new_root(indent(entry, column)).clone_for_update());
+    let separator = whitespace(&format!("\n{}", " ".repeat(column)));
+    let entries = collection
+        .children()
+        .filter(|child| child.kind() == kind)
+        .collect::<Vec<_>>();
+    if let Some(next) = entries.get(index) {
+        let index = next.index();
+        collection.splice_children(index..index, vec![entry, separator]);
+    } else if let Some(last) = entries.last() {
+        // keep trailing comment of the last entry on its line
+        let trailing = trailing_tokens(last);
+        let index = last.index() + 1;
+        if trailing
+            .iter()
+            .all(|token| token.parent().as_ref() == Some(collection))
+        {
+            let index = index + trailing.len();
+            collection.splice_children(index..index, vec![separator, entry]);
+        } else {
+            // trailing comment of the last entry may belong to ancestor,
+            // so it needs to be moved into this collection
+            let mut elements = trailing
+                .into_iter()
+                .map(SyntaxElement::Token)
+                .collect::<Vec<_>>();
+            elements.push(separator);
+            elements.push(entry);
+            collection.splice_children(index..index, elements);
+        }
+    }
+}
+
+fn remove_entry(entry: &SyntaxNode) {
+    let Some(parent) = entry.parent() else {
+        return;
+    };
+    let (siblings, others): (Vec<_>, Vec<_>) = trailing_tokens(entry)
+        .into_iter()
+        .partition(|token| token.parent().as_ref() == Some(&parent));
+    others.iter().for_each(|token| token.detach());
+    let mut start = entry.index();
+    let mut end = start + 1 + siblings.len();
+    if let Some(whitespace) = entry
+        .prev_sibling_or_token()
+        .filter(|element| element.kind() == SyntaxKind::WHITESPACE)
+    {
+        start = whitespace.index();
+    } else if parent
+        .children_with_tokens()
+        .nth(end)
+        .is_some_and(|element| element.kind() == SyntaxKind::WHITESPACE)
+    {
+        end += 1;
+    }
+    parent.splice_children(start..end, vec![]);
+}
+
+/// Remove flow entry with its comma,
+/// or with the comma before it if it's the last entry.
+fn remove_flow_entry(entry: &SyntaxNode) {
+    let Some(parent) = entry.parent() else {
+        return;
+    };
+    let children = parent.children_with_tokens().collect::<Vec<_>>();
+    let index = entry.index();
+    let is_trivia = |element: &SyntaxElement| {
+        matches!(element.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT)
+    };
+    let next_comma = children[index + 1..]
+        .iter()
+        .find(|element| !is_trivia(element))
+        .filter(|element| element.kind() == SyntaxKind::COMMA)
+        .map(|comma| comma.index());
+    let range = if let Some(comma) = next_comma {
+        let end = children[comma + 1..]
+            .iter()
+            .position(|element| element.kind() != SyntaxKind::WHITESPACE)
+            .map_or(children.len(), |offset| comma + 1 + offset);
+        index..end
+    } else if let Some(comma) = children[..index]
+        .iter()
+        .rposition(|element| element.kind() == SyntaxKind::COMMA)
+    {
+        comma..index + 1
+    } else {
+        index..index + 1
+    };
+    parent.splice_children(range, vec![]);
+}
+
+/// Replace children of the entry after the given token, which is `:` or `-`,
+/// with children of the new entry.
+fn replace_after(entry: &SyntaxNode, kind: SyntaxKind, new_entry: GreenNode) {
+    let new_entry = SyntaxNode::new_root(indent(new_entry, column(entry))).clone_for_update();
+    let new_children = new_entry
+        .children_with_tokens()
+        .skip_while(|element| element.kind() != kind)
+        .skip(1)
+        .collect::<Vec<_>>();
+    new_children.iter().for_each(|element| element.detach());
+    let start = entry
+        .children_with_tokens()
+        .find(|element| element.kind() == kind)
+        .map_or(0, |element| element.index() + 1);
+    let end = entry.children_with_tokens().count();
+    entry.splice_children(start..end, new_children);
+}
+
+/// Whitespaces and comment after the entry on the same line,
+/// or nothing if there's no trailing comment.
+fn trailing_tokens(entry: &SyntaxNode) -> Vec<SyntaxToken> {
+    let Some(comment) = trailing_comment(entry) else {
+        return vec![];
+    };
+    let mut tokens = vec![];
+    let mut token = entry.last_token().and_then(|token| token.next_token());
+    while let Some(current) = token {
+        token = current.next_token();
+        tokens.push(current.clone());
+        if current == comment {
+            break;
+        }
+    }
+    tokens
+}
+
+/// Column where the node starts, which is used as indentation of its entries.
+fn column(node: &SyntaxNode) -> usize {
+    let mut prefix = String::new();
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    while let Some(current) = token {
+        if let Some((_, rest)) = current.text().rsplit_once('\n') {
+            prefix.insert_str(0, rest);
+            break;
+        }
+        prefix.insert_str(0, current.text());
+        token = current.prev_token();
+    }
+    prefix.chars().count()
+}
+
+fn whitespace(text: &str) -> SyntaxElement {
+    let root = SyntaxNode::new_root(GreenNode::new(
+        SyntaxKind::ROOT.into(),
+        [NodeOrToken::Token(GreenToken::new(
+            SyntaxKind::WHITESPACE.into(),
+            text,
+        ))],
+    ))
+    .clone_for_update();
+    SyntaxElement::Token(root.first_token().expect("whitespace token"))
+}
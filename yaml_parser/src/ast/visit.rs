@@ -0,0 +1,109 @@
+//! Walk through typed AST in depth-first order.
+//!
+//! Implement [`Visitor`] with methods of nodes that you're interested in,
+//! then pass it to [`walk`]:
+//!
+//! ```
+//! use yaml_parser::{
+//!     ast::{
+//!         visit::{walk, Visitor},
+//!         AstNode, BlockMapKey, Root,
+//!     },
+//!     parse,
+//! };
+//!
+//! #[derive(Default)]
+//! struct Keys(Vec<String>);
+//!
+//! impl Visitor for Keys {
+//!     fn enter_block_map_key(&mut self, key: &BlockMapKey) {
+//!         self.0.push(key.syntax().to_string());
+//!     }
+//! }
+//!
+//! let root = Root::cast(parse("a: 1\nb:\n  c: 2\n").unwrap()).unwrap();
+//! let mut keys = Keys::default();
+//! walk(&root, &mut keys);
+//! assert_eq!(keys.0, ["a", "b", "c"]);
+//! ```
+
+use super::*;
+use rowan::WalkEvent;
+
+macro_rules! visitor {
+    ($($node:ident => $enter:ident, $leave:ident;)*) => {
+        /// Callbacks that are called when walking through AST by [`walk`].
+        ///
+        /// Every method does nothing by default.
+        /// For each node, the `enter_*` method is called before visiting its children,
+        /// and the `leave_*` method is called after visiting its children.
+        pub trait Visitor {
+            $(
+                #[doc = concat!("Called before visiting children of [`", stringify!($node), "`].")]
+                fn $enter(&mut self, _node: &$node) {}
+                #[doc = concat!("Called after visiting children of [`", stringify!($node), "`].")]
+                fn $leave(&mut self, _node: &$node) {}
+            )*
+        }
+
+        fn dispatch<V: Visitor + ?Sized>(event: WalkEvent<SyntaxNode>, visitor: &mut V) {
+            let (node, is_enter) = match event {
+                WalkEvent::Enter(node) => (node, true),
+                WalkEvent::Leave(node) => (node, false),
+            };
+            $(
+                if $node::can_cast(node.kind()) {
+                    let node = $node { syntax: node };
+                    if is_enter {
+                        visitor.$enter(&node);
+                    } else {
+                        visitor.$leave(&node);
+                    }
+                    return;
+                }
+            )*
+        }
+    };
+}
+
+visitor! {
+    Root => enter_root, leave_root;
+    Document => enter_document, leave_document;
+    Directive => enter_directive, leave_directive;
+    YamlDirective => enter_yaml_directive, leave_yaml_directive;
+    TagDirective => enter_tag_directive, leave_tag_directive;
+    ReservedDirective => enter_reserved_directive, leave_reserved_directive;
+    Block => enter_block, leave_block;
+    BlockMap => enter_block_map, leave_block_map;
+    BlockMapEntry => enter_block_map_entry, leave_block_map_entry;
+    BlockMapKey => enter_block_map_key, leave_block_map_key;
+    BlockMapValue => enter_block_map_value, leave_block_map_value;
+    BlockSeq => enter_block_seq, leave_block_seq;
+    BlockSeqEntry => enter_block_seq_entry, leave_block_seq_entry;
+    BlockScalar => enter_block_scalar, leave_block_scalar;
+    ChompingIndicator => enter_chomping_indicator, leave_chomping_indicator;
+    Flow => enter_flow, leave_flow;
+    FlowMap => enter_flow_map, leave_flow_map;
+    FlowMapEntries => enter_flow_map_entries, leave_flow_map_entries;
+    FlowMapEntry => enter_flow_map_entry, leave_flow_map_entry;
+    FlowMapKey => enter_flow_map_key, leave_flow_map_key;
+    FlowMapValue => enter_flow_map_value, leave_flow_map_value;
+    FlowPair => enter_flow_pair, leave_flow_pair;
+    FlowSeq => enter_flow_seq, leave_flow_seq;
+    FlowSeqEntries => enter_flow_seq_entries, leave_flow_seq_entries;
+    FlowSeqEntry => enter_flow_seq_entry, leave_flow_seq_entry;
+    Alias => enter_alias, leave_alias;
+    Properties => enter_properties, leave_properties;
+    AnchorProperty => enter_anchor_property, leave_anchor_property;
+    TagProperty => enter_tag_property, leave_tag_property;
+    ShorthandTag => enter_shorthand_tag, leave_shorthand_tag;
+    TagHandle => enter_tag_handle, leave_tag_handle;
+    NonSpecificTag => enter_non_specific_tag, leave_non_specific_tag;
+}
+
+/// Walk through all nodes of the tree in depth-first order and call methods of visitor.
+pub fn walk<V: Visitor + ?Sized>(root: &Root, visitor: &mut V) {
+    root.syntax
+        .preorder()
+        .for_each(|event| dispatch(event, visitor));
+}
@@ -0,0 +1,177 @@
+use crate::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+use rowan::{TextRange, TextSize};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Comments that are associated with a node.
+///
+/// Comments are attached by the following rules:
+///
+/// - Leading comments are comments on their own lines right before the node,
+///   as [`leading_comments`] finds. A blank line stops the search.
+/// - Trailing comment is the comment at the end of the line where the node ends,
+///   as [`trailing_comment`] finds.
+/// - Dangling comments are comments that are direct children of the node,
+///   but they're neither leading comments of the next child node
+///   nor trailing comment of the previous child node,
+///   such as comments in empty flow collections,
+///   comments after opening brackets, or comments separated from entries by blank lines.
+///
+/// Nested nodes that start or end at the same position share the same leading or trailing comments,
+/// for example, a block map entry and its key.
+/// Each comment is dangling for at most one node.
+///
+/// ```
+/// use yaml_parser::{ast::CommentAttachment, SyntaxKind};
+///
+/// let tree = yaml_parser::parse("# a\nkey: value # b\n# c\n\nseq: [ # d\n]\n").unwrap();
+/// let entry = tree
+///     .descendants()
+///     .find(|node| node.kind() == SyntaxKind::BLOCK_MAP_ENTRY)
+///     .unwrap();
+/// let comments = CommentAttachment::for_node(&entry);
+/// assert_eq!(comments.leading[0].text(), "# a");
+/// assert_eq!(comments.trailing.unwrap().text(), "# b");
+///
+/// let map = entry.parent().unwrap();
+/// assert_eq!(CommentAttachment::for_node(&map).dangling[0].text(), "# c");
+/// let seq = tree
+///     .descendants()
+///     .find(|node| node.kind() == SyntaxKind::FLOW_SEQ)
+///     .unwrap();
+/// assert_eq!(CommentAttachment::for_node(&seq).dangling[0].text(), "# d");
+/// ```
+pub struct CommentAttachment {
+    pub leading: Vec<SyntaxToken>,
+    pub trailing: Option<SyntaxToken>,
+    pub dangling: Vec<SyntaxToken>,
+}
+
+impl CommentAttachment {
+    /// Find comments that are associated with the given node.
+    pub fn for_node(node: &SyntaxNode) -> Self {
+        Self {
+            leading: leading_comments(node),
+            trailing: trailing_comment(node),
+            dangling: dangling_comments(node),
+        }
+    }
+}
+
+/// Find comments that are direct children of the given node
+/// and aren't attached to its child nodes, in source order.
+pub(crate) fn dangling_comments(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let mut prev_node = None::<SyntaxNode>;
+    let mut comments = vec![];
+    for element in node.children_with_tokens() {
+        match element {
+            SyntaxElement::Node(node) => prev_node = Some(node),
+            SyntaxElement::Token(token) if token.kind() == SyntaxKind::COMMENT => {
+                let is_trailing = prev_node
+                    .as_ref()
+                    .and_then(trailing_comment)
+                    .is_some_and(|trailing| trailing == token);
+                let is_leading = !is_trailing
+                    && token
+                        .siblings_with_tokens(rowan::Direction::Next)
+                        .find_map(SyntaxElement::into_node)
+                        .is_some_and(|next| leading_comments(&next).contains(&token));
+                if !is_trailing && !is_leading {
+                    comments.push(token);
+                }
+            }
+            SyntaxElement::Token(_) => {}
+        }
+    }
+    comments
+}
+
+/// Find comments on their own lines right before the given node, in source order.
+///
+/// Comments separated from the node by blank lines aren't considered as attached.
+pub fn leading_comments(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let mut comments = vec![];
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    loop {
+        let mut line_breaks = 0;
+        while let Some(whitespace) = token
+            .as_ref()
+            .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
+        {
+            line_breaks += whitespace.text().matches('\n').count();
+            token = whitespace.prev_token();
+        }
+        if line_breaks != 1 {
+            break;
+        }
+        match token {
+            Some(comment) if comment.kind() == SyntaxKind::COMMENT && is_own_line(&comment) => {
+                token = comment.prev_token();
+                comments.push(comment);
+            }
+            _ => break,
+        }
+    }
+    comments.reverse();
+    comments
+}
+
+/// Find comment at the end of the line where the given node ends.
+pub fn trailing_comment(node: &SyntaxNode) -> Option<SyntaxToken> {
+    line_end(node).and_then(|(_, comment)| comment)
+}
+
+/// Find the end of the line where the given node ends.
+///
+/// Returns the range of whitespaces at the end of that line, which is before the comment if any,
+/// and the comment at the end of that line.
+/// Returns `None` if comment can't be put at the end of that line,
+/// for example, that line ends inside a multi-line scalar.
+pub(crate) fn line_end(node: &SyntaxNode) -> Option<(TextRange, Option<SyntaxToken>)> {
+    let last = node.last_token()?;
+    if last.kind() == SyntaxKind::BLOCK_SCALAR_TEXT {
+        // comment after block scalar will be a part of its content
+        return None;
+    }
+    let mut whitespace_start = last.text_range().end();
+    let mut end = whitespace_start;
+    let mut token = last.next_token();
+    while let Some(current) = token {
+        let range = current.text_range();
+        match current.kind() {
+            SyntaxKind::COMMENT => {
+                return Some((
+                    TextRange::new(whitespace_start, range.start()),
+                    Some(current),
+                ));
+            }
+            SyntaxKind::WHITESPACE => {
+                if let Some(index) = current.text().find(['\n', '\r']) {
+                    let line_break = range.start() + TextSize::from(index as u32);
+                    return Some((TextRange::new(whitespace_start, line_break), None));
+                }
+            }
+            SyntaxKind::BLOCK_SCALAR_TEXT if current.text().starts_with(['\n', '\r']) => {
+                return Some((TextRange::new(whitespace_start, range.start()), None));
+            }
+            _ if current.text().contains(['\n', '\r']) => return None,
+            _ => whitespace_start = range.end(),
+        }
+        end = range.end();
+        token = current.next_token();
+    }
+    Some((TextRange::new(whitespace_start, end), None))
+}
+
+fn is_own_line(comment: &SyntaxToken) -> bool {
+    let mut token = comment.prev_token();
+    while let Some(whitespace) = token
+        .as_ref()
+        .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
+    {
+        if whitespace.text().contains('\n') {
+            return true;
+        }
+        token = whitespace.prev_token();
+    }
+    token.is_none()
+}
@@ -0,0 +1,121 @@
+use crate::{
+    ast::{AstNode, Document},
+    parse, SyntaxError,
+};
+use std::ops::Range;
+
+/// Split YAML stream into documents and parse them lazily.
+///
+/// Documents are split by document markers (`---` and `...`) at the start of lines,
+/// which can't appear in any content, so each document can be parsed independently
+/// and parsing can be stopped early when there's a syntax error.
+///
+/// Each document is parsed into its own tree,
+/// so text ranges of nodes and offsets of syntax errors are relative to the start of document.
+/// Comments before a document belong to that document,
+/// and comments after the last document belong to the last document,
+/// so they can be found in the root node of last document.
+///
+/// ```
+/// use yaml_parser::{ast::AstNode, parse_documents};
+///
+/// let mut documents = parse_documents("a: 1\n---\nb: [\n---\nc: 3\n");
+/// assert_eq!(documents.next().unwrap().unwrap().syntax().to_string(), "a: 1");
+/// assert!(documents.next().unwrap().is_err());
+/// assert_eq!(documents.next().unwrap().unwrap().syntax().to_string(), "---\nc: 3");
+/// assert!(documents.next().is_none());
+/// ```
+pub fn parse_documents(code: &str) -> Documents<'_> {
+    Documents {
+        code: code.trim_start_matches('\u{feff}'),
+        offset: 0,
+    }
+}
+
+/// Iterator of documents in YAML stream, created by [`parse_documents`].
+pub struct Documents<'s> {
+    code: &'s str,
+    offset: usize,
+}
+
+impl Documents<'_> {
+    /// Find the end of the next document.
+    fn next_document_end(&self) -> usize {
+        let mut has_content = false;
+        let mut offset = self.offset;
+        for line in self.code[self.offset..].split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let text = line.trim_end_matches(['\n', '\r']);
+            if is_marker(text, "---") {
+                if has_content {
+                    return start;
+                }
+                has_content = true;
+            } else if is_marker(text, "...") {
+                return offset;
+            } else if is_content(text) && !text.starts_with('%') {
+                has_content = true;
+            }
+        }
+        offset
+    }
+
+    /// Check if there're only comments or whitespaces after the given offset.
+    fn is_trivia_only(&self, offset: usize) -> bool {
+        !self.code[offset..]
+            .lines()
+            .any(|line| is_content(line) || line.starts_with('%'))
+    }
+
+    /// Get the range of next document, including comments and whitespaces around it.
+    fn next_range(&mut self) -> Option<Range<usize>> {
+        if self.offset >= self.code.len() {
+            return None;
+        }
+        let mut end = self.next_document_end();
+        if self.is_trivia_only(end) {
+            // keep trailing comments with the last document instead of dropping them
+            end = self.code.len();
+        }
+        let start = self.offset;
+        self.offset = end;
+        Some(start..end)
+    }
+}
+
+/// Split YAML stream into byte ranges of documents without parsing them.
+/// The ranges are contiguous and cover the whole code.
+pub(crate) fn split_documents(code: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut documents = Documents { code, offset: 0 };
+    std::iter::from_fn(move || documents.next_range())
+}
+
+impl Iterator for Documents<'_> {
+    type Item = Result<Document, SyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(range) = self.next_range() {
+            match parse(&self.code[range]) {
+                Ok(tree) => {
+                    // skip text that only contains comments or whitespaces
+                    if let Some(document) = tree.children().find_map(Document::cast) {
+                        return Some(Ok(document));
+                    }
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+fn is_content(line: &str) -> bool {
+    let line = line.trim_start();
+    !line.is_empty() && !line.starts_with('#')
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
@@ -1,11 +1,11 @@
 use crate::Input;
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, sync::Arc};
 use winnow::error::{ContextError, ParseError};
 
 #[derive(Clone, Debug)]
 /// Error type for syntax errors.
 pub struct SyntaxError {
-    input: String,
+    input: Arc<str>,
     offset: usize,
     message: String,
     code_frame: String,
@@ -32,6 +32,35 @@ impl SyntaxError {
     }
 }
 
+impl SyntaxError {
+    /// Move the error that is from parsing part of the whole input,
+    /// which starts at `base`, to the whole input.
+    pub(crate) fn relocate(self, base: usize, input: &Arc<str>) -> Self {
+        let offset = base + self.offset;
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let line_num = input[..line_start].matches('\n').count() + 1;
+        let column = input[line_start..offset].chars().count();
+        let gutter = " ".repeat(line_num.to_string().len());
+        // same format as the code frame from parser
+        let code_frame = format!(
+            "parse error at line {line_num}, column {}\n{gutter} |\n{line_num} | {}\n{gutter} | {}^\n{}",
+            column + 1,
+            &input[line_start..line_end],
+            " ".repeat(column),
+            self.message,
+        );
+        Self {
+            input: Arc::clone(input),
+            offset,
+            message: self.message,
+            code_frame,
+        }
+    }
+}
+
 impl fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.code_frame)
@@ -41,7 +70,7 @@ impl fmt::Display for SyntaxError {
 impl<'s> From<ParseError<Input<'s>, ContextError>> for SyntaxError {
     fn from(err: ParseError<Input<'s>, ContextError>) -> Self {
         Self {
-            input: err.input().to_string(),
+            input: Arc::from(err.input().input),
             offset: err.offset(),
             message: err.inner().to_string(),
             code_frame: err.to_string(),
@@ -0,0 +1,231 @@
+use crate::{
+    ast::{AstNode, Block, Document, Flow, Root},
+    node, tok, GreenElement,
+    SyntaxKind::{self, *},
+    SyntaxNode,
+};
+use rowan::{GreenNode, GreenToken, NodeOrToken};
+use std::fmt::Write;
+
+/// Build syntax trees without parsing source code.
+///
+/// Trees built by this factory have the same structure as trees produced by the parser,
+/// so they can be consumed by anything that accepts parsed trees, such as formatter.
+/// Block collections are indented by two spaces.
+pub struct SyntaxFactory;
+
+impl SyntaxFactory {
+    /// Create plain scalar with the given text.
+    ///
+    /// It's caller's responsibility to make sure the text can be represented as plain scalar.
+    pub fn plain_scalar(text: &str) -> Flow {
+        cast(node(FLOW, [tok(PLAIN_SCALAR, text)]))
+    }
+
+    /// Create double quoted scalar with the given value.
+    /// Special characters will be escaped.
+    pub fn double_quoted_scalar(value: &str) -> Flow {
+        let mut text = String::with_capacity(value.len() + 2);
+        text.push('"');
+        value.chars().for_each(|c| match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\t' => text.push_str("\\t"),
+            '\r' => text.push_str("\\r"),
+            '\0' => text.push_str("\\0"),
+            c if c.is_control() => {
+                let _ = write!(text, "\\u{:04x}", c as u32);
+            }
+            c => text.push(c),
+        });
+        text.push('"');
+        cast(node(FLOW, [tok(DOUBLE_QUOTED_SCALAR, &text)]))
+    }
+
+    /// Create flow sequence with the given items.
+    pub fn flow_seq(items: impl IntoIterator<Item = Flow>) -> Flow {
+        let entries = separated(
+            items
+                .into_iter()
+                .map(|item| node(FLOW_SEQ_ENTRY, [green(item.syntax())])),
+            |children| {
+                children.push(tok(COMMA, ","));
+                children.push(tok(WHITESPACE, " "));
+            },
+        );
+        cast(node(
+            FLOW,
+            [node(
+                FLOW_SEQ,
+                [
+                    tok(L_BRACKET, "["),
+                    node(FLOW_SEQ_ENTRIES, entries),
+                    tok(R_BRACKET, "]"),
+                ],
+            )],
+        ))
+    }
+
+    /// Create flow map with the given keys and values.
+    pub fn flow_map(entries: impl IntoIterator<Item = (Flow, Flow)>) -> Flow {
+        let entries = separated(
+            entries.into_iter().map(|(key, value)| {
+                node(
+                    FLOW_MAP_ENTRY,
+                    [
+                        node(FLOW_MAP_KEY, [green(key.syntax())]),
+                        tok(COLON, ":"),
+                        tok(WHITESPACE, " "),
+                        node(FLOW_MAP_VALUE, [green(value.syntax())]),
+                    ],
+                )
+            }),
+            |children| {
+                children.push(tok(COMMA, ","));
+                children.push(tok(WHITESPACE, " "));
+            },
+        );
+        cast(node(
+            FLOW,
+            [node(
+                FLOW_MAP,
+                [
+                    tok(L_BRACE, "{"),
+                    node(FLOW_MAP_ENTRIES, entries),
+                    tok(R_BRACE, "}"),
+                ],
+            )],
+        ))
+    }
+
+    /// Create block sequence with the given items.
+    /// Each item must be either [`Block`] or [`Flow`] node.
+    pub fn block_seq(items: impl IntoIterator<Item = SyntaxNode>) -> Block {
+        let entries = separated(
+            items.into_iter().map(|item| {
+                debug_assert!(matches!(item.kind(), BLOCK | FLOW));
+                node(
+                    BLOCK_SEQ_ENTRY,
+                    [
+                        tok(MINUS, "-"),
+                        tok(WHITESPACE, " "),
+                        NodeOrToken::Node(indent(item.green().into_owned(), 2)),
+                    ],
+                )
+            }),
+            |children| children.push(tok(WHITESPACE, "\n")),
+        );
+        cast(node(BLOCK, [node(BLOCK_SEQ, entries)]))
+    }
+
+    /// Create block map with the given keys and values.
+    /// Each value must be either [`Block`] or [`Flow`] node.
+    pub fn block_map(entries: impl IntoIterator<Item = (Flow, SyntaxNode)>) -> Block {
+        let entries = separated(
+            entries.into_iter().map(|(key, value)| {
+                debug_assert!(matches!(value.kind(), BLOCK | FLOW));
+                let (whitespace, value) = if value.kind() == BLOCK {
+                    ("\n  ", indent(value.green().into_owned(), 2))
+                } else {
+                    (" ", value.green().into_owned())
+                };
+                node(
+                    BLOCK_MAP_ENTRY,
+                    [
+                        node(BLOCK_MAP_KEY, [green(key.syntax())]),
+                        tok(COLON, ":"),
+                        tok(WHITESPACE, whitespace),
+                        node(BLOCK_MAP_VALUE, [NodeOrToken::Node(value)]),
+                    ],
+                )
+            }),
+            |children| children.push(tok(WHITESPACE, "\n")),
+        );
+        cast(node(BLOCK, [node(BLOCK_MAP, entries)]))
+    }
+
+    /// Create document with the given content, which must be either [`Block`] or [`Flow`] node.
+    pub fn document(content: SyntaxNode) -> Document {
+        debug_assert!(matches!(content.kind(), BLOCK | FLOW));
+        cast(node(DOCUMENT, [green(&content)]))
+    }
+
+    /// Create root with the given documents.
+    /// Document start marker (`---`) will be added to documents except the first one.
+    pub fn root(documents: impl IntoIterator<Item = Document>) -> Root {
+        let mut children = separated(
+            documents.into_iter().enumerate().map(|(i, document)| {
+                let document = document.syntax().green().into_owned();
+                if i > 0
+                    && document
+                        .children()
+                        .all(|child| child.kind() != DIRECTIVES_END.into())
+                {
+                    let mut children = vec![tok(DIRECTIVES_END, "---"), tok(WHITESPACE, "\n")];
+                    children.extend(document.children().map(|child| child.to_owned()));
+                    node(DOCUMENT, children)
+                } else {
+                    NodeOrToken::Node(document)
+                }
+            }),
+            |children| children.push(tok(WHITESPACE, "\n")),
+        );
+        children.push(tok(WHITESPACE, "\n"));
+        cast(node(ROOT, children))
+    }
+}
+
+fn cast<N: AstNode>(element: GreenElement) -> N {
+    let NodeOrToken::Node(green) = element else {
+        unreachable!("expected node");
+    };
+    N::cast(SyntaxNode::new_root(green)).expect("unexpected node kind")
+}
+
+fn green(node: &SyntaxNode) -> GreenElement {
+    NodeOrToken::Node(node.green().into_owned())
+}
+
+/// Collect elements with separators between them.
+fn separated(
+    elements: impl Iterator<Item = GreenElement>,
+    separator: impl Fn(&mut Vec<GreenElement>),
+) -> Vec<GreenElement> {
+    let mut children = vec![];
+    elements.enumerate().for_each(|(i, element)| {
+        if i > 0 {
+            separator(&mut children);
+        }
+        children.push(element);
+    });
+    children
+}
+
+/// Add indentation after line breaks in whitespaces.
+/// Trees built by factory don't contain multi-line scalars, so only whitespaces need to be handled.
+pub(crate) fn indent(green: GreenNode, width: usize) -> GreenNode {
+    if !green.to_string().contains('\n') {
+        return green;
+    }
+    let indentation = format!("\n{}", " ".repeat(width));
+    GreenNode::new(
+        green.kind(),
+        green
+            .children()
+            .map(|child| match child {
+                NodeOrToken::Node(node) => NodeOrToken::Node(indent(node.to_owned(), width)),
+                NodeOrToken::Token(token)
+                    if token.kind() == SyntaxKind::WHITESPACE.into()
+                        && token.text().contains('\n') =>
+                {
+                    NodeOrToken::Token(GreenToken::new(
+                        token.kind(),
+                        &token.text().replace('\n', &indentation),
+                    ))
+                }
+                NodeOrToken::Token(token) => NodeOrToken::Token(token.to_owned()),
+            })
+            .collect::<Vec<_>>(),
+    )
+}
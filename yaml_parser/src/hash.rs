@@ -0,0 +1,136 @@
+use crate::{SyntaxKind, SyntaxNode};
+use rowan::NodeOrToken;
+use std::{fmt, hash::Hasher};
+
+/// Identifier of a node, derived from its position in syntax tree.
+///
+/// It's computed from kinds of the node and its ancestors
+/// and the index of each of them among sibling nodes,
+/// so it stays the same when whitespaces, comments or text of scalars change,
+/// and it's different for two nodes in the same tree.
+/// See [`SyntaxNodeExt::node_id`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub u64);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Extra methods of [`SyntaxNode`] for caching and change detection.
+///
+/// Values are computed by FNV-1a hash function,
+/// so they're stable across runs, platforms and versions of Rust compiler,
+/// and can be persisted.
+///
+/// ```
+/// use yaml_parser::SyntaxNodeExt;
+///
+/// let a = yaml_parser::parse("key: [1, 2] # comment\n").unwrap();
+/// let b = yaml_parser::parse("key:   [ 1,2 ]\n").unwrap();
+/// assert_eq!(a.structural_hash(), b.structural_hash());
+/// assert_eq!(a.node_id(), b.node_id());
+///
+/// let c = yaml_parser::parse("key: [1, 3]\n").unwrap();
+/// assert_ne!(a.structural_hash(), c.structural_hash());
+/// ```
+pub trait SyntaxNodeExt {
+    /// Hash kind and content of the node, ignoring whitespaces and comments.
+    ///
+    /// Two nodes have the same hash if they have the same structure and the same text of tokens,
+    /// so it can tell whether a subtree changes semantically.
+    /// Position of the node isn't taken into account.
+    fn structural_hash(&self) -> u64;
+
+    /// Compute identifier of the node by its path from the root of syntax tree.
+    fn node_id(&self) -> NodeId;
+}
+
+impl SyntaxNodeExt for SyntaxNode {
+    fn structural_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hash_node(self, &mut hasher);
+        hasher.finish()
+    }
+
+    fn node_id(&self) -> NodeId {
+        let mut path = self
+            .ancestors()
+            .map(|node| {
+                let index = node
+                    .parent()
+                    .map(|parent| {
+                        parent
+                            .children()
+                            .position(|sibling| sibling == node)
+                            .unwrap_or_default()
+                    })
+                    .unwrap_or_default();
+                (node.kind(), index)
+            })
+            .collect::<Vec<_>>();
+        path.reverse();
+
+        let mut hasher = FnvHasher::default();
+        path.into_iter().for_each(|(kind, index)| {
+            hasher.write_u16(kind as u16);
+            hasher.write_usize(index);
+        });
+        NodeId(hasher.finish())
+    }
+}
+
+fn hash_node(node: &SyntaxNode, hasher: &mut FnvHasher) {
+    hasher.write_u8(NODE_START);
+    hasher.write_u16(node.kind() as u16);
+    node.children_with_tokens().for_each(|child| match child {
+        NodeOrToken::Node(node) => hash_node(&node, hasher),
+        NodeOrToken::Token(token) => {
+            if !matches!(token.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT) {
+                let text = token.text();
+                hasher.write_u8(TOKEN);
+                hasher.write_u16(token.kind() as u16);
+                hasher.write_usize(text.len());
+                hasher.write(text.as_bytes());
+            }
+        }
+    });
+    hasher.write_u8(NODE_END);
+}
+
+// Markers that separate nodes and tokens,
+// so different trees with the same sequence of tokens have different hashes.
+const NODE_START: u8 = 0;
+const NODE_END: u8 = 1;
+const TOKEN: u8 = 2;
+
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        bytes.iter().for_each(|byte| {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        });
+    }
+
+    // Integers are always hashed in little endian to make hashes the same on all platforms.
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,743 @@
+use crate::{
+    detect_base_indent, detect_ws_indent, is_flow_indicator, is_indicator, is_tag_char,
+    is_url_char, is_word_char, SyntaxKind,
+};
+use std::{collections::VecDeque, ops::Range};
+
+/// Split the given YAML code into tokens without building syntax tree,
+/// which is much cheaper than [`parse`](crate::parse) for syntax highlighting or simple linting.
+///
+/// Each item is the kind of token and its byte range in the code.
+/// Token kinds are the same as tokens in the tree produced by [`parse`](crate::parse),
+/// except that consecutive whitespaces are always yielded as one token
+/// and empty tokens are skipped.
+/// Leading byte order mark isn't part of any tokens.
+///
+/// The lexer tracks indentation and flow collections to tell plain scalars,
+/// block scalars and indicators apart, but it doesn't validate the structure,
+/// so it never fails: text that can't start any tokens is yielded as
+/// [`ERROR`](SyntaxKind::ERROR).
+///
+/// ```
+/// use yaml_parser::{lex, SyntaxKind};
+///
+/// let code = "key: [a, b] # comment\n";
+/// let tokens = lex(code)
+///     .map(|(kind, range)| (kind, &code[range]))
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     tokens,
+///     [
+///         (SyntaxKind::PLAIN_SCALAR, "key"),
+///         (SyntaxKind::COLON, ":"),
+///         (SyntaxKind::WHITESPACE, " "),
+///         (SyntaxKind::L_BRACKET, "["),
+///         (SyntaxKind::PLAIN_SCALAR, "a"),
+///         (SyntaxKind::COMMA, ","),
+///         (SyntaxKind::WHITESPACE, " "),
+///         (SyntaxKind::PLAIN_SCALAR, "b"),
+///         (SyntaxKind::R_BRACKET, "]"),
+///         (SyntaxKind::WHITESPACE, " "),
+///         (SyntaxKind::COMMENT, "# comment"),
+///         (SyntaxKind::WHITESPACE, "\n"),
+///     ]
+/// );
+/// ```
+pub fn lex(code: &str) -> impl Iterator<Item = (SyntaxKind, Range<usize>)> + '_ {
+    Lexer::new(code)
+}
+
+/// State of lexer mirrors state of parser as much as needed,
+/// since the parser decides where plain scalars and block scalars end
+/// by indentation and context.
+struct Lexer<'s> {
+    code: &'s str,
+    /// Length of byte order mark which is stripped from `code`.
+    offset: usize,
+    pos: usize,
+    tokens: VecDeque<(SyntaxKind, Range<usize>)>,
+    flow_depth: usize,
+    indent: usize,
+    prev_indent: Option<usize>,
+    last_ws_has_nl: bool,
+    document_top: bool,
+    top_level_map: bool,
+    /// Kind and end of the last token that isn't whitespace or comment.
+    last_kind: Option<SyntaxKind>,
+    last_end: usize,
+    /// Column of explicit key of block map entry which hasn't been paired with value.
+    explicit_key: Option<usize>,
+    /// Whether the last anchor name belongs to anchor property rather than alias.
+    last_is_anchor: bool,
+}
+
+impl<'s> Lexer<'s> {
+    fn new(code: &'s str) -> Self {
+        let stripped = code.trim_start_matches('\u{feff}');
+        Lexer {
+            code: stripped,
+            offset: code.len() - stripped.len(),
+            pos: 0,
+            tokens: VecDeque::new(),
+            flow_depth: 0,
+            indent: detect_base_indent(stripped).unwrap_or_default(),
+            prev_indent: None,
+            last_ws_has_nl: false,
+            document_top: true,
+            top_level_map: false,
+            last_kind: None,
+            last_end: 0,
+            last_is_anchor: false,
+            explicit_key: None,
+        }
+    }
+
+    fn push(&mut self, kind: SyntaxKind, end: usize) {
+        if end > self.pos {
+            self.tokens
+                .push_back((kind, self.offset + self.pos..self.offset + end));
+            if !matches!(kind, SyntaxKind::WHITESPACE | SyntaxKind::COMMENT) {
+                self.last_kind = Some(kind);
+                self.last_end = end;
+            }
+            self.pos = end;
+        }
+    }
+
+    fn char_at(&self, pos: usize) -> Option<char> {
+        self.code.get(pos..).and_then(|rest| rest.chars().next())
+    }
+
+    fn is_line_start(&self, pos: usize) -> bool {
+        pos == 0 || self.code[..pos].ends_with(['\n', '\r'])
+    }
+
+    fn column(&self, pos: usize) -> usize {
+        self.code[..pos]
+            .rfind(['\n', '\r'])
+            .map_or(pos, |index| pos - index - 1)
+    }
+
+    /// Check if there're only spaces before the position in the same line.
+    fn is_indentation(&self, pos: usize) -> bool {
+        self.code[..pos]
+            .rsplit(['\n', '\r'])
+            .next()
+            .is_some_and(|line| line.trim_start_matches([' ', '\t']).is_empty())
+    }
+
+    /// Check if colon at the position is value indicator of explicit entry.
+    fn is_explicit_value(&self, pos: usize) -> bool {
+        self.explicit_key == Some(self.column(pos)) && self.is_indentation(pos)
+    }
+
+    /// Lex tokens of the next construct, which may be more than one token.
+    fn lex_next(&mut self) {
+        let start = self.pos;
+        let Some(c) = self.char_at(start) else {
+            return;
+        };
+        let next = self.char_at(start + c.len_utf8());
+        let is_block = self.flow_depth == 0;
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                let end = take_while(self.code, start, |c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+                if is_block {
+                    if let Some(indent) = detect_ws_indent(&self.code[start..end]) {
+                        self.indent = indent;
+                        self.last_ws_has_nl = true;
+                    } else {
+                        self.last_ws_has_nl = false;
+                    }
+                }
+                self.push(SyntaxKind::WHITESPACE, end);
+            }
+            '#' => self.push(SyntaxKind::COMMENT, till_line_ending(self.code, start)),
+            '-' | '.' if is_block && self.is_document_marker(start) => {
+                let kind = if c == '-' {
+                    SyntaxKind::DIRECTIVES_END
+                } else {
+                    SyntaxKind::DOCUMENT_END
+                };
+                self.push(kind, start + 3);
+                self.document_top = true;
+                self.top_level_map = false;
+                self.explicit_key = None;
+                self.prev_indent = None;
+            }
+            '%' if is_block && self.is_line_start(start) => self.directive(),
+            '"' => {
+                let mut chars = self.code[start + 1..].char_indices();
+                let mut end = None;
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            end = Some(start + 1 + i + 1);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                self.quoted(SyntaxKind::DOUBLE_QUOTED_SCALAR, end);
+            }
+            '\'' => {
+                let mut end = None;
+                let mut i = start + 1;
+                while let Some(offset) = self.code[i..].find('\'') {
+                    i += offset + 1;
+                    if self.code[i..].starts_with('\'') {
+                        i += 1;
+                    } else {
+                        end = Some(i);
+                        break;
+                    }
+                }
+                self.quoted(SyntaxKind::SINGLE_QUOTED_SCALAR, end);
+            }
+            '[' | '{' => {
+                self.flow_depth += 1;
+                let kind = if c == '[' {
+                    SyntaxKind::L_BRACKET
+                } else {
+                    SyntaxKind::L_BRACE
+                };
+                self.push(kind, start + 1);
+            }
+            ']' | '}' if !is_block => {
+                self.flow_depth -= 1;
+                let kind = if c == ']' {
+                    SyntaxKind::R_BRACKET
+                } else {
+                    SyntaxKind::R_BRACE
+                };
+                self.push(kind, start + 1);
+            }
+            ',' if !is_block => self.push(SyntaxKind::COMMA, start + 1),
+            '&' | '*' => {
+                let kind = if c == '&' {
+                    SyntaxKind::AMPERSAND
+                } else {
+                    SyntaxKind::ASTERISK
+                };
+                self.push(kind, start + 1);
+                let end = anchor_name_end(self.code, self.pos);
+                self.push(SyntaxKind::ANCHOR_NAME, end);
+                self.last_is_anchor = c == '&';
+            }
+            '!' => self.tag(),
+            '|' | '>' if is_block => self.block_scalar(),
+            '-' | '?' if is_block && next.is_none_or(|c| c.is_ascii_whitespace() || c == '#') => {
+                let kind = if c == '-' {
+                    SyntaxKind::MINUS
+                } else {
+                    SyntaxKind::QUESTION_MARK
+                };
+                self.block_indicator(kind);
+            }
+            '?' if next.is_some_and(|c| c.is_ascii_whitespace()) => {
+                self.push(SyntaxKind::QUESTION_MARK, start + 1)
+            }
+            ':' if self.is_colon(start, next) => {
+                if is_block {
+                    self.block_indicator(SyntaxKind::COLON);
+                } else {
+                    self.push(SyntaxKind::COLON, start + 1);
+                }
+            }
+            c if is_plain_start(c, next) => self.plain_scalar(),
+            c => self.push(SyntaxKind::ERROR, start + c.len_utf8()),
+        }
+    }
+
+    fn quoted(&mut self, kind: SyntaxKind, end: Option<usize>) {
+        match end {
+            Some(end) => self.push(kind, end),
+            None => self.push(SyntaxKind::ERROR, self.code.len()),
+        }
+    }
+
+    /// Colon is an indicator if it's followed by whitespace.
+    /// In flow context, it's also an indicator after a node.
+    /// In block context, it's an indicator after JSON-like node in the same line,
+    /// or at the start of line after explicit key.
+    fn is_colon(&self, pos: usize, next: Option<char>) -> bool {
+        if next
+            .is_none_or(|c| c.is_ascii_whitespace() || self.flow_depth > 0 && is_flow_indicator(c))
+        {
+            true
+        } else if self.flow_depth > 0 {
+            self.is_after_node()
+        } else {
+            matches!(
+                self.last_kind,
+                Some(
+                    SyntaxKind::DOUBLE_QUOTED_SCALAR
+                        | SyntaxKind::SINGLE_QUOTED_SCALAR
+                        | SyntaxKind::R_BRACKET
+                        | SyntaxKind::R_BRACE
+                )
+            ) && self.last_end == self.code[..pos].trim_end_matches([' ', '\t']).len()
+                || self.is_explicit_value(pos)
+        }
+    }
+
+    /// Check if the last token that isn't whitespace or comment is the end of node,
+    /// rather than indicators or properties which expect following content.
+    fn is_after_node(&self) -> bool {
+        match self.last_kind {
+            Some(
+                SyntaxKind::COLON
+                | SyntaxKind::MINUS
+                | SyntaxKind::QUESTION_MARK
+                | SyntaxKind::COMMA
+                | SyntaxKind::L_BRACKET
+                | SyntaxKind::L_BRACE
+                | SyntaxKind::TAG_CHAR
+                | SyntaxKind::VERBATIM_TAG
+                | SyntaxKind::EXCLAMATION_MARK,
+            ) => false,
+            Some(SyntaxKind::ANCHOR_NAME) => !self.last_is_anchor,
+            _ => true,
+        }
+    }
+
+    /// `---` and `...` are document markers at the start of line,
+    /// unless the line is the key of top-level block map entry.
+    /// They're also document markers right after document ends,
+    /// such as after another document marker or after a node.
+    /// After properties, they're document markers only if content can't be in that line.
+    fn is_document_marker(&self, pos: usize) -> bool {
+        let rest = &self.code[pos..];
+        if !(rest.starts_with("...")
+            || rest.starts_with("---") && rest[3..].starts_with(|c: char| c.is_ascii_whitespace()))
+        {
+            return false;
+        }
+        if matches!(
+            self.last_kind,
+            Some(SyntaxKind::TAG_CHAR | SyntaxKind::VERBATIM_TAG | SyntaxKind::EXCLAMATION_MARK)
+        ) || self.last_kind == Some(SyntaxKind::ANCHOR_NAME) && self.last_is_anchor
+        {
+            // properties at top level can be followed by content in the next line,
+            // otherwise the content must be indented
+            !self.document_top && self.is_line_start(pos)
+        } else if self.is_line_start(pos) {
+            !(self.top_level_map && !self.document_top && self.is_implicit_key(pos))
+        } else if self.is_indentation(pos) && !self.document_top && self.is_implicit_key(pos) {
+            false
+        } else {
+            // indicators of block collections must be followed by whitespace if there's content
+            self.is_after_node() || self.last_end == pos
+        }
+    }
+
+    /// Indicators of block sequence entry and block map entry.
+    fn block_indicator(&mut self, kind: SyntaxKind) {
+        let start = self.pos;
+        let explicit = match kind {
+            SyntaxKind::QUESTION_MARK => {
+                self.explicit_key = Some(self.column(start));
+                true
+            }
+            SyntaxKind::COLON => {
+                let explicit = self.is_explicit_value(start);
+                if explicit {
+                    self.explicit_key = None;
+                }
+                explicit
+            }
+            _ => true,
+        };
+        if self.document_top && kind != SyntaxKind::MINUS {
+            self.top_level_map = true;
+        }
+        self.document_top = false;
+        self.prev_indent = Some(self.indent);
+        self.push(kind, start + 1);
+
+        // indentation of compact collection is the column after spaces,
+        // such as the column of `b` in `- b: c`
+        let spaces_end = take_while(self.code, self.pos, |c| c == ' ' || c == '\t');
+        if explicit && spaces_end > self.pos && self.is_compact_collection(spaces_end) {
+            self.indent += spaces_end - self.pos + 1;
+            self.last_ws_has_nl = false;
+            self.push(SyntaxKind::WHITESPACE, spaces_end);
+        }
+    }
+
+    fn is_compact_collection(&self, pos: usize) -> bool {
+        match self.char_at(pos) {
+            Some('-' | '?') => self
+                .char_at(pos + 1)
+                .is_none_or(|c| c.is_ascii_whitespace()),
+            Some(':') => true,
+            _ => self.is_implicit_key(pos),
+        }
+    }
+
+    /// Check if there's a key of block map entry at the position.
+    fn is_implicit_key(&self, pos: usize) -> bool {
+        scan_key(self.code, pos).is_some_and(|end| {
+            let end = take_while(self.code, end, |c| c == ' ' || c == '\t');
+            self.code[end..].starts_with(':')
+        })
+    }
+
+    fn plain_scalar(&mut self) {
+        let start = self.pos;
+        let in_flow = self.flow_depth > 0;
+        let first_len = self.char_at(start).map_or(0, char::len_utf8);
+        let mut end = plain_scalar_chars(self.code, start + first_len, in_flow);
+        let is_key = !in_flow && {
+            let after = take_while(self.code, end, |c| c == ' ' || c == '\t');
+            self.code[after..].starts_with(':')
+        };
+        if !is_key {
+            loop {
+                let ws_end = take_while(self.code, end, |c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+                let ws = &self.code[end..ws_end];
+                let rest = &self.code[ws_end..];
+                let Some(c) = rest.chars().next() else {
+                    break;
+                };
+                if ws.is_empty()
+                    || matches!(c, '\n' | '\r' | '#')
+                    || in_flow && is_flow_indicator(c)
+                    || c == ':'
+                        && rest[1..].chars().next().is_some_and(|c| {
+                            c.is_ascii_whitespace() || in_flow && is_flow_indicator(c)
+                        })
+                {
+                    break;
+                }
+                if (rest.starts_with("---") || rest.starts_with("..."))
+                    && rest[3..].starts_with([' ', '\t', '\n', '\r'])
+                {
+                    if ws.ends_with(['\n', '\r']) {
+                        break;
+                    }
+                } else if let Some(detected) = detect_ws_indent(ws) {
+                    let is_continued = if self.last_ws_has_nl {
+                        detected >= self.indent
+                    } else {
+                        detected > self.indent || self.document_top
+                    };
+                    if !is_continued {
+                        break;
+                    }
+                }
+                end = plain_scalar_chars(self.code, ws_end, in_flow);
+            }
+        }
+        self.push(SyntaxKind::PLAIN_SCALAR, end);
+    }
+
+    fn block_scalar(&mut self) {
+        let base_indent = self.prev_indent.unwrap_or(self.indent);
+        let kind = if self.code[self.pos..].starts_with('|') {
+            SyntaxKind::BAR
+        } else {
+            SyntaxKind::GREATER_THAN
+        };
+        self.push(kind, self.pos + 1);
+
+        let mut indent_indicator = None;
+        for _ in 0..2 {
+            match self.char_at(self.pos) {
+                Some(c @ '0'..='9') if indent_indicator.is_none() => {
+                    indent_indicator = c.to_digit(10).map(|value| value as usize);
+                    self.push(SyntaxKind::INDENT_INDICATOR, self.pos + 1);
+                }
+                Some('+') => self.push(SyntaxKind::PLUS, self.pos + 1),
+                Some('-') => self.push(SyntaxKind::MINUS, self.pos + 1),
+                _ => break,
+            }
+        }
+        let end = take_while(self.code, self.pos, |c| c == ' ' || c == '\t');
+        self.push(SyntaxKind::WHITESPACE, end);
+        if self.code[self.pos..].starts_with('#') {
+            self.push(SyntaxKind::COMMENT, till_line_ending(self.code, self.pos));
+        }
+
+        let indent = indent_indicator
+            .map(|value| base_indent + value)
+            .unwrap_or_else(|| {
+                let end = linebreaks_or_spaces(self.code, self.pos);
+                detect_ws_indent(&self.code[self.pos..end]).unwrap_or_default()
+            });
+        if indent <= base_indent && !self.document_top {
+            return;
+        }
+        let mut end = self.pos;
+        loop {
+            let ws_end = linebreaks_or_spaces(self.code, end);
+            let ws = &self.code[end..ws_end];
+            if detect_ws_indent(ws).is_none_or(|detected| detected < indent) {
+                break;
+            }
+            let line_end = till_line_ending(self.code, ws_end);
+            let line = &self.code[ws_end..line_end];
+            if line.is_empty() || ws.ends_with(['\n', '\r']) && (line == "..." || line == "---") {
+                break;
+            }
+            end = line_end;
+        }
+        self.push(SyntaxKind::BLOCK_SCALAR_TEXT, end);
+    }
+
+    fn tag(&mut self) {
+        let start = self.pos;
+        let rest = &self.code[start..];
+        if let Some(verbatim) = rest.strip_prefix("!<") {
+            let url_end = take_while(verbatim, 0, is_url_char);
+            if url_end > 0 && verbatim[url_end..].starts_with('>') {
+                self.push(SyntaxKind::VERBATIM_TAG, start + 2 + url_end + 1);
+            } else {
+                self.push(SyntaxKind::ERROR, start + 2 + url_end);
+            }
+            return;
+        }
+        let (kind, handle_end) =
+            tag_handle(self.code, start).unwrap_or((SyntaxKind::TAG_HANDLE_PRIMARY, start + 1));
+        let end = take_while(self.code, handle_end, is_tag_char);
+        if end > handle_end {
+            self.push(kind, handle_end);
+            self.push(SyntaxKind::TAG_CHAR, end);
+        } else {
+            self.push(SyntaxKind::EXCLAMATION_MARK, start + 1);
+        }
+    }
+
+    fn directive(&mut self) {
+        self.push(SyntaxKind::PERCENT, self.pos + 1);
+        let start = self.pos;
+        let code = self.code;
+        let rest = &code[start..];
+        let space_after = |pos: usize| take_while(code, pos, |c| c == ' ' || c == '\t');
+
+        if rest.starts_with("YAML") {
+            let space_end = space_after(start + 4);
+            let major_end = take_while(code, space_end, |c| c.is_ascii_digit());
+            let minor_end = code[major_end..]
+                .starts_with('.')
+                .then(|| take_while(code, major_end + 1, |c| c.is_ascii_digit()))
+                .filter(|end| *end > major_end + 1);
+            if let Some(minor_end) =
+                minor_end.filter(|_| space_end > start + 4 && major_end > space_end)
+            {
+                self.push(SyntaxKind::DIRECTIVE_NAME, start + 4);
+                self.push(SyntaxKind::WHITESPACE, space_end);
+                self.push(SyntaxKind::YAML_VERSION, minor_end);
+                return;
+            }
+        }
+        if rest.starts_with("TAG") {
+            let space_end = space_after(start + 3);
+            let handle = tag_handle(code, space_end);
+            let handle_end = handle.map_or(space_end, |(_, end)| end);
+            let prefix_start = space_after(handle_end);
+            if let Some((handle_kind, handle_end)) = handle.filter(|_| {
+                space_end > start + 3
+                    && prefix_start > handle_end
+                    && code[prefix_start..].starts_with(|c| c == '!' || is_tag_char(c))
+            }) {
+                self.push(SyntaxKind::DIRECTIVE_NAME, start + 3);
+                self.push(SyntaxKind::WHITESPACE, space_end);
+                self.push(handle_kind, handle_end);
+                self.push(SyntaxKind::WHITESPACE, prefix_start);
+                let end = take_while(code, prefix_start + 1, is_url_char);
+                self.push(SyntaxKind::TAG_PREFIX, end);
+                return;
+            }
+        }
+
+        let name_end = take_while(code, start, |c| !c.is_ascii_whitespace());
+        self.push(SyntaxKind::DIRECTIVE_NAME, name_end);
+        let space_end = space_after(name_end);
+        if name_end > start && space_end > name_end {
+            self.push(SyntaxKind::WHITESPACE, space_end);
+            let mut end = space_end;
+            loop {
+                let word_end = take_while(code, end, |c| !c.is_ascii_whitespace());
+                let space_end = space_after(word_end);
+                if word_end > end {
+                    end = word_end;
+                } else if space_end > end && !code[space_end..].starts_with('#') {
+                    end = space_end;
+                } else {
+                    break;
+                }
+            }
+            self.push(SyntaxKind::DIRECTIVE_PARAM, end);
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = (SyntaxKind, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.tokens.is_empty() && self.pos < self.code.len() {
+            self.lex_next();
+        }
+        let (kind, mut range) = self.tokens.pop_front()?;
+        if kind == SyntaxKind::WHITESPACE {
+            // whitespace after block scalar header may be followed by line breaks
+            if self.tokens.is_empty() && self.pos < self.code.len() {
+                self.lex_next();
+            }
+            if let Some((SyntaxKind::WHITESPACE, next)) = self.tokens.front() {
+                range.end = next.end;
+                self.tokens.pop_front();
+            }
+        }
+        Some((kind, range))
+    }
+}
+
+fn take_while(code: &str, pos: usize, predicate: impl Fn(char) -> bool) -> usize {
+    code[pos..]
+        .find(|c| !predicate(c))
+        .map_or(code.len(), |offset| pos + offset)
+}
+
+fn till_line_ending(code: &str, pos: usize) -> usize {
+    take_while(code, pos, |c| c != '\n' && c != '\r')
+}
+
+/// Without tabs.
+fn linebreaks_or_spaces(code: &str, pos: usize) -> usize {
+    take_while(code, pos, |c| c == ' ' || c == '\n' || c == '\r')
+}
+
+fn anchor_name_end(code: &str, pos: usize) -> usize {
+    take_while(code, pos, |c| {
+        !is_flow_indicator(c) && !c.is_ascii_whitespace()
+    })
+}
+
+fn tag_handle(code: &str, pos: usize) -> Option<(SyntaxKind, usize)> {
+    let rest = code[pos..].strip_prefix('!')?;
+    let word_end = take_while(rest, 0, is_word_char);
+    if word_end > 0 && rest[word_end..].starts_with('!') {
+        Some((SyntaxKind::TAG_HANDLE_NAMED, pos + word_end + 2))
+    } else if rest.starts_with('!') {
+        Some((SyntaxKind::TAG_HANDLE_SECONDARY, pos + 2))
+    } else {
+        Some((SyntaxKind::TAG_HANDLE_PRIMARY, pos + 1))
+    }
+}
+
+fn is_plain_start(c: char, next: Option<char>) -> bool {
+    if matches!(c, '-' | ':' | '?') {
+        next.is_some_and(|c| !c.is_ascii_whitespace() && !is_flow_indicator(c))
+    } else {
+        !c.is_ascii_whitespace() && !is_indicator(c)
+    }
+}
+
+/// Continue plain scalar in single line until it reaches `: `, ` #`, line break,
+/// or flow indicators in flow collections.
+fn plain_scalar_chars(code: &str, mut pos: usize, in_flow: bool) -> usize {
+    let is_stop = |c: char| c.is_ascii_whitespace() || in_flow && is_flow_indicator(c);
+    while let Some(c) = code[pos..].chars().next() {
+        if c == ':' {
+            if code[pos + 1..].chars().next().is_some_and(|c| !is_stop(c)) {
+                pos += 1;
+            } else {
+                break;
+            }
+        } else if c == ' ' || c == '\t' {
+            let end = take_while(code, pos, |c| c == ' ' || c == '\t');
+            let rest = &code[end..];
+            let stops = match rest.chars().next() {
+                None | Some('\n' | '\r' | '#') => true,
+                Some(':') => rest[1..].chars().next().is_some_and(is_stop),
+                Some(c) => in_flow && is_flow_indicator(c),
+            };
+            if stops {
+                break;
+            }
+            pos = end;
+        } else if is_stop(c) {
+            break;
+        } else {
+            pos = take_while(code, pos, |c| !is_stop(c) && c != ':');
+        }
+    }
+    pos
+}
+
+/// Find the end of node that may be a key of block map entry,
+/// without checking the colon after it.
+fn scan_key(code: &str, pos: usize) -> Option<usize> {
+    let rest = &code[pos..];
+    let c = rest.chars().next()?;
+    match c {
+        '*' => Some(anchor_name_end(code, pos + 1)),
+        '&' | '!' => {
+            let mut end = take_while(code, pos, |c| !c.is_ascii_whitespace());
+            let space_end = take_while(code, end, |c| c == ' ' || c == '\t');
+            if space_end > end && code[space_end..].starts_with(['&', '!']) {
+                end = take_while(code, space_end, |c| !c.is_ascii_whitespace());
+            }
+            let space_end = take_while(code, end, |c| c == ' ' || c == '\t');
+            if space_end > end {
+                if let Some(content_end) = scan_key(code, space_end) {
+                    return Some(content_end);
+                }
+            }
+            Some(end)
+        }
+        '"' | '\'' => {
+            let mut chars = rest.char_indices().skip(1);
+            while let Some((i, ch)) = chars.next() {
+                if ch == '\\' && c == '"' {
+                    chars.next();
+                } else if ch == c {
+                    if c == '\'' && rest[i + 1..].starts_with('\'') {
+                        chars.next();
+                    } else {
+                        return Some(pos + i + 1);
+                    }
+                }
+            }
+            None
+        }
+        '[' | '{' => {
+            let mut depth = 0;
+            let mut chars = rest.char_indices().peekable();
+            while let Some((i, ch)) = chars.next() {
+                match ch {
+                    '[' | '{' => depth += 1,
+                    ']' | '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(pos + i + 1);
+                        }
+                    }
+                    '"' | '\'' => {
+                        let end = scan_key(code, pos + i)?;
+                        while chars.peek().is_some_and(|(i, _)| pos + i < end) {
+                            chars.next();
+                        }
+                    }
+                    '#' if rest[..i].ends_with(|c: char| c.is_ascii_whitespace()) => {
+                        while chars.peek().is_some_and(|(_, c)| *c != '\n') {
+                            chars.next();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+        _ => {
+            let next = rest[c.len_utf8()..].chars().next();
+            is_plain_start(c, next).then(|| plain_scalar_chars(code, pos + c.len_utf8(), false))
+        }
+    }
+}
@@ -24,9 +24,23 @@
 //! assert!(matches!(ast, Some(Root { .. })));
 //! ```
 
-pub use self::error::SyntaxError;
+/// Utilities for resolving anchors and aliases, which is the same as [`resolve`] module.
+pub use self::resolve as resolver;
+pub use self::{
+    comment::{leading_comments, trailing_comment},
+    documents::{parse_documents, Documents},
+    error::SyntaxError,
+    factory::SyntaxFactory,
+    hash::{NodeId, SyntaxNodeExt},
+    lex::lex,
+    recover::parse_recoverable,
+    reparse::reparse,
+    sexp::to_sexp,
+    transaction::{TextEdit, Transaction, TransactionError},
+};
 use self::{indent::ParserExt as _, set_state::ParserExt as _, verify_state::verify_state};
 use rowan::{GreenNode, GreenToken, NodeOrToken};
+use std::mem;
 use winnow::{
     ascii::{digit1, line_ending, multispace1, space1, take_escaped, till_line_ending},
     combinator::{
@@ -35,14 +49,28 @@ use winnow::{
     },
     error::{ContextError, StrContext, StrContextValue},
     stream::Stateful,
-    token::{any, none_of, one_of, take_till, take_while},
+    token::{any, none_of, one_of, take_till, take_until, take_while},
     PResult, Parser,
 };
 
+pub mod analyze;
 pub mod ast;
+mod comment;
+mod documents;
 mod error;
+mod factory;
+mod hash;
 mod indent;
+mod lex;
+pub mod query;
+mod recover;
+mod reparse;
+pub mod resolve;
+mod scalar;
+pub mod schema;
 mod set_state;
+mod sexp;
+mod transaction;
 mod verify_state;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -86,6 +114,8 @@ pub enum SyntaxKind {
     YAML_VERSION,
     DIRECTIVE_PARAM,
     DOCUMENT_END,
+    /// Text that can't be parsed, which only appears in trees from [`parse_recoverable`].
+    ERROR,
 
     // SyntaxNode
     PROPERTIES,
@@ -365,13 +395,15 @@ fn plain_scalar(input: &mut Input) -> GreenResult {
 fn plain_scalar_one_line(input: &mut Input) -> PResult<()> {
     (
         alt((
-            none_of(|c: char| c.is_ascii_whitespace() || is_indicator(c)),
+            template_span,
+            none_of(|c: char| c.is_ascii_whitespace() || is_indicator(c)).void(),
             terminated(
                 one_of(['-', ':', '?']),
                 peek(none_of(|c: char| {
                     c.is_ascii_whitespace() || is_flow_indicator(c)
                 })),
-            ),
+            )
+            .void(),
         )),
         plain_scalar_chars,
     )
@@ -383,13 +415,20 @@ fn plain_scalar_chars(input: &mut Input) -> PResult<()> {
         input.state.bf_ctx,
         BlockFlowCtx::FlowIn | BlockFlowCtx::FlowKey
     );
+    let has_template = !matches!(input.state.template_syntax, TemplateSyntax::None);
     repeat(
         0..,
         alt((
+            template_span,
             take_till(1.., move |c: char| {
-                c.is_ascii_whitespace() || c == ':' || safe_in && is_flow_indicator(c)
+                c.is_ascii_whitespace()
+                    || c == ':'
+                    || safe_in && is_flow_indicator(c)
+                    || has_template && c == '{'
             })
             .void(),
+            // `{` that doesn't start template expression
+            one_of(move |c: char| c == '{' && has_template && !safe_in).void(),
             terminated(
                 ':'.void(),
                 peek(none_of(move |c: char| {
@@ -418,6 +457,26 @@ fn plain_scalar_chars(input: &mut Input) -> PResult<()> {
     .parse_next(input)
 }
 
+/// Template expression like `{{ .Values.name }}`, which is treated as opaque part of plain scalar.
+/// It can't contain line breaks.
+fn template_span(input: &mut Input) -> PResult<()> {
+    let delimiters: &[(&str, &str)] = match input.state.template_syntax {
+        TemplateSyntax::None => &[],
+        TemplateSyntax::Go => &[("{{", "}}")],
+        TemplateSyntax::Jinja2 => &[("{{", "}}"), ("{%", "%}"), ("{#", "#}")],
+    };
+    for &(open, close) in delimiters {
+        if input.input.starts_with(open) {
+            return (open, take_until(0.., close), close)
+                .take()
+                .verify(|text: &str| !text.contains(['\n', '\r']))
+                .void()
+                .parse_next(input);
+        }
+    }
+    fail.parse_next(input)
+}
+
 fn flow_sequence(input: &mut Input) -> GreenResult {
     (
         ascii_char::<'['>(L_BRACKET),
@@ -430,7 +489,8 @@ fn flow_sequence(input: &mut Input) -> GreenResult {
         .parse_next(input)
         .map(
             |(l_bracket, mut leading_trivias, entries, mut trailing_trivias, r_bracket)| {
-                let mut children = Vec::with_capacity(3);
+                let mut children =
+                    Vec::with_capacity(3 + leading_trivias.len() + trailing_trivias.len());
                 children.push(l_bracket);
                 children.append(&mut leading_trivias);
                 children.push(entries);
@@ -468,7 +528,8 @@ fn flow_map(input: &mut Input) -> GreenResult {
         .parse_next(input)
         .map(
             |(l_brace, mut leading_trivias, entries, mut trailing_trivias, r_brace)| {
-                let mut children = Vec::with_capacity(3);
+                let mut children =
+                    Vec::with_capacity(3 + leading_trivias.len() + trailing_trivias.len());
                 children.push(l_brace);
                 children.append(&mut leading_trivias);
                 children.push(entries);
@@ -493,7 +554,10 @@ fn flow_map_entry(input: &mut Input) -> GreenResult {
             opt((stateless_cmts_or_ws0, flow)),
         )
             .map(|(key, colon, value)| {
-                let mut children = Vec::with_capacity(3);
+                let mut children = Vec::with_capacity(
+                    3 + key.as_ref().map_or(0, |(_, trivias)| trivias.len())
+                        + value.as_ref().map_or(0, |(trivias, _)| trivias.len()),
+                );
                 if let Some((key, mut trivias_before_colon)) = key {
                     children.push(key);
                     children.append(&mut trivias_before_colon);
@@ -526,7 +590,11 @@ fn flow_pair(input: &mut Input) -> GreenResult {
                 )),
             )
                 .map(|(key, value)| {
-                    let mut children = Vec::with_capacity(3);
+                    let mut children = Vec::with_capacity(
+                        3 + value.as_ref().map_or(0, |(trivias, _, value)| {
+                            trivias.len() + value.as_ref().map_or(0, |(trivias, _)| trivias.len())
+                        }),
+                    );
                     children.push(key);
                     if let Some((mut trivias, colon, value)) = value {
                         children.append(&mut trivias);
@@ -547,7 +615,10 @@ fn flow_pair(input: &mut Input) -> GreenResult {
                 opt((stateless_cmts_or_ws0, flow)),
             )
                 .map(|(key, colon, value)| {
-                    let mut children = Vec::with_capacity(3);
+                    let mut children = Vec::with_capacity(
+                        3 + key.as_ref().map_or(0, |(_, trivias)| trivias.len())
+                            + value.as_ref().map_or(0, |(trivias, _)| trivias.len()),
+                    );
                     if let Some((key, mut trivias)) = key {
                         children.push(key);
                         children.append(&mut trivias);
@@ -572,7 +643,8 @@ fn flow_map_entry_key(input: &mut Input) -> GreenResult {
             opt((stateless_cmts_or_ws1, flow)),
         )
             .map(|(question_mark, key)| {
-                let mut children = Vec::with_capacity(3);
+                let mut children =
+                    Vec::with_capacity(2 + key.as_ref().map_or(0, |(trivias, _)| trivias.len()));
                 children.push(question_mark);
                 if let Some((mut trivias, key)) = key {
                     children.append(&mut trivias);
@@ -602,7 +674,8 @@ where
         ),
     )
     .fold(Vec::new, |mut children, (mut trivias, entry, comma)| {
-        children.reserve(3);
+        children
+            .reserve(trivias.len() + 2 + comma.as_ref().map_or(0, |(trivias, _)| trivias.len()));
         children.append(&mut trivias);
         children.push(entry);
         if let Some((mut trivias, comma)) = comma {
@@ -620,7 +693,13 @@ fn flow_content(input: &mut Input) -> GreenResult {
             '"' => double_qouted_scalar,
             '\'' => single_qouted_scalar,
             '[' => flow_sequence,
-            '{' => flow_map,
+            '{' => |input: &mut Input| {
+                if matches!(input.state.template_syntax, TemplateSyntax::None) {
+                    flow_map(input)
+                } else {
+                    alt((plain_scalar, flow_map)).parse_next(input)
+                }
+            },
             _ => plain_scalar,
         },
     )
@@ -666,7 +745,7 @@ fn block_scalar(input: &mut Input) -> GreenResult {
         peek(opt(linebreaks_or_spaces.verify_map(detect_ws_indent))),
     )
         .flat_map(|(style, indicator, space, comment, mut indent)| {
-            let mut children = Vec::with_capacity(3);
+            let mut children = Vec::with_capacity(6);
             children.push(style);
             if let Some(indicator) = indicator {
                 children.push(indicator.0);
@@ -703,7 +782,8 @@ fn block_scalar(input: &mut Input) -> GreenResult {
                 .take(),
             )
             .map(move |text| {
-                let mut children = children.clone();
+                // this parser runs only once, so children can be taken instead of cloned
+                let mut children = mem::take(&mut children);
                 if let Some(text) = text {
                     children.push(tok(BLOCK_SCALAR_TEXT, text));
                 }
@@ -733,28 +813,22 @@ fn chomping_indicator(input: &mut Input) -> GreenResult {
 }
 
 fn block_sequence(input: &mut Input) -> GreenResult {
-    trace(
-        "block_sequence",
-        (
-            block_sequence_entry,
-            repeat(0.., (cmts_or_ws1.verify_indent(), block_sequence_entry)),
-            block_collection_trailing_trivias(input.state.indent),
-        ),
-    )
-    .parse_next(input)
-    .map(|(first, rest, trailing_trivias): (_, Vec<_>, _)| {
-        let mut children = Vec::with_capacity(1 + rest.len());
-        children.push(first);
-        for (mut trivias, entry) in rest {
-            children.append(&mut trivias);
-            children.push(entry);
-        }
-        trailing_trivias.into_iter().for_each(|(ws, comment)| {
-            children.push(ws);
-            children.push(comment);
-        });
-        node(BLOCK_SEQ, children)
+    let indent = input.state.indent;
+    trace("block_sequence", move |input: &mut Input| {
+        let mut children = vec![block_sequence_entry.parse_next(input)?];
+        repeat(0.., (cmts_or_ws1.verify_indent(), block_sequence_entry))
+            .fold(
+                || (),
+                |_, (trivias, entry)| push_entry(&mut children, trivias, entry),
+            )
+            .parse_next(input)?;
+        push_trailing_trivias(
+            &mut children,
+            block_collection_trailing_trivias(indent).parse_next(input)?,
+        );
+        Ok(node(BLOCK_SEQ, children))
     })
+    .parse_next(input)
 }
 
 fn block_sequence_entry(input: &mut Input) -> GreenResult {
@@ -810,37 +884,31 @@ fn space_before_block_compact_collection(input: &mut Input) -> GreenResult {
 
 fn block_map(input: &mut Input) -> GreenResult {
     let indent = input.state.indent;
-    trace(
-        "block_map",
-        (
-            alt((block_map_implicit_entry, block_map_explicit_entry)),
-            repeat(
-                0..,
-                (
-                    terminated(
-                        terminated(cmts_or_ws1, verify_state(|state| state.last_ws_has_nl)),
-                        verify_state(move |state| state.indent == indent),
-                    ),
-                    alt((block_map_implicit_entry, block_map_explicit_entry)),
+    trace("block_map", move |input: &mut Input| {
+        let mut children =
+            vec![alt((block_map_implicit_entry, block_map_explicit_entry)).parse_next(input)?];
+        repeat(
+            0..,
+            (
+                terminated(
+                    terminated(cmts_or_ws1, verify_state(|state| state.last_ws_has_nl)),
+                    verify_state(move |state| state.indent == indent),
                 ),
+                alt((block_map_implicit_entry, block_map_explicit_entry)),
             ),
-            block_collection_trailing_trivias(indent),
-        ),
-    )
-    .parse_next(input)
-    .map(|(first, rest, trailing_trivias): (_, Vec<_>, _)| {
-        let mut children = Vec::with_capacity(1 + rest.len());
-        children.push(first);
-        for (mut trivias, entry) in rest {
-            children.append(&mut trivias);
-            children.push(entry);
-        }
-        trailing_trivias.into_iter().for_each(|(ws, comment)| {
-            children.push(ws);
-            children.push(comment);
-        });
-        node(BLOCK_MAP, children)
+        )
+        .fold(
+            || (),
+            |_, (trivias, entry)| push_entry(&mut children, trivias, entry),
+        )
+        .parse_next(input)?;
+        push_trailing_trivias(
+            &mut children,
+            block_collection_trailing_trivias(indent).parse_next(input)?,
+        );
+        Ok(node(BLOCK_MAP, children))
     })
+    .parse_next(input)
 }
 
 fn block_map_explicit_entry(input: &mut Input) -> GreenResult {
@@ -948,6 +1016,24 @@ fn block_map_implicit_key(input: &mut Input) -> GreenResult {
     .map(|child| node(BLOCK_MAP_KEY, [child]))
 }
 
+/// Push entry of block collection with its leading trivias,
+/// so there's no need to collect entries into intermediate vector.
+fn push_entry(children: &mut Vec<GreenElement>, trivias: Vec<GreenElement>, entry: GreenElement) {
+    children.reserve(trivias.len() + 1);
+    children.extend(trivias);
+    children.push(entry);
+}
+fn push_trailing_trivias(
+    children: &mut Vec<GreenElement>,
+    trailing_trivias: Vec<(GreenElement, GreenElement)>,
+) {
+    children.reserve(trailing_trivias.len() * 2);
+    trailing_trivias.into_iter().for_each(|(ws, comment)| {
+        children.push(ws);
+        children.push(comment);
+    });
+}
+
 fn block_collection_trailing_trivias<'s>(
     indent: usize,
 ) -> impl Parser<Input<'s>, Vec<(GreenElement, GreenElement)>, ContextError> {
@@ -1151,7 +1237,9 @@ fn document(input: &mut Input) -> GreenResult {
 }
 fn top_level_block(input: &mut Input) -> GreenResult {
     let result = preceded(
-        not("..."),
+        // document markers at the start of line can't be parsed as plain scalars,
+        // so `---` right after another `---` starts a new empty document
+        not(alt(("...".void(), directives_end.void()))),
         block.set_state(|state| {
             state.bf_ctx = BlockFlowCtx::BlockIn;
             state.document_top = true;
@@ -1267,6 +1355,31 @@ fn stateless_separate(input: &mut Input) -> PResult<Vec<GreenElement>> {
 
 /// Parse the given YAML code into CST.
 pub fn parse(code: &str) -> Result<SyntaxNode, SyntaxError> {
+    parse_with_template_syntax(code, TemplateSyntax::None)
+}
+
+/// Parse the given YAML code into CST, and treat expressions of template syntax
+/// as part of plain scalars.
+///
+/// Template expressions like `{{ .Values.name }}` can start plain scalars
+/// and contain characters that aren't allowed in plain scalars, such as `: ` and ` #`,
+/// so they're kept in [`PLAIN_SCALAR`](SyntaxKind::PLAIN_SCALAR) tokens as-is.
+/// Expressions can't span multiple lines,
+/// and control structures on their own lines like `{% if %}` aren't supported.
+///
+/// ```
+/// use yaml_parser::{parse_with_template_syntax, SyntaxKind, TemplateSyntax};
+///
+/// let tree = parse_with_template_syntax("image: {{ .Values.image }}\n", TemplateSyntax::Go).unwrap();
+/// assert!(tree
+///     .descendants_with_tokens()
+///     .any(|element| element.kind() == SyntaxKind::PLAIN_SCALAR
+///         && element.to_string() == "{{ .Values.image }}"));
+/// ```
+pub fn parse_with_template_syntax(
+    code: &str,
+    template_syntax: TemplateSyntax,
+) -> Result<SyntaxNode, SyntaxError> {
     let code = code.trim_start_matches('\u{feff}');
     let base_indent = detect_base_indent(code).unwrap_or_default();
     let input = Stateful {
@@ -1279,6 +1392,7 @@ pub fn parse(code: &str) -> Result<SyntaxNode, SyntaxError> {
             bf_ctx: BlockFlowCtx::BlockIn,
             document_top: true,
             prev_document_finished: true,
+            template_syntax,
         },
     };
     root.parse(input).map_err(SyntaxError::from)
@@ -1337,6 +1451,19 @@ struct State {
     bf_ctx: BlockFlowCtx,
     document_top: bool,
     prev_document_finished: bool,
+    template_syntax: TemplateSyntax,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// Syntax of template expressions embedded in YAML, like Helm charts and Ansible playbooks.
+pub enum TemplateSyntax {
+    #[default]
+    /// No template syntax. `{` always starts flow map.
+    None,
+    /// Go templates, which use `{{ ... }}`.
+    Go,
+    /// Jinja2 templates, which use `{{ ... }}`, `{% ... %}` and `{# ... #}`.
+    Jinja2,
 }
 
 #[derive(Clone, Debug)]
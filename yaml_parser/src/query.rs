@@ -0,0 +1,207 @@
+//! Look up nodes in syntax tree by key path.
+//!
+//! ```
+//! use yaml_parser::{ast::{AstNode, Root}, query::select};
+//!
+//! let code = "spec:\n  containers:\n    - name: app\n      image: app:1.0\n";
+//! let root = Root::cast(yaml_parser::parse(code).unwrap()).unwrap();
+//! let nodes = select(&root, "spec.containers[0].image");
+//! assert_eq!(nodes.len(), 1);
+//! assert_eq!(&code[nodes[0].text_range()], "app:1.0");
+//! ```
+
+use crate::{
+    analyze::entry_key_flow,
+    ast::{AstNode, Root},
+    SyntaxKind, SyntaxNode,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// `*` or `[*]`, which matches all values of map or all items of sequence.
+    Wildcard,
+}
+
+/// Select value nodes that match the path in all documents, in source order.
+///
+/// Path consists of segments:
+///
+/// - `key` or `.key` selects the value of map entry whose key is `key`.
+///   Keys are compared by their values, so `a` also matches `'a'` and `"a"`.
+///   Keys that contain special characters can be written in quotes, like `["a.b"]` or `['a[0]']`.
+/// - `[n]` selects the `n`-th item (zero-based) of sequence.
+/// - `*` or `[*]` selects all values of map or all items of sequence.
+///
+/// Empty path selects the top-level value of each document.
+/// Selected nodes are [`Block`](crate::ast::Block) or [`Flow`](crate::ast::Flow),
+/// or [`FlowPair`](crate::ast::FlowPair) for single pair in flow sequence.
+/// Entries without value are skipped, and aliases aren't resolved.
+/// If path is malformed, nothing is selected.
+pub fn select(root: &Root, path: &str) -> Vec<SyntaxNode> {
+    let Some(segments) = parse_path(path) else {
+        return vec![];
+    };
+    let nodes = root
+        .documents()
+        .filter_map(|document| {
+            document
+                .block()
+                .map(|block| block.syntax().clone())
+                .or_else(|| document.flow().map(|flow| flow.syntax().clone()))
+        })
+        .collect();
+    segments.iter().fold(nodes, |nodes, segment| {
+        nodes
+            .iter()
+            .flat_map(|node| select_children(node, segment))
+            .collect()
+    })
+}
+
+fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = vec![];
+    let mut chars = path.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '.' => {
+                // key must follow the dot
+                let (start, c) = chars.next()?;
+                if c == '*' {
+                    segments.push(Segment::Wildcard);
+                } else if c == '.' || c == '[' {
+                    return None;
+                } else {
+                    segments.push(Segment::Key(bare_key(path, start, &mut chars)));
+                }
+            }
+            '[' => {
+                let quote = chars.next_if(|(_, c)| *c == '"' || *c == '\'');
+                let segment = if let Some((_, quote)) = quote {
+                    let mut key = String::new();
+                    loop {
+                        match chars.next()? {
+                            (_, '\\') if quote == '"' => key.push(chars.next()?.1),
+                            (_, c) if c == quote => break,
+                            (_, c) => key.push(c),
+                        }
+                    }
+                    Segment::Key(key)
+                } else {
+                    let start = chars.peek()?.0;
+                    let end = path[start..].find(']')? + start;
+                    let content = &path[start..end];
+                    while chars.next_if(|(i, _)| *i < end).is_some() {}
+                    if content == "*" {
+                        Segment::Wildcard
+                    } else {
+                        Segment::Index(content.parse().ok()?)
+                    }
+                };
+                chars.next_if(|(_, c)| *c == ']')?;
+                segments.push(segment);
+            }
+            '*' if start == 0 => segments.push(Segment::Wildcard),
+            _ if start == 0 => segments.push(Segment::Key(bare_key(path, start, &mut chars))),
+            _ => return None,
+        }
+    }
+    Some(segments)
+}
+
+/// Consume characters of key that isn't quoted, until `.` or `[`.
+fn bare_key(
+    path: &str,
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices>,
+) -> String {
+    while chars.next_if(|(_, c)| *c != '.' && *c != '[').is_some() {}
+    let end = chars.peek().map_or(path.len(), |(i, _)| *i);
+    path[start..end].to_owned()
+}
+
+/// Select values from block/flow node by one segment.
+fn select_children(node: &SyntaxNode, segment: &Segment) -> Vec<SyntaxNode> {
+    let Some(collection) = node
+        .children()
+        .find(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::BLOCK_MAP
+                    | SyntaxKind::BLOCK_SEQ
+                    | SyntaxKind::FLOW_MAP
+                    | SyntaxKind::FLOW_SEQ
+            )
+        })
+        .or_else(|| (node.kind() == SyntaxKind::FLOW_PAIR).then(|| node.clone()))
+    else {
+        return vec![];
+    };
+    match collection.kind() {
+        SyntaxKind::BLOCK_MAP | SyntaxKind::FLOW_MAP | SyntaxKind::FLOW_PAIR => {
+            let entries = if collection.kind() == SyntaxKind::FLOW_MAP {
+                collection
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::FLOW_MAP_ENTRIES)
+                    .map(|entries| entries.children().collect())
+                    .unwrap_or_default()
+            } else if collection.kind() == SyntaxKind::FLOW_PAIR {
+                vec![collection]
+            } else {
+                collection.children().collect::<Vec<_>>()
+            };
+            entries
+                .into_iter()
+                .filter(|entry| match segment {
+                    Segment::Key(key) => entry_key_flow(entry)
+                        .is_some_and(|flow| flow.scalar_value().is_some_and(|text| text == *key)),
+                    Segment::Wildcard => true,
+                    Segment::Index(..) => false,
+                })
+                .filter_map(|entry| {
+                    entry
+                        .children()
+                        .find(|child| {
+                            matches!(
+                                child.kind(),
+                                SyntaxKind::BLOCK_MAP_VALUE | SyntaxKind::FLOW_MAP_VALUE
+                            )
+                        })?
+                        .children()
+                        .find(|child| matches!(child.kind(), SyntaxKind::BLOCK | SyntaxKind::FLOW))
+                })
+                .collect()
+        }
+        _ => {
+            let items = if collection.kind() == SyntaxKind::FLOW_SEQ {
+                collection
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::FLOW_SEQ_ENTRIES)
+                    .map(|entries| entries.children().collect())
+                    .unwrap_or_default()
+            } else {
+                collection.children().collect::<Vec<_>>()
+            };
+            // empty entries are counted for indexes, though they don't have values
+            let values = |entry: SyntaxNode| {
+                entry.children().find(|child| {
+                    matches!(
+                        child.kind(),
+                        SyntaxKind::BLOCK | SyntaxKind::FLOW | SyntaxKind::FLOW_PAIR
+                    )
+                })
+            };
+            match segment {
+                Segment::Index(index) => items
+                    .into_iter()
+                    .nth(*index)
+                    .and_then(values)
+                    .into_iter()
+                    .collect(),
+                Segment::Wildcard => items.into_iter().filter_map(values).collect(),
+                Segment::Key(..) => vec![],
+            }
+        }
+    }
+}
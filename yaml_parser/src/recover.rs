@@ -0,0 +1,164 @@
+use crate::{documents::split_documents, parse, GreenElement, SyntaxError, SyntaxKind, SyntaxNode};
+use rowan::{GreenNode, GreenNodeData, GreenToken, NodeOrToken, TextRange, TextSize};
+use std::sync::Arc;
+
+/// Parse the given YAML code into CST, and continue parsing when there're syntax errors.
+///
+/// Lines that can't be parsed are put into [`ERROR`](SyntaxKind::ERROR) tokens,
+/// and the rest of code is parsed as usual,
+/// so the tree covers the whole input and can be used for syntax highlighting.
+/// Like [`parse`], leading byte order mark is skipped,
+/// so the tree and offsets of errors don't include it.
+/// Syntax errors are reported in the order they're found.
+///
+/// Documents are recovered independently, so an error only causes its own document to be reparsed.
+/// If a document still has errors after too many lines are masked,
+/// the whole document will be put into an error token.
+///
+/// ```
+/// use yaml_parser::{parse_recoverable, SyntaxKind};
+///
+/// let (tree, errors) = parse_recoverable("a: 1\nb: [\nc: 2\n");
+/// assert_eq!(tree.to_string(), "a: 1\nb: [\nc: 2\n");
+/// assert!(!errors.is_empty());
+/// assert!(tree
+///     .descendants_with_tokens()
+///     .any(|element| element.kind() == SyntaxKind::ERROR));
+/// ```
+pub fn parse_recoverable(code: &str) -> (SyntaxNode, Vec<SyntaxError>) {
+    let code = code.trim_start_matches('\u{feff}');
+    if let Ok(tree) = parse(code) {
+        return (tree, Vec::new());
+    }
+    let input = Arc::<str>::from(code);
+    let mut errors = Vec::new();
+    let mut children = Vec::new();
+    for range in split_documents(code) {
+        let start = range.start;
+        let (green, document_errors) = parse_document(&code[range]);
+        errors.extend(
+            document_errors
+                .into_iter()
+                .map(|error| error.relocate(start, &input)),
+        );
+        children.extend(green.children().map(|child| child.to_owned()));
+    }
+    let green = GreenNode::new(SyntaxKind::ROOT.into(), children);
+    (SyntaxNode::new_root(green), errors)
+}
+
+/// Maximum number of lines that can be masked in a single document.
+const MAX_MASKED_LINES: usize = 32;
+
+/// Parse single document by masking error lines until it can be parsed.
+fn parse_document(code: &str) -> (GreenNode, Vec<SyntaxError>) {
+    let mut errors = Vec::new();
+    let mut masked = String::from(code);
+    let mut error_ranges = Vec::new();
+    loop {
+        match parse(&masked) {
+            Ok(tree) => {
+                if error_ranges.is_empty() {
+                    return (tree.green().into_owned(), errors);
+                }
+                let green = restore(&tree.green(), TextSize::default(), code, &error_ranges);
+                return (green, errors);
+            }
+            Err(error) => {
+                let range = if error_ranges.len() < MAX_MASKED_LINES {
+                    find_error_line(&masked, error.offset())
+                } else {
+                    None
+                };
+                errors.push(error);
+                let Some(range) = range else {
+                    // everything has been masked but it still fails, which shouldn't happen,
+                    // or there're too many errors, so give up this document
+                    let green = GreenNode::new(
+                        SyntaxKind::ROOT.into(),
+                        [NodeOrToken::Token(GreenToken::new(
+                            SyntaxKind::ERROR.into(),
+                            code,
+                        ))],
+                    );
+                    return (green, errors);
+                };
+                // mask the line with comment of the same length, so offsets are kept
+                let start = usize::from(range.start());
+                let end = usize::from(range.end());
+                let mut comment = String::with_capacity(end - start);
+                comment.push('#');
+                comment.extend(std::iter::repeat_n(' ', end - start - 1));
+                masked.replace_range(start..end, &comment);
+                error_ranges.push(range);
+            }
+        }
+    }
+}
+
+/// Find the range of line that should be masked for the given error offset.
+///
+/// Errors like unclosed flow collections are reported at the end of input or at the next line,
+/// so if the line at offset is blank, a comment or has been masked, previous lines will be looked up.
+fn find_error_line(code: &str, offset: usize) -> Option<TextRange> {
+    let mut end = code[offset.min(code.len())..]
+        .find(['\n', '\r'])
+        .map_or(code.len(), |i| offset + i);
+    loop {
+        let start = code[..end].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+        let line = &code[start..end];
+        let trimmed = line.trim_start();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            return Some(TextRange::new(
+                TextSize::from(start as u32),
+                TextSize::from(end as u32),
+            ));
+        }
+        if start == 0 {
+            return None;
+        }
+        end = start - 1;
+    }
+}
+
+/// Replace comments that mask error lines with error tokens of original text.
+///
+/// Masked lines may be inside multi-line scalars instead of being comments,
+/// then original text is put back to those tokens.
+fn restore(
+    green: &GreenNodeData,
+    offset: TextSize,
+    code: &str,
+    error_ranges: &[TextRange],
+) -> GreenNode {
+    let mut offset = offset;
+    let children = green
+        .children()
+        .map(|child| {
+            let start = offset;
+            offset += child.text_len();
+            match child {
+                NodeOrToken::Node(node) => {
+                    GreenElement::Node(restore(node, start, code, error_ranges))
+                }
+                NodeOrToken::Token(token) => {
+                    let range = TextRange::at(start, token.text_len());
+                    if token.kind() == SyntaxKind::COMMENT.into()
+                        && error_ranges.iter().any(|error| error.contains_range(range))
+                    {
+                        GreenElement::Token(GreenToken::new(SyntaxKind::ERROR.into(), &code[range]))
+                    } else if error_ranges.iter().any(|error| {
+                        error
+                            .intersect(range)
+                            .is_some_and(|intersection| !intersection.is_empty())
+                    }) {
+                        GreenElement::Token(GreenToken::new(token.kind(), &code[range]))
+                    } else {
+                        GreenElement::Token(token.to_owned())
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    GreenNode::new(green.kind(), children)
+}
@@ -0,0 +1,249 @@
+use crate::{parse, SyntaxElement, SyntaxError, SyntaxKind, SyntaxNode, TextEdit};
+use rowan::{GreenNodeData, GreenTokenData, NodeOrToken, TextRange, TextSize};
+
+/// Apply the edit to the syntax tree and reparse only the part that is affected,
+/// which is much cheaper than parsing the whole text again for large files.
+///
+/// If the edit is inside a top-level entry of block mapping or block sequence,
+/// and the edited entry can be parsed on its own, only that entry is reparsed.
+/// Otherwise, documents that are touched by the edit are reparsed.
+/// In both cases, green nodes outside the reparsed part are shared
+/// between the old tree and the new tree.
+/// If the result can't be reparsed incrementally, the whole text will be parsed.
+///
+/// The old tree should be the root node, and range of edit is based on its text.
+///
+/// ```
+/// use rowan::TextRange;
+/// use yaml_parser::{parse, reparse, TextEdit};
+///
+/// let tree = parse("a: 1\nb: 2\n").unwrap();
+/// let edit = TextEdit {
+///     range: TextRange::new(8.into(), 9.into()),
+///     text: "[3, 4]".into(),
+/// };
+/// let tree = reparse(&tree, edit).unwrap();
+/// assert_eq!(tree.to_string(), "a: 1\nb: [3, 4]\n");
+/// ```
+///
+/// # Panics
+///
+/// Panics if range of edit is out of the old tree.
+pub fn reparse(old_tree: &SyntaxNode, edit: TextEdit) -> Result<SyntaxNode, SyntaxError> {
+    assert!(
+        TextRange::up_to(old_tree.text_range().len()).contains_range(edit.range),
+        "edit at {:?} is out of bounds",
+        edit.range
+    );
+    if old_tree.kind() != SyntaxKind::ROOT || old_tree.parent().is_some() {
+        return parse(&apply_edit(&old_tree.to_string(), &edit));
+    }
+    if let Some(tree) = reparse_entry(old_tree, &edit) {
+        return Ok(tree);
+    }
+    let text = apply_edit(&old_tree.to_string(), &edit);
+    reparse_documents(old_tree, &[edit], &text)
+}
+
+fn apply_edit(text: &str, edit: &TextEdit) -> String {
+    let mut text = text.to_owned();
+    text.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.text);
+    text
+}
+
+/// Reparse the top-level entry of block mapping or block sequence that contains the edit,
+/// then replace it in green tree of the original root.
+///
+/// Such entries start at column 0 and lines of their contents must be indented,
+/// so they can be parsed without other parts of document.
+/// The edited entry is parsed with its previous entry and next entry,
+/// and it's accepted only if those entries and trivias between them are unchanged,
+/// which means the edited entry doesn't interact with its siblings,
+/// for example, by absorbing comments into block scalar or pairing with explicit value.
+/// `None` is returned if there isn't such entry or it isn't accepted.
+fn reparse_entry(root: &SyntaxNode, edit: &TextEdit) -> Option<SyntaxNode> {
+    let entry = root
+        .children()
+        .find(|document| document.text_range().contains_range(edit.range))
+        .filter(|document| document.kind() == SyntaxKind::DOCUMENT)?
+        .children()
+        .find(|node| node.kind() == SyntaxKind::BLOCK)?
+        .children()
+        .find(|node| matches!(node.kind(), SyntaxKind::BLOCK_MAP | SyntaxKind::BLOCK_SEQ))?
+        .children()
+        .find(|entry| entry.text_range().contains_range(edit.range))
+        .filter(|entry| {
+            matches!(
+                entry.kind(),
+                SyntaxKind::BLOCK_MAP_ENTRY | SyntaxKind::BLOCK_SEQ_ENTRY
+            ) && is_line_start(entry)
+        })?;
+    // trivias after the last entry don't belong to the collection
+    let next = entry.next_sibling()?;
+    let prev = entry.prev_sibling();
+    let first = prev.as_ref().unwrap_or(&entry);
+
+    let range = TextRange::new(first.text_range().start(), next.text_range().end());
+    let text = apply_edit(
+        &root.text().slice(range).to_string(),
+        &TextEdit {
+            range: edit.range - range.start(),
+            text: edit.text.clone(),
+        },
+    );
+    let tree = parse(&text).ok()?;
+    let collection = tree
+        .children()
+        .find(|node| node.kind() == SyntaxKind::DOCUMENT)?
+        .children()
+        .find(|node| node.kind() == SyntaxKind::BLOCK)?
+        .children()
+        .find(|node| Some(node.kind()) == entry.parent().map(|parent| parent.kind()))
+        .filter(|node| node.text_range() == tree.text_range())?;
+
+    let old_elements = entry
+        .parent()?
+        .children_with_tokens()
+        .skip_while(|element| element.text_range().start() < range.start())
+        .take_while(|element| element.text_range().end() <= range.end())
+        .collect::<Vec<_>>();
+    let new_elements = collection.children_with_tokens().collect::<Vec<_>>();
+    if old_elements.len() != new_elements.len() {
+        return None;
+    }
+    let mut new_entry = None;
+    for (old, new) in old_elements.iter().zip(&new_elements) {
+        match (old, new) {
+            (SyntaxElement::Node(old), SyntaxElement::Node(new)) if *old == entry => {
+                if new.kind() != entry.kind() {
+                    return None;
+                }
+                new_entry = Some(new.green().into_owned());
+            }
+            (SyntaxElement::Node(old), SyntaxElement::Node(new)) if old.green() == new.green() => {}
+            (SyntaxElement::Token(old), SyntaxElement::Token(new))
+                if old.kind() == new.kind() && old.text() == new.text() => {}
+            _ => return None,
+        }
+    }
+    Some(SyntaxNode::new_root(entry.replace_with(new_entry?)))
+}
+
+/// Check if the node is at the start of a line.
+fn is_line_start(node: &SyntaxNode) -> bool {
+    let Some(token) = node.first_token().and_then(|token| token.prev_token()) else {
+        return true;
+    };
+    token.kind() == SyntaxKind::WHITESPACE && token.text().ends_with('\n')
+}
+
+/// Reparse documents that are touched by edits,
+/// then replace them in green tree of the original root, so other documents are shared.
+/// Edits must be sorted by range and `text` is the whole text after applying edits.
+pub(crate) fn reparse_documents(
+    root: &SyntaxNode,
+    edits: &[TextEdit],
+    text: &str,
+) -> Result<SyntaxNode, SyntaxError> {
+    let parse_all = || parse(text);
+    let (Some(first), Some(last)) = (
+        edits.first().map(|edit| edit.range.start()),
+        edits.iter().map(|edit| edit.range.end()).max(),
+    ) else {
+        return parse_all();
+    };
+    if root.kind() != SyntaxKind::ROOT || root.parent().is_some() {
+        return parse_all();
+    }
+    let children = root.children_with_tokens().collect::<Vec<_>>();
+    let (Some(mut start), Some(mut end)) = (
+        children
+            .iter()
+            .position(|child| child.text_range().end() >= first),
+        children
+            .iter()
+            .rposition(|child| child.text_range().start() <= last),
+    ) else {
+        return parse_all();
+    };
+    // documents that don't start with `---` depend on the previous document,
+    // and trivias before next document may belong to the previous document
+    while start > 0 && !is_document_start(&children[start]) {
+        start -= 1;
+    }
+    while end + 1 < children.len() && !is_document_start(&children[end + 1]) {
+        end += 1;
+    }
+
+    // whether `---` starts a new document depends on the previous document,
+    // so documents around are parsed together and they must be unchanged
+    let context_start = children[..start]
+        .iter()
+        .rposition(|child| child.as_node().is_some())
+        .unwrap_or(start);
+    let context_end = children
+        .iter()
+        .skip(end + 1)
+        .position(|child| child.as_node().is_some())
+        .map_or(end, |index| end + 1 + index);
+
+    let range = TextRange::new(
+        children[context_start].text_range().start(),
+        children[context_end].text_range().end(),
+    );
+    let inserted = edits
+        .iter()
+        .map(|edit| TextSize::of(&*edit.text))
+        .sum::<TextSize>();
+    let deleted = edits.iter().map(|edit| edit.range.len()).sum::<TextSize>();
+    let Some(segment) =
+        text.get(usize::from(range.start())..usize::from(range.end() + inserted - deleted))
+    else {
+        return parse_all();
+    };
+    let Ok(segment) = parse(segment) else {
+        // report error with offset in the whole text
+        return parse_all();
+    };
+    let segment = segment.green();
+    let new_children = segment.children().collect::<Vec<_>>();
+    let (before, after) = (start - context_start, context_end - end);
+    if new_children.len() < before + after
+        || !children[context_start..start]
+            .iter()
+            .zip(&new_children[..before])
+            .chain(
+                children[end + 1..=context_end]
+                    .iter()
+                    .zip(&new_children[new_children.len() - after..]),
+            )
+            .all(|(old, new)| is_same(old, *new))
+    {
+        return parse_all();
+    }
+    let green = root.green().splice_children(
+        start..=end,
+        new_children[before..new_children.len() - after]
+            .iter()
+            .copied()
+            .map(|child| child.to_owned()),
+    );
+    Ok(SyntaxNode::new_root(green))
+}
+
+fn is_same(old: &SyntaxElement, new: NodeOrToken<&GreenNodeData, &GreenTokenData>) -> bool {
+    match (old, new) {
+        (NodeOrToken::Node(old), NodeOrToken::Node(new)) => *old.green() == *new,
+        (NodeOrToken::Token(old), NodeOrToken::Token(new)) => old.green() == new,
+        _ => false,
+    }
+}
+
+fn is_document_start(element: &SyntaxElement) -> bool {
+    element.as_node().is_some_and(|node| {
+        node.kind() == SyntaxKind::DOCUMENT
+            && node
+                .first_token()
+                .is_some_and(|token| token.kind() == SyntaxKind::DIRECTIVES_END)
+    })
+}
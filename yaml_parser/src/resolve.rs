@@ -0,0 +1,204 @@
+//! Utilities for resolving anchors and aliases.
+//!
+//! ```
+//! use yaml_parser::{ast::{Alias, AstNode, Root}, resolve::AnchorTable};
+//!
+//! let root = Root::cast(yaml_parser::parse("a: &x 1\nb: *x\nc: *y\n").unwrap()).unwrap();
+//! let table = AnchorTable::new(&root);
+//! let alias = root.syntax().descendants().find_map(Alias::cast).unwrap();
+//! assert_eq!(table.resolve_alias(&alias).unwrap().to_string(), "&x 1");
+//! assert_eq!(table.undefined_aliases().count(), 1);
+//! assert_eq!(table.diagnostics()[0].to_string(), "alias `*y` refers to undefined anchor");
+//! ```
+
+use crate::{
+    ast::{Alias, AnchorProperty, AstNode, Document, Root},
+    SyntaxKind, SyntaxNode,
+};
+use rowan::{TextRange, TextSize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Table of anchors and aliases in syntax tree.
+///
+/// Anchors are scoped to their documents,
+/// and each alias refers to the closest anchor with the same name that comes before it.
+#[derive(Clone, Debug, Default)]
+pub struct AnchorTable {
+    anchors: Vec<AnchorProperty>,
+    /// Names of anchors mapped to their indexes in `anchors`.
+    names: BTreeMap<String, Vec<usize>>,
+    /// Aliases and the indexes of anchors they refer to.
+    aliases: Vec<(Alias, Option<usize>)>,
+    /// Start offsets of aliases mapped to their indexes in `aliases`.
+    alias_offsets: BTreeMap<TextSize, usize>,
+    /// Indexes of anchors whose names have been defined before in the same document,
+    /// mapped to indexes of those previous anchors.
+    duplicates: BTreeMap<usize, usize>,
+}
+
+impl AnchorTable {
+    /// Collect anchors and aliases from all documents.
+    pub fn new(root: &Root) -> Self {
+        Self::from_node(root.syntax())
+    }
+
+    /// Collect anchors and aliases from single document.
+    pub fn for_document(document: &Document) -> Self {
+        Self::from_node(document.syntax())
+    }
+
+    fn from_node(node: &SyntaxNode) -> Self {
+        let mut table = Self::default();
+        // anchors that are visible in current document
+        let mut bindings = BTreeMap::<String, usize>::new();
+        node.descendants().for_each(|node| match node.kind() {
+            SyntaxKind::DOCUMENT => bindings.clear(),
+            SyntaxKind::ANCHOR_PROPERTY => {
+                let Some(anchor) = AnchorProperty::cast(node) else {
+                    return;
+                };
+                let index = table.anchors.len();
+                if let Some(name) = anchor.anchor_name() {
+                    if let Some(previous) = bindings.insert(name.text().to_owned(), index) {
+                        table.duplicates.insert(index, previous);
+                    }
+                    table
+                        .names
+                        .entry(name.text().to_owned())
+                        .or_default()
+                        .push(index);
+                }
+                table.anchors.push(anchor);
+            }
+            SyntaxKind::ALIAS => {
+                let Some(alias) = Alias::cast(node) else {
+                    return;
+                };
+                let anchor = alias
+                    .anchor_name()
+                    .and_then(|name| bindings.get(name.text()).copied());
+                table
+                    .alias_offsets
+                    .insert(alias.syntax().text_range().start(), table.aliases.len());
+                table.aliases.push((alias, anchor));
+            }
+            _ => {}
+        });
+        table
+    }
+
+    /// All anchors in source order.
+    pub fn anchors(&self) -> impl Iterator<Item = &AnchorProperty> {
+        self.anchors.iter()
+    }
+
+    /// All aliases in source order.
+    pub fn aliases(&self) -> impl Iterator<Item = &Alias> {
+        self.aliases.iter().map(|(alias, _)| alias)
+    }
+
+    /// Anchors with the given name in source order.
+    /// There may be multiple anchors because names can be redefined or used in different documents.
+    pub fn anchors_by_name<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = &'a AnchorProperty> + 'a {
+        self.names
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.anchors[*index])
+    }
+
+    /// Find the anchor that the given alias refers to.
+    /// The alias must come from the same tree that this table is built from.
+    pub fn anchor_of(&self, alias: &Alias) -> Option<&AnchorProperty> {
+        self.alias_offsets
+            .get(&alias.syntax().text_range().start())
+            .and_then(|index| self.aliases[*index].1)
+            .map(|index| &self.anchors[index])
+    }
+
+    /// Find the node that the given alias refers to,
+    /// which is [`Flow`](crate::ast::Flow) or [`Block`](crate::ast::Block) node with the anchor.
+    pub fn resolve_alias(&self, alias: &Alias) -> Option<SyntaxNode> {
+        self.anchor_of(alias).and_then(anchored_node)
+    }
+
+    /// Aliases that don't refer to any anchors.
+    pub fn undefined_aliases(&self) -> impl Iterator<Item = &Alias> {
+        self.aliases
+            .iter()
+            .filter(|(_, anchor)| anchor.is_none())
+            .map(|(alias, _)| alias)
+    }
+
+    /// Anchors whose names have been defined before in the same document.
+    ///
+    /// This is allowed by YAML spec, but usually it's a mistake.
+    pub fn duplicate_anchors(&self) -> impl Iterator<Item = &AnchorProperty> {
+        self.duplicates.keys().map(|index| &self.anchors[*index])
+    }
+
+    /// Report undefined aliases and duplicate anchors in source order.
+    pub fn diagnostics(&self) -> Vec<ResolveDiagnostic> {
+        let mut diagnostics = self
+            .undefined_aliases()
+            .cloned()
+            .map(ResolveDiagnostic::UndefinedAlias)
+            .chain(self.duplicates.iter().map(|(index, previous)| {
+                ResolveDiagnostic::DuplicateAnchor {
+                    anchor: self.anchors[*index].clone(),
+                    previous: self.anchors[*previous].clone(),
+                }
+            }))
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.range().start());
+        diagnostics
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Problem about anchors and aliases found by [`AnchorTable`].
+pub enum ResolveDiagnostic {
+    /// Alias doesn't refer to any anchors.
+    UndefinedAlias(Alias),
+    /// Anchor name has been defined before in the same document.
+    DuplicateAnchor {
+        anchor: AnchorProperty,
+        previous: AnchorProperty,
+    },
+}
+
+impl ResolveDiagnostic {
+    /// Range of the alias or anchor that causes this problem.
+    pub fn range(&self) -> TextRange {
+        match self {
+            ResolveDiagnostic::UndefinedAlias(alias) => alias.syntax().text_range(),
+            ResolveDiagnostic::DuplicateAnchor { anchor, .. } => anchor.syntax().text_range(),
+        }
+    }
+}
+
+impl fmt::Display for ResolveDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveDiagnostic::UndefinedAlias(alias) => {
+                write!(f, "alias `{}` refers to undefined anchor", alias.syntax())
+            }
+            ResolveDiagnostic::DuplicateAnchor { anchor, .. } => {
+                write!(f, "anchor `{}` has been defined before", anchor.syntax())
+            }
+        }
+    }
+}
+
+/// Get the node that has the given anchor,
+/// which is [`Flow`](crate::ast::Flow) or [`Block`](crate::ast::Block) node.
+pub fn anchored_node(anchor: &AnchorProperty) -> Option<SyntaxNode> {
+    anchor
+        .syntax()
+        .parent()
+        .and_then(|properties| properties.parent())
+}
@@ -0,0 +1,306 @@
+use crate::{
+    ast::{AstNode, BlockScalar, Flow},
+    SyntaxKind, SyntaxNode,
+};
+use rowan::{GreenNodeData, NodeOrToken};
+use std::borrow::Cow;
+
+/// Get the value of flow scalar, with line folding and escapes resolved.
+/// Text of source is borrowed if it doesn't need to be changed.
+pub(crate) fn scalar_value(flow: &Flow) -> Option<Cow<'_, str>> {
+    match flow.syntax().green() {
+        Cow::Borrowed(green) => green_scalar_value(green),
+        Cow::Owned(green) => green_scalar_value(&green).map(|value| Cow::Owned(value.into_owned())),
+    }
+}
+
+fn green_scalar_value(green: &GreenNodeData) -> Option<Cow<'_, str>> {
+    green.children().find_map(|child| {
+        let NodeOrToken::Token(token) = child else {
+            return None;
+        };
+        let text = token.text();
+        match token.kind() {
+            kind if kind == SyntaxKind::PLAIN_SCALAR.into() => Some(fold_lines(text, false)),
+            kind if kind == SyntaxKind::SINGLE_QUOTED_SCALAR.into() => {
+                text.get(1..text.len() - 1).map(|text| {
+                    let folded = fold_lines(text, false);
+                    if folded.contains("''") {
+                        Cow::Owned(folded.replace("''", "'"))
+                    } else {
+                        folded
+                    }
+                })
+            }
+            kind if kind == SyntaxKind::DOUBLE_QUOTED_SCALAR.into() => {
+                text.get(1..text.len() - 1).map(|text| {
+                    let folded = fold_lines(text, true);
+                    if folded.contains('\\') {
+                        Cow::Owned(unescape_double_quoted(&folded))
+                    } else {
+                        folded
+                    }
+                })
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Fold line breaks of multi-line flow scalar.
+/// A line break becomes a space, and empty lines become line breaks.
+/// Escaped line break in double quoted scalar is removed with its backslash,
+/// and escaped white space at the end of line is kept.
+fn fold_lines(text: &str, is_double_quoted: bool) -> Cow<'_, str> {
+    let mut lines = text.split('\n').map(|line| line.trim_end_matches('\r'));
+    let Some(first) = lines.next() else {
+        return Cow::Borrowed("");
+    };
+    let mut lines = lines.peekable();
+    if lines.peek().is_none() {
+        return Cow::Borrowed(first);
+    }
+    let mut folded = trim_line_end(first, is_double_quoted).to_owned();
+    let mut empty_lines = 0;
+    while let Some(line) = lines.next() {
+        let is_last = lines.peek().is_none();
+        let line = line.trim_start_matches([' ', '\t']);
+        let line = if is_last {
+            line
+        } else {
+            trim_line_end(line, is_double_quoted)
+        };
+        if line.is_empty() && !is_last {
+            empty_lines += 1;
+            continue;
+        }
+        if is_double_quoted && ends_with_escape(&folded) {
+            folded.pop();
+        } else if empty_lines == 0 {
+            folded.push(' ');
+        }
+        folded.extend(std::iter::repeat_n('\n', empty_lines));
+        folded.push_str(line);
+        empty_lines = 0;
+    }
+    Cow::Owned(folded)
+}
+
+/// Trim white spaces at the end of line, except escaped one in double quoted scalar.
+fn trim_line_end(line: &str, is_double_quoted: bool) -> &str {
+    let trimmed = line.trim_end_matches([' ', '\t']);
+    if is_double_quoted && trimmed.len() < line.len() && ends_with_escape(trimmed) {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+/// Check if text ends with a backslash that escapes the next character.
+fn ends_with_escape(text: &str) -> bool {
+    (text.len() - text.trim_end_matches('\\').len()) % 2 == 1
+}
+
+/// Resolve escape sequences in double quoted scalar.
+/// Invalid escape sequences are kept as-is.
+fn unescape_double_quoted(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let Some((_, escape)) = chars.next() else {
+            unescaped.push(c);
+            break;
+        };
+        let hex_len = match escape {
+            'x' => 2,
+            'u' => 4,
+            'U' => 8,
+            _ => 0,
+        };
+        let resolved = match escape {
+            '0' => Some('\0'),
+            'a' => Some('\u{7}'),
+            'b' => Some('\u{8}'),
+            't' | '\t' => Some('\t'),
+            'n' => Some('\n'),
+            'v' => Some('\u{b}'),
+            'f' => Some('\u{c}'),
+            'r' => Some('\r'),
+            'e' => Some('\u{1b}'),
+            ' ' => Some(' '),
+            '"' => Some('"'),
+            '/' => Some('/'),
+            '\\' => Some('\\'),
+            'N' => Some('\u{85}'),
+            '_' => Some('\u{a0}'),
+            'L' => Some('\u{2028}'),
+            'P' => Some('\u{2029}'),
+            'x' | 'u' | 'U' => text
+                .get(i + 2..i + 2 + hex_len)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32),
+            _ => None,
+        };
+        if let Some(resolved) = resolved {
+            unescaped.push(resolved);
+            if hex_len > 0 {
+                chars.nth(hex_len - 1);
+            }
+        } else {
+            unescaped.push(c);
+            unescaped.push(escape);
+        }
+    }
+    unescaped
+}
+
+/// Get the value of block scalar, with indentation removed and line folding and chomping applied.
+pub(crate) fn block_scalar_value(block_scalar: &BlockScalar) -> Option<String> {
+    block_scalar.bar().or_else(|| block_scalar.greater_than())?;
+    let Some(token) = block_scalar.text() else {
+        return Some(String::new());
+    };
+    let mut lines = token
+        .text()
+        .split('\n')
+        .map(|line| line.trim_end_matches('\r'));
+    // the rest of header line
+    lines.next();
+    let lines = lines.collect::<Vec<_>>();
+    let indent = block_scalar
+        .indent_indicator()
+        .and_then(|indicator| indicator.text().parse::<usize>().ok())
+        .map(|indicator| indicator + parent_indent(block_scalar.syntax()))
+        .or_else(|| {
+            lines
+                .iter()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start_matches(' ').len())
+        })
+        .unwrap_or_default();
+
+    let is_folded = block_scalar.greater_than().is_some();
+    let mut value = String::with_capacity(token.text().len());
+    let mut empty_lines = 0;
+    let mut is_first = true;
+    let mut was_more_indented = false;
+    for line in lines {
+        let Some(content) = line.get(indent..).filter(|content| !content.is_empty()) else {
+            empty_lines += 1;
+            continue;
+        };
+        let is_more_indented = content.starts_with([' ', '\t']);
+        if is_first {
+            value.extend(std::iter::repeat_n('\n', empty_lines));
+        } else if is_folded && !was_more_indented && !is_more_indented {
+            // single line break between normal lines is folded into a space
+            if empty_lines == 0 {
+                value.push(' ');
+            } else {
+                value.extend(std::iter::repeat_n('\n', empty_lines));
+            }
+        } else {
+            value.extend(std::iter::repeat_n('\n', empty_lines + 1));
+        }
+        value.push_str(content);
+        empty_lines = 0;
+        is_first = false;
+        was_more_indented = is_more_indented;
+    }
+
+    let chomping = block_scalar
+        .chomping_indicator()
+        .map(|indicator| indicator.syntax().to_string());
+    match chomping.as_deref() {
+        Some("-") => {}
+        Some("+") => {
+            // trailing empty lines are in the whitespace after block scalar
+            let trailing = iter_whitespaces_after(block_scalar.syntax())
+                .map(|text| text.matches('\n').count())
+                .sum::<usize>();
+            value.extend(std::iter::repeat_n('\n', trailing.max(1) + empty_lines));
+        }
+        _ => {
+            if !is_first {
+                value.push('\n');
+            }
+        }
+    }
+    Some(value)
+}
+
+fn iter_whitespaces_after(node: &SyntaxNode) -> impl Iterator<Item = String> {
+    std::iter::successors(
+        node.last_token().and_then(|token| token.next_token()),
+        |token| token.next_token(),
+    )
+    .take_while(|token| token.kind() == SyntaxKind::WHITESPACE)
+    .map(|token| token.text().to_owned())
+}
+
+/// Indentation of the closest block collection entry that contains the node.
+fn parent_indent(node: &SyntaxNode) -> usize {
+    let Some(entry) = node.ancestors().find(|ancestor| {
+        matches!(
+            ancestor.kind(),
+            SyntaxKind::BLOCK_MAP_ENTRY | SyntaxKind::BLOCK_SEQ_ENTRY
+        )
+    }) else {
+        return 0;
+    };
+    let mut column = 0;
+    let mut prev = entry.first_token().and_then(|token| token.prev_token());
+    while let Some(token) = prev {
+        let text = token.text();
+        if let Some(index) = text.rfind('\n') {
+            column += text[index + 1..].chars().count();
+            break;
+        }
+        column += text.chars().count();
+        prev = token.prev_token();
+    }
+    column
+}
+
+/// Check if the plain scalar is resolved to a non-string value like `null`, `true` or `1`
+/// in YAML core schema, so it's different from the quoted one with the same text.
+pub(crate) fn is_non_string_plain(text: &str) -> bool {
+    matches!(
+        text,
+        "" | "~"
+            | "null"
+            | "Null"
+            | "NULL"
+            | "true"
+            | "True"
+            | "TRUE"
+            | "false"
+            | "False"
+            | "FALSE"
+            | ".inf"
+            | ".Inf"
+            | ".INF"
+            | "+.inf"
+            | "+.Inf"
+            | "+.INF"
+            | "-.inf"
+            | "-.Inf"
+            | "-.INF"
+            | ".nan"
+            | ".NaN"
+            | ".NAN"
+    ) || text
+        .strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        || text
+            .strip_prefix("0o")
+            .is_some_and(|oct| !oct.is_empty() && oct.bytes().all(|b| matches!(b, b'0'..=b'7')))
+        || text
+            .trim_start_matches(['+', '-'])
+            .starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && text.parse::<f64>().is_ok()
+}
@@ -0,0 +1,295 @@
+//! Resolving types of scalars by tags and YAML schemas.
+//!
+//! ```
+//! use yaml_parser::{ast::{AstNode, Flow, Root}, schema::{resolve_scalar, ScalarType}};
+//!
+//! let root = Root::cast(yaml_parser::parse("[1, 1.5, no, ~, '1', !!str true]").unwrap()).unwrap();
+//! let types = root
+//!     .syntax()
+//!     .descendants()
+//!     .filter_map(Flow::cast)
+//!     .skip(1)
+//!     .map(|flow| resolve_scalar(&flow))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(
+//!     types,
+//!     [
+//!         ScalarType::Int,
+//!         ScalarType::Float,
+//!         ScalarType::String,
+//!         ScalarType::Null,
+//!         ScalarType::String,
+//!         ScalarType::String,
+//!     ],
+//! );
+//! ```
+
+use crate::ast::{AstNode, Document, Flow};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Type of scalar.
+pub enum ScalarType {
+    Null,
+    Bool,
+    Int,
+    Float,
+    String,
+    /// Node isn't a scalar, such as collections and aliases,
+    /// or it has a tag that isn't a scalar type of core schema.
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// YAML version that decides how plain scalars are resolved.
+pub enum YamlVersion {
+    /// YAML 1.1 with its type repository, where `yes`, `off` and `0755` aren't strings.
+    V1_1,
+    /// YAML 1.2 with core schema.
+    #[default]
+    V1_2,
+}
+
+/// Resolve type of flow scalar.
+///
+/// Explicit tags like `!!int` or `!<tag:yaml.org,2002:int>` decide the type directly,
+/// and quoted scalars or scalars with non-specific tag `!` are strings.
+/// Plain scalars are resolved by [`resolve_plain`] with YAML version of the document
+/// that contains the scalar. See [`document_version`].
+pub fn resolve_scalar(flow: &Flow) -> ScalarType {
+    resolve_scalar_by(flow, || {
+        flow.syntax()
+            .ancestors()
+            .find_map(Document::cast)
+            .map(|document| document_version(&document))
+            .unwrap_or_default()
+    })
+}
+
+/// Resolve type of flow scalar like [`resolve_scalar`],
+/// but plain scalars are resolved with the given YAML version
+/// regardless of `%YAML` directive of the document.
+///
+/// ```
+/// use yaml_parser::{
+///     ast::{AstNode, Flow, Root},
+///     schema::{resolve_scalar, resolve_scalar_with_version, ScalarType, YamlVersion},
+/// };
+///
+/// let root = Root::cast(yaml_parser::parse("%YAML 1.1\n---\nyes").unwrap()).unwrap();
+/// let flow = root.syntax().descendants().find_map(Flow::cast).unwrap();
+/// assert_eq!(resolve_scalar(&flow), ScalarType::Bool);
+/// assert_eq!(resolve_scalar_with_version(&flow, YamlVersion::V1_2), ScalarType::String);
+/// ```
+pub fn resolve_scalar_with_version(flow: &Flow, version: YamlVersion) -> ScalarType {
+    resolve_scalar_by(flow, || version)
+}
+
+fn resolve_scalar_by(flow: &Flow, version: impl FnOnce() -> YamlVersion) -> ScalarType {
+    let tag = flow
+        .properties()
+        .and_then(|properties| properties.tag_property());
+    if let Some(tag) = tag {
+        if tag.non_specific_tag().is_some() {
+            return ScalarType::String;
+        }
+        let text = tag.syntax().to_string();
+        let name = text.strip_prefix("!!").or_else(|| {
+            text.strip_prefix("!<tag:yaml.org,2002:")
+                .and_then(|name| name.strip_suffix('>'))
+        });
+        return match name {
+            Some("null") => ScalarType::Null,
+            Some("bool") => ScalarType::Bool,
+            Some("int") => ScalarType::Int,
+            Some("float") => ScalarType::Float,
+            Some("str") => ScalarType::String,
+            _ => ScalarType::Unknown,
+        };
+    }
+    if flow.flow_seq().is_some() || flow.flow_map().is_some() || flow.alias().is_some() {
+        ScalarType::Unknown
+    } else if flow.single_quoted_scalar().is_some() || flow.double_qouted_scalar().is_some() {
+        ScalarType::String
+    } else {
+        // node with properties only is empty plain scalar
+        resolve_plain(&flow.scalar_value().unwrap_or_default(), version())
+    }
+}
+
+/// Get YAML version from `%YAML` directive of document.
+/// Versions before 1.2 are treated as YAML 1.1, and YAML 1.2 is used if there's no directive.
+pub fn document_version(document: &Document) -> YamlVersion {
+    document
+        .directives()
+        .filter_map(|directive| directive.yaml_directive()?.yaml_version())
+        .last()
+        .and_then(|version| {
+            let (major, minor) = version.text().split_once('.')?;
+            Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?))
+        })
+        .filter(|version| *version < (1, 2))
+        .map_or(YamlVersion::V1_2, |_| YamlVersion::V1_1)
+}
+
+/// Resolve type of plain scalar by its value, which should have line folding resolved.
+pub fn resolve_plain(text: &str, version: YamlVersion) -> ScalarType {
+    match version {
+        YamlVersion::V1_2 => {
+            if matches!(text, "" | "~" | "null" | "Null" | "NULL") {
+                ScalarType::Null
+            } else if matches!(text, "true" | "True" | "TRUE" | "false" | "False" | "FALSE") {
+                ScalarType::Bool
+            } else if is_core_int(text) {
+                ScalarType::Int
+            } else if is_core_float(text) {
+                ScalarType::Float
+            } else {
+                ScalarType::String
+            }
+        }
+        YamlVersion::V1_1 => {
+            if matches!(text, "" | "~" | "null" | "Null" | "NULL") {
+                ScalarType::Null
+            } else if matches!(
+                text,
+                "y" | "Y"
+                    | "yes"
+                    | "Yes"
+                    | "YES"
+                    | "n"
+                    | "N"
+                    | "no"
+                    | "No"
+                    | "NO"
+                    | "true"
+                    | "True"
+                    | "TRUE"
+                    | "false"
+                    | "False"
+                    | "FALSE"
+                    | "on"
+                    | "On"
+                    | "ON"
+                    | "off"
+                    | "Off"
+                    | "OFF"
+            ) {
+                ScalarType::Bool
+            } else if is_int_1_1(text) {
+                ScalarType::Int
+            } else if is_float_1_1(text) {
+                ScalarType::Float
+            } else {
+                ScalarType::String
+            }
+        }
+    }
+}
+
+fn is_digits(text: &str, pred: impl Fn(u8) -> bool) -> bool {
+    !text.is_empty() && text.bytes().all(pred)
+}
+
+fn strip_sign(text: &str) -> &str {
+    text.strip_prefix(['+', '-']).unwrap_or(text)
+}
+
+fn is_infinity_or_nan(text: &str) -> bool {
+    matches!(strip_sign(text), ".inf" | ".Inf" | ".INF") || matches!(text, ".nan" | ".NaN" | ".NAN")
+}
+
+/// `[-+]?[0-9]+`, `0o[0-7]+` or `0x[0-9a-fA-F]+`
+fn is_core_int(text: &str) -> bool {
+    is_digits(strip_sign(text), |b| b.is_ascii_digit())
+        || text
+            .strip_prefix("0o")
+            .is_some_and(|oct| is_digits(oct, |b| matches!(b, b'0'..=b'7')))
+        || text
+            .strip_prefix("0x")
+            .is_some_and(|hex| is_digits(hex, |b| b.is_ascii_hexdigit()))
+}
+
+/// `[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?`, infinity or NaN
+fn is_core_float(text: &str) -> bool {
+    if is_infinity_or_nan(text) {
+        return true;
+    }
+    let text = strip_sign(text);
+    let (mantissa, exponent) = text
+        .split_once(['e', 'E'])
+        .map_or((text, None), |(mantissa, exponent)| {
+            (mantissa, Some(exponent))
+        });
+    let is_mantissa = match mantissa.split_once('.') {
+        Some(("", fraction)) => is_digits(fraction, |b| b.is_ascii_digit()),
+        Some((integer, fraction)) => {
+            is_digits(integer, |b| b.is_ascii_digit())
+                && fraction.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => is_digits(mantissa, |b| b.is_ascii_digit()),
+    };
+    is_mantissa
+        && exponent.is_none_or(|exponent| is_digits(strip_sign(exponent), |b| b.is_ascii_digit()))
+}
+
+/// Integers of YAML 1.1, which allow `_` as separator and include binary, octal with `0` prefix
+/// and base 60 like `1:30`.
+fn is_int_1_1(text: &str) -> bool {
+    let text = strip_sign(text);
+    if let Some(bin) = text.strip_prefix("0b") {
+        is_digits(bin, |b| matches!(b, b'0' | b'1' | b'_'))
+    } else if let Some(hex) = text.strip_prefix("0x") {
+        is_digits(hex, |b| b.is_ascii_hexdigit() || b == b'_')
+    } else if let Some(oct) = text.strip_prefix('0') {
+        oct.bytes().all(|b| matches!(b, b'0'..=b'7' | b'_'))
+    } else if let Some((first, rest)) = text.split_once(':') {
+        is_decimal_1_1(first) && is_base_60(rest)
+    } else {
+        is_decimal_1_1(text)
+    }
+}
+
+/// Floats of YAML 1.1, including base 60 like `1:30.5`.
+fn is_float_1_1(text: &str) -> bool {
+    if is_infinity_or_nan(text) {
+        return true;
+    }
+    let text = strip_sign(text);
+    if let Some((first, rest)) = text.split_once(':') {
+        return rest.split_once('.').is_some_and(|(base_60, fraction)| {
+            text.starts_with(|c: char| c.is_ascii_digit())
+                && first.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+                && is_base_60(base_60)
+                && fraction.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+        });
+    }
+    let (mantissa, exponent) = text
+        .split_once(['e', 'E'])
+        .map_or((text, None), |(mantissa, exponent)| {
+            (mantissa, Some(exponent))
+        });
+    mantissa.split_once('.').is_some_and(|(integer, fraction)| {
+        (integer.is_empty() || integer.starts_with(|c: char| c.is_ascii_digit()))
+            && integer.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+            && fraction.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+            && mantissa.bytes().any(|b| b.is_ascii_digit())
+    }) && exponent.is_none_or(|exponent| {
+        exponent.starts_with(['+', '-']) && is_digits(&exponent[1..], |b| b.is_ascii_digit())
+    })
+}
+
+/// `0|[1-9][0-9_]*`
+fn is_decimal_1_1(text: &str) -> bool {
+    text == "0"
+        || text.starts_with(|c: char| matches!(c, '1'..='9'))
+            && text.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+}
+
+/// `[0-5]?[0-9](:[0-5]?[0-9])*`
+fn is_base_60(text: &str) -> bool {
+    text.split(':').all(|part| match part.as_bytes() {
+        [digit] => digit.is_ascii_digit(),
+        [tens, digit] => matches!(tens, b'0'..=b'5') && digit.is_ascii_digit(),
+        _ => false,
+    })
+}
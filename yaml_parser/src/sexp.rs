@@ -0,0 +1,107 @@
+use crate::{SyntaxElement, SyntaxKind, SyntaxNode};
+use rowan::TextSize;
+use std::fmt::Write;
+
+/// Export syntax tree as tree-sitter style S-expression, like `(block_map (block_map_entry ...))`.
+///
+/// Whitespaces and punctuations such as `:` or `-` are omitted,
+/// while tokens that carry content such as scalars and comments are kept.
+/// Unparseable code in trees from [`parse_recoverable`](crate::parse_recoverable)
+/// is exported as `(ERROR)`.
+/// If `with_ranges` is `true`, each node will be followed by its range
+/// in the format of `[row, column] - [row, column]`, which are zero-based.
+pub fn to_sexp(node: &SyntaxNode, with_ranges: bool) -> String {
+    let line_starts = if with_ranges {
+        let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+        collect_line_starts(&root.to_string())
+    } else {
+        vec![]
+    };
+    let mut output = String::new();
+    write_element(
+        &SyntaxElement::Node(node.clone()),
+        0,
+        &line_starts,
+        with_ranges,
+        &mut output,
+    );
+    output
+}
+
+fn write_element(
+    element: &SyntaxElement,
+    depth: usize,
+    line_starts: &[TextSize],
+    with_ranges: bool,
+    output: &mut String,
+) {
+    if depth > 0 {
+        output.push('\n');
+        output.push_str(&"  ".repeat(depth));
+    }
+    if element.kind() == SyntaxKind::ERROR {
+        // same as tree-sitter, errors are in upper case so they can be distinguished
+        output.push_str("(ERROR");
+    } else {
+        let _ = write!(
+            output,
+            "({}",
+            format!("{:?}", element.kind()).to_ascii_lowercase()
+        );
+    }
+    if with_ranges {
+        let range = element.text_range();
+        let (start_row, start_col) = position(line_starts, range.start());
+        let (end_row, end_col) = position(line_starts, range.end());
+        let _ = write!(
+            output,
+            " [{start_row}, {start_col}] - [{end_row}, {end_col}]"
+        );
+    }
+    if let SyntaxElement::Node(node) = element {
+        node.children_with_tokens()
+            .filter(|child| match child {
+                SyntaxElement::Node(..) => true,
+                SyntaxElement::Token(token) => is_named_token(token.kind()),
+            })
+            .for_each(|child| write_element(&child, depth + 1, line_starts, with_ranges, output));
+    }
+    output.push(')');
+}
+
+fn is_named_token(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::COMMENT
+            | SyntaxKind::ERROR
+            | SyntaxKind::INDENT_INDICATOR
+            | SyntaxKind::VERBATIM_TAG
+            | SyntaxKind::TAG_CHAR
+            | SyntaxKind::TAG_HANDLE_NAMED
+            | SyntaxKind::TAG_HANDLE_SECONDARY
+            | SyntaxKind::TAG_HANDLE_PRIMARY
+            | SyntaxKind::TAG_PREFIX
+            | SyntaxKind::ANCHOR_NAME
+            | SyntaxKind::DOUBLE_QUOTED_SCALAR
+            | SyntaxKind::SINGLE_QUOTED_SCALAR
+            | SyntaxKind::PLAIN_SCALAR
+            | SyntaxKind::BLOCK_SCALAR_TEXT
+            | SyntaxKind::DIRECTIVE_NAME
+            | SyntaxKind::YAML_VERSION
+            | SyntaxKind::DIRECTIVE_PARAM
+    )
+}
+
+fn collect_line_starts(text: &str) -> Vec<TextSize> {
+    let mut line_starts = vec![TextSize::from(0)];
+    line_starts.extend(
+        text.match_indices('\n')
+            .map(|(index, _)| TextSize::from(index as u32 + 1)),
+    );
+    line_starts
+}
+
+fn position(line_starts: &[TextSize], offset: TextSize) -> (usize, u32) {
+    let row = line_starts.partition_point(|start| *start <= offset) - 1;
+    (row, u32::from(offset - line_starts[row]))
+}
@@ -0,0 +1,271 @@
+use crate::{
+    comment::{leading_comments, line_end},
+    reparse::reparse_documents,
+    SyntaxError, SyntaxNode,
+};
+use rowan::{TextRange, TextSize};
+use std::{error::Error, fmt};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Replacement of text in specific range.
+pub struct TextEdit {
+    /// Range of original text to be replaced. Empty range means insertion.
+    pub range: TextRange,
+    /// New text. Empty text means deletion.
+    pub text: String,
+}
+
+#[derive(Clone, Debug)]
+/// Error type for committing [`Transaction`].
+pub enum TransactionError {
+    /// Two edits touch the same text, so they can't be applied together.
+    Conflict(TextEdit, TextEdit),
+    /// Range of edit is out of the syntax tree.
+    OutOfBounds(TextEdit),
+    /// Text after applying edits isn't valid YAML.
+    Syntax(SyntaxError),
+    /// Trailing comment can't be put after the node in this range,
+    /// for example, the line where node ends is inside a multi-line scalar.
+    CommentNotAllowed(TextRange),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionError::Conflict(a, b) => write!(
+                f,
+                "edit at {:?} conflicts with edit at {:?}",
+                a.range, b.range
+            ),
+            TransactionError::OutOfBounds(edit) => {
+                write!(f, "edit at {:?} is out of bounds", edit.range)
+            }
+            TransactionError::Syntax(error) => write!(f, "{error}"),
+            TransactionError::CommentNotAllowed(range) => {
+                write!(f, "comment can't be put after node at {range:?}")
+            }
+        }
+    }
+}
+
+impl Error for TransactionError {}
+
+/// Record multiple edits, then apply them all at once.
+///
+/// Ranges of edits are based on the original tree, so edits don't affect ranges of each other.
+/// If any edits are conflicting or the result is invalid, nothing will be applied.
+///
+/// When committing, only documents that are touched by edits are reparsed,
+/// and their green nodes are replaced in the original green tree,
+/// so untouched documents are shared between the original tree and the new tree.
+///
+/// ```
+/// use rowan::TextRange;
+/// use yaml_parser::{parse, Transaction};
+///
+/// let tree = parse("a: 1\nb: 2\n").unwrap();
+/// let mut transaction = Transaction::new(&tree);
+/// transaction
+///     .replace(TextRange::new(3.into(), 4.into()), "10")
+///     .insert(10.into(), "c: 3\n");
+/// let (tree, changed) = transaction.commit().unwrap();
+/// assert_eq!(tree.to_string(), "a: 10\nb: 2\nc: 3\n");
+/// assert_eq!(changed.len(), 2);
+/// ```
+pub struct Transaction {
+    root: SyntaxNode,
+    edits: Vec<TextEdit>,
+    /// Error that is found when recording edits, which will be reported when committing.
+    error: Option<TransactionError>,
+}
+
+impl Transaction {
+    /// Create transaction for the given syntax tree.
+    pub fn new(root: &SyntaxNode) -> Self {
+        Self {
+            root: root.clone(),
+            edits: vec![],
+            error: None,
+        }
+    }
+
+    /// Replace text in the given range.
+    pub fn replace(&mut self, range: TextRange, text: impl Into<String>) -> &mut Self {
+        self.edits.push(TextEdit {
+            range,
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Insert text at the given offset.
+    pub fn insert(&mut self, offset: TextSize, text: impl Into<String>) -> &mut Self {
+        self.replace(TextRange::empty(offset), text)
+    }
+
+    /// Delete text in the given range.
+    pub fn delete(&mut self, range: TextRange) -> &mut Self {
+        self.replace(range, "")
+    }
+
+    /// Replace the whole node with the given text.
+    pub fn replace_node(&mut self, node: &SyntaxNode, text: impl Into<String>) -> &mut Self {
+        self.replace(node.text_range(), text)
+    }
+
+    /// Add comment lines before the given node, or replace existing leading comments.
+    ///
+    /// Each line of text will be prefixed with `#` and indented as the line where node starts.
+    pub fn set_leading_comment(&mut self, node: &SyntaxNode, text: &str) -> &mut Self {
+        let comments = leading_comments(node);
+        let (offset, indent) = line_indent(node);
+        let mut comment = text
+            .lines()
+            .map(format_comment)
+            .collect::<Vec<_>>()
+            .join(&format!("\n{indent}"));
+        if let Some(first) = comments.first() {
+            self.replace(
+                TextRange::new(first.text_range().start(), node.text_range().start()),
+                comment + "\n" + &indent,
+            )
+        } else {
+            comment.push('\n');
+            comment.push_str(&indent);
+            self.insert(offset, comment)
+        }
+    }
+
+    /// Remove comment lines before the given node.
+    pub fn remove_leading_comment(&mut self, node: &SyntaxNode) -> &mut Self {
+        if let Some(first) = leading_comments(node).first() {
+            self.delete(TextRange::new(
+                first.text_range().start(),
+                node.text_range().start(),
+            ));
+        }
+        self
+    }
+
+    /// Add comment at the end of the line where the given node ends,
+    /// or replace existing trailing comment.
+    ///
+    /// Line breaks in text will be replaced with spaces.
+    /// If comment can't be put there, such as the line ends inside a multi-line scalar,
+    /// [`TransactionError::CommentNotAllowed`] will be returned when committing.
+    pub fn set_trailing_comment(&mut self, node: &SyntaxNode, text: &str) -> &mut Self {
+        let comment = format_comment(&text.replace(['\r', '\n'], " "));
+        match line_end(node) {
+            Some((_, Some(token))) => self.replace(token.text_range(), comment),
+            Some((whitespaces, None)) => self.replace(whitespaces, format!(" {comment}")),
+            None => {
+                self.error
+                    .get_or_insert(TransactionError::CommentNotAllowed(node.text_range()));
+                self
+            }
+        }
+    }
+
+    /// Remove comment at the end of the line where the given node ends.
+    pub fn remove_trailing_comment(&mut self, node: &SyntaxNode) -> &mut Self {
+        if let Some((whitespaces, Some(token))) = line_end(node) {
+            self.delete(TextRange::new(
+                whitespaces.start(),
+                token.text_range().end(),
+            ));
+        }
+        self
+    }
+
+    /// Recorded edits in insertion order.
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    /// Apply all edits, then return the new syntax tree
+    /// and ranges in new tree that are changed by edits.
+    ///
+    /// Changed ranges are sorted and merged if they're adjacent,
+    /// and deletions are represented as empty ranges.
+    pub fn commit(self) -> Result<(SyntaxNode, Vec<TextRange>), TransactionError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut edits = self.edits;
+        let full_range = self.root.text_range();
+        if let Some(edit) = edits
+            .iter()
+            .find(|edit| !full_range.contains_range(edit.range))
+        {
+            return Err(TransactionError::OutOfBounds(edit.clone()));
+        }
+
+        // stable sort keeps insertion order of edits at the same position
+        edits.sort_by_key(|edit| (edit.range.start(), edit.range.end()));
+        if let Some((a, b)) = edits
+            .windows(2)
+            .map(|pair| (&pair[0], &pair[1]))
+            .find(|(a, b)| is_conflicting(a, b))
+        {
+            return Err(TransactionError::Conflict(a.clone(), b.clone()));
+        }
+
+        let source = self.root.to_string();
+        let mut text = String::with_capacity(source.len());
+        let mut changed: Vec<TextRange> = Vec::with_capacity(edits.len());
+        let mut last = 0;
+        for edit in &edits {
+            text.push_str(&source[last..usize::from(edit.range.start())]);
+            let start = TextSize::of(&*text);
+            text.push_str(&edit.text);
+            let range = TextRange::new(start, TextSize::of(&*text));
+            match changed.last_mut() {
+                Some(prev) if prev.end() == range.start() => *prev = prev.cover(range),
+                _ => changed.push(range),
+            }
+            last = edit.range.end().into();
+        }
+        text.push_str(&source[last..]);
+
+        reparse_documents(&self.root, &edits, &text)
+            .map(|tree| (tree, changed))
+            .map_err(TransactionError::Syntax)
+    }
+}
+
+fn format_comment(text: &str) -> String {
+    let text = text.trim_end();
+    if text.is_empty() {
+        "#".into()
+    } else {
+        format!("# {text}")
+    }
+}
+
+/// Get the position after indentation of the line where node starts, and that indentation.
+fn line_indent(node: &SyntaxNode) -> (TextSize, String) {
+    let mut prefix = String::new();
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    while let Some(current) = token {
+        if let Some((_, rest)) = current.text().rsplit_once('\n') {
+            prefix.insert_str(0, rest);
+            break;
+        }
+        prefix.insert_str(0, current.text());
+        token = current.prev_token();
+    }
+    let line_start = node.text_range().start() - TextSize::of(&*prefix);
+    let indent = &prefix[..prefix.len() - prefix.trim_start_matches([' ', '\t']).len()];
+    (line_start + TextSize::of(indent), indent.to_string())
+}
+
+/// Edits must be sorted by range.
+/// Insertions at the same position are conflicting since their order is ambiguous,
+/// and insertion inside replaced range is conflicting, too.
+fn is_conflicting(a: &TextEdit, b: &TextEdit) -> bool {
+    if a.range.is_empty() && b.range.is_empty() {
+        a.range.start() == b.range.start()
+    } else {
+        a.range.start() < b.range.end() && b.range.start() < a.range.end()
+    }
+}
@@ -0,0 +1,106 @@
+use yaml_parser::{
+    analyze::{duplicate_keys, effective_entries},
+    ast::{AstNode, Root},
+    parse,
+    resolve::AnchorTable,
+    SyntaxKind,
+};
+
+fn find_duplicates(code: &str) -> Vec<(String, String, String)> {
+    let root = Root::cast(parse(code).unwrap()).unwrap();
+    duplicate_keys(&root)
+        .into_iter()
+        .map(|duplicate| {
+            (
+                duplicate.key,
+                duplicate.entry.to_string(),
+                duplicate.previous.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn block_map() {
+    assert_eq!(
+        find_duplicates("a: 1\nb: 2\n\"a\": 3\n'a': 4\n"),
+        [
+            ("a".into(), "\"a\": 3".into(), "a: 1".into()),
+            ("a".into(), "'a': 4".into(), "\"a\": 3".into()),
+        ]
+    );
+}
+
+#[test]
+fn flow_map() {
+    assert_eq!(
+        find_duplicates("[{a: 1, b, a}, {a: 2}]\n"),
+        [("a".into(), "a".into(), "a: 1".into())]
+    );
+}
+
+#[test]
+fn nested_maps() {
+    // same keys in different maps aren't duplicates
+    assert!(find_duplicates("a:\n  a: 1\nb:\n  a: 2\n---\na: 3\n").is_empty());
+    assert_eq!(
+        find_duplicates("a:\n  b: 1\n  b: 2\nc: 3\n"),
+        [("b".into(), "b: 2".into(), "b: 1".into())]
+    );
+}
+
+#[test]
+fn resolved_keys() {
+    assert_eq!(
+        find_duplicates("\"a\\tb\": 1\n\"a\tb\": 2\n? a\n  b\n: 3\na b: 4\n").len(),
+        2
+    );
+    // plain `1` is integer while quoted `"1"` is string
+    assert!(find_duplicates("1: a\n\"1\": b\n").is_empty());
+    assert_eq!(find_duplicates("1: a\n1: b\n").len(), 1);
+    // keys with properties are skipped
+    assert!(find_duplicates("!!str a: 1\na: 2\n").is_empty());
+}
+
+/// Effective entries of the last block map in the code, with whether they're merged.
+fn find_effective_entries(code: &str) -> Vec<(String, bool)> {
+    let root = Root::cast(parse(code).unwrap()).unwrap();
+    let table = AnchorTable::new(&root);
+    let map = root
+        .syntax()
+        .descendants()
+        .filter(|node| node.kind() == SyntaxKind::BLOCK_MAP)
+        .last()
+        .unwrap();
+    effective_entries(&map, &table)
+        .into_iter()
+        .map(|entry| (entry.entry.to_string(), entry.merged_by.is_some()))
+        .collect()
+}
+
+#[test]
+fn merge_keys() {
+    assert_eq!(
+        find_effective_entries("base: &b {x: 1, y: 2}\njob:\n  a: 0\n  <<: *b\n  y: 3\n"),
+        [
+            ("a: 0".into(), false),
+            ("x: 1".into(), true),
+            ("y: 3".into(), false),
+        ]
+    );
+    // earlier maps in sequence take precedence
+    assert_eq!(
+        find_effective_entries("a: &a {x: 1}\nb: &b {x: 2, y: 2}\nc:\n  <<: [*a, *b]\n"),
+        [("x: 1".into(), true), ("y: 2".into(), true)]
+    );
+    // merged maps are resolved recursively
+    assert_eq!(
+        find_effective_entries("a: &a {x: 1}\nb: &b {<<: *a, y: 2}\nc:\n  <<: *b\n"),
+        [("x: 1".into(), true), ("y: 2".into(), true)]
+    );
+    // undefined aliases and non-map values are skipped
+    assert_eq!(
+        find_effective_entries("s: &s 1\nc:\n  <<: [*s, *u]\n  z: 0\n"),
+        [("z: 0".into(), false)]
+    );
+}
@@ -0,0 +1,212 @@
+use yaml_parser::{
+    ast::{AstNode, BlockMap, BlockMapEntry, CommentAttachment, Root},
+    leading_comments, parse, trailing_comment, SyntaxKind, SyntaxNode, SyntaxToken, Transaction,
+    TransactionError,
+};
+
+fn map_entries(tree: &SyntaxNode) -> Vec<SyntaxNode> {
+    tree.descendants()
+        .filter_map(BlockMap::cast)
+        .flat_map(|map| map.entries())
+        .map(|entry| entry.syntax().clone())
+        .collect()
+}
+
+#[test]
+fn find_comments() {
+    let tree = parse("# a\n# b\n\n# c\nkey: value # d\nnested:\n  # e\n  k: v\n").unwrap();
+    let entries = map_entries(&tree);
+
+    let leading = leading_comments(&entries[0]);
+    assert_eq!(
+        leading.iter().map(|token| token.text()).collect::<Vec<_>>(),
+        ["# c"]
+    );
+    assert_eq!(trailing_comment(&entries[0]).unwrap().text(), "# d");
+
+    assert!(leading_comments(&entries[1]).is_empty());
+    assert!(trailing_comment(&entries[1]).is_none());
+
+    let leading = leading_comments(&entries[2]);
+    assert_eq!(
+        leading.iter().map(|token| token.text()).collect::<Vec<_>>(),
+        ["# e"]
+    );
+}
+
+#[test]
+fn edit_leading_comments() {
+    let tree = parse("a: 1\nb:\n  # old\n  # old\n  c: 2\n").unwrap();
+    let entries = map_entries(&tree);
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .set_leading_comment(&entries[0], "managed by X\n\ndo not edit")
+        .set_leading_comment(&entries[2], "new");
+    let (tree, _) = transaction.commit().unwrap();
+    assert_eq!(
+        tree.to_string(),
+        "# managed by X\n#\n# do not edit\na: 1\nb:\n  # new\n  c: 2\n"
+    );
+
+    let entries = map_entries(&tree);
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .remove_leading_comment(&entries[0])
+        .remove_leading_comment(&entries[2]);
+    let (tree, _) = transaction.commit().unwrap();
+    assert_eq!(tree.to_string(), "a: 1\nb:\n  c: 2\n");
+}
+
+#[test]
+fn edit_trailing_comments() {
+    let tree = parse("a: 1 # old\nb: 2\n").unwrap();
+    let entries = map_entries(&tree);
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .set_trailing_comment(&entries[0], "new")
+        .set_trailing_comment(&entries[1], "added");
+    let (tree, _) = transaction.commit().unwrap();
+    assert_eq!(tree.to_string(), "a: 1 # new\nb: 2 # added\n");
+
+    let entries = map_entries(&tree);
+    let mut transaction = Transaction::new(&tree);
+    transaction.remove_trailing_comment(&entries[0]);
+    let (tree, _) = transaction.commit().unwrap();
+    assert_eq!(tree.to_string(), "a: 1\nb: 2 # added\n");
+}
+
+#[test]
+fn trailing_comments_of_nodes_not_at_line_end() {
+    let tree = parse("a: 1 # old\nb: [1,\n  2]\nc: |\n  text\n").unwrap();
+    let entries = map_entries(&tree);
+    let keys = entries
+        .iter()
+        .filter_map(|entry| BlockMapEntry::cast(entry.clone()))
+        .filter_map(|entry| entry.key())
+        .map(|key| key.syntax().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(trailing_comment(&keys[0]).unwrap().text(), "# old");
+
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .set_trailing_comment(&keys[0], "new")
+        .set_trailing_comment(&keys[1], "first")
+        .set_trailing_comment(&keys[2], "header");
+    let (tree, _) = transaction.commit().unwrap();
+    assert_eq!(
+        tree.to_string(),
+        "a: 1 # new\nb: [1, # first\n  2]\nc: | # header\n  text\n"
+    );
+
+    let entries = map_entries(&tree);
+    let key = BlockMapEntry::cast(entries[0].clone())
+        .and_then(|entry| entry.key())
+        .unwrap();
+    let mut transaction = Transaction::new(&tree);
+    transaction.remove_trailing_comment(key.syntax());
+    let (tree, _) = transaction.commit().unwrap();
+    assert!(tree.to_string().starts_with("a: 1\nb:"));
+}
+
+#[test]
+fn trailing_comments_not_allowed() {
+    let tree = parse("a: \"multi\n  line\"\nb: |\n  text\n").unwrap();
+    let entries = map_entries(&tree);
+    let key = BlockMapEntry::cast(entries[0].clone())
+        .and_then(|entry| entry.key())
+        .unwrap();
+    let mut transaction = Transaction::new(&tree);
+    transaction.set_trailing_comment(key.syntax(), "comment");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::CommentNotAllowed(..))
+    ));
+
+    let mut transaction = Transaction::new(&tree);
+    transaction.set_trailing_comment(&entries[1], "comment");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::CommentNotAllowed(..))
+    ));
+}
+
+#[test]
+fn comment_attachment() {
+    let tree = parse(
+        "# top\na:\n  # lead\n  b: 1 # trail\n  # end\n\n# before c\nc: [ # open\n  d, # after d\n  # dangling\n]\n# eof\n",
+    )
+    .unwrap();
+    let texts = |tokens: &[SyntaxToken]| {
+        tokens
+            .iter()
+            .map(|token| token.text().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let entries = map_entries(&tree);
+
+    let a = CommentAttachment::for_node(&entries[0]);
+    assert_eq!(texts(&a.leading), ["# top"]);
+    assert_eq!(a.trailing.unwrap().text(), "# trail");
+    assert!(a.dangling.is_empty());
+
+    let b = CommentAttachment::for_node(&entries[2]);
+    assert_eq!(texts(&b.leading), ["# lead"]);
+    assert_eq!(b.trailing.unwrap().text(), "# trail");
+
+    let c = CommentAttachment::for_node(&entries[1]);
+    assert_eq!(texts(&c.leading), ["# before c"]);
+    assert!(c.trailing.is_none());
+
+    let outer_map = entries[0].parent().unwrap();
+    assert_eq!(
+        texts(&CommentAttachment::for_node(&outer_map).dangling),
+        ["# end"]
+    );
+
+    let flow_seq = tree
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::FLOW_SEQ)
+        .unwrap();
+    assert_eq!(
+        texts(&CommentAttachment::for_node(&flow_seq).dangling),
+        ["# open", "# dangling"]
+    );
+    let d = tree
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::FLOW_SEQ_ENTRY)
+        .unwrap();
+    assert_eq!(
+        CommentAttachment::for_node(&d).trailing.unwrap().text(),
+        "# after d"
+    );
+
+    assert_eq!(
+        texts(&CommentAttachment::for_node(&tree).dangling),
+        ["# eof"]
+    );
+}
+
+#[test]
+fn root_trivia() {
+    let texts = |tokens: Vec<SyntaxToken>| {
+        tokens
+            .iter()
+            .map(|token| token.text().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let root = Root::cast(parse("# a\n\nkey: value\n---\nb\n\n# c\n# d\n").unwrap()).unwrap();
+    assert_eq!(texts(root.leading_trivia()), ["# a", "\n\n"]);
+    assert_eq!(
+        texts(root.trailing_trivia()),
+        ["\n\n", "# c", "\n", "# d", "\n"]
+    );
+
+    let root = Root::cast(parse("  # a\n# b").unwrap()).unwrap();
+    assert_eq!(texts(root.leading_trivia()), ["  ", "# a", "\n", "# b"]);
+    assert!(root.trailing_trivia().is_empty());
+
+    let root = Root::cast(parse("").unwrap()).unwrap();
+    assert!(root.leading_trivia().is_empty());
+    assert!(root.trailing_trivia().is_empty());
+}
@@ -0,0 +1,34 @@
+use yaml_parser::{
+    ast::{AstNode, Root},
+    parse,
+};
+
+/// Document markers at the start of line are forbidden in plain scalars,
+/// so a `---` right after a directives end marker starts another document
+/// instead of being the content of the previous one.
+#[test]
+fn directives_end_after_directives_end() {
+    let root = Root::cast(parse("---\n---\n").unwrap()).unwrap();
+    let documents = root.documents().collect::<Vec<_>>();
+    assert_eq!(documents.len(), 2);
+    assert!(documents
+        .iter()
+        .all(|document| document.block().is_none() && document.syntax().to_string() == "---"));
+}
+
+#[test]
+fn directives_end_before_content() {
+    let root = Root::cast(parse("---\n---\na: 1\n").unwrap()).unwrap();
+    let documents = root.documents().collect::<Vec<_>>();
+    assert_eq!(documents.len(), 2);
+    assert!(documents[0].block().is_none());
+    assert_eq!(documents[1].syntax().to_string(), "---\na: 1");
+}
+
+#[test]
+fn document_end_after_directives_end() {
+    let root = Root::cast(parse("---\n...\n").unwrap()).unwrap();
+    let documents = root.documents().collect::<Vec<_>>();
+    assert_eq!(documents.len(), 1);
+    assert!(documents[0].document_end().is_some());
+}
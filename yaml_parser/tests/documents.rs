@@ -0,0 +1,58 @@
+use insta::glob;
+use std::fs;
+use yaml_parser::{
+    ast::{AstNode, Document, Root},
+    parse, parse_documents,
+};
+
+#[test]
+fn same_documents_as_parse() {
+    glob!("pass/**/*.yaml", |path| {
+        let input = fs::read_to_string(path).unwrap();
+        let root = Root::cast(parse(&input).unwrap()).unwrap();
+        let expected = root
+            .documents()
+            .map(|document| document.syntax().to_string())
+            .collect::<Vec<_>>();
+        let actual = parse_documents(&input)
+            .map(|document| document.unwrap().syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected, "documents of '{}'", path.display());
+    });
+}
+
+#[test]
+fn directives_and_comments() {
+    let input = "# first\na: 1\n...\n%YAML 1.2\n---\nb: 2\n# trailing\n";
+    let documents = parse_documents(input)
+        .collect::<Result<Vec<Document>, _>>()
+        .unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0].syntax().to_string(), "a: 1\n...");
+    assert_eq!(documents[1].directives().count(), 1);
+}
+
+#[test]
+fn comments_after_last_document() {
+    let input = "a: 1\n---\nb: 2\n# comment\n";
+    let documents = parse_documents(input)
+        .collect::<Result<Vec<Document>, _>>()
+        .unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[1].syntax().to_string(), "---\nb: 2");
+    let text = documents
+        .iter()
+        .map(|document| document.syntax().parent().unwrap().to_string())
+        .collect::<String>();
+    assert_eq!(text, input);
+}
+
+#[test]
+fn comments_after_document_end_marker() {
+    let input = "a: 1\n...\n# comment\n";
+    let mut documents = parse_documents(input);
+    let document = documents.next().unwrap().unwrap();
+    assert_eq!(document.syntax().to_string(), "a: 1\n...");
+    assert_eq!(document.syntax().parent().unwrap().to_string(), input);
+    assert!(documents.next().is_none());
+}
@@ -0,0 +1,128 @@
+use yaml_parser::{
+    ast::{AstNode, BlockMap, BlockMapEntry, BlockSeq, BlockSeqEntry, Document, Flow},
+    parse, SyntaxFactory, SyntaxNode,
+};
+
+fn find<N: AstNode>(tree: &SyntaxNode, nth: usize) -> N {
+    tree.descendants().filter_map(N::cast).nth(nth).unwrap()
+}
+
+#[test]
+fn edit_block_map() {
+    let tree = parse("a: 1 # one\nb:\n  c: 2\n")
+        .unwrap()
+        .clone_for_update();
+    let nested = find::<BlockMap>(&tree, 1);
+    nested.push_entry(
+        SyntaxFactory::plain_scalar("d"),
+        SyntaxFactory::block_seq([SyntaxFactory::plain_scalar("x").syntax().clone()])
+            .syntax()
+            .clone(),
+    );
+    nested.insert_entry(
+        0,
+        SyntaxFactory::plain_scalar("z"),
+        SyntaxFactory::plain_scalar("0").syntax().clone(),
+    );
+    let root = find::<BlockMap>(&tree, 0);
+    root.insert_entry(
+        1,
+        SyntaxFactory::plain_scalar("e"),
+        SyntaxFactory::plain_scalar("3").syntax().clone(),
+    );
+    assert_eq!(
+        tree.to_string(),
+        "a: 1 # one\ne: 3\nb:\n  z: 0\n  c: 2\n  d:\n    - x\n"
+    );
+    assert!(parse(&tree.to_string()).is_ok());
+
+    root.entries().next().unwrap().remove();
+    assert_eq!(
+        tree.to_string(),
+        "e: 3\nb:\n  z: 0\n  c: 2\n  d:\n    - x\n"
+    );
+    find::<BlockMap>(&tree, 1)
+        .entries()
+        .nth(1)
+        .unwrap()
+        .remove();
+    assert_eq!(tree.to_string(), "e: 3\nb:\n  z: 0\n  d:\n    - x\n");
+}
+
+#[test]
+fn edit_block_seq() {
+    let tree = parse("- a\n- b # comment\n").unwrap().clone_for_update();
+    let seq = find::<BlockSeq>(&tree, 0);
+    seq.push_entry(
+        SyntaxFactory::block_map([(
+            SyntaxFactory::plain_scalar("k"),
+            SyntaxFactory::plain_scalar("v").syntax().clone(),
+        )])
+        .syntax()
+        .clone(),
+    );
+    seq.insert_entry(0, SyntaxFactory::plain_scalar("first").syntax().clone());
+    assert_eq!(tree.to_string(), "- first\n- a\n- b # comment\n- k: v\n");
+
+    seq.entries().nth(2).unwrap().remove();
+    assert_eq!(tree.to_string(), "- first\n- a\n- k: v\n");
+}
+
+#[test]
+fn replace_scalar() {
+    let tree = parse("a: &x 1\nb: [c]\n").unwrap().clone_for_update();
+    find::<Flow>(&tree, 1).replace_scalar(SyntaxFactory::double_quoted_scalar("new"));
+    find::<Flow>(&tree, 3).replace_scalar(SyntaxFactory::plain_scalar("d"));
+    assert_eq!(tree.to_string(), "a: &x \"new\"\nb: d\n");
+}
+
+#[test]
+fn replace_value() {
+    let tree = parse("a: 1 # one\nb:\n  c: 2\nd:\n  - x\n  - y\n")
+        .unwrap()
+        .clone_for_update();
+    find::<BlockMapEntry>(&tree, 0).replace_value(
+        SyntaxFactory::block_map([(
+            SyntaxFactory::plain_scalar("e"),
+            SyntaxFactory::plain_scalar("3").syntax().clone(),
+        )])
+        .syntax()
+        .clone(),
+    );
+    find::<BlockMapEntry>(&tree, 2)
+        .replace_value(SyntaxFactory::plain_scalar("4").syntax().clone());
+    find::<BlockSeqEntry>(&tree, 1).replace_value(
+        SyntaxFactory::block_map([(
+            SyntaxFactory::plain_scalar("k"),
+            SyntaxFactory::plain_scalar("v").syntax().clone(),
+        )])
+        .syntax()
+        .clone(),
+    );
+    assert_eq!(
+        tree.to_string(),
+        "a:\n  e: 3 # one\nb: 4\nd:\n  - x\n  - k: v\n"
+    );
+    assert!(parse(&tree.to_string()).is_ok());
+}
+
+#[test]
+fn remove_node() {
+    let tree = parse("a: [x, y, z]\nb: {k: v, l: w}\nc:\n  - 1\n  - 2\n")
+        .unwrap()
+        .clone_for_update();
+    let document = find::<Document>(&tree, 0);
+    assert!(document.remove_node(find::<Flow>(&tree, 3).syntax()));
+    assert_eq!(
+        tree.to_string(),
+        "a: [x, z]\nb: {k: v, l: w}\nc:\n  - 1\n  - 2\n"
+    );
+    assert!(document.remove_node(find::<Flow>(&tree, 8).syntax()));
+    assert_eq!(tree.to_string(), "a: [x, z]\nb: {k: v}\nc:\n  - 1\n  - 2\n");
+    assert!(document.remove_node(find::<BlockSeqEntry>(&tree, 0).syntax()));
+    assert_eq!(tree.to_string(), "a: [x, z]\nb: {k: v}\nc:\n  - 2\n");
+    assert!(!document.remove_node(document.syntax()));
+
+    let other = parse("a: 1\n").unwrap().clone_for_update();
+    assert!(!document.remove_node(&find::<Flow>(&other, 0).syntax().clone()));
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use yaml_parser::{parse, SyntaxKind, SyntaxNode, SyntaxNodeExt};
+
+fn find(tree: &SyntaxNode, kind: SyntaxKind, index: usize) -> SyntaxNode {
+    tree.descendants()
+        .filter(|node| node.kind() == kind)
+        .nth(index)
+        .unwrap()
+}
+
+#[test]
+fn ignore_trivia() {
+    let a = parse("# head\na: 1\nb:\n  - x # item\n  - y\n").unwrap();
+    let b = parse("a:   1\n\nb:\n    - x\n    -   y\n").unwrap();
+    assert_eq!(a.structural_hash(), b.structural_hash());
+
+    let a = parse("{a: 1, b: [x, y]}\n").unwrap();
+    let b = parse("{ a: 1,\n  # comment\n  b: [ x,y ] }\n").unwrap();
+    assert_eq!(a.structural_hash(), b.structural_hash());
+}
+
+#[test]
+fn detect_changes() {
+    let tree = parse("a: 1\nb: 2\n").unwrap();
+    assert_ne!(
+        tree.structural_hash(),
+        parse("a: 1\nb: 3\n").unwrap().structural_hash()
+    );
+    assert_ne!(
+        tree.structural_hash(),
+        parse("a: 1\nb: '2'\n").unwrap().structural_hash()
+    );
+    assert_ne!(
+        tree.structural_hash(),
+        parse("b: 2\na: 1\n").unwrap().structural_hash()
+    );
+    assert_ne!(
+        parse("a: [x]\n").unwrap().structural_hash(),
+        parse("a: [[x]]\n").unwrap().structural_hash()
+    );
+    assert_ne!(
+        parse("a: [x, y]\n").unwrap().structural_hash(),
+        parse("a:\n  - x\n  - y\n").unwrap().structural_hash()
+    );
+}
+
+#[test]
+fn subtree() {
+    let a = parse("a:\n  b: 1\nc: 2\n").unwrap();
+    let b = parse("c: 3\nd:\n  b: 1\n").unwrap();
+    let map_a = find(&a, SyntaxKind::BLOCK_MAP, 1);
+    let map_b = find(&b, SyntaxKind::BLOCK_MAP, 1);
+    assert_eq!(map_a.structural_hash(), map_b.structural_hash());
+    assert_ne!(a.structural_hash(), b.structural_hash());
+}
+
+#[test]
+fn node_id() {
+    let a = parse("a: 1\nb: [x, y]\n").unwrap();
+    let b = parse("# comment\na:   100\nb: [ x,  z ]\n").unwrap();
+    a.descendants()
+        .zip(b.descendants())
+        .for_each(|(a, b)| assert_eq!(a.node_id(), b.node_id()));
+
+    let ids = a
+        .descendants()
+        .map(|node| node.node_id())
+        .collect::<HashSet<_>>();
+    assert_eq!(ids.len(), a.descendants().count());
+
+    let c = parse("z: 0\na: 1\nb: [x, y]\n").unwrap();
+    assert_ne!(
+        find(&a, SyntaxKind::FLOW_SEQ, 0).node_id(),
+        find(&c, SyntaxKind::FLOW_SEQ, 0).node_id()
+    );
+}
+
+#[test]
+fn stable_values() {
+    let tree = parse("a: 1\n").unwrap();
+    assert_eq!(
+        tree.structural_hash(),
+        tree.clone_subtree().structural_hash()
+    );
+    assert_eq!(tree.structural_hash(), 4430919146418066646);
+    assert_eq!(tree.node_id().to_string(), "c4658ab3fb9fc65f");
+    assert_eq!(
+        find(&tree, SyntaxKind::BLOCK_MAP, 0).node_id(),
+        find(&parse("a: 2\n").unwrap(), SyntaxKind::BLOCK_MAP, 0).node_id()
+    );
+}
@@ -0,0 +1,181 @@
+use insta::glob;
+use std::fs;
+use yaml_parser::{lex, parse, SyntaxKind};
+
+fn lex_tokens(code: &str) -> Vec<(SyntaxKind, &str)> {
+    lex(code)
+        .map(|(kind, range)| (kind, &code[range]))
+        .collect()
+}
+
+/// Tokens of syntax tree with consecutive whitespaces merged.
+fn tree_tokens(code: &str) -> Vec<(SyntaxKind, String)> {
+    let tree = parse(code).unwrap();
+    let mut tokens = Vec::<(SyntaxKind, String)>::new();
+    tree.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.text().is_empty())
+        .for_each(|token| match tokens.last_mut() {
+            Some((SyntaxKind::WHITESPACE, text)) if token.kind() == SyntaxKind::WHITESPACE => {
+                text.push_str(token.text());
+            }
+            _ => tokens.push((token.kind(), token.text().to_owned())),
+        });
+    tokens
+}
+
+#[test]
+fn same_as_tree() {
+    glob!("pass/*.yaml", |path| {
+        let input = fs::read_to_string(path).unwrap();
+        let tokens = lex_tokens(&input)
+            .into_iter()
+            .map(|(kind, text)| (kind, text.to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            tree_tokens(&input),
+            "tokens of '{}' don't match syntax tree",
+            path.display()
+        );
+    });
+}
+
+#[test]
+fn plain_scalars() {
+    assert_eq!(
+        lex_tokens("a: b\n  c\nd: e\n"),
+        [
+            (SyntaxKind::PLAIN_SCALAR, "a"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "b\n  c"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::PLAIN_SCALAR, "d"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "e"),
+            (SyntaxKind::WHITESPACE, "\n"),
+        ]
+    );
+    // continuation lines of value in compact mapping must be indented more than its key
+    assert_eq!(
+        lex_tokens("- a: b\n  c: d\n"),
+        [
+            (SyntaxKind::MINUS, "-"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "a"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "b"),
+            (SyntaxKind::WHITESPACE, "\n  "),
+            (SyntaxKind::PLAIN_SCALAR, "c"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "d"),
+            (SyntaxKind::WHITESPACE, "\n"),
+        ]
+    );
+    assert_eq!(
+        lex_tokens("[a:b, c d]"),
+        [
+            (SyntaxKind::L_BRACKET, "["),
+            (SyntaxKind::PLAIN_SCALAR, "a:b"),
+            (SyntaxKind::COMMA, ","),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "c d"),
+            (SyntaxKind::R_BRACKET, "]"),
+        ]
+    );
+}
+
+#[test]
+fn block_scalar() {
+    assert_eq!(
+        lex_tokens("a: |+2 # comment\n    b\n\n   c\nd: e\n"),
+        [
+            (SyntaxKind::PLAIN_SCALAR, "a"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::BAR, "|"),
+            (SyntaxKind::PLUS, "+"),
+            (SyntaxKind::INDENT_INDICATOR, "2"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::COMMENT, "# comment"),
+            (SyntaxKind::BLOCK_SCALAR_TEXT, "\n    b\n\n   c"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::PLAIN_SCALAR, "d"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::PLAIN_SCALAR, "e"),
+            (SyntaxKind::WHITESPACE, "\n"),
+        ]
+    );
+}
+
+#[test]
+fn documents() {
+    assert_eq!(
+        lex_tokens("%YAML 1.2\n%TAG !e! tag:e.com:\n--- !e!x &a *b\n...\n"),
+        [
+            (SyntaxKind::PERCENT, "%"),
+            (SyntaxKind::DIRECTIVE_NAME, "YAML"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::YAML_VERSION, "1.2"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::PERCENT, "%"),
+            (SyntaxKind::DIRECTIVE_NAME, "TAG"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::TAG_HANDLE_NAMED, "!e!"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::TAG_PREFIX, "tag:e.com:"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::DIRECTIVES_END, "---"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::TAG_HANDLE_NAMED, "!e!"),
+            (SyntaxKind::TAG_CHAR, "x"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::AMPERSAND, "&"),
+            (SyntaxKind::ANCHOR_NAME, "a"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::ASTERISK, "*"),
+            (SyntaxKind::ANCHOR_NAME, "b"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::DOCUMENT_END, "..."),
+            (SyntaxKind::WHITESPACE, "\n"),
+        ]
+    );
+}
+
+#[test]
+fn byte_order_mark() {
+    let code = "\u{feff}a: 1";
+    assert_eq!(
+        lex(code).collect::<Vec<_>>(),
+        [
+            (SyntaxKind::PLAIN_SCALAR, 3..4),
+            (SyntaxKind::COLON, 4..5),
+            (SyntaxKind::WHITESPACE, 5..6),
+            (SyntaxKind::PLAIN_SCALAR, 6..7),
+        ]
+    );
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(
+        lex_tokens("a: ]b\nc: \"d"),
+        [
+            (SyntaxKind::PLAIN_SCALAR, "a"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::ERROR, "]"),
+            (SyntaxKind::PLAIN_SCALAR, "b"),
+            (SyntaxKind::WHITESPACE, "\n"),
+            (SyntaxKind::PLAIN_SCALAR, "c"),
+            (SyntaxKind::COLON, ":"),
+            (SyntaxKind::WHITESPACE, " "),
+            (SyntaxKind::ERROR, "\"d"),
+        ]
+    );
+}
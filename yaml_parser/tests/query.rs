@@ -0,0 +1,69 @@
+use yaml_parser::{
+    ast::{AstNode, Root},
+    parse,
+    query::select,
+};
+
+fn select_text(code: &str, path: &str) -> Vec<String> {
+    let root = Root::cast(parse(code).unwrap()).unwrap();
+    select(&root, path)
+        .into_iter()
+        .map(|node| node.to_string())
+        .collect()
+}
+
+#[test]
+fn keys() {
+    let code = "a:\n  b: 1\n  'c.d': 2\n  e: {f: 3, \"g\": [4]}\n";
+    assert_eq!(select_text(code, "a.b"), ["1"]);
+    assert_eq!(select_text(code, ".a.b"), ["1"]);
+    assert_eq!(select_text(code, "a['c.d']"), ["2"]);
+    assert_eq!(select_text(code, "a[\"c.d\"]"), ["2"]);
+    assert_eq!(select_text(code, "a.e.f"), ["3"]);
+    assert_eq!(select_text(code, "a.e.g[0]"), ["4"]);
+    assert!(select_text(code, "a.x").is_empty());
+    assert!(select_text(code, "a.b.c").is_empty());
+    assert!(select_text(code, "a[0]").is_empty());
+}
+
+#[test]
+fn indexes() {
+    let code = "- a\n-\n- [b, c: d]\n- - e\n  - f\n";
+    assert_eq!(select_text(code, "[0]"), ["a"]);
+    assert!(select_text(code, "[1]").is_empty());
+    assert_eq!(select_text(code, "[2][0]"), ["b"]);
+    assert_eq!(select_text(code, "[2][1].c"), ["d"]);
+    assert_eq!(select_text(code, "[3][1]"), ["f"]);
+    assert!(select_text(code, "[4]").is_empty());
+}
+
+#[test]
+fn wildcards() {
+    let code = "a:\n  - name: x\n  - name: y\n  - other: z\nb: {name: w}\n";
+    assert_eq!(select_text(code, "a[*].name"), ["x", "y"]);
+    assert_eq!(select_text(code, "*[*].name"), ["x", "y"]);
+    assert_eq!(select_text(code, "*.name"), ["w"]);
+    assert_eq!(select_text(code, "b.*"), ["w"]);
+}
+
+#[test]
+fn documents() {
+    let code = "a: 1\n---\na: 2\n--- [a]\n";
+    assert_eq!(select_text(code, "a"), ["1", "2"]);
+    assert_eq!(select_text(code, ""), ["a: 1", "a: 2", "[a]"]);
+}
+
+#[test]
+fn properties_and_aliases() {
+    let code = "a: &x\n  b: !!str 1\nc: *x\n";
+    assert_eq!(select_text(code, "a.b"), ["!!str 1"]);
+    assert!(select_text(code, "c.b").is_empty());
+}
+
+#[test]
+fn malformed_path() {
+    let code = "a: [1]\n";
+    for path in ["a.", "a..b", "a[", "a[x]", "a[0", "a['0]", "a[0]b"] {
+        assert!(select_text(code, path).is_empty(), "{path}");
+    }
+}
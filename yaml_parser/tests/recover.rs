@@ -0,0 +1,111 @@
+use insta::glob;
+use rowan::TextRange;
+use std::fs;
+use yaml_parser::{parse_recoverable, SyntaxKind};
+
+#[test]
+fn recover_fail_fixtures() {
+    glob!("fail/*.yaml", |path| {
+        let input = fs::read_to_string(path).unwrap();
+        let (tree, errors) = parse_recoverable(&input);
+        assert_eq!(
+            tree.to_string(),
+            input.trim_start_matches('\u{feff}'),
+            "'{}' should be fully covered",
+            path.display()
+        );
+        assert!(!errors.is_empty(), "expected '{}' to fail", path.display());
+        assert!(tree
+            .descendants_with_tokens()
+            .any(|element| element.kind() == SyntaxKind::ERROR));
+    });
+}
+
+#[test]
+fn keep_valid_parts() {
+    let input = "a: 1\nb: {\nc:\n  - d\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(tree.to_string(), input);
+    let error = tree
+        .descendants_with_tokens()
+        .find(|element| element.kind() == SyntaxKind::ERROR)
+        .unwrap();
+    assert_eq!(error.to_string(), "b: {");
+    assert!(tree
+        .descendants()
+        .any(|node| node.kind() == SyntaxKind::BLOCK_SEQ));
+}
+
+#[test]
+fn no_errors() {
+    let (tree, errors) = parse_recoverable("a: 1\n");
+    assert!(errors.is_empty());
+    assert_eq!(
+        tree.to_string(),
+        yaml_parser::parse("a: 1\n").unwrap().to_string()
+    );
+}
+
+#[test]
+fn byte_order_mark() {
+    let input = "\u{feff}a: 1\nb: [\nc: 2\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert_eq!(tree.to_string(), "a: 1\nb: [\nc: 2\n");
+    assert_eq!(errors.len(), 1);
+    let error = tree
+        .descendants_with_tokens()
+        .find(|element| element.kind() == SyntaxKind::ERROR)
+        .unwrap();
+    assert_eq!(error.text_range(), TextRange::new(5.into(), 9.into()));
+    assert_eq!(errors[0].offset(), 8);
+}
+
+#[test]
+fn skip_indented_comments() {
+    let input = "a: [\n  # comment\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(tree.to_string(), input);
+    assert!(tree
+        .descendants_with_tokens()
+        .any(|element| element.kind() == SyntaxKind::COMMENT));
+}
+
+#[test]
+fn error_in_multi_line_quoted_scalar() {
+    let input = "a: \"foo\n  bar\nc: 2\n---\nd: 1\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert!(!errors.is_empty());
+    assert_eq!(tree.to_string(), input);
+    assert!(tree
+        .descendants()
+        .any(|node| node.kind() == SyntaxKind::BLOCK_MAP));
+}
+
+#[test]
+fn error_after_block_scalar() {
+    let input = "a: >\n  text\n\n  more\n b: [\nc: 1\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(tree.to_string(), input);
+    let block_scalar = tree
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::BLOCK_SCALAR)
+        .unwrap();
+    assert_eq!(block_scalar.to_string(), ">\n  text\n\n  more");
+}
+
+#[test]
+fn errors_in_other_documents() {
+    let input = "a: [\n---\nb: 1\n---\nc: {\n";
+    let (tree, errors) = parse_recoverable(input);
+    assert_eq!(tree.to_string(), input);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].offset(), 3);
+    assert_eq!(errors[1].offset(), 21);
+    assert!(errors[1].to_string().contains("5 | c: {"));
+    assert!(tree
+        .descendants()
+        .any(|node| node.kind() == SyntaxKind::BLOCK_MAP && node.to_string() == "b: 1"));
+}
@@ -0,0 +1,112 @@
+use rowan::{TextRange, TextSize};
+use std::ptr;
+use yaml_parser::{parse, reparse, SyntaxKind, SyntaxNode, TextEdit};
+
+fn edit(start: u32, end: u32, text: &str) -> TextEdit {
+    TextEdit {
+        range: TextRange::new(start.into(), end.into()),
+        text: text.into(),
+    }
+}
+
+/// Reparse and check that the result is the same as parsing the whole text.
+fn check(code: &str, edit: TextEdit) -> (SyntaxNode, SyntaxNode) {
+    let tree = parse(code).unwrap();
+    let mut text = code.to_string();
+    text.replace_range(
+        usize::from(edit.range.start())..usize::from(edit.range.end()),
+        &edit.text,
+    );
+    let new_tree = reparse(&tree, edit).unwrap();
+    assert_eq!(new_tree.to_string(), text);
+    assert_eq!(
+        format!("{new_tree:#?}"),
+        format!("{:#?}", parse(&text).unwrap())
+    );
+    (tree, new_tree)
+}
+
+fn is_shared(old: &SyntaxNode, new: &SyntaxNode) -> bool {
+    ptr::eq(&*old.green(), &*new.green())
+}
+
+fn entries(tree: &SyntaxNode) -> Vec<SyntaxNode> {
+    tree.descendants()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::BLOCK_MAP_ENTRY | SyntaxKind::BLOCK_SEQ_ENTRY
+            ) && node
+                .parent()
+                .and_then(|parent| parent.parent())
+                .is_some_and(|block| {
+                    block.parent().map(|node| node.kind()) == Some(SyntaxKind::DOCUMENT)
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn top_level_entry() {
+    let (old, new) = check("a: 1\nb:\n  c: 2\nd: 3\n", edit(12, 13, "[2, 3]"));
+    let (old, new) = (entries(&old), entries(&new));
+    assert!(is_shared(&old[0], &new[0]));
+    assert!(!is_shared(&old[1], &new[1]));
+    assert!(is_shared(&old[2], &new[2]));
+
+    let (old, new) = check("- a\n- b: 1\n- c\n", edit(6, 7, "bb"));
+    let (old, new) = (entries(&old), entries(&new));
+    assert!(is_shared(&old[0], &new[0]));
+    assert!(!is_shared(&old[1], &new[1]));
+    assert!(is_shared(&old[2], &new[2]));
+}
+
+#[test]
+fn entry_interacting_with_siblings() {
+    // block scalar absorbs indented comment
+    check("a: 1\n  # comment\nb: 2\n", edit(3, 4, "|"));
+    // explicit value pairs with explicit key
+    check("? a\nb: 1\nc: 2\n", edit(4, 5, ""));
+    // entry becomes multiple entries
+    check("a: 1\nb: 2\n", edit(4, 4, "\nc: 3"));
+    // last entry with trailing comments
+    check("a: 1\nb: 2\n# comment\n", edit(8, 9, "|"));
+}
+
+#[test]
+fn documents() {
+    let (old, new) = check("a: 1\n---\nb: [1,\n  2]\n---\nc: 3\n", edit(12, 13, "0"));
+    let documents = |tree: &SyntaxNode| tree.children().collect::<Vec<_>>();
+    let (old, new) = (documents(&old), documents(&new));
+    assert!(is_shared(&old[0], &new[0]));
+    assert!(!is_shared(&old[1], &new[1]));
+    assert!(is_shared(&old[2], &new[2]));
+}
+
+#[test]
+fn document_markers() {
+    // `---` isn't document marker here
+    check("a: 1\n---\nc: 2\n", edit(8, 9, " :"));
+    // document with only properties before `---`
+    check("a\n---\n", edit(0, 1, "&x"));
+    check("a: 1\n...\n---\nb: 2\n", edit(5, 8, ""));
+}
+
+#[test]
+fn syntax_error() {
+    let tree = parse("a: 1\nb: 2\n").unwrap();
+    assert!(reparse(&tree, edit(8, 9, "[")).is_err());
+}
+
+#[test]
+#[should_panic]
+fn out_of_bounds() {
+    let tree = parse("a: 1\n").unwrap();
+    let _ = reparse(
+        &tree,
+        TextEdit {
+            range: TextRange::empty(TextSize::from(10)),
+            text: "b".into(),
+        },
+    );
+}
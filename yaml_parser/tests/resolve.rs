@@ -0,0 +1,73 @@
+use yaml_parser::{
+    ast::{Alias, AstNode, Root},
+    parse,
+    resolve::{AnchorTable, ResolveDiagnostic},
+};
+
+#[test]
+fn resolve_aliases() {
+    let root =
+        Root::cast(parse("a: &x 1\nb: &x [2]\nc: *x\n---\nd: *x\ne: &y\n  f: *y\n").unwrap())
+            .unwrap();
+    let table = AnchorTable::new(&root);
+    let aliases = root
+        .syntax()
+        .descendants()
+        .filter_map(Alias::cast)
+        .collect::<Vec<_>>();
+
+    assert_eq!(table.anchors().count(), 3);
+    assert_eq!(table.aliases().count(), 3);
+    assert_eq!(table.anchors_by_name("x").count(), 2);
+    assert_eq!(
+        table.resolve_alias(&aliases[0]).unwrap().to_string(),
+        "&x [2]"
+    );
+    // anchors are scoped to documents
+    assert!(table.resolve_alias(&aliases[1]).is_none());
+    // alias inside its anchored node
+    assert!(table
+        .resolve_alias(&aliases[2])
+        .unwrap()
+        .to_string()
+        .starts_with("&y"));
+
+    assert_eq!(
+        table
+            .undefined_aliases()
+            .map(|alias| alias.syntax().to_string())
+            .collect::<Vec<_>>(),
+        ["*x"]
+    );
+    assert_eq!(
+        table
+            .duplicate_anchors()
+            .map(|anchor| anchor.syntax().text_range().start())
+            .collect::<Vec<_>>(),
+        [11.into()]
+    );
+
+    let diagnostics = table.diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+    assert!(matches!(
+        &diagnostics[0],
+        ResolveDiagnostic::DuplicateAnchor { previous, .. }
+            if previous.syntax().text_range().start() == 3.into()
+    ));
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "alias `*x` refers to undefined anchor"
+    );
+}
+
+#[test]
+fn resolver_module() {
+    let root = Root::cast(parse("a: &x 1\nb: *x\nc: *y\n").unwrap()).unwrap();
+    let table = yaml_parser::resolver::AnchorTable::new(&root);
+    let alias = root.syntax().descendants().find_map(Alias::cast).unwrap();
+    assert_eq!(table.resolve_alias(&alias).unwrap().to_string(), "&x 1");
+    assert!(matches!(
+        table.diagnostics()[..],
+        [yaml_parser::resolver::ResolveDiagnostic::UndefinedAlias(..)]
+    ));
+}
@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+use yaml_parser::{
+    ast::{AstNode, BlockScalar, Flow},
+    parse,
+};
+
+fn flow_value(code: &str) -> Option<String> {
+    let tree = parse(code).unwrap();
+    let flow = tree.descendants().find_map(Flow::cast).unwrap();
+    flow.scalar_value().map(Cow::into_owned)
+}
+
+fn block_value(code: &str) -> Option<String> {
+    let tree = parse(code).unwrap();
+    let block_scalar = tree.descendants().find_map(BlockScalar::cast).unwrap();
+    block_scalar.value()
+}
+
+#[test]
+fn flow_scalars() {
+    assert_eq!(flow_value("a b\n  c\n\n  d").as_deref(), Some("a b c\nd"));
+    assert_eq!(flow_value("'a''b\n  c'").as_deref(), Some("a'b c"));
+    assert_eq!(
+        flow_value(
+            r#""a\"\n\t\x41☺\
+  b""#
+        )
+        .as_deref(),
+        Some("a\"\n\tA\u{263a}b")
+    );
+    assert_eq!(flow_value(r#""\q""#).as_deref(), Some("\\q"));
+    assert_eq!(flow_value("\"a\\ \n  b\"").as_deref(), Some("a  b"));
+    assert_eq!(flow_value("\"a\\\t \n  b\"").as_deref(), Some("a\t b"));
+    assert_eq!(flow_value("\"a\\\\ \n  b\"").as_deref(), Some("a\\ b"));
+    assert_eq!(flow_value("\"a\\\n\n  \\ b\"").as_deref(), Some("a\n b"));
+    assert_eq!(flow_value("a\\\n  b").as_deref(), Some("a\\ b"));
+    assert_eq!(flow_value("'a\\\n  b'").as_deref(), Some("a\\ b"));
+    assert_eq!(flow_value("''").as_deref(), Some(""));
+    assert_eq!(flow_value("[a]"), None);
+    assert_eq!(flow_value("*a"), None);
+}
+
+#[test]
+fn borrowed_flow_scalars() {
+    let tree = parse("- a\n- 'b'\n- \"c\"\n- 'd''e'\n").unwrap();
+    let values = tree
+        .descendants()
+        .filter_map(Flow::cast)
+        .map(|flow| matches!(flow.scalar_value(), Some(Cow::Borrowed(..))))
+        .collect::<Vec<_>>();
+    assert_eq!(values, [true, true, true, false]);
+}
+
+#[test]
+fn literal() {
+    assert_eq!(
+        block_value("|\n  a\n   b\n\n  c\n").as_deref(),
+        Some("a\n b\n\nc\n")
+    );
+    assert_eq!(block_value("a: |-\n  b\n\n").as_deref(), Some("b"));
+    assert_eq!(
+        block_value("a: |+\n  b\n\n\nc: d").as_deref(),
+        Some("b\n\n\n")
+    );
+    assert_eq!(block_value("- |1\n  a\n").as_deref(), Some(" a\n"));
+    assert_eq!(block_value("a: |\nb: c").as_deref(), Some(""));
+}
+
+#[test]
+fn folded() {
+    assert_eq!(
+        block_value(">\n  a\n  b\n\n  c\n    d\n  e\n").as_deref(),
+        Some("a b\nc\n  d\ne\n")
+    );
+    assert_eq!(block_value("a: >-\n  b\n  c\n").as_deref(), Some("b c"));
+}
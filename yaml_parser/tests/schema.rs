@@ -0,0 +1,117 @@
+use yaml_parser::{
+    ast::{AstNode, Flow, Root},
+    parse,
+    schema::{resolve_plain, resolve_scalar, resolve_scalar_with_version, ScalarType, YamlVersion},
+};
+
+fn resolve_values(code: &str) -> Vec<ScalarType> {
+    let root = Root::cast(parse(code).unwrap()).unwrap();
+    root.syntax()
+        .descendants()
+        .filter_map(Flow::cast)
+        .filter(|flow| flow.flow_seq().is_none())
+        .map(|flow| resolve_scalar(&flow))
+        .collect()
+}
+
+#[test]
+fn core_schema() {
+    use ScalarType::*;
+    for (text, expected) in [
+        ("", Null),
+        ("~", Null),
+        ("NULL", Null),
+        ("nULL", String),
+        ("True", Bool),
+        ("yes", String),
+        ("off", String),
+        ("0", Int),
+        ("-12", Int),
+        ("+12", Int),
+        ("0o17", Int),
+        ("0x1F", Int),
+        ("0b1", String),
+        ("1_000", String),
+        ("1:30", String),
+        ("1.5", Float),
+        ("1.", Float),
+        (".5", Float),
+        ("-1e3", Float),
+        ("1.2E+3", Float),
+        ("1e", String),
+        (".", String),
+        ("-.inf", Float),
+        (".NaN", Float),
+        ("-.nan", String),
+        ("1.2.3", String),
+    ] {
+        assert_eq!(resolve_plain(text, YamlVersion::V1_2), expected, "{text}");
+    }
+}
+
+#[test]
+fn yaml_1_1() {
+    use ScalarType::*;
+    for (text, expected) in [
+        ("~", Null),
+        ("yes", Bool),
+        ("NO", Bool),
+        ("y", Bool),
+        ("On", Bool),
+        ("oFF", String),
+        ("0755", Int),
+        ("0789", String),
+        ("0b1010", Int),
+        ("0x_1F", Int),
+        ("1_000", Int),
+        ("-190:20:30", Int),
+        ("1:60", String),
+        ("0o17", String),
+        ("1.5", Float),
+        ("1_000.5", Float),
+        ("1.0e+3", Float),
+        ("1e3", String),
+        ("190:20:30.15", Float),
+        (".inf", Float),
+        (".", String),
+    ] {
+        assert_eq!(resolve_plain(text, YamlVersion::V1_1), expected, "{text}");
+    }
+}
+
+#[test]
+fn tags_and_styles() {
+    use ScalarType::*;
+    assert_eq!(
+        resolve_values(
+            "- !!str 1\n- !<tag:yaml.org,2002:int> '1'\n- ! true\n- !custom 1\n- \"null\"\n- [&a , *a, !!float ]\n"
+        ),
+        [String, Int, String, Unknown, String, Null, Unknown, Float]
+    );
+}
+
+#[test]
+fn document_version() {
+    use ScalarType::*;
+    assert_eq!(
+        resolve_values("%YAML 1.1\n---\n[no, 010]\n...\n%YAML 1.2\n---\n[no, 010]\n---\n[no]\n"),
+        [Bool, Int, String, Int, String]
+    );
+}
+
+#[test]
+fn explicit_version() {
+    use ScalarType::*;
+    let root =
+        Root::cast(parse("%YAML 1.1\n---\n[no, 010, !!str on]\n---\n[no]\n").unwrap()).unwrap();
+    let resolve = |version| {
+        root.syntax()
+            .descendants()
+            .filter_map(Flow::cast)
+            .filter(|flow| flow.flow_seq().is_none())
+            .map(|flow| resolve_scalar_with_version(&flow, version))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(resolve(YamlVersion::V1_1), [Bool, Int, String, Bool]);
+    assert_eq!(resolve(YamlVersion::V1_2), [String, Int, String, String]);
+}
@@ -0,0 +1,80 @@
+use yaml_parser::{parse, parse_recoverable, to_sexp};
+
+#[test]
+fn sexp() {
+    let tree = parse("a: 1 # comment\nb:\n  - &x c\n").unwrap();
+    assert_eq!(
+        to_sexp(&tree, false),
+        "(root
+  (document
+    (block
+      (block_map
+        (block_map_entry
+          (block_map_key
+            (flow
+              (plain_scalar)))
+          (block_map_value
+            (flow
+              (plain_scalar))))
+        (comment)
+        (block_map_entry
+          (block_map_key
+            (flow
+              (plain_scalar)))
+          (block_map_value
+            (block
+              (block_seq
+                (block_seq_entry
+                  (flow
+                    (properties
+                      (anchor_property
+                        (anchor_name)))
+                    (plain_scalar)))))))))))"
+    );
+}
+
+#[test]
+fn sexp_with_ranges() {
+    let tree = parse("a: 1\nb: [c]\n").unwrap();
+    let value = tree
+        .descendants()
+        .find(|node| node.kind() == yaml_parser::SyntaxKind::FLOW_SEQ)
+        .unwrap();
+    assert_eq!(
+        to_sexp(&value, true),
+        "(flow_seq [1, 3] - [1, 6]
+  (flow_seq_entries [1, 4] - [1, 5]
+    (flow_seq_entry [1, 4] - [1, 5]
+      (flow [1, 4] - [1, 5]
+        (plain_scalar [1, 4] - [1, 5])))))"
+    );
+}
+
+#[test]
+fn sexp_with_errors() {
+    let (tree, errors) = parse_recoverable("a: 1\nb: [\nc: 2\n");
+    assert!(!errors.is_empty());
+    assert_eq!(
+        to_sexp(&tree, false),
+        "(root
+  (document
+    (block
+      (block_map
+        (block_map_entry
+          (block_map_key
+            (flow
+              (plain_scalar)))
+          (block_map_value
+            (flow
+              (plain_scalar))))
+        (ERROR)
+        (block_map_entry
+          (block_map_key
+            (flow
+              (plain_scalar)))
+          (block_map_value
+            (flow
+              (plain_scalar))))))))"
+    );
+    assert!(to_sexp(&tree, true).contains("(ERROR [1, 0] - [1, 4])"));
+}
@@ -0,0 +1,81 @@
+use yaml_parser::{parse, parse_with_template_syntax, SyntaxKind, SyntaxNode, TemplateSyntax};
+
+fn plain_scalars(tree: &SyntaxNode) -> Vec<String> {
+    tree.descendants_with_tokens()
+        .filter(|element| element.kind() == SyntaxKind::PLAIN_SCALAR)
+        .map(|element| element.to_string())
+        .collect()
+}
+
+#[test]
+fn go_template() {
+    let code = "\
+image: {{ .Values.image }}
+name: {{ .Release.Name }}-app
+labels: {{- include \"labels\" . | nindent 4 }}
+url: http://{{ .Values.host }}:{{ .Values.port }}/
+items: [{{ .Values.a }}, b]
+{{ .Values.key }}: value
+flow: {a: 1}
+brace: a{b
+";
+    let tree = parse_with_template_syntax(code, TemplateSyntax::Go).unwrap();
+    assert_eq!(tree.to_string(), code);
+    assert_eq!(
+        plain_scalars(&tree),
+        [
+            "image",
+            "{{ .Values.image }}",
+            "name",
+            "{{ .Release.Name }}-app",
+            "labels",
+            "{{- include \"labels\" . | nindent 4 }}",
+            "url",
+            "http://{{ .Values.host }}:{{ .Values.port }}/",
+            "items",
+            "{{ .Values.a }}",
+            "b",
+            "{{ .Values.key }}",
+            "value",
+            "flow",
+            "a",
+            "1",
+            "brace",
+            "a{b",
+        ]
+    );
+}
+
+#[test]
+fn jinja2_template() {
+    let code = "\
+name: {{ item.name }}
+when: {% if enabled %}yes{% endif %} {# comment: here #}
+";
+    let tree = parse_with_template_syntax(code, TemplateSyntax::Jinja2).unwrap();
+    assert_eq!(
+        plain_scalars(&tree),
+        [
+            "name",
+            "{{ item.name }}",
+            "when",
+            "{% if enabled %}yes{% endif %} {# comment: here #}",
+        ]
+    );
+
+    // Go templates don't have `{%`
+    assert!(parse_with_template_syntax(code, TemplateSyntax::Go).is_err());
+}
+
+#[test]
+fn without_template_syntax() {
+    let tree = parse("a: {{ b }}\n").unwrap();
+    assert!(tree
+        .descendants()
+        .any(|node| node.kind() == SyntaxKind::FLOW_MAP));
+    assert!(
+        parse_with_template_syntax("a: {{ b\n  }}\n", TemplateSyntax::Go).is_ok_and(|tree| tree
+            .descendants()
+            .any(|node| node.kind() == SyntaxKind::FLOW_MAP))
+    );
+}
@@ -0,0 +1,128 @@
+use rowan::TextRange;
+use std::ptr;
+use yaml_parser::{parse, SyntaxNode, Transaction, TransactionError};
+
+#[test]
+fn apply_edits() {
+    let tree = parse("a: 1\nb: 2\nc: 3\n").unwrap();
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .delete(TextRange::new(10.into(), 15.into()))
+        .replace(TextRange::new(3.into(), 4.into()), "[1, 2]")
+        .insert(5.into(), "x: 0\n");
+    let (tree, changed) = transaction.commit().unwrap();
+    assert_eq!(tree.to_string(), "a: [1, 2]\nx: 0\nb: 2\n");
+    assert_eq!(
+        changed,
+        [
+            TextRange::new(3.into(), 9.into()),
+            TextRange::new(10.into(), 15.into()),
+            TextRange::empty(20.into()),
+        ]
+    );
+}
+
+#[test]
+fn conflicting_edits() {
+    let tree = parse("key: value\n").unwrap();
+
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .replace(TextRange::new(0.into(), 3.into()), "k")
+        .replace(TextRange::new(2.into(), 10.into()), "v");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::Conflict(..))
+    ));
+
+    let mut transaction = Transaction::new(&tree);
+    transaction.insert(0.into(), "a").insert(0.into(), "b");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::Conflict(..))
+    ));
+
+    let mut transaction = Transaction::new(&tree);
+    transaction
+        .replace(TextRange::new(0.into(), 3.into()), "k")
+        .insert(3.into(), "ey");
+    assert_eq!(transaction.commit().unwrap().0.to_string(), "key: value\n");
+}
+
+#[test]
+fn invalid_edits() {
+    let tree = parse("key: value\n").unwrap();
+
+    let mut transaction = Transaction::new(&tree);
+    transaction.insert(100.into(), "a");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::OutOfBounds(..))
+    ));
+
+    let mut transaction = Transaction::new(&tree);
+    transaction.insert(5.into(), "[");
+    assert!(matches!(
+        transaction.commit(),
+        Err(TransactionError::Syntax(..))
+    ));
+}
+
+#[test]
+fn reparse_touched_documents() {
+    let tree = parse("a: 1\n---\nb: 2\n# comment\n---\nc: 3\n").unwrap();
+    let mut transaction = Transaction::new(&tree);
+    transaction.replace(TextRange::new(12.into(), 13.into()), "[2, 3]");
+    let (new_tree, _) = transaction.commit().unwrap();
+    assert_eq!(
+        new_tree.to_string(),
+        "a: 1\n---\nb: [2, 3]\n# comment\n---\nc: 3\n"
+    );
+    assert_eq!(
+        format!("{new_tree:#?}"),
+        format!("{:#?}", parse(&new_tree.to_string()).unwrap())
+    );
+
+    let documents = |tree: &SyntaxNode| tree.children().collect::<Vec<_>>();
+    let (old_documents, new_documents) = (documents(&tree), documents(&new_tree));
+    let is_shared = |index: usize| {
+        ptr::eq(
+            &*old_documents[index].green(),
+            &*new_documents[index].green(),
+        )
+    };
+    assert!(is_shared(0));
+    assert!(!is_shared(1));
+    assert!(is_shared(2));
+}
+
+#[test]
+fn reparse_with_document_markers() {
+    let source = "a: 1\n---\nb: 2\n---\nc: 3\n";
+    let cases = [
+        // remove document start marker, so documents are merged
+        (TextRange::new(5.into(), 9.into()), ""),
+        // add document end marker
+        (TextRange::empty(14.into()), "...\n"),
+        // add document
+        (TextRange::empty(14.into()), "---\nx: 0\n"),
+        (TextRange::empty(0.into()), "%YAML 1.2\n---\n"),
+    ];
+    for (range, text) in cases {
+        let tree = parse(source).unwrap();
+        let mut transaction = Transaction::new(&tree);
+        transaction.replace(range, text);
+        let expected = {
+            let mut expected = source.to_string();
+            expected.replace_range(usize::from(range.start())..usize::from(range.end()), text);
+            expected
+        };
+        match (transaction.commit(), parse(&expected)) {
+            (Ok((tree, _)), Ok(expected)) => {
+                assert_eq!(format!("{tree:#?}"), format!("{expected:#?}"));
+            }
+            (Err(TransactionError::Syntax(..)), Err(..)) => {}
+            _ => panic!("unexpected result of replacing {range:?} with {text:?}"),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use yaml_parser::{
+    ast::{
+        visit::{walk, Visitor},
+        AstNode, BlockMap, BlockMapEntry, Flow, FlowSeq, Root,
+    },
+    parse,
+};
+
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl Visitor for Events {
+    fn enter_block_map(&mut self, _: &BlockMap) {
+        self.0.push("enter map".into());
+    }
+    fn leave_block_map(&mut self, _: &BlockMap) {
+        self.0.push("leave map".into());
+    }
+    fn enter_block_map_entry(&mut self, entry: &BlockMapEntry) {
+        self.0.push(format!("enter entry {}", entry.syntax()));
+    }
+    fn leave_block_map_entry(&mut self, _: &BlockMapEntry) {
+        self.0.push("leave entry".into());
+    }
+    fn enter_flow_seq(&mut self, _: &FlowSeq) {
+        self.0.push("enter seq".into());
+    }
+    fn leave_flow_seq(&mut self, _: &FlowSeq) {
+        self.0.push("leave seq".into());
+    }
+    fn enter_flow(&mut self, flow: &Flow) {
+        if let Some(scalar) = flow.plain_scalar() {
+            self.0.push(format!("scalar {}", scalar.text()));
+        }
+    }
+}
+
+#[test]
+fn order() {
+    let root = Root::cast(parse("a: [1, 2]\nb:\n  c: 3\n").unwrap()).unwrap();
+    let mut events = Events::default();
+    walk(&root, &mut events);
+    assert_eq!(
+        events.0,
+        [
+            "enter map",
+            "enter entry a: [1, 2]",
+            "scalar a",
+            "enter seq",
+            "scalar 1",
+            "scalar 2",
+            "leave seq",
+            "leave entry",
+            "enter entry b:\n  c: 3",
+            "scalar b",
+            "enter map",
+            "enter entry c: 3",
+            "scalar c",
+            "scalar 3",
+            "leave entry",
+            "leave map",
+            "leave entry",
+            "leave map",
+        ]
+    );
+}
+
+#[test]
+fn multiple_documents() {
+    #[derive(Default)]
+    struct Counter {
+        documents: usize,
+        depth: usize,
+        max_depth: usize,
+    }
+    impl Visitor for Counter {
+        fn enter_document(&mut self, _: &yaml_parser::ast::Document) {
+            self.documents += 1;
+        }
+        fn enter_block(&mut self, _: &yaml_parser::ast::Block) {
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+        }
+        fn leave_block(&mut self, _: &yaml_parser::ast::Block) {
+            self.depth -= 1;
+        }
+    }
+
+    let root = Root::cast(parse("- a\n---\n- - b\n---\nc\n").unwrap()).unwrap();
+    let mut counter = Counter::default();
+    walk(&root, &mut counter);
+    assert_eq!(counter.documents, 3);
+    assert_eq!(counter.depth, 0);
+    assert_eq!(counter.max_depth, 2);
+}